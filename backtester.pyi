@@ -0,0 +1,1635 @@
+# backtester.pyi: type stubs of the `backtester` extension module.
+# Generated by build.rs from the Rust sources on every build; do not edit.
+
+from typing import Any, Dict, List, Optional, Sequence, Tuple, Union
+
+import numpy as np
+import numpy.typing as npt
+from typing_extensions import NotRequired, TypedDict
+
+class BacktestSession:
+    """
+    Preloaded backtest for external optimizers (Optuna, Bayesian opt, ...).
+
+    The market data and base signals are copied into Rust once; each trial
+    only ships a small parameter dict in and scalar metrics out, and the
+    engine runs with the GIL released.
+    """
+    def __init__(
+        self,
+        timestamp: npt.NDArray[np.float64],
+        open: npt.NDArray[np.float64],
+        high: npt.NDArray[np.float64],
+        low: npt.NDArray[np.float64],
+        close: npt.NDArray[np.float64],
+        long_signals: npt.NDArray[np.bool_],
+        short_signals: npt.NDArray[np.bool_],
+        long_tp: npt.NDArray[np.float64],
+        long_sl: npt.NDArray[np.float64],
+        short_tp: npt.NDArray[np.float64],
+        short_sl: npt.NDArray[np.float64],
+        long_size: npt.NDArray[np.float64],
+        short_size: npt.NDArray[np.float64],
+        expiration_times: npt.NDArray[np.float64],
+        entry_fee_rate: float,
+        exit_fee_rate: float,
+        slippage_rate: float,
+        initial_equity: float,
+        objective: str = "sharpe_ratio",
+        metrics: Optional[Sequence[str]] = None,
+    ) -> None: ...
+    def evaluate(
+        self,
+        params: Dict[str, Any],
+        overrides: Optional[Dict[str, Any]] = None,
+    ) -> Dict[str, Any]:
+        """
+        Run one trial and return its scalar metrics (recorded in `trials`).
+
+        `params` may use the builtin grid names; `overrides` optionally
+        replaces signal arrays for this trial (any `run_backtest` array key).
+        """
+    def score(self, params: Dict[str, Any], overrides: Optional[Dict[str, Any]] = None) -> float:
+        """Like `evaluate`, but returns only the objective value"""
+    def evaluate_many(self, batch: Sequence[Dict[str, Any]]) -> List[Dict[str, Any]]:
+        """Evaluate a batch of parameter dicts in parallel"""
+    @property
+    def trials(self) -> List[BacktestSessionTrialDict]:
+        """Every recorded trial as `{"params", "metrics"}` dicts"""
+    def best_trial(self) -> Optional[BacktestSessionBestTrialDict]:
+        """Best recorded trial by the objective (None before any trial)"""
+    @property
+    def objective(self) -> str: ...
+
+class PaperSession:
+    """
+    Live bar feed driven through the backtest engine.
+
+    Each `on_bar` appends one completed bar (and the signal decided on its
+    close), enters the previous bar's signal at its open and advances the
+    open positions, so positions and costs are exactly what `run_backtest`
+    reports on the same bars.  The newest bar's signal stays pending until
+    the next bar supplies its fill open.  Like a chunked run with one‐bar
+    chunks, closed trades are final and only the positions still open are
+    re‐simulated (from their entry, so a callback costs their holding time,
+    not the history); `result()` rebuilds the curve and metrics over every
+    bar on request.
+    """
+    def __init__(
+        self,
+        entry_fee_rate: float,
+        exit_fee_rate: float,
+        slippage_rate: float,
+        initial_equity: float,
+    ) -> None: ...
+    def on_bar(
+        self,
+        timestamp: float,
+        open: float,
+        high: float,
+        low: float,
+        close: float,
+        long_signal: bool = False,
+        short_signal: bool = False,
+        long_tp: float = ...,
+        long_sl: float = ...,
+        short_tp: float = ...,
+        short_sl: float = ...,
+        long_size: float = 0.0,
+        short_size: float = 0.0,
+        expiration_time: Optional[float] = None,
+    ) -> List[Union[OpenPositionDict, ClosedPositionDict]]:
+        """
+        Feed one completed bar and the signal decided on its close.
+
+        Returns the events the bar produced, oldest first: `{"event":
+        "entry", ...open position}` and `{"event": "exit", ...closed
+        position}`.  `expiration_time=None` never expires.
+        """
+    @property
+    def bars(self) -> int:
+        """Number of bars fed so far"""
+    @property
+    def pending_entry(self) -> bool:
+        """True when the last bar's signal is waiting for the next bar's open"""
+    def result(self) -> Optional[BacktestResultDict]:
+        """
+        Current positions, costs and metrics in the `run_backtest` layout
+        (None before the first bar); rebuilt over every bar on each call
+        """
+    def open_positions(self) -> List[OpenPositionDict]:
+        """Positions still open after the last bar, marked at its close"""
+    def closed_positions(self) -> List[ClosedPositionDict]:
+        """Closed positions so far"""
+    def parity(self, closed_positions: List[Any], tolerance: float = 1e-9) -> None:
+        """
+        Compare this session's closed trades with another run's (e.g. live
+        fills or a `run_backtest` result): the first mismatching trade, or
+        None when every shared trade agrees on entry/exit index and prices
+        within `tolerance`
+        """
+
+class BacktestResult:
+    """
+    A stored `run_backtest` result loaded back for comparison.
+
+    `from_dict` / `from_json` accept results written by any earlier version
+    and upgrade them to the current schema; `loaded_from_schema` keeps the
+    version the data was written with.
+    """
+    @staticmethod
+    def from_dict(result: Dict[str, Any]) -> BacktestResult:
+        """Load (a copy of) a result dict"""
+    @staticmethod
+    def from_json(text: str) -> BacktestResult:
+        """Load a result saved with `json.dumps(out)` or `to_json()`"""
+    def to_dict(self) -> Any: ...
+    def to_json(self) -> str:
+        """JSON text of the result (numpy arrays written as lists)"""
+    @property
+    def schema_version(self) -> int: ...
+    @property
+    def loaded_from_schema(self) -> int: ...
+    @property
+    def version(self) -> Optional[Any]:
+        """Crate version that produced the result (None without a manifest)"""
+    @property
+    def closed_positions(self) -> Any: ...
+    @property
+    def open_positions(self) -> Any: ...
+    @property
+    def exposure_time_series(self) -> Any: ...
+    @property
+    def metrics(self) -> Any: ...
+    def scalar_metrics(self) -> Dict[str, float]:
+        """Overall scalar metrics by name (the `grid_search` metric names)"""
+    def compare(
+        self,
+        other: BacktestResult,
+        metrics: Optional[Sequence[str]] = None,
+    ) -> Dict[str, BacktestResultCompareEntryDict]:
+        """
+        Side‐by‐side scalar metrics: `{name: {"self", "other", "delta"}}`
+        for every metric both results report (or the `metrics` chosen)
+        """
+    def filter_trades(
+        self,
+        start_time: Optional[float] = None,
+        end_time: Optional[float] = None,
+        side: Optional[str] = None,
+        tag: Optional[str] = None,
+        exit_condition: Optional[str] = None,
+        prices: Optional[npt.NDArray[np.float64]] = None,
+    ) -> BacktestResult:
+        """
+        The result restricted to the trades matching every given criterion,
+        with the exposure curve and metrics re‐derived from them.
+
+        Trades are selected by entry time (`start_time` ≤ entry <
+        `end_time`), `side`, `tag` and `exit_condition`; the curve still
+        spans every bar.  Open trades are marked at `prices` (the close
+        series of the run) when given, otherwise the subset's equity only
+        moves when its trades exit.  Cost and metric options come from the
+        run's `run_info` parameters.
+        """
+    def __getitem__(self, key: str) -> Any: ...
+
+def run_backtest(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    bootstrap_samples: int = 0,
+    bootstrap_block_length: Optional[int] = None,
+    bootstrap_seed: int = 0,
+    bootstrap_confidence: float = 0.95,
+    tags: Optional[Any] = None,
+    contract_ids: Optional[Any] = None,
+    roll_prices: Optional[npt.NDArray[np.float64]] = None,
+    contract_type: str = "linear",
+    latency_bars: int = 0,
+    latency_seconds: Optional[float] = None,
+    fill_probability: Optional[float] = None,
+    fill_full_depth: float = 0.001,
+    fill_seed: int = 0,
+    max_trailing_drawdown: Optional[float] = None,
+    trailing_drawdown_pct: bool = False,
+    throttle_window: Optional[int] = None,
+    throttle_scale: float = 0.0,
+    max_positions: Optional[int] = None,
+    cash_constrained: bool = False,
+    compounding: bool = False,
+    max_loss: Optional[float] = None,
+    max_loss_mode: str = "tighten",
+    tick_size: Optional[float] = None,
+    lot_size: Optional[float] = None,
+    min_notional: Optional[float] = None,
+    size_mode: str = "units",
+    fill_rate: Optional[float] = None,
+    volume: Optional[npt.NDArray[np.float64]] = None,
+    participation_rate: float = 0.1,
+    slippage_sl: Optional[float] = None,
+    slippage_tp: Optional[float] = None,
+    slippage_exp: Optional[float] = None,
+    maker_fee_rate: Optional[float] = None,
+    taker_fee_rate: Optional[float] = None,
+    leverage: float = 1.0,
+    maintenance_margin: Optional[float] = None,
+    liquidation_buffer: float = 0.05,
+    signal_strength: Optional[npt.NDArray[np.float64]] = None,
+    signal: Optional[npt.NDArray[np.int64]] = None,
+    signal_mode: str = "independent",
+    amendments: Optional[Sequence[Dict[str, Any]]] = None,
+    sl_decay_bars: Optional[int] = None,
+    sl_decay_steps: Optional[Sequence[Tuple[int, float]]] = None,
+    trail_atr: Optional[npt.NDArray[np.float64]] = None,
+    trail_mult: float = 3.0,
+    funding_rates: Optional[npt.NDArray[np.float64]] = None,
+    mark_price: Optional[npt.NDArray[np.float64]] = None,
+    fx_rates: Optional[npt.NDArray[np.float64]] = None,
+    dividends: Optional[npt.NDArray[np.float64]] = None,
+    split_factors: Optional[npt.NDArray[np.float64]] = None,
+    shortable: Optional[npt.NDArray[np.bool_]] = None,
+    borrow_available: Optional[npt.NDArray[np.float64]] = None,
+    bid: Optional[npt.NDArray[np.float64]] = None,
+    ask: Optional[npt.NDArray[np.float64]] = None,
+    bid_size: Optional[npt.NDArray[np.float64]] = None,
+    ask_size: Optional[npt.NDArray[np.float64]] = None,
+    queue_volume: Optional[npt.NDArray[np.float64]] = None,
+    queue_volume_share: float = 0.1,
+    gross_metrics: bool = False,
+    weighted_returns: bool = False,
+    check_invariants: bool = False,
+    undefined_ratios: str = "legacy",
+    ratio_cap: Optional[float] = None,
+    equity_basis: str = "pnl",
+    return_type: str = "simple",
+    return_equity: str = "marked",
+    cost_accrual: str = "exit",
+    net_floating: bool = False,
+    sub_bars: Optional[Dict[str, Any]] = None,
+    expiry_fill: str = "close",
+    expiry_policy: str = "close",
+    expiry_extension: Optional[float] = None,
+    exit_priority: str = "sl,
+    tp: Any,
+    exp": Any,
+    level_check: str = "touch",
+    sl_order_type: str = "stop",
+    sl_limit_offset: Optional[float] = None,
+    overlap_threshold: int = 1,
+    regimes: Optional[Any] = None,
+    benchmark: Optional[npt.NDArray[np.float64]] = None,
+    cash_flows: Optional[npt.NDArray[np.float64]] = None,
+    factors: Optional[Dict[str, Any]] = None,
+    beta_window: int = 60,
+    autocorr_lags: Optional[Sequence[int]] = None,
+    duration_buckets: Optional[Sequence[float]] = None,
+    warmup_bars: int = 0,
+    start_time: Optional[float] = None,
+    end_time: Optional[float] = None,
+    orders: bool = False,
+    timeline: Optional[str] = None,
+    journal: Optional[str] = None,
+    journal_label: Optional[str] = None,
+) -> RunBacktestResultDict: ...
+
+def grid_search(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    param_grid: Dict[str, Any],
+    signal_fn: Optional[Any] = None,
+    rank_by: str = "sharpe_ratio",
+    ascending: bool = False,
+    metrics: Optional[Sequence[str]] = None,
+    top_k: int = 0,
+    n_trials: Optional[int] = None,
+) -> List[GridSearchResultItemDict]:
+    """
+    Exhaustive grid search.
+
+    `param_grid` maps parameter names to candidate values.  The builtin axes
+    (`tp_mult`, `sl_mult`, `size_fraction`, `entry_fee_rate`, `exit_fee_rate`,
+    `slippage_rate`) are applied by the engine; any other name requires a
+    `signal_fn(params: dict) -> dict` that returns replacement arrays (any of
+    the `run_backtest` signal/level/size keys).  Builtin axes are applied on
+    top of whatever `signal_fn` returns.
+
+    Each row also carries the probabilistic Sharpe (vs 0) and the deflated
+    Sharpe: the probability its Sharpe beats the best expected from
+    `n_trials` zero‐skill trials (default: the grid size) with the grid's
+    Sharpe dispersion.
+    """
+
+def sensitivity_surface(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    x_param: str,
+    x_values: Sequence[float],
+    y_param: Optional[str] = None,
+    y_values: Optional[Sequence[float]] = None,
+    metric: str = "sharpe_ratio",
+    signal_fn: Optional[Any] = None,
+) -> SensitivitySurfaceResultDict:
+    """
+    Metric surface over one or two parameters.
+
+    Returns `surface[y][x]` (a single row when `y_param` is omitted) along
+    with the best cell and the mean of its neighbours: a best value far above
+    its neighbourhood indicates a knife‐edge optimum.  Parameters follow the
+    `grid_search` rules (builtin names or a `signal_fn`).
+    """
+
+def compare_exit_policies(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    policies: List[Any],
+    rank_by: str = "sharpe_ratio",
+    ascending: bool = False,
+    metrics: Optional[Sequence[str]] = None,
+    top_k: int = 0,
+) -> List[CompareExitPoliciesResultItemDict]:
+    """
+    Compare exit policies over one shared entry scan.
+
+    `policies` is a list of dicts with `tp_mult` / `sl_mult` (default 1):
+    each scales the TP / SL distances from the signal‐bar close like the
+    `grid_search` axes.  Entries are scanned once and every policy only
+    re‐simulates exits and metrics, in parallel.  Returns one row per policy,
+    in the given order, with its `rank` by `rank_by`, its `policy` and
+    `metrics`; `top_k` attaches full results to the best rows.
+    """
+
+def stream_backtest(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    output_dir: str,
+    chunk_bars: int = 1000000,
+    format: str = "csv",
+) -> StreamBacktestResultDict:
+    """
+    Chunked backtest for very long bar series.
+
+    Processes `chunk_bars` bars at a time and writes closed trades and
+    per‐bar exposure incrementally to `output_dir` (`trades.csv` /
+    `exposure.csv`, or `.parquet` through pyarrow with `format="parquet"`),
+    keeping only the positions still open in memory.  Trades still open after
+    the last bar are written with `is_closed` 0.  Returns the headline
+    metrics accumulated on the way and the written paths.
+    """
+
+def scan_entries(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    slippage_rate: float,
+    contract_type: str = "linear",
+    size_mode: str = "units",
+    tags: Optional[Any] = None,
+) -> ScanEntriesResultDict:
+    """
+    Entry stage: the positions the signals open, with levels and entry costs
+    but not yet exited.
+
+    Returns `{"positions": [...], "skipped_entries": [...]}`; the position
+    dicts are accepted by `simulate_exits` as they are.
+    """
+
+def simulate_exits(
+    positions: Sequence[Dict[str, Any]],
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    exit_fee_rate: float,
+    slippage_rate: float,
+    slippage_sl: Optional[float] = None,
+    slippage_tp: Optional[float] = None,
+    slippage_exp: Optional[float] = None,
+    maker_fee_rate: Optional[float] = None,
+) -> List[Union[ClosedPositionDict, OpenPositionDict]]:
+    """
+    Exit stage: TP/SL/expiry exits of `positions` over the bars.
+
+    Positions already closed are passed through unchanged.  Returns every
+    position in input order, closed ones with exit fields and excursions.
+    With `maker_fee_rate`, take‐profit exits (resting limits) pay it instead
+    of `exit_fee_rate`.
+    """
+
+def compute_exposure_series(
+    positions: Sequence[Dict[str, Any]],
+    timestamp: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    initial_equity: float,
+    leverage: float = 1.0,
+) -> List[SnapshotDict]:
+    """
+    Exposure stage: per‐bar exposure, equity and margin of `positions`
+    marked at `close`.
+    """
+
+def compute_summary_metrics(
+    positions: Sequence[Dict[str, Any]],
+    exposure: Sequence[Dict[str, Any]],
+    initial_equity: float,
+    undefined_ratios: str = "legacy",
+    ratio_cap: Optional[float] = None,
+    equity_basis: str = "pnl",
+    return_type: str = "simple",
+    return_equity: str = "marked",
+) -> SummaryMetricsDict:
+    """
+    Metrics stage: the `metrics` dict of `run_backtest` from trades and an
+    exposure series.
+
+    Only closed positions count as trades, so the output of
+    `simulate_exits` can be passed whole; trades filtered or edited
+    beforehand should come with the exposure recomputed from them.
+    """
+
+def compute_metrics(
+    trades: Sequence[Dict[str, Any]],
+    timestamp: npt.NDArray[np.float64],
+    equity_curve: Optional[npt.NDArray[np.float64]] = None,
+    prices: Optional[npt.NDArray[np.float64]] = None,
+    config: Optional[Dict[str, Any]] = None,
+) -> SummaryMetricsDict:
+    """
+    The `metrics` dict of `run_backtest` for trades produced elsewhere.
+
+    `trades` are dicts with `position_type`, `entry_time`, `entry_price`,
+    `position_size` and, once closed, `exit_time` and `exit_price`; `pnl`,
+    `fee_entry`, `fee_exit`, `sl`, `tag` and the other trade fields are
+    optional.  Missing fees are charged at the config's rates by liquidity
+    (`entry_liquidity` / `exit_liquidity`, by default taker except for "TP"
+    exits).  Fills are placed on the last bar of `timestamp` starting at
+    or before them.  The equity comes from `prices` (trades marked at each
+    bar's price, as in a run) or from an account `equity_curve`; pass one.
+    `config` takes `run_backtest` keyword arguments (e.g. the `run` table of
+    `load_config`), of which only `initial_equity`, the fee rates,
+    `contract_type`, `leverage`, the cost accrual and the metric options are
+    used.
+    """
+
+def merge_equity_curves(
+    runs: Sequence[Any],
+    weights: Optional[Sequence[float]] = None,
+    names: Optional[Sequence[str]] = None,
+    align: str = "union",
+    rebalance: bool = True,
+    initial_equity: Optional[float] = None,
+    undefined_ratios: str = "legacy",
+    ratio_cap: Optional[float] = None,
+) -> MergeEquityCurvesResultDict:
+    """
+    Combine the equity curves of several runs.
+
+    `runs` are `run_backtest` results (or any mappings with `timestamps`
+    and `equity`), `names` label them (default "0", "1", …).  The curves
+    are aligned on the union (`align="union"`, holding each curve's last
+    value) or intersection of their timestamps and blended with `weights`
+    (equal by default, normalized to sum 1), rebalanced every bar or, with
+    `rebalance=False`, bought once.  Returns the index, the aligned curves,
+    the `combined` curve, per‐curve and combined stats, the correlation
+    matrix of bar returns and the diversification ratio.
+    """
+
+def monte_carlo_trades(
+    trades: Any,
+    initial_equity: float,
+    n_simulations: int = 1000,
+    method: str = "bootstrap",
+    seed: int = 0,
+    confidence: float = 0.95,
+) -> MonteCarloTradesResultDict:
+    """
+    Monte Carlo resampling of closed trades.
+
+    `trades` is either `result["closed_positions"]` or a sequence of trade
+    PnLs (in exit order).  Returns the distribution and confidence interval of
+    final equity, max drawdown and max drawdown duration (in trades), plus the
+    same statistics for the observed order.
+    """
+
+def permutation_test(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    n_permutations: int = 1000,
+    seed: int = 0,
+    metrics: Optional[Sequence[str]] = None,
+) -> PermutationTestResultDict:
+    """
+    Permutation test of the entry signals.
+
+    Each permutation scatters the same number of long and short signals over
+    random bars (levels, sizes and expirations are read from the new bars) and
+    re‐runs the backtest.  The p‐value is the share of permutations that did at
+    least as well as the real signals.
+    """
+
+def random_entry_baseline(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    n_runs: int = 1000,
+    seed: int = 0,
+    n_entries: Optional[int] = None,
+    long_fraction: Optional[float] = None,
+    metrics: Optional[Sequence[str]] = None,
+) -> RandomEntryBaselineResultDict:
+    """
+    Random‐entry baseline.
+
+    Runs `n_runs` backtests whose entries are random bars (by default as many
+    as the real strategy, with its long/short mix) but whose exits, levels and
+    costs are identical.  Shows how much performance the entry signal adds on
+    top of the exit logic alone.
+    """
+
+def run_portfolio_backtest(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    strategies: Sequence[Dict[str, Any]],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    shared_cash: bool = True,
+    max_gross_exposure: Optional[float] = None,
+    quote_currency: Optional[str] = None,
+    account_currency: Optional[str] = None,
+    fx_rates: Optional[Dict[str, Any]] = None,
+    accounts: Optional[Sequence[Dict[str, Any]]] = None,
+) -> RunPortfolioBacktestResultDict:
+    """
+    Multi‐strategy portfolio backtest.
+
+    `strategies` is a list of dicts, each with an `"id"` and the nine
+    `run_backtest` signal arrays (plus an optional `"max_exposure"` notional
+    cap).  Positions from all strategies share one account: with
+    `shared_cash` an entry needs free cash for its full notional, and
+    `max_gross_exposure` caps total open notional.  Returns the combined
+    result (positions tagged with `strategy_id`), the rejected entries, and
+    per‐strategy attribution.
+
+    A strategy may trade another instrument: `"symbol"` names it and the
+    first strategy naming it supplies its `"open"`, `"high"`, `"low"` and
+    `"close"` arrays (on the shared `timestamp`).  Strategies without a
+    symbol trade the top‐level prices as `"default"`.  `out["symbols"]`
+    holds each symbol's full metrics block and its contribution shares, and
+    `out["correlation"]` the correlation matrices (`labels`, `matrix`) of the
+    strategies' and symbols' contributions to portfolio bar returns.
+
+    Symbols quoted in another currency set `"quote_currency"` (alongside
+    their prices; `quote_currency` for the top‐level prices) and `fx_rates`
+    maps each such currency to a per‐bar array of account currency per unit;
+    their PnL, costs and curves are converted before aggregation (capital
+    rules still compare quote‐currency notionals).
+
+    `accounts` splits the capital into named sub‐accounts: dicts with an
+    `"id"`, their `"initial_equity"` (together at most `initial_equity`; the
+    rest stays idle) and optionally their own `"shared_cash"` and
+    `"max_gross_exposure"`.  Every strategy then names its `"account"`, and
+    its entries are admitted against that account's capital and rules, while
+    the top‐level `max_gross_exposure` caps the open notional of all
+    accounts together (reason `"portfolio_exposure"`).  The combined result covers all of them; `out["accounts"]` holds
+    each account's metrics on its own capital and its strategies.
+    """
+
+def run_pair_backtest(
+    timestamp: npt.NDArray[np.float64],
+    open_a: npt.NDArray[np.float64],
+    close_a: npt.NDArray[np.float64],
+    open_b: npt.NDArray[np.float64],
+    close_b: npt.NDArray[np.float64],
+    hedge_ratio: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+) -> RunPairBacktestResultDict:
+    """
+    Spread (pair) backtest.
+
+    A long signal buys `size` units of leg A and sells `hedge_ratio × size`
+    of leg B at the next bar's opens; a short signal does the reverse.  TP/SL
+    arrays are levels of the spread `A − hedge_ratio × B` and are checked on
+    bar closes.  Fees and slippage apply to each leg separately.
+    """
+
+def stress_test(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    scenarios: Sequence[Dict[str, Any]],
+    metrics: Optional[Sequence[str]] = None,
+) -> StressTestResultDict:
+    """
+    Scenario stress test.
+
+    Each scenario is a shock dict (`{"type": "gap", "timestamp", "magnitude"}`
+    or `{"type": "volatility", "start", "end", "factor"}`, optional `"name"`)
+    or `{"name", "shocks": [...]}` to combine several.  The same signals are
+    re‐run on every shocked series and metric deltas vs the baseline reported.
+    """
+
+def execution_jitter(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    repetitions: int = 100,
+    slippage_jitter_bps: float = 0.0,
+    max_delay_bars: int = 0,
+    seed: int = 0,
+    confidence: float = 0.95,
+    metrics: Optional[Sequence[str]] = None,
+) -> ExecutionJitterResultDict:
+    """
+    Execution‐assumption sensitivity.
+
+    Re‐runs the same signals `repetitions` times, each with the slippage
+    rate moved uniformly within ±`slippage_jitter_bps` basis points and
+    entries/exits filled 0‥`max_delay_bars` bars late.  Every repetition
+    draws from its own seed (reported with the drawn values, so any run can
+    be reproduced with `run_backtest`).  Returns the unjittered baseline,
+    the runs and each metric's distribution with a `confidence` interval.
+    """
+
+def to_duckdb(
+    result: Dict[str, Any],
+    database: Optional[str] = None,
+    connection: Optional[Any] = None,
+    prefix: str = "",
+) -> Any:
+    """
+    Load a `run_backtest` result into DuckDB tables for SQL analysis.
+
+    Writes `trades` (closed positions), `open_positions`, `exposure` and
+    `trade_equity` (each name prefixed by `prefix`, replaced if present)
+    into `connection`, or into a new connection on `database` (a `.duckdb`
+    file; in‐memory when omitted).  Empty lists create no table.  Requires
+    the `duckdb` Python package; returns the connection.
+    """
+
+def load_config(path: str) -> LoadConfigResultDict:
+    """
+    Parse and validate an experiment file without running it.
+
+    Returns `{"run": run_backtest kwargs, "data": {"bars", "signals",
+    "delimiter"}, "grid": {axis: values}, "grid_options": {...}}`.
+    """
+
+def run_config(path: str) -> Dict[str, Any]:
+    """
+    Run the experiment an experiment file describes.
+
+    Loads the bar (and optional signal) CSVs, calls `run_backtest` with the
+    configured settings and, when the file has a `[grid]`, adds
+    `out["grid_search"]` from `grid_search` over the same inputs.
+    """
+
+def embargoed_split(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    n_segments: int = 5,
+    test_segments: Optional[Sequence[int]] = None,
+    embargo_bars: int = 0,
+    evaluate: str = "test",
+    metrics: Optional[Sequence[str]] = None,
+) -> EmbargoedSplitResultDict:
+    """
+    Embargoed train/test split.
+
+    Cuts the bars into `n_segments` contiguous blocks, marks `test_segments`
+    (default: the last block) as test and the rest as train, and drops
+    `embargo_bars` bars on each side of every train/test boundary so trades
+    near one block can't leak into the other.  Each block of the `evaluate`
+    role ("test", "train" or "all") is backtested on its own bars; positions
+    still open expire at the end of the block's tradable bars.
+    """
+
+def cpcv(
+    timestamp: npt.NDArray[np.float64],
+    open: npt.NDArray[np.float64],
+    high: npt.NDArray[np.float64],
+    low: npt.NDArray[np.float64],
+    close: npt.NDArray[np.float64],
+    long_signals: npt.NDArray[np.bool_],
+    short_signals: npt.NDArray[np.bool_],
+    long_tp: npt.NDArray[np.float64],
+    long_sl: npt.NDArray[np.float64],
+    short_tp: npt.NDArray[np.float64],
+    short_sl: npt.NDArray[np.float64],
+    long_size: npt.NDArray[np.float64],
+    short_size: npt.NDArray[np.float64],
+    expiration_times: npt.NDArray[np.float64],
+    entry_fee_rate: float,
+    exit_fee_rate: float,
+    slippage_rate: float,
+    initial_equity: float,
+    n_groups: int = 6,
+    n_test_groups: int = 2,
+    embargo_bars: int = 0,
+    confidence: float = 0.95,
+) -> CpcvResultDict:
+    """
+    Combinatorial purged cross‐validation.
+
+    Cuts the bars into `n_groups` contiguous groups and forms every split
+    with `n_test_groups` of them as test (the rest train), purging
+    `embargo_bars` on each side of every train/test boundary.  Each split's
+    test groups are backtested on their own bars, and the group results are
+    stitched into C(n_groups−1, n_test_groups−1) full‐length out‐of‐sample
+    paths; per‐path Sharpe (per bar, on account equity returns), max
+    drawdown and total return are reported with their distributions.
+    """
+
+def generate_gbm(
+    n_bars: int,
+    mu: float = 0.0,
+    sigma: float = 0.01,
+    start_price: float = 100.0,
+    start_time: float = 0.0,
+    bar_seconds: float = 60.0,
+    steps_per_bar: int = 10,
+    seed: int = 0,
+) -> MarketDict:
+    """GBM bars as `{"timestamp", "open", "high", "low", "close"}` arrays"""
+
+def generate_ou(
+    n_bars: int,
+    theta: float = 0.05,
+    sigma: float = 0.01,
+    mean: Optional[float] = None,
+    start_price: float = 100.0,
+    start_time: float = 0.0,
+    bar_seconds: float = 60.0,
+    steps_per_bar: int = 10,
+    seed: int = 0,
+) -> MarketDict:
+    """Mean‐reverting (log‐OU) bars; `mean` defaults to `start_price`"""
+
+def generate_regime_switching(
+    n_bars: int,
+    regimes: Sequence[Tuple[float, float]],
+    transition: Optional[Sequence[Sequence[float]]] = None,
+    switch_probability: float = 0.01,
+    start_price: float = 100.0,
+    start_time: float = 0.0,
+    bar_seconds: float = 60.0,
+    steps_per_bar: int = 10,
+    seed: int = 0,
+) -> GenerateRegimeSwitchingResultDict:
+    """
+    Regime‐switching GBM; also returns the per‐bar `regime` index.
+
+    `regimes` is a list of `(mu, sigma)` pairs.  Without a `transition`
+    matrix, each bar leaves its regime with `switch_probability`, moving to
+    one of the others uniformly.
+    """
+
+class BacktestSessionTrialDict(TypedDict):
+    params: Dict[str, float]
+    metrics: Dict[str, Any]
+
+class BacktestSessionBestTrialDict(TypedDict):
+    params: Dict[str, float]
+    metrics: Dict[str, Any]
+
+class OpenPositionFillDict(TypedDict):
+    bar: int
+    size: float
+    price: float
+    fee: float
+
+class OpenPositionDict(TypedDict):
+    position_id: int
+    entry_time: float
+    position_type: str
+    entry_index: int
+    entry_price: float
+    tp: float
+    sl: float
+    expiration_time: Optional[float]
+    position_size: float
+    fee_entry: float
+    slippage_entry: float
+    entry_liquidity: str
+    is_closed: bool
+    expiry_action: NotRequired[Optional[str]]
+    mark_price: NotRequired[float]
+    unrealized_pnl: NotRequired[float]
+    unrealized_return: NotRequired[float]
+    bars_held: NotRequired[int]
+    roll_count: NotRequired[int]
+    roll_cost: NotRequired[float]
+    margin: NotRequired[float]
+    strategy_id: NotRequired[str]
+    symbol: NotRequired[str]
+    tag: NotRequired[str]
+    fills: NotRequired[List[OpenPositionFillDict]]
+    unfilled_size: NotRequired[float]
+    original_sl: NotRequired[float]
+    original_size: NotRequired[float]
+    throttled: NotRequired[bool]
+    tp_attempts: NotRequired[int]
+    tp_rejections: NotRequired[int]
+    entry_delay: NotRequired[Optional[float]]
+    exit_delay: NotRequired[Optional[float]]
+    latency_cost: NotRequired[float]
+
+class ClosedPositionFillDict(TypedDict):
+    bar: int
+    size: float
+    price: float
+    fee: float
+
+class ClosedPositionDict(TypedDict):
+    position_id: int
+    entry_time: float
+    position_type: str
+    entry_index: int
+    entry_price: float
+    tp: float
+    sl: float
+    expiration_time: Optional[float]
+    exit_index: Optional[int]
+    exit_price: Optional[float]
+    exit_condition: Optional[str]
+    exit_time: NotRequired[Optional[float]]
+    mfe: float
+    mae: float
+    entry_efficiency: NotRequired[float]
+    exit_efficiency: NotRequired[float]
+    total_efficiency: NotRequired[float]
+    max_runup: NotRequired[float]
+    giveback: NotRequired[float]
+    r_multiple: NotRequired[float]
+    expiry_slippage: NotRequired[Optional[float]]
+    expiry_action: NotRequired[Optional[str]]
+    position_size: float
+    fee_entry: float
+    slippage_entry: float
+    fee_exit: float
+    slippage_exit: float
+    entry_liquidity: str
+    exit_liquidity: Optional[str]
+    absolute_return: Optional[float]
+    real_return: Optional[float]
+    pnl: Optional[float]
+    is_closed: bool
+    gross_pnl: float
+    slippage_cost: float
+    funding_cost: NotRequired[float]
+    dividend_income: NotRequired[float]
+    roll_count: NotRequired[int]
+    roll_cost: NotRequired[float]
+    margin: NotRequired[float]
+    strategy_id: NotRequired[str]
+    symbol: NotRequired[str]
+    tag: NotRequired[str]
+    fills: NotRequired[List[ClosedPositionFillDict]]
+    unfilled_size: NotRequired[float]
+    original_sl: NotRequired[float]
+    original_size: NotRequired[float]
+    throttled: NotRequired[bool]
+    tp_attempts: NotRequired[int]
+    tp_rejections: NotRequired[int]
+    entry_delay: NotRequired[Optional[float]]
+    exit_delay: NotRequired[Optional[float]]
+    latency_cost: NotRequired[float]
+
+class SnapshotDict(TypedDict):
+    timestamp: float
+    long_exposure: float
+    short_exposure: float
+    total_exposure: float
+    realized_equity: float
+    floating_pnl: float
+    total_equity: float
+    open_longs: int
+    open_shorts: int
+    avg_open_age: float
+    cash: float
+    margin_used: float
+    free_margin: float
+    long_notional: float
+    short_notional: float
+    total_notional: float
+    long_pct_of_equity: float
+    short_pct_of_equity: float
+    total_pct_of_equity: float
+    long_avg_entry: float
+    short_avg_entry: float
+
+class CostsDict(TypedDict):
+    gross_pnl: float
+    fees: float
+    rebates: float
+    slippage: float
+    funding: float
+    rolls: float
+    latency: float
+    dividends: float
+    net_pnl: float
+
+class TradeMetricsDict(TypedDict):
+    number_of_trades: int
+    win_rate: float
+    loss_rate: float
+    average_trade_return: float
+    average_trade_pnl: float
+    profit_factor: Optional[float]
+    expectancy: float
+    average_duration: float
+    average_entry_efficiency: float
+    average_exit_efficiency: float
+    average_total_efficiency: float
+    average_max_runup: float
+    average_giveback: float
+    profit_to_loss_rate: float
+    trade_returns: List[float]
+    trade_pnls: List[float]
+    durations: List[float]
+
+class DistributionDict(TypedDict):
+    values: List[float]
+    mean: float
+    median: float
+    ci_lower: float
+    ci_upper: float
+
+class BootstrapDict(TypedDict):
+    n_samples: int
+    block_length: int
+    seed: int
+    confidence: float
+    sharpe_ratio: DistributionDict
+    cagr: DistributionDict
+    max_drawdown: DistributionDict
+
+class TimeMetricsRunsTestDict(TypedDict):
+    runs: int
+    expected_runs: float
+    z_score: float
+    p_value: float
+
+class TimeMetricsHighWaterDict(TypedDict):
+    new_highs: int
+    average_bars_between_highs: float
+    longest_without_high: int
+    bars_since_high: int
+
+class TimeMetricsDict(TypedDict):
+    returns: List[float]
+    mean_return: float
+    volatility: float
+    sharpe_ratio: Optional[float]
+    cumulative_return: float
+    max_drawdown: float
+    skewness: float
+    kurtosis: float
+    probabilistic_sharpe: float
+    bootstrap: NotRequired[BootstrapDict]
+    autocorrelation: Dict[int, float]
+    runs_test: TimeMetricsRunsTestDict
+    high_water: TimeMetricsHighWaterDict
+
+class SideMetricsDict(TypedDict):
+    total_return: float
+    total_pnl: float
+    costs: CostsDict
+    trade_metrics: TradeMetricsDict
+    time_metrics: TimeMetricsDict
+
+class ConcurrencyDict(TypedDict):
+    max_concurrent: int
+    mean_concurrent: float
+    distribution: Dict[int, int]
+    threshold: int
+    pnl_above_threshold: float
+    pnl_share_above_threshold: float
+    bar_share_above_threshold: float
+
+class SummaryMetricsReturnDefinitionDict(TypedDict):
+    equity_basis: str
+    return_type: str
+    return_equity: str
+
+class SummaryMetricsByTagEntryDict(TradeMetricsDict):
+    total_pnl: float
+
+class SummaryMetricsGrossVsNetDict(TypedDict):
+    gross: Dict[Any, Optional[float]]
+    net: Dict[Any, Optional[float]]
+    cost_drag: Dict[Any, Optional[float]]
+
+class SummaryMetricsByRegimeEntryDict(TypedDict):
+    bars: int
+    bar_share: float
+    pnl: float
+    mean_return: float
+    volatility: float
+    sharpe_ratio: Optional[float]
+    trades: TradeMetricsDict
+
+class SummaryMetricsBenchmarkDict(TypedDict):
+    benchmark_return: float
+    strategy_return: float
+    excess_return: float
+    up_capture: Optional[float]
+    down_capture: Optional[float]
+    up_bars: int
+    down_bars: int
+    max_relative_drawdown: float
+    max_underwater_gap: float
+
+class SummaryMetricsFactorBetasEntryDict(TypedDict):
+    beta: float
+    rolling_beta: npt.NDArray[np.float64]
+
+class SummaryMetricsByDurationItemDict(TypedDict):
+    label: str
+    min_secs: float
+    max_secs: float
+    number_of_trades: int
+    win_rate: float
+    average_pnl: float
+    average_r: float
+    r_trades: int
+
+class SummaryMetricsWeightedReturnsDict(TypedDict):
+    time_weighted_return: float
+    time_weighted_return_annualized: float
+    money_weighted_return: float
+
+class SummaryMetricsCashFlowsDict(TypedDict):
+    deposits: float
+    withdrawals: float
+    net_flows: float
+    final_account_value: float
+    trading_pnl: float
+
+class SummaryMetricsExecutionDict(TypedDict):
+    maker_fills: int
+    taker_fills: int
+    maker_share: float
+    maker_fees: float
+    taker_fees: float
+
+class SummaryMetricsDict(TypedDict):
+    overall: SideMetricsDict
+    long: SideMetricsDict
+    short: SideMetricsDict
+    return_definition: SummaryMetricsReturnDefinitionDict
+    by_tag: NotRequired[Dict[str, SummaryMetricsByTagEntryDict]]
+    gross_vs_net: NotRequired[SummaryMetricsGrossVsNetDict]
+    by_regime: NotRequired[Dict[str, SummaryMetricsByRegimeEntryDict]]
+    benchmark: NotRequired[SummaryMetricsBenchmarkDict]
+    factor_betas: NotRequired[Dict[str, SummaryMetricsFactorBetasEntryDict]]
+    by_duration: NotRequired[List[SummaryMetricsByDurationItemDict]]
+    concurrency: NotRequired[ConcurrencyDict]
+    weighted_returns: NotRequired[SummaryMetricsWeightedReturnsDict]
+    cash_flows: NotRequired[SummaryMetricsCashFlowsDict]
+    execution: SummaryMetricsExecutionDict
+
+class SkippedEntryDict(TypedDict):
+    signal_index: int
+    entry_index: int
+    position_type: str
+    reason: str
+
+class BacktestResultResampledEntryDict(TypedDict):
+    timestamps: List[float]
+    equity: List[float]
+    returns: List[float]
+    periods_per_year: float
+    annualized_return: float
+    annualized_volatility: float
+    sharpe_ratio: Optional[float]
+
+class BacktestResultTradeEquityCurveItemDict(TypedDict):
+    exit_index: int
+    timestamp: float
+    position_id: int
+    pnl: float
+    equity: float
+
+class BacktestResultDict(TypedDict):
+    schema_version: int
+    closed_positions: List[ClosedPositionDict]
+    open_positions: List[OpenPositionDict]
+    exposure_time_series: List[SnapshotDict]
+    timestamps: npt.NDArray[np.float64]
+    equity: npt.NDArray[np.float64]
+    drawdown: npt.NDArray[np.float64]
+    bar_returns: npt.NDArray[np.float64]
+    account_value: NotRequired[npt.NDArray[np.float64]]
+    warnings: List[str]
+    invariant_violations: NotRequired[List[str]]
+    resampled: Dict[str, BacktestResultResampledEntryDict]
+    trade_equity_curve: List[BacktestResultTradeEquityCurveItemDict]
+    metrics: SummaryMetricsDict
+    skipped_entries: NotRequired[List[SkippedEntryDict]]
+
+class BacktestResultCompareEntryDict(TypedDict):
+    self: Any
+    other: Any
+    delta: Any
+
+class BreachDict(TypedDict):
+    bar: int
+    timestamp: float
+    equity: float
+    high_water_mark: float
+
+class OrderDict(TypedDict):
+    order_id: int
+    position_id: int
+    timestamp: float
+    side: str
+    order_type: str
+    price: Optional[float]
+    limit_price: Optional[float]
+    amendments: int
+    size: float
+    reduce_only: bool
+    purpose: str
+    status: str
+    update_time: Optional[float]
+    fill_price: Optional[float]
+
+class TimelineEntryItemDict(TypedDict):
+    position_id: NotRequired[int]
+    event: str
+    bar: int
+    timestamp: float
+    price: Optional[float]
+    size: Optional[float]
+    tp: Optional[float]
+    sl: Optional[float]
+    condition: Optional[str]
+
+class RunInfoInputsEntryDict(TypedDict):
+    length: int
+    hash: str
+
+class RunInfoDict(TypedDict):
+    version: str
+    params: Dict[str, Any]
+    inputs: Dict[str, RunInfoInputsEntryDict]
+    threads: int
+    seeds: Dict[str, int]
+
+class RunBacktestResultDict(BacktestResultDict):
+    drawdown_breach: NotRequired[Optional[BreachDict]]
+    orders: NotRequired[List[OrderDict]]
+    near_liquidation: NotRequired[npt.NDArray[np.bool_]]
+    timeline: NotRequired[Union[Dict[int, List[TimelineEntryItemDict]], List[TimelineEntryItemDict]]]
+    journal_run_id: NotRequired[int]
+    run_info: RunInfoDict
+
+class GridSearchResultItemDict(TypedDict):
+    rank: int
+    params: Dict[str, float]
+    metrics: Dict[str, float]
+    probabilistic_sharpe: float
+    deflated_sharpe: float
+    result: NotRequired[BacktestResultDict]
+
+class SensitivitySurfaceResultBestDict(TypedDict):
+    x: float
+    y: Optional[float]
+    value: float
+    neighbourhood_mean: float
+
+class SensitivitySurfaceResultDict(TypedDict):
+    metric: str
+    x_param: str
+    x_values: List[float]
+    y_param: Optional[str]
+    y_values: Optional[List[float]]
+    surface: List[List[float]]
+    best: SensitivitySurfaceResultBestDict
+
+class CompareExitPoliciesResultItemPolicyDict(TypedDict):
+    tp_mult: Any
+    sl_mult: Any
+
+class CompareExitPoliciesResultItemDict(TypedDict):
+    rank: Any
+    policy: CompareExitPoliciesResultItemPolicyDict
+    metrics: Dict[str, float]
+    result: NotRequired[BacktestResultDict]
+
+class SummaryDict(TypedDict):
+    bars: int
+    number_of_trades: int
+    open_positions: int
+    skipped_entries: int
+    win_rate: float
+    profit_factor: float
+    total_pnl: float
+    final_equity: float
+    mean_return: float
+    volatility: float
+    sharpe_ratio: float
+    cumulative_return: float
+    max_drawdown: float
+    peak_positions_in_memory: int
+
+class StreamBacktestResultDict(TypedDict):
+    metrics: SummaryDict
+    trades_path: str
+    exposure_path: str
+
+class ScanEntriesResultDict(TypedDict):
+    positions: List[Union[ClosedPositionDict, OpenPositionDict]]
+    skipped_entries: List[SkippedEntryDict]
+
+class StatsDict(TypedDict):
+    total_return: Any
+    mean_return: Any
+    volatility: Any
+    sharpe_ratio: Optional[float]
+    max_drawdown: Any
+
+class MergeEquityCurvesResultCombinedDict(StatsDict):
+    equity: npt.NDArray[np.float64]
+
+class MergeEquityCurvesResultDict(TypedDict):
+    timestamps: npt.NDArray[np.float64]
+    names: List[str]
+    curves: Dict[str, npt.NDArray[np.float64]]
+    weights: Dict[str, Any]
+    stats: Dict[str, StatsDict]
+    combined: MergeEquityCurvesResultCombinedDict
+    correlation: Any
+    diversification_ratio: Optional[float]
+
+class MonteCarloTradesResultObservedDict(TypedDict):
+    final_equity: float
+    max_drawdown: float
+    max_drawdown_duration: float
+
+class MonteCarloTradesResultDict(TypedDict):
+    n_simulations: int
+    n_trades: int
+    method: str
+    seed: int
+    confidence: float
+    final_equity: DistributionDict
+    max_drawdown: DistributionDict
+    max_drawdown_duration: DistributionDict
+    observed: MonteCarloTradesResultObservedDict
+
+class ComparisonsEntryDict(TypedDict):
+    observed: float
+    p_value: float
+    percentile: float
+    null: DistributionDict
+
+class PermutationTestResultDict(TypedDict):
+    n_permutations: int
+    seed: int
+    metrics: Dict[str, ComparisonsEntryDict]
+
+class RandomEntryBaselineResultDict(TypedDict):
+    n_runs: int
+    seed: int
+    n_entries: int
+    long_fraction: Any
+    metrics: Dict[str, ComparisonsEntryDict]
+
+class RunPortfolioBacktestResultRejectedEntryDict(TypedDict):
+    strategy_id: str
+    entry_index: int
+    position_type: str
+    reason: str
+
+class RunPortfolioBacktestResultStrategiesEntryDict(TypedDict):
+    number_of_trades: int
+    rejected_entries: int
+    total_pnl: float
+    sharpe_ratio: float
+    max_drawdown: float
+    drawdown_pnl: float
+    drawdown_contribution: float
+    pnl_curve: List[float]
+
+class RunPortfolioBacktestResultSymbolsEntryDict(TypedDict):
+    metrics: SummaryMetricsDict
+    number_of_trades: int
+    total_pnl: float
+    pnl_share: float
+    exposure_share: float
+    trade_share: float
+    pnl_curve: List[float]
+
+class RunPortfolioBacktestResultAccountsEntryDict(TypedDict):
+    initial_equity: float
+    strategies: List[str]
+    metrics: SummaryMetricsDict
+    number_of_trades: int
+    rejected_entries: int
+    total_pnl: float
+    pnl_share: float
+    pnl_curve: List[float]
+
+class RunPortfolioBacktestResultCorrelationEntryDict(TypedDict):
+    labels: Any
+    matrix: Any
+
+class RunPortfolioBacktestResultDict(BacktestResultDict):
+    rejected_entries: List[RunPortfolioBacktestResultRejectedEntryDict]
+    strategies: Dict[str, RunPortfolioBacktestResultStrategiesEntryDict]
+    symbols: Dict[str, RunPortfolioBacktestResultSymbolsEntryDict]
+    accounts: Dict[str, RunPortfolioBacktestResultAccountsEntryDict]
+    correlation: Dict[Any, RunPortfolioBacktestResultCorrelationEntryDict]
+
+class PairTradeDict(TypedDict):
+    position_id: int
+    entry_time: float
+    position_type: str
+    signal_index: int
+    entry_index: int
+    hedge_ratio: float
+    entry_price_a: float
+    entry_price_b: float
+    entry_spread: float
+    tp: float
+    sl: float
+    expiration_time: Optional[float]
+    exit_index: Optional[int]
+    exit_price_a: Optional[float]
+    exit_price_b: Optional[float]
+    exit_spread: Optional[float]
+    exit_condition: Optional[str]
+    position_size: float
+    fee_entry_a: float
+    fee_entry_b: float
+    fee_exit_a: float
+    fee_exit_b: float
+    pnl_a: Optional[float]
+    pnl_b: Optional[float]
+    pnl: Optional[float]
+    is_closed: bool
+
+class RunPairBacktestResultDict(TypedDict):
+    closed_positions: List[PairTradeDict]
+    open_positions: List[PairTradeDict]
+    exposure_time_series: List[SnapshotDict]
+    metrics: SummaryMetricsDict
+
+class StressTestResultScenarioDict(TypedDict):
+    name: str
+    metrics: Any
+    deltas: Any
+
+class StressTestResultDict(TypedDict):
+    baseline: Any
+    scenarios: List[StressTestResultScenarioDict]
+
+class ExecutionJitterResultRunDict(TypedDict):
+    seed: Any
+    slippage_rate: Any
+    delay_bars: Any
+    metrics: Any
+
+class ExecutionJitterResultDict(TypedDict):
+    baseline: Any
+    runs: List[ExecutionJitterResultRunDict]
+    distributions: Dict[str, DistributionDict]
+
+class LoadConfigResultDataDict(TypedDict):
+    bars: Any
+    signals: Optional[Any]
+    delimiter: str
+
+class LoadConfigResultDict(TypedDict):
+    run: Dict[str, Any]
+    data: LoadConfigResultDataDict
+    grid: Dict[Any, Any]
+    grid_options: Dict[str, Any]
+
+class EmbargoedSplitResultSegmentDict(TypedDict):
+    index: int
+    role: str
+    start_time: float
+    end_time: float
+    bars: int
+    trade_bars: int
+    metrics: NotRequired[Optional[Dict[str, List[float]]]]
+
+class EmbargoedSplitResultDict(TypedDict):
+    n_segments: int
+    test_segments: List[int]
+    embargo_bars: int
+    segments: List[EmbargoedSplitResultSegmentDict]
+
+class CpcvResultPathDict(TypedDict):
+    splits: List[int]
+    sharpe_ratio: float
+    max_drawdown: float
+    total_return: float
+
+class CpcvResultDict(TypedDict):
+    n_groups: int
+    n_test_groups: int
+    embargo_bars: int
+    splits: List[List[int]]
+    paths: List[CpcvResultPathDict]
+    sharpe_ratio: DistributionDict
+    max_drawdown: DistributionDict
+    total_return: DistributionDict
+
+class MarketDict(TypedDict):
+    timestamp: npt.NDArray[np.float64]
+    open: npt.NDArray[np.float64]
+    high: npt.NDArray[np.float64]
+    low: npt.NDArray[np.float64]
+    close: npt.NDArray[np.float64]
+
+class GenerateRegimeSwitchingResultDict(MarketDict):
+    regime: npt.NDArray[np.uintp]
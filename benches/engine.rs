@@ -0,0 +1,29 @@
+// benches/engine.rs
+
+//! `cargo bench --no-default-features --features bench [-- BARS...]`
+//!
+//! Times the engine stages on seeded synthetic workloads (default 10k,
+//! 100k and 1M bars) and prints one row per stage and size.
+
+use backtester::bench::{suite, Workload, WorkloadSpec};
+
+fn main() {
+    let sizes: Vec<usize> = std::env::args()
+        .skip(1)
+        .filter_map(|a| a.parse().ok())
+        .collect();
+    let sizes = if sizes.is_empty() { vec![10_000, 100_000, 1_000_000] } else { sizes };
+
+    println!("{:>10} {:>8} {:<16} {:>12} {:>12} {:>14}", "bars", "signals", "stage", "min", "median", "bars/s");
+    for bars in sizes {
+        let spec = WorkloadSpec { bars, ..WorkloadSpec::default() };
+        let signals = Workload::new(spec).signal_count();
+        let iterations = (2_000_000 / bars).clamp(3, 50);
+        for t in suite(spec, iterations) {
+            println!(
+                "{:>10} {:>8} {:<16} {:>12.3?} {:>12.3?} {:>14.0}",
+                bars, signals, t.name, t.min, t.median, t.bars_per_second()
+            );
+        }
+    }
+}
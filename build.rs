@@ -0,0 +1,33 @@
+// build.rs
+
+//! Generates `backtester.pyi`, the type stubs maturin ships next to the
+//! extension (with a `py.typed` marker), into `OUT_DIR` whenever the
+//! sources change.  The checked‐in copy is only compared against it: run
+//! `cargo run --bin backtester-stubs` to refresh that.
+
+// same allowance as src/lib.rs for the stub writer's signature helpers
+#![allow(clippy::too_many_arguments)]
+
+use std::{env, fs, path::PathBuf};
+
+#[path = "build/stubs.rs"]
+mod stubs;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=build");
+    println!("cargo:rerun-if-changed=backtester.pyi");
+    if env::var_os("CARGO_FEATURE_PYTHON").is_none() {
+        return;
+    }
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let out = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR")).join("backtester.pyi");
+    let text = stubs::generate(&root.join("src")).unwrap_or_else(|e| {
+        println!("cargo:warning=type stubs not generated: {}", e);
+        String::new()
+    });
+    fs::write(&out, &text).expect("write OUT_DIR/backtester.pyi");
+    if !text.is_empty() && fs::read_to_string(root.join("backtester.pyi")).ok().as_deref() != Some(text.as_str()) {
+        println!("cargo:warning=backtester.pyi is out of date; run `cargo run --bin backtester-stubs` to regenerate it");
+    }
+}
@@ -0,0 +1,1285 @@
+// build/stubs.rs
+
+//! Python type stubs from the Rust sources.
+//!
+//! Signatures come from each registered `#[pyfunction]` / `#[pymethods]`
+//! item: its `#[pyo3(signature = …)]` defaults and its Rust parameter
+//! types.  Result shapes come from following the dict‐building code: every
+//! `set_item("key", value)` on a `PyDict` becomes a `TypedDict` field (kept
+//! `NotRequired` when set conditionally), `append` types a list, and values
+//! are typed from the struct fields, methods and helpers they are read
+//! from.  Anything that cannot be followed is typed `Any`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use quote::ToTokens;
+use syn::{
+    parse_quote, Attribute, Block, Expr, FnArg, ImplItem, Item, Lit, Pat, ReturnType, Signature, Stmt, Type,
+};
+
+/// Python type of an argument, field or return value
+#[derive(Clone, PartialEq, Debug)]
+enum Py {
+    Any,
+    None,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Array(&'static str),
+    List(Box<Py>),
+    Seq(Box<Py>),
+    Dict(Box<Py>, Box<Py>),
+    Tuple(Vec<Py>),
+    Optional(Box<Py>),
+    Union(Vec<Py>),
+    Named(String),
+    /// A dict / list under construction in the function being followed
+    Node(usize),
+}
+
+impl Py {
+    fn render(&self) -> String {
+        match self {
+            Py::Any      => "Any".into(),
+            Py::None     => "None".into(),
+            Py::Bool     => "bool".into(),
+            Py::Int      => "int".into(),
+            Py::Float    => "float".into(),
+            Py::Str      => "str".into(),
+            Py::Array(d) => format!("npt.NDArray[np.{}]", d),
+            Py::List(t)  => format!("List[{}]", t.render()),
+            Py::Seq(t)   => format!("Sequence[{}]", t.render()),
+            Py::Dict(k, v) => format!("Dict[{}, {}]", k.render(), v.render()),
+            Py::Tuple(ts) if ts.is_empty() => "Tuple[()]".into(),
+            Py::Tuple(ts) => format!("Tuple[{}]", ts.iter().map(Py::render).collect::<Vec<_>>().join(", ")),
+            Py::Optional(t) => format!("Optional[{}]", t.render()),
+            Py::Union(ts) => format!("Union[{}]", ts.iter().map(Py::render).collect::<Vec<_>>().join(", ")),
+            Py::Named(n) => n.clone(),
+            Py::Node(_)  => "Any".into(),
+        }
+    }
+
+    /// Either of two types
+    fn or(self, other: Py) -> Py {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Py::Any, _) | (_, Py::Any) => Py::Any,
+            (Py::None, t) | (t, Py::None) => match t {
+                Py::Optional(_) => t,
+                t => Py::Optional(Box::new(t)),
+            },
+            (Py::Optional(a), b) | (b, Py::Optional(a)) => Py::Optional(Box::new(a.or(b))),
+            (Py::Int, Py::Float) | (Py::Float, Py::Int) => Py::Float,
+            (Py::Union(mut ts), b) => {
+                if !ts.contains(&b) {
+                    ts.push(b);
+                }
+                Py::Union(ts)
+            }
+            (a, b) => Py::Union(vec![a, b]),
+        }
+    }
+}
+
+/// A dict or list being built by a followed function
+enum Shape {
+    Dict {
+        base:    Option<String>,
+        /// (key, type, required)
+        keys:    Vec<(String, Py, bool)>,
+        /// Key and value types of non‐literal keys
+        dynamic: Option<(Py, Py)>,
+        depth:   usize,
+    },
+    List(Option<Py>),
+}
+
+/// What a local variable holds
+#[derive(Clone)]
+enum Bind {
+    Rust(Box<Type>),
+    Py(Py),
+}
+
+/// Items of every source file
+#[derive(Default)]
+struct Index {
+    files:     Vec<syn::File>,
+    structs:   HashMap<String, Vec<(String, Type)>>,
+    /// (type, method) → signature
+    methods:   HashMap<(String, String), Signature>,
+    /// name → (file, signature, body)
+    fns:       HashMap<String, Vec<(usize, Signature, Block)>>,
+    consts:    HashMap<String, Type>,
+    /// Rust struct name → Python class name
+    pyclasses: HashMap<String, String>,
+}
+
+impl Index {
+    fn load(dir: &Path, index: &mut Index) -> Result<(), String> {
+        let mut entries: Vec<_> = fs::read_dir(dir).map_err(|e| e.to_string())?.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                Index::load(&path, index)?;
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                let text = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+                let file = syn::parse_file(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+                index.add(file);
+            }
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, file: syn::File) {
+        let id = self.files.len();
+        for item in &file.items {
+            match item {
+                Item::Struct(s) => {
+                    let name = s.ident.to_string();
+                    if let Some(attr) = s.attrs.iter().find(|a| a.path.is_ident("pyclass")) {
+                        let py_name = attr_value(attr, "name").unwrap_or_else(|| name.clone());
+                        self.pyclasses.insert(name.clone(), py_name);
+                    }
+                    let fields = s.fields.iter()
+                        .filter_map(|f| Some((f.ident.as_ref()?.to_string(), f.ty.clone())))
+                        .collect();
+                    self.structs.insert(name, fields);
+                }
+                Item::Impl(imp) => {
+                    let Some(owner) = type_name(&imp.self_ty) else { continue };
+                    for it in &imp.items {
+                        if let ImplItem::Method(m) = it {
+                            let mut sig = m.sig.clone();
+                            sig.output = replace_self(&sig.output, &owner);
+                            self.methods.insert((owner.clone(), m.sig.ident.to_string()), sig);
+                        }
+                    }
+                }
+                Item::Fn(f) => {
+                    self.fns.entry(f.sig.ident.to_string()).or_default().push((id, f.sig.clone(), (*f.block).clone()));
+                }
+                Item::Const(c) => {
+                    self.consts.insert(c.ident.to_string(), (*c.ty).clone());
+                }
+                _ => {}
+            }
+        }
+        self.files.push(file);
+    }
+
+    /// Free function `name`, preferring the one in `file`
+    fn function(&self, file: usize, name: &str) -> Option<&(usize, Signature, Block)> {
+        let candidates = self.fns.get(name)?;
+        candidates.iter().find(|(f, _, _)| *f == file).or(if candidates.len() == 1 { candidates.first() } else { None })
+    }
+}
+
+/// `Self` in a return type spelled out as `owner`
+fn replace_self(output: &ReturnType, owner: &str) -> ReturnType {
+    let text = output.to_token_stream().to_string();
+    let replaced = text.split(' ').map(|t| if t == "Self" { owner } else { t }).collect::<Vec<_>>().join(" ");
+    syn::parse_str(&replaced).unwrap_or_else(|_| output.clone())
+}
+
+/// `key = "value"` inside an attribute's parentheses
+fn attr_value(attr: &Attribute, key: &str) -> Option<String> {
+    let text = attr.tokens.to_string();
+    let rest = &text[text.find(key)? + key.len()..];
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start().strip_prefix('"')?;
+    Some(rest[..rest.find('"')?].to_string())
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|a| a.path.is_ident(name))
+}
+
+/// Doc comment lines of an item
+fn doc(attrs: &[Attribute]) -> Vec<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path.is_ident("doc"))
+        .filter_map(|a| match a.parse_meta().ok()? {
+            syn::Meta::NameValue(nv) => match nv.lit {
+                Lit::Str(s) => Some(s.value().strip_prefix(' ').map(str::to_string).unwrap_or_else(|| s.value())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    lines
+}
+
+fn strip_refs(t: &Type) -> &Type {
+    match t {
+        Type::Reference(r) => strip_refs(&r.elem),
+        Type::Paren(p)     => strip_refs(&p.elem),
+        Type::Group(g)     => strip_refs(&g.elem),
+        t => t,
+    }
+}
+
+/// Last path segment and its type arguments
+fn segment(t: &Type) -> Option<(String, Vec<Type>)> {
+    let Type::Path(p) = strip_refs(t) else { return None };
+    let seg = p.path.segments.last()?;
+    let args = match &seg.arguments {
+        syn::PathArguments::AngleBracketed(a) => a.args.iter().filter_map(|g| match g {
+            syn::GenericArgument::Type(t) => Some(t.clone()),
+            _ => None,
+        }).collect(),
+        _ => Vec::new(),
+    };
+    Some((seg.ident.to_string(), args))
+}
+
+fn type_name(t: &Type) -> Option<String> {
+    segment(t).map(|(name, _)| name)
+}
+
+/// `T` of `Option<T>`, `Result<T, _>` and smart pointers
+fn unwrap_type(t: &Type, wrappers: &[&str]) -> Option<Type> {
+    let (name, args) = segment(t)?;
+    wrappers.contains(&name.as_str()).then(|| args.into_iter().next()).flatten()
+}
+
+/// Element type when iterating over `t`
+fn element(t: &Type) -> Option<Type> {
+    match strip_refs(t) {
+        Type::Slice(s) => Some((*s.elem).clone()),
+        Type::Array(a) => Some((*a.elem).clone()),
+        t => {
+            let (name, args) = segment(t)?;
+            match name.as_str() {
+                "Vec" | "VecDeque" | "Option" | "HashSet" | "BTreeSet" | "Box" => args.into_iter().next(),
+                "HashMap" | "BTreeMap" if args.len() == 2 => Some(Type::Tuple(parse_quote!((#(#args),*)))),
+                "Range" | "RangeInclusive" => args.into_iter().next().or(Some(parse_quote!(usize))),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn dtype(t: &Type) -> &'static str {
+    match type_name(t).as_deref() {
+        Some("f32")   => "float32",
+        Some("bool")  => "bool_",
+        Some("i64")   => "int64",
+        Some("i32")   => "int32",
+        Some("u64")   => "uint64",
+        Some("u32")   => "uint32",
+        Some("usize") => "uintp",
+        _             => "float64",
+    }
+}
+
+/// Camel case of a snake‐case name
+fn camel(name: &str) -> String {
+    name.split('_')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut c = w.chars();
+            c.next().map(|f| f.to_uppercase().chain(c).collect::<String>()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// One emitted `TypedDict`
+struct TypedDict {
+    name:   String,
+    base:   Option<String>,
+    fields: Vec<(String, String, bool)>,
+}
+
+/// Follows functions and collects the `TypedDict`s they build
+struct Gen<'a> {
+    index:      &'a Index,
+    dicts:      Vec<TypedDict>,
+    names:      HashSet<String>,
+    /// Result type of each followed function
+    followed:   HashMap<String, Py>,
+    in_progress: HashSet<String>,
+}
+
+/// State while following one function body
+struct Frame {
+    file:    usize,
+    scopes:  Vec<HashMap<String, Bind>>,
+    nodes:   Vec<Shape>,
+    /// Final type of each finished node
+    done:    HashMap<usize, Py>,
+    /// Conditional nesting (if / match / loop bodies)
+    depth:   usize,
+    self_ty: Option<String>,
+}
+
+impl Frame {
+    fn lookup(&self, name: &str) -> Option<&Bind> {
+        self.scopes.iter().rev().find_map(|s| s.get(name))
+    }
+
+    fn bind(&mut self, name: String, b: Bind) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, b);
+        }
+    }
+
+    /// Rebind `name` in the scope that defines it
+    fn rebind(&mut self, name: &str, b: Bind) {
+        if let Some(scope) = self.scopes.iter_mut().rev().find(|s| s.contains_key(name)) {
+            scope.insert(name.to_string(), b);
+        }
+    }
+
+    fn node(&mut self, shape: Shape) -> Py {
+        self.nodes.push(shape);
+        Py::Node(self.nodes.len() - 1)
+    }
+}
+
+/// Identifier of a plain variable expression (through `&`, `?`, parens)
+fn var_name(e: &Expr) -> Option<String> {
+    match e {
+        Expr::Path(p) if p.path.segments.len() == 1 => Some(p.path.segments[0].ident.to_string()),
+        Expr::Reference(r) => var_name(&r.expr),
+        Expr::Try(t)       => var_name(&t.expr),
+        Expr::Paren(p)     => var_name(&p.expr),
+        _ => None,
+    }
+}
+
+/// Last path segment of a call's function
+fn call_path(e: &Expr) -> Option<Vec<String>> {
+    match e {
+        Expr::Path(p) => Some(p.path.segments.iter().map(|s| s.ident.to_string()).collect()),
+        _ => None,
+    }
+}
+
+fn lit_type(l: &Lit) -> Py {
+    match l {
+        Lit::Str(_)   => Py::Str,
+        Lit::Int(_)   => Py::Int,
+        Lit::Float(_) => Py::Float,
+        Lit::Bool(_)  => Py::Bool,
+        _             => Py::Any,
+    }
+}
+
+fn lit_rust(l: &Lit) -> Option<Type> {
+    Some(match l {
+        Lit::Str(_)   => parse_quote!(String),
+        Lit::Int(i) if i.suffix().is_empty() => parse_quote!(i64),
+        Lit::Int(i)   => syn::parse_str(i.suffix()).ok()?,
+        Lit::Float(_) => parse_quote!(f64),
+        Lit::Bool(_)  => parse_quote!(bool),
+        _ => return None,
+    })
+}
+
+impl<'a> Gen<'a> {
+    fn new(index: &'a Index) -> Self {
+        Gen { index, dicts: Vec::new(), names: HashSet::new(), followed: HashMap::new(), in_progress: HashSet::new() }
+    }
+
+    /// Python type of a Rust type (`params`: as accepted in an argument)
+    fn rust_to_py(&self, t: &Type, params: bool) -> Py {
+        match strip_refs(t) {
+            Type::Tuple(tt) if tt.elems.is_empty() => Py::None,
+            Type::Tuple(tt) => Py::Tuple(tt.elems.iter().map(|e| self.rust_to_py(e, params)).collect()),
+            Type::Slice(s) => {
+                let inner = Box::new(self.rust_to_py(&s.elem, params));
+                if params { Py::Seq(inner) } else { Py::List(inner) }
+            }
+            t => {
+                let Some((name, args)) = segment(t) else { return Py::Any };
+                let arg = |i: usize| args.get(i).map(|a| self.rust_to_py(a, params)).unwrap_or(Py::Any);
+                match name.as_str() {
+                    "f64" | "f32" => Py::Float,
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+                    | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => Py::Int,
+                    "bool" => Py::Bool,
+                    "String" | "str" | "char" | "Cow" => Py::Str,
+                    "Option" => Py::Optional(Box::new(arg(0))),
+                    "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => {
+                        if params { Py::Seq(Box::new(arg(0))) } else { Py::List(Box::new(arg(0))) }
+                    }
+                    "HashMap" | "BTreeMap" => Py::Dict(Box::new(arg(0)), Box::new(arg(1))),
+                    "PyArray1" => Py::Array(args.first().map(dtype).unwrap_or("float64")),
+                    "PyDict" => Py::Dict(Box::new(Py::Str), Box::new(Py::Any)),
+                    "PyList" => Py::List(Box::new(Py::Any)),
+                    "PyResult" | "Result" | "Box" | "Rc" | "Arc" => arg(0),
+                    other => match self.index.pyclasses.get(other) {
+                        Some(py_name) => Py::Named(py_name.clone()),
+                        None => Py::Any,
+                    },
+                }
+            }
+        }
+    }
+
+    /// Rust type of an expression, when it can be told from the sources
+    fn rust_ty(&mut self, fr: &mut Frame, e: &Expr) -> Option<Type> {
+        match e {
+            Expr::Reference(r) => self.rust_ty(fr, &r.expr),
+            Expr::Paren(p)     => self.rust_ty(fr, &p.expr),
+            Expr::Try(t) => {
+                let inner = self.rust_ty(fr, &t.expr)?;
+                unwrap_type(&inner, &["PyResult", "Result", "Option"]).or(Some(inner))
+            }
+            Expr::Lit(l) => lit_rust(&l.lit),
+            Expr::Cast(c) => Some((*c.ty).clone()),
+            Expr::Path(p) => {
+                let name = p.path.segments.last()?.ident.to_string();
+                if p.path.segments.len() == 1 {
+                    if let Some(Bind::Rust(t)) = fr.lookup(&name) {
+                        return Some((**t).clone());
+                    }
+                }
+                self.index.consts.get(&name).cloned()
+            }
+            Expr::Field(f) => {
+                let base = self.rust_ty(fr, &f.base)?;
+                match &f.member {
+                    syn::Member::Named(ident) => {
+                        let owner = type_name(&unwrap_type(&base, &["Box", "Rc", "Arc"]).unwrap_or(base))?;
+                        let fields = self.index.structs.get(&owner)?;
+                        fields.iter().find(|(n, _)| n == &ident.to_string()).map(|(_, t)| t.clone())
+                    }
+                    syn::Member::Unnamed(i) => match strip_refs(&base) {
+                        Type::Tuple(tt) => tt.elems.iter().nth(i.index as usize).cloned(),
+                        _ => None,
+                    },
+                }
+            }
+            Expr::Index(i) => element(&self.rust_ty(fr, &i.expr)?),
+            Expr::Binary(b) => self.rust_ty(fr, &b.left),
+            Expr::Unary(u) => self.rust_ty(fr, &u.expr),
+            Expr::Call(c) => {
+                let path = call_path(&c.func)?;
+                match path.as_slice() {
+                    [f] if f == "Some" => {
+                        let inner = self.rust_ty(fr, c.args.first()?)?;
+                        Some(parse_quote!(Option<#inner>))
+                    }
+                    [f] => match &self.index.function(fr.file, f)?.1.output {
+                        ReturnType::Type(_, t) => Some((**t).clone()),
+                        ReturnType::Default => None,
+                    },
+                    [.., owner, f] => match &self.index.methods.get(&(owner.clone(), f.clone()))?.output {
+                        ReturnType::Type(_, t) => Some((**t).clone()),
+                        ReturnType::Default => None,
+                    },
+                    [] => None,
+                }
+            }
+            Expr::MethodCall(m) => {
+                let method = m.method.to_string();
+                if method == "allow_threads" {
+                    if let Some(Expr::Closure(c)) = m.args.first() {
+                        return self.rust_ty(fr, &c.body);
+                    }
+                }
+                let recv = self.rust_ty(fr, &m.receiver);
+                match method.as_str() {
+                    "iter" | "iter_mut" | "into_iter" | "clone" | "to_owned" | "as_ref" | "as_deref" | "copied" | "cloned" => recv,
+                    "unwrap" | "unwrap_or" | "unwrap_or_default" | "unwrap_or_else" | "expect" => {
+                        unwrap_type(&recv?, &["Option", "Result", "PyResult"])
+                    }
+                    "first" | "last" | "get" | "next" => {
+                        let elem = element(&recv?)?;
+                        Some(parse_quote!(Option<#elem>))
+                    }
+                    "len" | "count" => Some(parse_quote!(usize)),
+                    "to_string" | "name" => Some(parse_quote!(String)),
+                    "is_some" | "is_none" | "is_empty" | "contains" | "is_finite" | "is_nan" => Some(parse_quote!(bool)),
+                    "abs" | "sqrt" | "ln" | "exp" | "max" | "min" | "powi" | "powf" => recv,
+                    "sum" | "product" | "collect" => match &m.turbofish {
+                        Some(tf) => tf.args.iter().find_map(|a| match a {
+                            syn::GenericMethodArgument::Type(t) => Some(t.clone()),
+                            _ => None,
+                        }),
+                        None => None,
+                    },
+                    _ => {
+                        let owner = type_name(&recv?)?;
+                        match &self.index.methods.get(&(owner, method))?.output {
+                            ReturnType::Type(_, t) => Some((**t).clone()),
+                            ReturnType::Default => None,
+                        }
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Python type of a value handed to Python
+    fn py_ty(&mut self, fr: &mut Frame, e: &Expr) -> Py {
+        match e {
+            Expr::Reference(r) => self.py_ty(fr, &r.expr),
+            Expr::Paren(p)     => self.py_ty(fr, &p.expr),
+            Expr::Try(t)       => self.py_ty(fr, &t.expr),
+            Expr::Lit(l)       => lit_type(&l.lit),
+            Expr::Macro(m) if m.mac.path.is_ident("format") => Py::Str,
+            Expr::Tuple(t) => Py::Tuple(t.elems.iter().map(|x| self.py_ty(fr, x)).collect()),
+            Expr::Cast(c) => self.rust_to_py(&c.ty, false),
+            Expr::Unary(u) => match u.op {
+                syn::UnOp::Not(_) => Py::Bool,
+                _ => self.py_ty(fr, &u.expr),
+            },
+            Expr::Binary(b) => {
+                use syn::BinOp::*;
+                match b.op {
+                    Eq(_) | Ne(_) | Lt(_) | Le(_) | Gt(_) | Ge(_) | And(_) | Or(_) => Py::Bool,
+                    _ => {
+                        let (l, r) = (self.py_ty(fr, &b.left), self.py_ty(fr, &b.right));
+                        if l == Py::Float || r == Py::Float { Py::Float } else { l }
+                    }
+                }
+            }
+            Expr::Path(p) if p.path.is_ident("None") => Py::None,
+            Expr::Path(p) if p.path.segments.len() == 1 => {
+                let name = p.path.segments[0].ident.to_string();
+                match fr.lookup(&name).cloned() {
+                    Some(Bind::Py(t))   => t,
+                    Some(Bind::Rust(t)) => self.rust_to_py(&t, false),
+                    None => match self.index.consts.get(&name) {
+                        Some(t) => self.rust_to_py(t, false),
+                        None => Py::Any,
+                    },
+                }
+            }
+            Expr::If(i) => {
+                let then = self.block_ty(fr, &i.then_branch);
+                match &i.else_branch {
+                    Some((_, e)) => then.or(self.py_ty(fr, e)),
+                    None => then,
+                }
+            }
+            Expr::Match(m) => {
+                let mut out: Option<Py> = None;
+                for arm in &m.arms {
+                    let t = self.py_ty(fr, &arm.body);
+                    out = Some(match out { Some(o) => o.or(t), None => t });
+                }
+                out.unwrap_or(Py::Any)
+            }
+            Expr::Block(b) => self.block_ty(fr, &b.block),
+            Expr::Call(c) => {
+                let Some(path) = call_path(&c.func) else { return Py::Any };
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                match path.as_slice() {
+                    ["Some", ..] | ["Ok", ..] => c.args.first().map(|a| self.py_ty(fr, a)).unwrap_or(Py::Any),
+                    [.., "PyDict", "new"] => {
+                        let depth = fr.depth;
+                        fr.node(Shape::Dict { base: None, keys: Vec::new(), dynamic: None, depth })
+                    }
+                    [.., "PyList", "empty"] => fr.node(Shape::List(None)),
+                    [.., "PyList", "new"] => {
+                        let elem = c.args.iter().nth(1)
+                            .and_then(|a| self.rust_ty(fr, a))
+                            .and_then(|t| element(&t))
+                            .map(|t| self.rust_to_py(&t, false));
+                        fr.node(Shape::List(elem))
+                    }
+                    [.., "PyArray1", _] => {
+                        let elem = c.args.iter().nth(1).and_then(|a| self.rust_ty(fr, a)).and_then(|t| element(&t));
+                        Py::Array(elem.as_ref().map(dtype).unwrap_or("float64"))
+                    }
+                    [name] => self.call_ty(fr.file, name),
+                    _ => self.rust_ty(fr, e).map(|t| self.rust_to_py(&t, false)).unwrap_or(Py::Any),
+                }
+            }
+            Expr::MethodCall(m) => {
+                let method = m.method.to_string();
+                match method.as_str() {
+                    "into" | "to_object" | "into_py" | "clone" | "to_owned" | "transpose" | "as_ref" => self.py_ty(fr, &m.receiver),
+                    "None" => Py::None,
+                    "to_string" | "name" => Py::Str,
+                    "len" | "count" => Py::Int,
+                    "is_some" | "is_none" | "is_empty" | "contains" => Py::Bool,
+                    "map" => {
+                        // Option::map(|x| …) → Optional of the closure's value
+                        let Some(Expr::Closure(c)) = m.args.first() else { return Py::Any };
+                        let inner = self.rust_ty(fr, &m.receiver).and_then(|t| unwrap_type(&t, &["Option"]));
+                        fr.scopes.push(HashMap::new());
+                        if let (Some(Pat::Ident(p)), Some(t)) = (c.inputs.first(), inner) {
+                            fr.bind(p.ident.to_string(), Bind::Rust(Box::new(t)));
+                        }
+                        let t = self.py_ty(fr, &c.body);
+                        fr.scopes.pop();
+                        Py::Optional(Box::new(t))
+                    }
+                    _ => self.rust_ty(fr, e).map(|t| self.rust_to_py(&t, false)).unwrap_or(Py::Any),
+                }
+            }
+            _ => self.rust_ty(fr, e).map(|t| self.rust_to_py(&t, false)).unwrap_or(Py::Any),
+        }
+    }
+
+    /// Type of a block's tail expression (after following its statements)
+    fn block_ty(&mut self, fr: &mut Frame, b: &Block) -> Py {
+        fr.scopes.push(HashMap::new());
+        let mut out = Py::Any;
+        for (i, s) in b.stmts.iter().enumerate() {
+            match s {
+                Stmt::Expr(e) if i + 1 == b.stmts.len() => out = self.py_ty(fr, e),
+                s => self.stmt(fr, s),
+            }
+        }
+        fr.scopes.pop();
+        out
+    }
+
+    /// Result of calling free function `name` from `file`
+    fn call_ty(&mut self, file: usize, name: &str) -> Py {
+        let Some((f, sig, body)) = self.index.function(file, name).cloned() else { return Py::Any };
+        let output = match &sig.output {
+            ReturnType::Type(_, t) => (**t).clone(),
+            ReturnType::Default => return Py::None,
+        };
+        let text = output.to_token_stream().to_string();
+        if text.contains("PyDict") || text.contains("PyList") || text.contains("PyObject") {
+            let stem = match name.strip_suffix("_to_py") {
+                Some("result") => "BacktestResult".to_string(),
+                Some(stem) => camel(stem),
+                None => camel(name),
+            };
+            self.follow(f, &sig, &body, None, &stem)
+        } else {
+            self.rust_to_py(&output, false)
+        }
+    }
+
+    /// Type of the value a function returns to Python, naming the dicts it
+    /// builds after `stem`
+    fn follow(&mut self, file: usize, sig: &Signature, body: &Block, self_ty: Option<&str>, stem: &str) -> Py {
+        let key = format!("{}::{}::{}", file, self_ty.unwrap_or(""), sig.ident);
+        if let Some(t) = self.followed.get(&key) {
+            return t.clone();
+        }
+        if !self.in_progress.insert(key.clone()) {
+            return Py::Any;
+        }
+        let mut fr = Frame { file, scopes: vec![HashMap::new()], nodes: Vec::new(), done: HashMap::new(), depth: 0, self_ty: self_ty.map(str::to_string) };
+        for arg in &sig.inputs {
+            match arg {
+                FnArg::Typed(pt) => {
+                    if let Pat::Ident(p) = &*pt.pat {
+                        fr.bind(p.ident.to_string(), Bind::Rust(pt.ty.clone()));
+                    }
+                }
+                FnArg::Receiver(_) => {
+                    if let Some(owner) = &fr.self_ty {
+                        let ident = syn::Ident::new(owner, proc_macro2::Span::call_site());
+                        fr.bind("self".into(), Bind::Rust(Box::new(parse_quote!(#ident))));
+                    }
+                }
+            }
+        }
+        let value = self.block_ty(&mut fr, body);
+        let value = match value {
+            Py::Any => match &sig.output {
+                ReturnType::Type(_, t) => self.rust_to_py(t, false),
+                ReturnType::Default => Py::None,
+            },
+            v => v,
+        };
+        let out = self.finish(&mut fr, value, stem);
+        self.in_progress.remove(&key);
+        self.followed.insert(key, out.clone());
+        out
+    }
+
+    fn stmt(&mut self, fr: &mut Frame, s: &Stmt) {
+        match s {
+            Stmt::Local(l) => {
+                let (pat, ty) = match &l.pat {
+                    Pat::Type(pt) => (&*pt.pat, Some((*pt.ty).clone())),
+                    p => (p, None),
+                };
+                let init = l.init.as_ref().map(|(_, e)| &**e);
+                match pat {
+                    Pat::Ident(p) => {
+                        let bind = match (ty, init) {
+                            (Some(t), _) => Bind::Rust(Box::new(t)),
+                            (None, Some(e)) => self.bind_of(fr, e),
+                            (None, None) => Bind::Py(Py::Any),
+                        };
+                        // a helper's dict may be extended below; its keys
+                        // are required relative to this binding
+                        let bind = match bind {
+                            Bind::Py(Py::Named(base)) if self.names.contains(&base) => {
+                                let depth = fr.depth;
+                                Bind::Py(fr.node(Shape::Dict { base: Some(base), keys: Vec::new(), dynamic: None, depth }))
+                            }
+                            b => b,
+                        };
+                        fr.bind(p.ident.to_string(), bind);
+                    }
+                    pat => {
+                        let t = ty.or_else(|| init.and_then(|e| self.rust_ty(fr, e)));
+                        self.bind_pattern(fr, pat, t);
+                    }
+                }
+            }
+            Stmt::Expr(e) | Stmt::Semi(e, _) => self.walk(fr, e),
+            Stmt::Item(_) => {}
+        }
+    }
+
+    /// Binding of `let x = e`: a Python value when `e` builds one
+    fn bind_of(&mut self, fr: &mut Frame, e: &Expr) -> Bind {
+        let python = match e {
+            Expr::Try(t) => matches!(&*t.expr, Expr::Call(_)),
+            Expr::Call(c) => call_path(&c.func).is_some_and(|p| p.iter().any(|s| s == "PyDict" || s == "PyList")),
+            _ => false,
+        };
+        if python {
+            let t = self.py_ty(fr, e);
+            if t != Py::Any {
+                if let Some(rust) = self.rust_ty(fr, e).filter(|r| !r.to_token_stream().to_string().contains("Py")) {
+                    return Bind::Rust(Box::new(rust));
+                }
+                return Bind::Py(t);
+            }
+        }
+        match self.rust_ty(fr, e) {
+            Some(t) => Bind::Rust(Box::new(t)),
+            None => Bind::Py(self.py_ty(fr, e)),
+        }
+    }
+
+    /// Bind the variables of a `for` / `let` / `if let` pattern of type `t`
+    fn bind_pattern(&mut self, fr: &mut Frame, pat: &Pat, t: Option<Type>) {
+        match pat {
+            Pat::Ident(p) => {
+                let b = t.map(|t| Bind::Rust(Box::new(t))).unwrap_or(Bind::Py(Py::Any));
+                fr.bind(p.ident.to_string(), b);
+            }
+            Pat::Reference(r) => self.bind_pattern(fr, &r.pat, t),
+            Pat::Tuple(tp) => {
+                let elems: Vec<Option<Type>> = match t.as_ref().map(strip_refs) {
+                    Some(Type::Tuple(tt)) => tt.elems.iter().cloned().map(Some).collect(),
+                    _ => Vec::new(),
+                };
+                for (i, p) in tp.elems.iter().enumerate() {
+                    self.bind_pattern(fr, p, elems.get(i).cloned().flatten());
+                }
+            }
+            Pat::TupleStruct(ts) if ts.path.is_ident("Some") || ts.path.is_ident("Ok") => {
+                let inner = t.and_then(|t| unwrap_type(&t, &["Option", "Result", "PyResult"]));
+                if let Some(p) = ts.pat.elems.first() {
+                    self.bind_pattern(fr, p, inner);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Element type of a `for` loop's iterator expression
+    fn iter_elem(&mut self, fr: &mut Frame, e: &Expr) -> Option<Type> {
+        if let Expr::MethodCall(m) = e {
+            match m.method.to_string().as_str() {
+                "enumerate" => {
+                    let inner = self.iter_elem(fr, &m.receiver).unwrap_or(parse_quote!(()));
+                    return Some(parse_quote!((usize, #inner)));
+                }
+                "zip" => {
+                    let a = self.iter_elem(fr, &m.receiver).unwrap_or(parse_quote!(()));
+                    let b = m.args.first().and_then(|x| self.iter_elem(fr, x)).unwrap_or(parse_quote!(()));
+                    return Some(parse_quote!((#a, #b)));
+                }
+                "iter" | "iter_mut" | "into_iter" | "copied" | "cloned" | "rev" | "skip" | "take" => {
+                    return self.iter_elem(fr, &m.receiver);
+                }
+                _ => {}
+            }
+        }
+        element(&self.rust_ty(fr, e)?)
+    }
+
+    /// Follow the effects of an expression statement
+    fn walk(&mut self, fr: &mut Frame, e: &Expr) {
+        match e {
+            Expr::Try(t) => self.walk(fr, &t.expr),
+            Expr::MethodCall(m) => {
+                let method = m.method.to_string();
+                if method == "set_item" && m.args.len() == 2 {
+                    self.set_item(fr, &m.receiver, &m.args[0], &m.args[1]);
+                } else if method == "append" && m.args.len() == 1 {
+                    let item = self.py_ty(fr, &m.args[0]);
+                    if let Some(Py::Node(id)) = var_name(&m.receiver).and_then(|v| match fr.lookup(&v) {
+                        Some(Bind::Py(p)) => Some(p.clone()),
+                        _ => None,
+                    }) {
+                        if let Shape::List(elem) = &mut fr.nodes[id] {
+                            *elem = Some(match elem.take() { Some(t) => t.or(item), None => item });
+                        }
+                    }
+                } else {
+                    self.walk(fr, &m.receiver);
+                }
+            }
+            Expr::ForLoop(f) => {
+                let elem = self.iter_elem(fr, &f.expr);
+                fr.scopes.push(HashMap::new());
+                fr.depth += 1;
+                self.bind_pattern(fr, &f.pat, elem);
+                self.walk_block(fr, &f.body);
+                fr.depth -= 1;
+                fr.scopes.pop();
+            }
+            Expr::If(i) => {
+                fr.scopes.push(HashMap::new());
+                fr.depth += 1;
+                if let Expr::Let(l) = &*i.cond {
+                    let t = self.rust_ty(fr, &l.expr);
+                    self.bind_pattern(fr, &l.pat, t);
+                }
+                self.walk_block(fr, &i.then_branch);
+                fr.scopes.pop();
+                if let Some((_, e)) = &i.else_branch {
+                    self.walk(fr, e);
+                }
+                fr.depth -= 1;
+            }
+            Expr::Match(m) => {
+                fr.depth += 1;
+                for arm in &m.arms {
+                    fr.scopes.push(HashMap::new());
+                    self.walk(fr, &arm.body);
+                    fr.scopes.pop();
+                }
+                fr.depth -= 1;
+            }
+            Expr::While(w) => {
+                fr.depth += 1;
+                self.walk_block(fr, &w.body);
+                fr.depth -= 1;
+            }
+            Expr::Loop(l) => {
+                fr.depth += 1;
+                self.walk_block(fr, &l.body);
+                fr.depth -= 1;
+            }
+            Expr::Block(b) => self.walk_block(fr, &b.block),
+            _ => {}
+        }
+    }
+
+    fn walk_block(&mut self, fr: &mut Frame, b: &Block) {
+        fr.scopes.push(HashMap::new());
+        for s in &b.stmts {
+            self.stmt(fr, s);
+        }
+        fr.scopes.pop();
+    }
+
+    /// `target.set_item(key, value)`
+    fn set_item(&mut self, fr: &mut Frame, target: &Expr, key: &Expr, value: &Expr) {
+        let Some(var) = var_name(target) else { return };
+        let id = match fr.lookup(&var).cloned() {
+            Some(Bind::Py(Py::Node(id))) => id,
+            // adding keys to a dict returned by a helper extends its type
+            Some(Bind::Py(Py::Named(base))) => {
+                let depth = fr.depth;
+                let node = fr.node(Shape::Dict { base: Some(base), keys: Vec::new(), dynamic: None, depth });
+                fr.rebind(&var, Bind::Py(node.clone()));
+                match node { Py::Node(id) => id, _ => return }
+            }
+            _ => return,
+        };
+        let value = self.py_ty(fr, value);
+        let key = match key {
+            Expr::Lit(l) => match &l.lit {
+                Lit::Str(s) => Ok(s.value()),
+                other => Err(lit_type(other)),
+            },
+            k => Err(self.py_ty(fr, k)),
+        };
+        let depth = fr.depth;
+        if let Shape::Dict { keys, dynamic, depth: created, .. } = &mut fr.nodes[id] {
+            match key {
+                Ok(name) => {
+                    let required = depth == *created;
+                    match keys.iter_mut().find(|(k, _, _)| *k == name) {
+                        Some(entry) => {
+                            entry.1 = entry.1.clone().or(value);
+                            entry.2 = entry.2 || required;
+                        }
+                        None => keys.push((name, value, required)),
+                    }
+                }
+                Err(k) => {
+                    *dynamic = Some(match dynamic.take() {
+                        Some((dk, dv)) => (dk.or(k), dv.or(value)),
+                        None => (k, value),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Replace the function's dict / list nodes by emitted `TypedDict`s
+    fn finish(&mut self, fr: &mut Frame, t: Py, stem: &str) -> Py {
+        match t {
+            Py::Node(id) => {
+                if let Some(t) = fr.done.get(&id) {
+                    return t.clone();
+                }
+                fr.done.insert(id, Py::Any);
+                let shape = std::mem::replace(&mut fr.nodes[id], Shape::List(None));
+                let out = match shape {
+                    Shape::List(elem) => {
+                        let singular = if stem.ends_with("Series") || stem.ends_with("ss") {
+                            format!("{}Item", stem)
+                        } else if let Some(s) = stem.strip_suffix("ies") {
+                            format!("{}y", s)
+                        } else {
+                            stem.strip_suffix('s').map(str::to_string).unwrap_or_else(|| format!("{}Item", stem))
+                        };
+                        Py::List(Box::new(match elem {
+                            Some(e) => self.finish(fr, e, &singular),
+                            None => Py::Any,
+                        }))
+                    }
+                    Shape::Dict { base, mut keys, dynamic, .. } => {
+                        // keys the base already declares only change its values
+                        if let Some(b) = self.dicts.iter().find(|d| Some(&d.name) == base.as_ref()) {
+                            keys.retain(|(k, _, _)| !b.fields.iter().any(|(f, _, _)| f == k));
+                        }
+                        if keys.is_empty() && dynamic.is_none() {
+                            match base {
+                                Some(b) => Py::Named(b),
+                                None => Py::Dict(Box::new(Py::Str), Box::new(Py::Any)),
+                            }
+                        } else if keys.is_empty() && base.is_none() {
+                            let (k, v) = dynamic.unwrap_or((Py::Str, Py::Any));
+                            let k = self.finish(fr, k, stem);
+                            let v = self.finish(fr, v, &format!("{}Entry", stem));
+                            Py::Dict(Box::new(k), Box::new(v))
+                        } else {
+                            let fields = keys.into_iter()
+                                .map(|(k, v, req)| {
+                                    let v = self.finish(fr, v, &format!("{}{}", stem, camel(&k)));
+                                    (k, v.render(), req)
+                                })
+                                .collect();
+                            Py::Named(self.emit(stem, base, fields))
+                        }
+                    }
+                };
+                fr.done.insert(id, out.clone());
+                out
+            }
+            Py::List(e) => Py::List(Box::new(self.finish(fr, *e, stem))),
+            Py::Seq(e) => Py::Seq(Box::new(self.finish(fr, *e, stem))),
+            Py::Optional(e) => Py::Optional(Box::new(self.finish(fr, *e, stem))),
+            Py::Dict(k, v) => Py::Dict(Box::new(self.finish(fr, *k, stem)), Box::new(self.finish(fr, *v, stem))),
+            Py::Tuple(ts) => Py::Tuple(ts.into_iter().map(|t| self.finish(fr, t, stem)).collect()),
+            Py::Union(ts) => Py::Union(ts.into_iter().map(|t| self.finish(fr, t, stem)).collect()),
+            t => t,
+        }
+    }
+
+    /// Add a `TypedDict` named after `stem` (made unique)
+    fn emit(&mut self, stem: &str, base: Option<String>, fields: Vec<(String, String, bool)>) -> String {
+        let root = format!("{}Dict", stem);
+        let mut name = root.clone();
+        let mut n = 2;
+        while self.names.contains(&name) || self.index.pyclasses.values().any(|c| *c == name) {
+            name = format!("{}{}", root, n);
+            n += 1;
+        }
+        self.names.insert(name.clone());
+        self.dicts.push(TypedDict { name: name.clone(), base, fields });
+        name
+    }
+}
+
+/// Parameters of a `#[pyo3(signature = (…))]` attribute: name, default
+fn signature_params(attrs: &[Attribute]) -> Option<Vec<(String, Option<String>)>> {
+    let attr = attrs.iter().find(|a| a.path.is_ident("pyo3") && a.tokens.to_string().contains("signature"))?;
+    let text = attr.tokens.to_string();
+    let start = text.find("signature")?;
+    let open = start + text[start..].find('(')?;
+    let mut depth = 0;
+    let mut end = open;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    end = open + i;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let inner = &text[open + 1..end];
+    let mut params = Vec::new();
+    let (mut depth, mut cur) = (0, String::new());
+    for c in inner.chars().chain(std::iter::once(',')) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                let item = cur.trim().to_string();
+                cur.clear();
+                if item.is_empty() {
+                    continue;
+                }
+                match item.split_once('=') {
+                    Some((name, default)) => params.push((name.trim().to_string(), Some(default.trim().to_string()))),
+                    None => params.push((item.replace(' ', ""), None)),
+                }
+                continue;
+            }
+            _ => {}
+        }
+        cur.push(c);
+    }
+    Some(params)
+}
+
+/// A Rust default expression as Python (`...` when not a plain literal)
+fn py_default(rust: &str) -> String {
+    let s = rust.replace(' ', "");
+    match s.as_str() {
+        "None" => "None".into(),
+        "true" => "True".into(),
+        "false" => "False".into(),
+        s if s.starts_with('"') => s.to_string(),
+        s if s.trim_start_matches('-').chars().next().is_some_and(|c| c.is_ascii_digit()) => {
+            let num = s.replace('_', "");
+            let num = num.trim_end_matches("f64").trim_end_matches("usize").trim_end_matches("u64");
+            if num.parse::<f64>().is_ok() { num.to_string() } else { "...".into() }
+        }
+        _ => "...".into(),
+    }
+}
+
+/// Docstring body, indented
+fn docstring(out: &mut String, lines: &[String], indent: &str) {
+    if lines.is_empty() {
+        return;
+    }
+    let text = lines.join("\n").replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let text = text.trim_matches('\n');
+    if !text.contains('\n') {
+        let _ = writeln!(out, "{}\"\"\"{}\"\"\"", indent, text);
+        return;
+    }
+    let _ = writeln!(out, "{}\"\"\"", indent);
+    for line in text.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "{}{}", indent, line);
+        }
+    }
+    let _ = writeln!(out, "{}\"\"\"", indent);
+}
+
+/// `def name(…) -> ret:` with its docstring
+fn render_def(
+    out: &mut String,
+    gen: &mut Gen,
+    indent: &str,
+    name: &str,
+    receiver: Option<&str>,
+    sig: &Signature,
+    attrs: &[Attribute],
+    ret: &str,
+) {
+    let typed: HashMap<String, Type> = sig.inputs.iter()
+        .filter_map(|a| match a {
+            FnArg::Typed(pt) => match &*pt.pat {
+                Pat::Ident(p) => Some((p.ident.to_string(), (*pt.ty).clone())),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let rust_params: Vec<String> = sig.inputs.iter()
+        .filter_map(|a| match a {
+            FnArg::Typed(pt) => match (&*pt.pat, type_name(&pt.ty).as_deref()) {
+                (_, Some("Python")) | (_, Some("PyRef")) | (_, Some("PyRefMut")) => None,
+                (Pat::Ident(p), _) => Some(p.ident.to_string()),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .filter(|n| n != "slf")
+        .collect();
+    let params: Vec<(String, Option<String>)> = match signature_params(attrs) {
+        Some(p) => p,
+        // without a signature, trailing `Option` arguments default to None
+        None => {
+            let mut p: Vec<(String, Option<String>)> = rust_params.iter().map(|n| (n.clone(), None)).collect();
+            for (name, default) in p.iter_mut().rev() {
+                match typed.get(name).and_then(type_name).as_deref() {
+                    Some("Option") => *default = Some("None".into()),
+                    _ => break,
+                }
+            }
+            p
+        }
+    };
+    let mut rendered: Vec<String> = receiver.map(|r| vec![r.to_string()]).unwrap_or_default();
+    for (name, default) in &params {
+        let (stars, bare) = match name.strip_prefix("**") {
+            Some(b) => ("**", b),
+            None => match name.strip_prefix('*') {
+                Some(b) => ("*", b),
+                None => ("", name.as_str()),
+            },
+        };
+        if bare.is_empty() || bare == "/" {
+            rendered.push(name.clone());
+            continue;
+        }
+        let ty = match typed.get(bare) {
+            Some(t) if stars.is_empty() => gen.rust_to_py(t, true).render(),
+            _ => "Any".into(),
+        };
+        rendered.push(match default {
+            Some(d) => format!("{}{}: {} = {}", stars, bare, ty, py_default(d)),
+            None => format!("{}{}: {}", stars, bare, ty),
+        });
+    }
+    let one_line = format!("{}def {}({}) -> {}:", indent, name, rendered.join(", "), ret);
+    if one_line.len() <= 100 {
+        out.push_str(&one_line);
+    } else {
+        let _ = writeln!(out, "{}def {}(", indent, name);
+        for p in &rendered {
+            let _ = writeln!(out, "{}    {},", indent, p);
+        }
+        let _ = write!(out, "{}) -> {}:", indent, ret);
+    }
+    let lines = doc(attrs);
+    if lines.is_empty() {
+        out.push_str(" ...\n");
+    } else {
+        out.push('\n');
+        docstring(out, &lines, &format!("{}    ", indent));
+    }
+}
+
+/// Registered functions and classes of the `#[pymodule]`, in order
+fn registrations(lib: &str) -> (Vec<String>, Vec<String>) {
+    let last = |path: &str| path.rsplit("::").next().unwrap_or(path).trim().to_string();
+    let mut fns = Vec::new();
+    let mut classes = Vec::new();
+    for line in lib.lines() {
+        if let Some(rest) = line.split("wrap_pyfunction!(").nth(1) {
+            fns.push(last(rest.split(',').next().unwrap_or("")));
+        } else if let Some(rest) = line.split("add_class::<").nth(1) {
+            classes.push(last(rest.split('>').next().unwrap_or("")));
+        }
+    }
+    (fns, classes)
+}
+
+/// The stub file for the extension module in `src`
+pub fn generate(src: &Path) -> Result<String, String> {
+    let mut index = Index::default();
+    Index::load(src, &mut index)?;
+    let lib = fs::read_to_string(src.join("lib.rs")).map_err(|e| e.to_string())?;
+    let (fn_names, class_names) = registrations(&lib);
+
+    let mut gen = Gen::new(&index);
+    let mut body = String::new();
+
+    // classes
+    for class in &class_names {
+        let py_name = index.pyclasses.get(class).cloned().unwrap_or_else(|| class.clone());
+        let _ = writeln!(body, "class {}:", py_name);
+        let struct_doc = index.files.iter().flat_map(|f| &f.items).find_map(|i| match i {
+            Item::Struct(s) if s.ident == class.as_str() => Some(doc(&s.attrs)),
+            _ => None,
+        });
+        docstring(&mut body, &struct_doc.unwrap_or_default(), "    ");
+        let methods = index.files.iter().enumerate().flat_map(|(f, file)| file.items.iter().map(move |i| (f, i)))
+            .filter_map(|(f, i)| match i {
+                Item::Impl(imp) if has_attr(&imp.attrs, "pymethods") && type_name(&imp.self_ty).as_deref() == Some(class) => Some((f, imp)),
+                _ => None,
+            })
+            .flat_map(|(f, imp)| imp.items.iter().filter_map(move |it| match it {
+                ImplItem::Method(m) => Some((f, m)),
+                _ => None,
+            }));
+        for (file, m) in methods {
+            let name = m.sig.ident.to_string();
+            let mut sig = m.sig.clone();
+            sig.output = replace_self(&sig.output, class);
+            if has_attr(&m.attrs, "new") {
+                render_def(&mut body, &mut gen, "    ", "__init__", Some("self"), &sig, &m.attrs, "None");
+                continue;
+            }
+            let stem = format!("{}{}", camel(&py_name), camel(&name));
+            let ret = gen.follow(file, &sig, &m.block, Some(class), &stem).render();
+            if has_attr(&m.attrs, "getter") {
+                let _ = writeln!(body, "    @property");
+                render_def(&mut body, &mut gen, "    ", &name, Some("self"), &sig, &m.attrs, &ret);
+            } else if has_attr(&m.attrs, "staticmethod") {
+                let _ = writeln!(body, "    @staticmethod");
+                render_def(&mut body, &mut gen, "    ", &name, None, &sig, &m.attrs, &ret);
+            } else if has_attr(&m.attrs, "classmethod") {
+                let _ = writeln!(body, "    @classmethod");
+                render_def(&mut body, &mut gen, "    ", &name, Some("cls"), &sig, &m.attrs, &ret);
+            } else {
+                render_def(&mut body, &mut gen, "    ", &name, Some("self"), &sig, &m.attrs, &ret);
+            }
+        }
+        body.push('\n');
+    }
+
+    // functions
+    for name in &fn_names {
+        let found = index.files.iter().enumerate().find_map(|(f, file)| file.items.iter().find_map(|i| match i {
+            Item::Fn(func) if func.sig.ident == name.as_str() && has_attr(&func.attrs, "pyfunction") => Some((f, func)),
+            _ => None,
+        }));
+        let Some((file, func)) = found else {
+            return Err(format!("registered function ‘{}’ has no #[pyfunction]", name));
+        };
+        let ret = gen.follow(file, &func.sig, &func.block, None, &format!("{}Result", camel(name))).render();
+        render_def(&mut body, &mut gen, "", name, None, &func.sig, &func.attrs, &ret);
+        body.push('\n');
+    }
+
+    let mut out = String::new();
+    out.push_str("# backtester.pyi: type stubs of the `backtester` extension module.\n");
+    out.push_str("# Generated by build.rs from the Rust sources on every build; do not edit.\n\n");
+    out.push_str("from typing import Any, Dict, List, Optional, Sequence, Tuple, Union\n\n");
+    out.push_str("import numpy as np\nimport numpy.typing as npt\nfrom typing_extensions import NotRequired, TypedDict\n\n");
+    out.push_str(&body);
+    for d in &gen.dicts {
+        match &d.base {
+            Some(b) => { let _ = writeln!(out, "class {}({}):", d.name, b); }
+            None => { let _ = writeln!(out, "class {}(TypedDict):", d.name); }
+        }
+        for (k, t, required) in &d.fields {
+            if *required {
+                let _ = writeln!(out, "    {}: {}", k, t);
+            } else {
+                let _ = writeln!(out, "    {}: NotRequired[{}]", k, t);
+            }
+        }
+        out.push('\n');
+    }
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    Ok(out)
+}
@@ -0,0 +1,61 @@
+// js/backtester.js
+//
+// Browser / Node wrapper around the `wasm` build:
+//
+//   cargo build --release --target wasm32-unknown-unknown --no-default-features --features wasm
+//
+//   const bt = await loadBacktester(fetch("backtester.wasm"));
+//   const out = bt.runBacktest({ timestamp, open, high, low, close,
+//                                longSignals, longTp, longSl, longSize, ... });
+
+const COLUMNS = [
+  "timestamp", "open", "high", "low", "close",
+  "longSignals", "shortSignals",
+  "longTp", "longSl", "shortTp", "shortSl",
+  "longSize", "shortSize",
+  "expirationTimes",
+];
+
+export async function loadBacktester(source) {
+  const { instance } = source instanceof Promise || source instanceof Response
+    ? await WebAssembly.instantiateStreaming(source)
+    : await WebAssembly.instantiate(source);
+  const wasm = instance.exports;
+
+  // Arrays (or typed arrays) in, the engine's JSON result out.  Missing
+  // signal / level / size columns default to false / NaN / 0, and missing
+  // expirations never expire.
+  function runBacktest(inputs, {
+    entryFeeRate = 0, exitFeeRate = 0, slippageRate = 0, initialEquity = 10000,
+  } = {}) {
+    const n = inputs.timestamp.length;
+    const defaults = {
+      longSignals: 0, shortSignals: 0,
+      longTp: NaN, longSl: NaN, shortTp: NaN, shortSl: NaN,
+      longSize: 0, shortSize: 0,
+      expirationTimes: Infinity,
+    };
+    const ptrs = COLUMNS.map((name) => {
+      const ptr = wasm.bt_alloc(n);
+      const view = new Float64Array(wasm.memory.buffer, ptr, n);
+      const values = inputs[name];
+      if (values === undefined) {
+        view.fill(defaults[name]);
+      } else {
+        for (let i = 0; i < n; i++) view[i] = Number(values[i]);
+      }
+      return ptr;
+    });
+    try {
+      const out = wasm.bt_run(n, ...ptrs, entryFeeRate, exitFeeRate, slippageRate, initialEquity);
+      const bytes = new Uint8Array(wasm.memory.buffer, out, wasm.bt_result_len());
+      const result = JSON.parse(new TextDecoder().decode(bytes));
+      if (result.error !== undefined) throw new Error(result.error);
+      return result;
+    } finally {
+      ptrs.forEach((ptr) => wasm.bt_free(ptr, n));
+    }
+  }
+
+  return { runBacktest };
+}
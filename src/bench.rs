@@ -0,0 +1,214 @@
+// src/bench.rs
+
+//! Benchmark API (feature `bench`): seeded synthetic workloads and timed
+//! entry points for the hot stages of the engine, pure Rust so they run
+//! without Python:
+//!
+//!   cargo bench --no-default-features --features bench
+//!
+//! `benches/engine.rs` runs `suite` over a few sizes; `Workload` and the
+//! `run_*` stages can equally be driven from an external harness such as
+//! Criterion (`b.iter(|| w.simulate_exits())`).
+
+use std::time::{Duration, Instant};
+
+use crate::engine::{
+    backtest::{run_engine, BacktestConfig, MarketData, SignalSet},
+    exposure::compute_exposure_series,
+    position::Position,
+    rng::Rng,
+    scan_entries::scan_entries,
+    simulate_exits::simulate_position_exits,
+};
+
+/// Shape of a synthetic workload
+#[derive(Clone, Copy, Debug)]
+pub struct WorkloadSpec {
+    pub bars:         usize,
+    /// Probability of a signal on each bar (long or short, 50/50)
+    pub signal_rate:  f64,
+    /// TP / SL distance from the signal‐bar close as a fraction of price
+    /// (wider levels hold positions longer)
+    pub level_width:  f64,
+    /// Per‐bar log‐return volatility of the random walk
+    pub volatility:   f64,
+    pub seed:         u64,
+}
+
+impl Default for WorkloadSpec {
+    fn default() -> Self {
+        WorkloadSpec { bars: 100_000, signal_rate: 0.05, level_width: 0.02, volatility: 0.002, seed: 7 }
+    }
+}
+
+/// Bars, signals and costs of one benchmark case
+pub struct Workload {
+    pub spec:    WorkloadSpec,
+    market:  MarketData,
+    signals: SignalSet,
+    config:  BacktestConfig,
+}
+
+impl Workload {
+    /// Random‐walk bars (one‐minute, four sub‐steps per bar for high/low)
+    /// with random signals; the same spec always gives the same workload
+    pub fn new(spec: WorkloadSpec) -> Self {
+        let n = spec.bars;
+        let mut rng = Rng::new(spec.seed);
+        let mut market = MarketData {
+            timestamps: Vec::with_capacity(n),
+            open:       Vec::with_capacity(n),
+            high:       Vec::with_capacity(n),
+            low:        Vec::with_capacity(n),
+            close:      Vec::with_capacity(n),
+        };
+        let mut x = 100f64.ln();
+        for t in 0..n {
+            let (mut hi, mut lo) = (x, x);
+            market.open.push(x.exp());
+            for _ in 0..4 {
+                x += spec.volatility * 0.5 * rng.normal();
+                hi = hi.max(x);
+                lo = lo.min(x);
+            }
+            market.timestamps.push(t as f64 * 60.0);
+            market.high.push(hi.exp());
+            market.low.push(lo.exp());
+            market.close.push(x.exp());
+        }
+
+        let mut long_signals = vec![false; n];
+        let mut short_signals = vec![false; n];
+        for t in 0..n {
+            if rng.next_f64() < spec.signal_rate {
+                if rng.next_f64() < 0.5 { long_signals[t] = true } else { short_signals[t] = true }
+            }
+        }
+        let level = |sign: f64| -> Vec<f64> { market.close.iter().map(|c| c * (1.0 + sign * spec.level_width)).collect() };
+        let signals = SignalSet {
+            long_signals,
+            short_signals,
+            long_tp:  level(1.0),
+            long_sl:  level(-1.0),
+            short_tp: level(-1.0),
+            short_sl: level(1.0),
+            long_size:  vec![1.0; n],
+            short_size: vec![1.0; n],
+            expiration_times: vec![f64::INFINITY; n],
+            tags:      None,
+            direction: None,
+        };
+        let config = BacktestConfig {
+            entry_fee_rate: 0.0005,
+            exit_fee_rate:  0.0005,
+            slippage_rate:  0.0001,
+            initial_equity: 100_000.0,
+            ..BacktestConfig::default()
+        };
+        Workload { spec, market, signals, config }
+    }
+
+    /// Pending positions of every signal (the input of `simulate_exits`)
+    pub fn entries(&self) -> Vec<Position> {
+        let (m, s, c) = (&self.market, &self.signals, &self.config);
+        scan_entries(
+            0..m.open.len(),
+            &m.timestamps, &m.open,
+            &s.long_signals, &s.short_signals,
+            &s.long_tp, &s.long_sl, &s.short_tp, &s.short_sl,
+            &s.long_size, &s.short_size,
+            &s.expiration_times,
+            c.entry_fee_rate, c.slippage_rate, c.contract, c.size_mode,
+            c.latency, c.loss_cap, c.filters,
+            &mut Vec::new(),
+        )
+    }
+
+    /// Exit simulation of `entries` (the positions are exited in place)
+    pub fn simulate_exits(&self, entries: &mut [Position]) {
+        let (m, c) = (&self.market, &self.config);
+        simulate_position_exits(
+            entries,
+            &m.timestamps, &m.open, &m.high, &m.low, &m.close,
+            c.exit_fees(), c.exit_slippage(),
+            c.latency, c.fill_model, c.queue_model.as_ref(), c.filters, c.exit_rules.as_ref(),
+            c.sub_bars.as_ref(), c.expiry_fill, c.expiry_policy, c.exit_priority, c.level_check, c.stop_type,
+        );
+    }
+
+    /// Bar‐by‐bar exposure of simulated positions; returns the bar count
+    pub fn exposure(&self, positions: &[Position]) -> usize {
+        let m = &self.market;
+        compute_exposure_series(positions, &m.close, &m.timestamps, self.config.initial_equity, self.config.leverage()).len()
+    }
+
+    /// The whole pipeline; returns the number of closed trades
+    pub fn full_run(&self) -> usize {
+        run_engine(&self.market, &self.signals, &self.config).closed.len()
+    }
+
+    pub fn signal_count(&self) -> usize {
+        self.signals.long_signals.iter().chain(&self.signals.short_signals).filter(|&&s| s).count()
+    }
+}
+
+/// Wall‐clock statistics of one timed stage
+#[derive(Clone, Debug)]
+pub struct Timing {
+    pub name:       String,
+    pub bars:       usize,
+    pub iterations: usize,
+    pub min:        Duration,
+    pub median:     Duration,
+    pub mean:       Duration,
+}
+
+impl Timing {
+    /// Bars processed per second at the median time
+    pub fn bars_per_second(&self) -> f64 {
+        self.bars as f64 / self.median.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+/// Time `iterations` runs of `f` (after one warm‐up run); `setup` builds
+/// each run's input outside the timed section
+pub fn time_stage<S, T, F>(name: &str, bars: usize, iterations: usize, mut setup: S, mut f: F) -> Timing
+where
+    S: FnMut() -> T,
+    F: FnMut(T),
+{
+    f(setup());
+    let iterations = iterations.max(1);
+    let mut samples: Vec<Duration> = (0..iterations)
+        .map(|_| {
+            let input = setup();
+            let start = Instant::now();
+            f(input);
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    Timing {
+        name: name.to_string(),
+        bars,
+        iterations,
+        min:    samples[0],
+        median: samples[iterations / 2],
+        mean:   samples.iter().sum::<Duration>() / iterations as u32,
+    }
+}
+
+/// Time the entry scan, exit simulation, exposure and the full run on one
+/// workload
+pub fn suite(spec: WorkloadSpec, iterations: usize) -> Vec<Timing> {
+    let w = Workload::new(spec);
+    let bars = spec.bars;
+    let mut exited = w.entries();
+    w.simulate_exits(&mut exited);
+    vec![
+        time_stage("scan_entries", bars, iterations, || (), |_| { std::hint::black_box(w.entries()); }),
+        time_stage("simulate_exits", bars, iterations, || w.entries(), |mut e| w.simulate_exits(&mut e)),
+        time_stage("exposure", bars, iterations, || (), |_| { std::hint::black_box(w.exposure(&exited)); }),
+        time_stage("full_run", bars, iterations, || (), |_| { std::hint::black_box(w.full_run()); }),
+    ]
+}
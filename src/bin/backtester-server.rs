@@ -0,0 +1,13 @@
+// src/bin/backtester-server.rs
+
+//! `backtester-server [ADDR]`: serve the engine over HTTP (default
+//! 127.0.0.1:8080).  Build with `--no-default-features --features server`.
+
+fn main() {
+    let addr = std::env::args().nth(1).unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    eprintln!("backtester-server listening on {}", addr);
+    if let Err(e) = backtester::server::serve(&addr) {
+        eprintln!("backtester-server: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,36 @@
+// src/bin/backtester-stubs.rs
+
+//! `backtester-stubs`: write the type stubs `build.rs` generated from the
+//! current sources over the checked‐in `backtester.pyi`.
+
+/// Stubs generated by `build.rs` for this build's sources
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/backtester.pyi"));
+
+/// The checked‐in stub file maturin ships
+const CHECKED_IN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/backtester.pyi");
+
+fn main() {
+    if GENERATED.is_empty() {
+        eprintln!("backtester-stubs: build.rs could not generate the stubs (see its warnings)");
+        std::process::exit(1);
+    }
+    if let Err(e) = std::fs::write(CHECKED_IN, GENERATED) {
+        eprintln!("backtester-stubs: could not write {}: {}", CHECKED_IN, e);
+        std::process::exit(1);
+    }
+    eprintln!("backtester-stubs: wrote {}", CHECKED_IN);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_in_stubs_are_current() {
+        let current = std::fs::read_to_string(CHECKED_IN).unwrap_or_default();
+        assert!(
+            current == GENERATED,
+            "backtester.pyi is out of date; run `cargo run --bin backtester-stubs` to regenerate it"
+        );
+    }
+}
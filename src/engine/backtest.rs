@@ -0,0 +1,674 @@
+// src/engine/backtest.rs
+
+use crate::engine::{
+    bootstrap::{block_bootstrap, span_years, BootstrapOptions},
+    contract::{ContractType, SizeMode},
+    exit_rules::ExitRules,
+    bracket::StopType,
+    funding::apply_funding,
+    corporate::apply_dividends,
+    borrow::apply_borrow_limits,
+    drawdown_limit::{find_breach, stop_at_breach, DrawdownBreach, TrailingDrawdown},
+    fill_model::{FillModel, QueueModel},
+    instrument::InstrumentFilters,
+    latency::Latency,
+    partial_fills::{apply_partial_fills, PartialFills},
+    loss_cap::LossCap,
+    prepare_inputs::prepare_inputs,
+    rolls::{adjustment_offsets, apply_roll_accounting, back_adjust, to_raw_prices, RollSchedule},
+    scan_entries::scan_entries,
+    signal_input::{close_on_signal, SignalMode},
+    sub_bars::SubBars,
+    warnings::collect_warnings,
+    invariants::check_invariants,
+    concurrency::concurrency_stats,
+    regimes::regime_breakdown,
+    benchmark::{account_equity, bar_returns, benchmark_metrics},
+    factors::factor_beta,
+    holding::{default_edges, duration_buckets},
+    excursions::record_excursions,
+    liquidation::{record_liquidation, LiquidationModel},
+    calendar::{resample, Frequency, ResampledCurve},
+    simulate_exits::{simulate_position_exits, ExitFees, ExitSlippage, ExitPriority, ExpiryFill, ExpiryPolicy, LevelCheck},
+    throttle::EquityThrottle,
+    event_loop::{run_event_loop, AccountRules},
+    weighted_returns::weighted_returns,
+    cash_flows::{account_value, summarize},
+    exposure::{
+        compute_exposure_series, compute_exposure_series_with, compute_trade_close_curve, mark_open_positions,
+        CostAccrual, ExposureOptions, ExposureSnapshot, OpenMark, TradeClosePoint,
+    },
+    fx::{convert_exposure, convert_positions},
+    metrics::{autocorrelations, compute_summary_metrics_with, without_costs, HeadlineMetrics, RatioPolicy, ReturnSpec, SummaryMetrics},
+    position::Position,
+};
+
+/// Bar series shared by every run
+#[derive(Clone, Debug)]
+pub struct MarketData {
+    pub timestamps: Vec<f64>,
+    pub open:       Vec<f64>,
+    pub high:       Vec<f64>,
+    pub low:        Vec<f64>,
+    pub close:      Vec<f64>,
+}
+
+/// Per‐bar signals, levels and sizes (all aligned to the signal bar)
+#[derive(Clone, Debug)]
+pub struct SignalSet {
+    pub long_signals:     Vec<bool>,
+    pub short_signals:    Vec<bool>,
+    pub long_tp:          Vec<f64>,
+    pub long_sl:          Vec<f64>,
+    pub short_tp:         Vec<f64>,
+    pub short_sl:         Vec<f64>,
+    pub long_size:        Vec<f64>,
+    pub short_size:       Vec<f64>,
+    pub expiration_times: Vec<f64>,
+    /// Optional per‐bar setup labels copied onto positions
+    pub tags:             Option<Vec<Option<String>>>,
+    /// Per‐bar −1/0/+1 target when entries came from a `signal` array
+    pub direction:        Option<Vec<i64>>,
+}
+
+/// Scalar cost + accounting parameters and optional engine features
+#[derive(Clone, Debug, Default)]
+pub struct BacktestConfig {
+    pub entry_fee_rate: f64,
+    pub exit_fee_rate:  f64,
+    /// Fee rate of exits resting as limits (take‐profits); `exit_fee_rate`
+    /// otherwise
+    pub maker_fee_rate: Option<f64>,
+    pub slippage_rate:  f64,
+    /// Exit slippage overrides for stop, target and expiry exits
+    pub slippage_sl:    Option<f64>,
+    pub slippage_tp:    Option<f64>,
+    pub slippage_exp:   Option<f64>,
+    pub initial_equity: f64,
+    /// Margin = entry notional / leverage (1 when unset)
+    pub leverage:       Option<f64>,
+    /// Block‐bootstrap CIs on the bar returns
+    pub bootstrap:      Option<BootstrapOptions>,
+    /// Futures roll schedule (back‐adjusted continuous simulation)
+    pub rolls:          Option<RollSchedule>,
+    /// Linear or inverse (coin‐margined) contracts
+    pub contract:       ContractType,
+    /// Units or notional sizing
+    pub size_mode:      SizeMode,
+    /// Signal→fill and trigger→fill delay
+    pub latency:        Option<Latency>,
+    /// Probabilistic TP fills
+    pub fill_model:     Option<FillModel>,
+    /// Queue‐position TP fills from L1 quotes
+    pub queue_model:    Option<QueueModel>,
+    /// Stop trading once equity falls this far below its high‐water mark
+    pub trailing_drawdown: Option<TrailingDrawdown>,
+    /// Scale down / pause entries while equity is below its moving average
+    pub throttle:       Option<EquityThrottle>,
+    /// Open‐position cap, free‐margin check and compounding
+    pub account:        AccountRules,
+    /// Hard per‐trade loss cap (currency)
+    pub loss_cap:       Option<LossCap>,
+    /// Tick / lot / min‐notional rounding and rejection
+    pub filters:        Option<InstrumentFilters>,
+    /// Entries filled over several bars
+    pub partial_fills:  Option<PartialFills>,
+    /// Whether `signal` changes also close positions
+    pub signal_mode:    SignalMode,
+    /// Bar‐by‐bar TP/SL updates
+    pub exit_rules:     Option<ExitRules>,
+    /// Per‐bar funding rate on open notional (longs pay positive rates)
+    pub funding_rates:  Option<Vec<f64>>,
+    /// Per‐bar mark price for floating PnL, margin and liquidation (fills
+    /// stay on traded prices)
+    pub mark_price:     Option<Vec<f64>>,
+    /// Per‐bar account currency per unit of quote currency
+    pub fx_rates:       Option<Vec<f64>>,
+    /// Per‐bar cash dividend per unit going ex on that bar
+    pub dividends:      Option<Vec<f64>>,
+    /// Per‐bar units available to borrow for short entries
+    pub borrow_available: Option<Vec<f64>>,
+    /// Also report headline metrics without costs
+    pub gross_metrics:  bool,
+    /// Also report time‐ and money‐weighted returns
+    pub weighted_returns: bool,
+    /// Verify conservation properties of the result after the run
+    pub check_invariants: bool,
+    /// Value of ratios with a zero denominator
+    pub ratio_policy:   RatioPolicy,
+    /// Whether entry fees hit equity at the fill or with the exit PnL
+    pub cost_accrual:   CostAccrual,
+    /// Subtract open positions' paid fees and accrued funding from
+    /// floating PnL
+    pub net_floating:   bool,
+    /// Curve and return convention of the time‐series metrics
+    pub returns:        ReturnSpec,
+    /// Finer bars ordering same‐bar SL/TP touches
+    pub sub_bars:       Option<SubBars>,
+    /// Bar and price an expiring position closes at
+    pub expiry_fill:    ExpiryFill,
+    /// What an expiry does while the take‐profit still works
+    pub expiry_policy:  ExpiryPolicy,
+    /// Which exit wins a bar where several trigger
+    pub exit_priority:  ExitPriority,
+    /// Whether SL/TP trigger on high/low touches or only on the close
+    pub level_check:    LevelCheck,
+    /// Liquidation prices and near‐liquidation bars (reporting only)
+    pub liquidation:    Option<LiquidationModel>,
+    /// Order type of every stop‐loss leg
+    pub stop_type:      StopType,
+    /// K for the PnL earned with more than K positions open (default 1)
+    pub overlap_threshold: Option<usize>,
+    /// Per‐bar regime labels to bucket metrics by
+    pub regimes:        Option<Vec<Option<String>>>,
+    /// Benchmark price per bar (e.g. an index close)
+    pub benchmark:      Option<Vec<f64>>,
+    /// External deposit (+) / withdrawal (−) booked at each bar's close
+    pub cash_flows:     Option<Vec<f64>>,
+    /// Named per‐bar factor return series to compute betas against
+    pub factors:        Vec<(String, Vec<f64>)>,
+    /// Bars per rolling beta (0: full sample only)
+    pub beta_window:    usize,
+    /// Return autocorrelation lags (default `DEFAULT_AUTOCORR_LAGS`)
+    pub autocorr_lags:  Option<Vec<usize>>,
+    /// Holding‐time bucket edges in seconds (default from the bar interval)
+    pub duration_edges: Option<Vec<f64>>,
+}
+
+/// A signal whose entry the engine declined
+#[derive(Clone, Debug)]
+pub struct SkippedEntry {
+    pub signal_index:  usize,
+    pub entry_index:   usize,
+    pub position_type: String,
+    pub reason:        &'static str,
+}
+
+impl BacktestConfig {
+    /// Account leverage (1 when unset)
+    pub fn leverage(&self) -> f64 {
+        self.leverage.unwrap_or(1.0)
+    }
+
+    /// Price open positions are valued at each bar: the mark, else the close
+    pub fn marks<'a>(&'a self, market: &'a MarketData) -> &'a [f64] {
+        self.mark_price.as_deref().unwrap_or(&market.close)
+    }
+
+    /// Cost handling of the bar‐by‐bar equity
+    pub fn exposure_options(&self) -> ExposureOptions<'_> {
+        ExposureOptions {
+            accrual:       self.cost_accrual,
+            net_floating:  self.net_floating,
+            funding_rates: self.funding_rates.as_deref(),
+        }
+    }
+
+    /// Fee rates may be negative (rebates), but no fill can be charged or
+    /// rebated its whole notional
+    pub fn validate_fees(&self) -> Result<(), String> {
+        let rates = [
+            ("entry_fee_rate", Some(self.entry_fee_rate)),
+            ("exit_fee_rate",  Some(self.exit_fee_rate)),
+            ("maker_fee_rate", self.maker_fee_rate),
+        ];
+        for (name, rate) in rates {
+            if let Some(r) = rate {
+                if !(r.is_finite() && r.abs() < 1.0) {
+                    return Err(format!("{} must be in (−1, 1), got {}", name, r));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn exit_fees(&self) -> ExitFees {
+        ExitFees {
+            maker: self.maker_fee_rate.unwrap_or(self.exit_fee_rate),
+            taker: self.exit_fee_rate,
+        }
+    }
+
+    /// Exit slippage per condition, defaulting to `slippage_rate`
+    pub fn exit_slippage(&self) -> ExitSlippage {
+        ExitSlippage {
+            sl:  self.slippage_sl.unwrap_or(self.slippage_rate),
+            tp:  self.slippage_tp.unwrap_or(self.slippage_rate),
+            exp: self.slippage_exp.unwrap_or(self.slippage_rate),
+        }
+    }
+}
+
+impl SkippedEntry {
+    pub fn from_position(pos: &Position, reason: &'static str) -> Self {
+        SkippedEntry {
+            signal_index:  pos.signal_index,
+            entry_index:   pos.entry_index,
+            position_type: pos.position_type.clone(),
+            reason,
+        }
+    }
+}
+
+/// Everything a single run produces
+pub struct BacktestResult {
+    pub closed:   Vec<Position>,
+    pub open:     Vec<Position>,
+    /// Last‐bar mark of each `open` position (same order)
+    pub open_marks: Vec<OpenMark>,
+    pub exposure: Vec<ExposureSnapshot>,
+    /// Account equity (initial equity + PnL) at every bar close
+    pub equity:   Vec<f64>,
+    /// `equity` plus the external flows booked so far (runs with
+    /// `cash_flows` only)
+    pub account_value: Option<Vec<f64>>,
+    /// Added to `total_equity` when reporting snapshots (the initial equity
+    /// under account‐value accounting, else 0)
+    pub equity_base: f64,
+    pub metrics:  SummaryMetrics,
+    /// Set when a trailing‐drawdown limit stopped the run
+    pub breach:   Option<DrawdownBreach>,
+    /// Signals that never became positions
+    pub skipped:  Vec<SkippedEntry>,
+    /// Realized equity after each trade close
+    pub trade_equity: Vec<TradeClosePoint>,
+    /// Suspicious but non‐fatal conditions of the run
+    pub warnings: Vec<String>,
+    /// Broken conservation properties (runs with `check_invariants` only)
+    pub invariant_violations: Option<Vec<String>>,
+    /// Account equity resampled to daily / weekly / monthly periods
+    pub resampled: Vec<ResampledCurve>,
+}
+
+/// Ensure `arr.len() == expected`
+pub(crate) fn validate_length<T>(arr: &[T], name: &str, expected: usize) -> Result<(), String> {
+    if arr.len() != expected {
+        Err(format!(
+            "‘{}’ length {} != expected {}",
+            name, arr.len(), expected
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Bar series checks: equal lengths, no NaN, strictly increasing time
+pub fn validate_market(market: &mut MarketData) -> Result<usize, String> {
+    if !market.timestamps.windows(2).all(|w| w[1] > w[0]) {
+        return Err("timestamps must be strictly increasing".into());
+    }
+    let n = prepare_inputs(&mut [
+        &mut market.timestamps,
+        &mut market.open,
+        &mut market.high,
+        &mut market.low,
+        &mut market.close,
+    ])?;
+    if n == 0 {
+        return Err("input arrays must not be empty".into());
+    }
+    Ok(n)
+}
+
+/// Signal‐set checks against an already validated bar series
+pub fn validate_signals(signals: &SignalSet, timestamps: &[f64]) -> Result<(), String> {
+    let n = timestamps.len();
+    validate_length(&signals.long_signals,     "long_signals",     n)?;
+    validate_length(&signals.short_signals,    "short_signals",    n)?;
+    validate_length(&signals.long_tp,          "long_tp",          n)?;
+    validate_length(&signals.long_sl,          "long_sl",          n)?;
+    validate_length(&signals.short_tp,         "short_tp",         n)?;
+    validate_length(&signals.short_sl,         "short_sl",         n)?;
+    validate_length(&signals.long_size,        "long_size",        n)?;
+    validate_length(&signals.short_size,       "short_size",       n)?;
+    validate_length(&signals.expiration_times, "expiration_times", n)?;
+    if let Some(tags) = &signals.tags {
+        validate_length(tags, "tags", n)?;
+    }
+    if let Some(direction) = &signals.direction {
+        validate_length(direction, "signal", n)?;
+    }
+
+    // Signal mutual‐exclusion
+    for i in 0..n {
+        if signals.long_signals[i] && signals.short_signals[i] {
+            return Err(format!(
+                "both long and short signals true at index {}", i
+            ));
+        }
+    }
+
+    // Expirations must not precede their bar‐timestamp
+    for (i, (&et, &ts)) in signals.expiration_times.iter().zip(timestamps).enumerate() {
+        if et < ts {
+            return Err(format!(
+                "expiration_time {} < timestamp {} at index {}",
+                et, ts, i
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Full pipeline on validated inputs: entries → exits → exposure → metrics
+pub fn run_engine(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+) -> BacktestResult {
+    let mut result = match &config.rolls {
+        Some(schedule) if !schedule.roll_bars.is_empty() => run_rolled(market, signals, config, schedule),
+        _ => run_continuous(market, signals, config),
+    };
+    result.warnings = collect_warnings(&result, market, signals, config);
+    if config.check_invariants {
+        result.invariant_violations = Some(check_invariants(&result, market, config));
+    }
+    result
+}
+
+/// Futures run: simulate cost‐free on the back‐adjusted series, then charge
+/// fees, slippage and roll costs in raw contract prices
+fn run_rolled(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    schedule: &RollSchedule,
+) -> BacktestResult {
+    let offsets = adjustment_offsets(schedule, &market.open);
+    let (adj_market, adj_signals) = back_adjust(market, signals, &offsets);
+    let free = BacktestConfig {
+        entry_fee_rate: 0.0,
+        exit_fee_rate:  0.0,
+        maker_fee_rate: None,
+        slippage_rate:  0.0,
+        slippage_sl:    None,
+        slippage_tp:    None,
+        slippage_exp:   None,
+        rolls:          None,
+        ..config.clone()
+    };
+
+    let mut skipped = Vec::new();
+    let mut positions = simulate(&adj_market, &adj_signals, &free, &mut skipped);
+    apply_roll_accounting(&mut positions, schedule, &offsets, &market.open, config);
+    if config.throttle.is_some() || config.account.is_active() {
+        positions = run_event_loop(positions, &adj_market.close, config, &mut skipped);
+    }
+    // mark on the continuous series, report in contract prices
+    let exposure = compute_exposure_series_with(
+        &positions, &adj_market.close, &market.timestamps, config.initial_equity, config.leverage(), config.exposure_options(),
+    );
+    let gross = config.gross_metrics.then(|| gross_headline(&positions, &adj_market.close, &market.timestamps, config));
+    // excursions are price differences, unchanged by the adjustment
+    record_excursions(&mut positions, &adj_market.high, &adj_market.low);
+    if let Some(model) = &config.liquidation {
+        record_liquidation(&mut positions, &adj_market.high, &adj_market.low, config.leverage(), model);
+    }
+    // the last bar is unadjusted, so the mark is already in contract prices
+    let marks = mark_open_positions(&positions, &adj_market.close);
+    to_raw_prices(&mut positions, &offsets);
+    let mut result = finish(market, positions, marks, exposure, config);
+    result.metrics.gross = gross;
+    result.skipped = skipped;
+    result
+}
+
+fn run_continuous(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+) -> BacktestResult {
+    let mut skipped = Vec::new();
+    let entries = scan(market, signals, config, &mut skipped);
+    run_scanned(market, signals, config, entries, skipped)
+}
+
+/// Continuous run from already scanned entries (and the signals the scan
+/// skipped)
+pub(crate) fn run_scanned(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    entries: Vec<Position>,
+    mut skipped: Vec<SkippedEntry>,
+) -> BacktestResult {
+    let mut positions = simulate_scanned(market, signals, config, entries, &mut skipped);
+    let marks = config.marks(market);
+    if config.throttle.is_some() || config.account.is_active() {
+        positions = run_event_loop(positions, marks, config, &mut skipped);
+    }
+    let mut exposure = compute_exposure_series_with(
+        &positions, marks, &market.timestamps, config.initial_equity, config.leverage(), config.exposure_options(),
+    );
+
+    let breach = config
+        .trailing_drawdown
+        .and_then(|rule| find_breach(&exposure, config.initial_equity, &rule));
+    if let Some(b) = &breach {
+        stop_at_breach(&mut positions, b.bar, market, config);
+        if let Some(rates) = &config.funding_rates {
+            apply_funding(&mut positions, &market.close, rates);
+        }
+        if let Some(divs) = &config.dividends {
+            apply_dividends(&mut positions, divs);
+        }
+        exposure = compute_exposure_series_with(
+            &positions, marks, &market.timestamps, config.initial_equity, config.leverage(), config.exposure_options(),
+        );
+    }
+    if let Some(rates) = &config.fx_rates {
+        convert_positions(&mut positions, rates);
+        exposure = compute_exposure_series_with(
+            &positions, marks, &market.timestamps, config.initial_equity, config.leverage(), config.exposure_options(),
+        );
+        convert_exposure(&mut exposure, rates, config.initial_equity);
+    }
+
+    let gross = config.gross_metrics.then(|| gross_headline(&positions, marks, &market.timestamps, config));
+    record_excursions(&mut positions, &market.high, &market.low);
+    if let Some(model) = &config.liquidation {
+        // a mark series triggers liquidation on its own, not on traded extremes
+        let (high, low) = match &config.mark_price {
+            Some(mark) => (mark, mark),
+            None       => (&market.high, &market.low),
+        };
+        record_liquidation(&mut positions, high, low, config.leverage(), model);
+    }
+    let mut open_marks = mark_open_positions(&positions, marks);
+    if let Some(fx) = config.fx_rates.as_ref().and_then(|r| r.last()) {
+        open_marks.iter_mut().for_each(|m| m.unrealized_pnl *= fx);
+    }
+    let mut result = finish(market, positions, open_marks, exposure, config);
+    result.metrics.gross = gross;
+    result.breach  = breach;
+    result.skipped = skipped;
+    result
+}
+
+/// Entries + exits; refused entries are appended to `skipped`
+fn simulate(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    skipped: &mut Vec<SkippedEntry>,
+) -> Vec<Position> {
+    let entries = scan(market, signals, config, skipped);
+    simulate_scanned(market, signals, config, entries, skipped)
+}
+
+/// Entries of a run: pending positions with their levels, not yet exited
+pub(crate) fn scan(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    skipped: &mut Vec<SkippedEntry>,
+) -> Vec<Position> {
+    // 1) Entries
+    let mut positions = scan_entries(
+        0..market.open.len(),
+        &market.timestamps,
+        &market.open,
+        &signals.long_signals, &signals.short_signals,
+        &signals.long_tp, &signals.long_sl,
+        &signals.short_tp, &signals.short_sl,
+        &signals.long_size, &signals.short_size,
+        &signals.expiration_times,
+        config.entry_fee_rate,
+        config.slippage_rate,
+        config.contract,
+        config.size_mode,
+        config.latency,
+        config.loss_cap,
+        config.filters,
+        skipped,
+    );
+    if let Some(available) = &config.borrow_available {
+        positions = apply_borrow_limits(positions, available, skipped);
+    }
+
+    if let Some(tags) = &signals.tags {
+        for pos in &mut positions {
+            pos.tag = tags[pos.signal_index].clone();
+        }
+    }
+    positions
+}
+
+/// Exits, progressive fills, funding and dividends of scanned entries
+fn simulate_scanned(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    mut positions: Vec<Position>,
+    skipped: &mut Vec<SkippedEntry>,
+) -> Vec<Position> {
+    // 2) Exits
+    simulate_position_exits(
+        &mut positions,
+        &market.timestamps, &market.open, &market.high, &market.low, &market.close,
+        config.exit_fees(), config.exit_slippage(),
+        config.latency, config.fill_model, config.queue_model.as_ref(), config.filters, config.exit_rules.as_ref(),
+        config.sub_bars.as_ref(), config.expiry_fill, config.expiry_policy, config.exit_priority, config.level_check, config.stop_type,
+    );
+
+    if config.signal_mode == SignalMode::Reverse {
+        if let Some(direction) = &signals.direction {
+            close_on_signal(&mut positions, direction, market, config);
+        }
+    }
+
+    // 3) Progressive entry fills
+    let mut positions = match &config.partial_fills {
+        Some(model) => apply_partial_fills(positions, market, model, config, skipped),
+        None        => positions,
+    };
+
+    // 4) Funding and dividends while open
+    if let Some(rates) = &config.funding_rates {
+        apply_funding(&mut positions, &market.close, rates);
+    }
+    if let Some(divs) = &config.dividends {
+        apply_dividends(&mut positions, divs);
+    }
+    positions
+}
+
+/// Overall headline metrics of `positions` re‐marked without costs
+fn gross_headline(positions: &[Position], close: &[f64], timestamps: &[f64], config: &BacktestConfig) -> HeadlineMetrics {
+    let gross = without_costs(positions);
+    let exposure = compute_exposure_series(&gross, close, timestamps, config.initial_equity, config.leverage());
+    let closed: Vec<Position> = gross.into_iter().filter(|p| p.is_closed).collect();
+    HeadlineMetrics::of(&compute_summary_metrics_with(config.initial_equity, &closed, &exposure, config.ratio_policy, config.returns).overall)
+}
+
+/// Metrics over simulated positions and their exposure curve
+pub(crate) fn finish(
+    market: &MarketData,
+    positions: Vec<Position>,
+    open_marks: Vec<OpenMark>,
+    exposure: Vec<ExposureSnapshot>,
+    config: &BacktestConfig,
+) -> BacktestResult {
+    // 3) Metrics
+    let concurrency = concurrency_stats(&positions, &exposure, config.overlap_threshold.unwrap_or(1));
+    let (closed, open): (Vec<Position>, Vec<Position>) =
+        positions.into_iter().partition(|p| p.is_closed);
+    let equity = account_equity(&exposure, config.initial_equity);
+    let account = config.cash_flows.as_ref().map(|flows| account_value(&equity, flows));
+    let mut metrics = run_metrics(&market.timestamps, &closed, &exposure, &equity, account.as_deref(), config);
+    metrics.concurrency = Some(concurrency);
+
+    let trade_equity = compute_trade_close_curve(&closed, &market.timestamps, config.initial_equity);
+    let resampled = Frequency::ALL
+        .iter()
+        .map(|&f| resample(&market.timestamps, &equity, config.initial_equity, f, config.ratio_policy))
+        .collect();
+    BacktestResult {
+        closed, open, open_marks, exposure, equity, account_value: account, metrics,
+        equity_base: config.returns.basis.base(config.initial_equity), breach: None, skipped: Vec::new(), trade_equity,
+        warnings: Vec::new(), invariant_violations: None, resampled,
+    }
+}
+
+/// Summary metrics of closed trades over their exposure curve, with every
+/// breakdown `config` asks for (concurrency is left to the caller, which
+/// also sees the open positions)
+pub(crate) fn run_metrics(
+    timestamps: &[f64],
+    closed: &[Position],
+    exposure: &[ExposureSnapshot],
+    equity: &[f64],
+    account: Option<&[f64]>,
+    config: &BacktestConfig,
+) -> SummaryMetrics {
+    let mut metrics = compute_summary_metrics_with(
+        config.initial_equity, closed, exposure, config.ratio_policy, config.returns,
+    );
+    if let Some(regimes) = &config.regimes {
+        metrics.by_regime = regime_breakdown(
+            regimes, closed, exposure, &metrics.overall.time_metrics.returns, config.ratio_policy,
+        );
+    }
+    if let Some(bench) = &config.benchmark {
+        metrics.benchmark = Some(benchmark_metrics(equity, bench, config.ratio_policy));
+    }
+    if !config.factors.is_empty() {
+        let returns = bar_returns(equity);
+        metrics.factor_betas = config
+            .factors
+            .iter()
+            .map(|(name, f)| factor_beta(name, &returns, f, config.beta_window))
+            .collect();
+    }
+
+    let edges = config.duration_edges.clone().unwrap_or_else(|| default_edges(timestamps));
+    metrics.by_duration = duration_buckets(closed, timestamps, &edges);
+    if let (Some(account), Some(flows)) = (account, &config.cash_flows) {
+        metrics.cash_flows = Some(summarize(account, flows, config.initial_equity));
+    }
+    if config.weighted_returns {
+        metrics.weighted = Some(weighted_returns(
+            timestamps,
+            account.unwrap_or(equity),
+            config.initial_equity,
+            config.cash_flows.as_deref(),
+        ));
+    }
+    if let Some(lags) = &config.autocorr_lags {
+        let ac = autocorrelations(&metrics.overall.time_metrics.returns, lags);
+        for side in [&mut metrics.overall, &mut metrics.longs, &mut metrics.shorts] {
+            side.time_metrics.autocorrelation = ac.clone();
+        }
+    }
+
+    // Optional bootstrap CIs (one curve, shared by every side)
+    if let Some(opts) = &config.bootstrap {
+        let years = span_years(timestamps);
+        let ci = block_bootstrap(&metrics.overall.time_metrics.returns, years, opts);
+        metrics.longs.time_metrics.bootstrap  = Some(ci.clone());
+        metrics.shorts.time_metrics.bootstrap = Some(ci.clone());
+        metrics.overall.time_metrics.bootstrap = Some(ci);
+    }
+    metrics
+}
@@ -0,0 +1,94 @@
+// src/engine/benchmark.rs
+
+use crate::engine::{
+    exposure::ExposureSnapshot,
+    metrics::RatioPolicy,
+};
+
+/// Strategy vs a benchmark price series over the same bars
+#[derive(Debug, Clone)]
+pub struct BenchmarkMetrics {
+    /// Buy‐and‐hold return of the benchmark
+    pub benchmark_return:      f64,
+    /// Account return (initial equity + PnL) of the strategy
+    pub strategy_return:       f64,
+    pub excess_return:         f64,
+    /// Geometric mean strategy / benchmark return over the benchmark's up bars
+    pub up_capture:            f64,
+    /// Same over the benchmark's down bars
+    pub down_capture:          f64,
+    pub up_bars:               usize,
+    pub down_bars:             usize,
+    /// Deepest drawdown of the strategy / benchmark wealth ratio
+    pub max_relative_drawdown: f64,
+    /// Largest (strategy − benchmark) underwater difference on one bar
+    pub max_underwater_gap:    f64,
+}
+
+/// Simple bar‐to‐bar returns of a positive series
+pub fn bar_returns(xs: &[f64]) -> Vec<f64> {
+    xs.windows(2).map(|w| if w[0] != 0.0 { w[1] / w[0] - 1.0 } else { 0.0 }).collect()
+}
+
+/// Account equity (initial equity + PnL) per bar
+pub fn account_equity(exposure: &[ExposureSnapshot], initial_equity: f64) -> Vec<f64> {
+    exposure.iter().map(|s| initial_equity + s.total_equity).collect()
+}
+
+/// Drawdown from the running peak at each bar, as a fraction of the peak
+pub fn underwater(xs: &[f64]) -> Vec<f64> {
+    let mut peak = f64::NEG_INFINITY;
+    xs.iter()
+        .map(|&x| {
+            peak = peak.max(x);
+            if peak > 0.0 { (peak - x) / peak } else { 0.0 }
+        })
+        .collect()
+}
+
+/// Geometric mean per‐bar return of the selected bars
+fn geometric_mean(returns: &[f64], bars: &[usize]) -> f64 {
+    if bars.is_empty() {
+        return 0.0;
+    }
+    let log_sum: f64 = bars.iter().map(|&i| (1.0 + returns[i]).ln()).sum();
+    (log_sum / bars.len() as f64).exp() - 1.0
+}
+
+pub fn benchmark_metrics(equity: &[f64], benchmark: &[f64], policy: RatioPolicy) -> BenchmarkMetrics {
+    let total = |xs: &[f64]| match (xs.first(), xs.last()) {
+        (Some(&a), Some(&b)) if a != 0.0 => b / a - 1.0,
+        _ => 0.0,
+    };
+    let strat_r = bar_returns(equity);
+    let bench_r = bar_returns(benchmark);
+    let up:   Vec<usize> = (0..bench_r.len()).filter(|&i| bench_r[i] > 0.0).collect();
+    let down: Vec<usize> = (0..bench_r.len()).filter(|&i| bench_r[i] < 0.0).collect();
+    let capture = |bars: &[usize]| {
+        policy.ratio(geometric_mean(&strat_r, bars), geometric_mean(&bench_r, bars), 0.0)
+    };
+
+    let relative: Vec<f64> = equity
+        .iter()
+        .zip(benchmark)
+        .map(|(&e, &b)| if b != 0.0 { e / b } else { 0.0 })
+        .collect();
+    let gap = underwater(equity)
+        .into_iter()
+        .zip(underwater(benchmark))
+        .map(|(s, b)| s - b)
+        .fold(0.0, f64::max);
+
+    let (strategy_return, benchmark_return) = (total(equity), total(benchmark));
+    BenchmarkMetrics {
+        benchmark_return,
+        strategy_return,
+        excess_return:         strategy_return - benchmark_return,
+        up_capture:            capture(&up),
+        down_capture:          capture(&down),
+        up_bars:               up.len(),
+        down_bars:             down.len(),
+        max_relative_drawdown: underwater(&relative).into_iter().fold(0.0, f64::max),
+        max_underwater_gap:    gap,
+    }
+}
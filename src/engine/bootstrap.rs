@@ -0,0 +1,102 @@
+// src/engine/bootstrap.rs
+
+use crate::engine::par::*;
+
+use crate::engine::{
+    rng::Rng,
+    stats::{mean, summarize, Distribution},
+};
+
+pub(crate) const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 3600.0;
+
+/// Block‐bootstrap settings for the bar‐return series
+#[derive(Clone, Debug)]
+pub struct BootstrapOptions {
+    pub n_samples:    usize,
+    /// Bars per block; `None` → ⌈n^(1/3)⌉
+    pub block_length: Option<usize>,
+    pub seed:         u64,
+    pub confidence:   f64,
+}
+
+/// Confidence intervals for the headline time‐series metrics
+#[derive(Clone, Debug)]
+pub struct BootstrapIntervals {
+    pub n_samples:    usize,
+    pub block_length: usize,
+    pub seed:         u64,
+    pub confidence:   f64,
+    pub sharpe_ratio: Distribution,
+    pub cagr:         Distribution,
+    pub max_drawdown: Distribution,
+}
+
+/// Length of the bar series in years (timestamps in seconds)
+pub fn span_years(timestamps: &[f64]) -> f64 {
+    match (timestamps.first(), timestamps.last()) {
+        (Some(a), Some(b)) => (b - a) / SECONDS_PER_YEAR,
+        _ => 0.0,
+    }
+}
+
+pub(crate) fn sharpe(returns: &[f64]) -> f64 {
+    let m = returns.len() as f64;
+    if m < 2.0 {
+        return 0.0;
+    }
+    let mu  = mean(returns);
+    let vol = (returns.iter().map(|&x| (x - mu).powi(2)).sum::<f64>() / (m - 1.0)).sqrt();
+    if vol != 0.0 { mu / vol } else { 0.0 }
+}
+
+/// CAGR + max drawdown of the curve obtained by compounding `returns` from 1
+pub(crate) fn compound_stats(returns: &[f64], years: f64) -> (f64, f64) {
+    let mut eq     = 1.0_f64;
+    let mut peak   = 1.0_f64;
+    let mut max_dd = 0.0_f64;
+    for &r in returns {
+        eq  *= 1.0 + r;
+        peak = peak.max(eq);
+        let dd = if peak != 0.0 { (peak - eq) / peak } else { 0.0 };
+        max_dd = max_dd.max(dd);
+    }
+    let cagr = if years > 0.0 && eq > 0.0 { eq.powf(1.0 / years) - 1.0 } else { 0.0 };
+    (cagr, max_dd)
+}
+
+/// Circular block bootstrap of `returns`: each sample concatenates blocks of
+/// `block_length` consecutive bars starting at random offsets (wrapping at the
+/// end) until it matches the original length.
+pub fn block_bootstrap(returns: &[f64], years: f64, opts: &BootstrapOptions) -> BootstrapIntervals {
+    let n = returns.len();
+    let block = opts
+        .block_length
+        .unwrap_or_else(|| (n as f64).cbrt().ceil() as usize)
+        .clamp(1, n.max(1));
+
+    let samples: Vec<(f64, f64, f64)> = (0..opts.n_samples)
+        .into_par_iter()
+        .map(|k| {
+            let mut rng    = Rng::fork(opts.seed, k as u64);
+            let mut sample = Vec::with_capacity(n);
+            while sample.len() < n {
+                let start = rng.below(n);
+                for j in 0..block.min(n - sample.len()) {
+                    sample.push(returns[(start + j) % n]);
+                }
+            }
+            let (cagr, max_dd) = compound_stats(&sample, years);
+            (sharpe(&sample), cagr, max_dd)
+        })
+        .collect();
+
+    BootstrapIntervals {
+        n_samples:    opts.n_samples,
+        block_length: block,
+        seed:         opts.seed,
+        confidence:   opts.confidence,
+        sharpe_ratio: summarize(samples.iter().map(|s| s.0).collect(), opts.confidence),
+        cagr:         summarize(samples.iter().map(|s| s.1).collect(), opts.confidence),
+        max_drawdown: summarize(samples.iter().map(|s| s.2).collect(), opts.confidence),
+    }
+}
@@ -0,0 +1,55 @@
+// src/engine/borrow.rs
+
+use crate::engine::{
+    backtest::SkippedEntry,
+    position::Position,
+};
+
+/// Drop short entries the instrument can't be borrowed for on their fill
+/// bar.  `available[t]` is the units that can be borrowed (∞ when unlimited,
+/// 0 when not shortable); refused entries are reported as `not_shortable`
+/// or `borrow_limit`.
+pub fn apply_borrow_limits(
+    positions: Vec<Position>,
+    available: &[f64],
+    skipped: &mut Vec<SkippedEntry>,
+) -> Vec<Position> {
+    positions
+        .into_iter()
+        .filter(|pos| match borrow_refusal(pos, available) {
+            Some(reason) => {
+                skipped.push(SkippedEntry::from_position(pos, reason));
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Why a short entry can't be borrowed for at its size (None for longs and
+/// borrowable shorts)
+pub fn borrow_refusal(pos: &Position, available: &[f64]) -> Option<&'static str> {
+    if pos.position_type != "short" {
+        return None;
+    }
+    let avail = available[pos.entry_index];
+    if avail.is_nan() || avail <= 0.0 {
+        Some("not_shortable")
+    } else if pos.position_size > avail {
+        Some("borrow_limit")
+    } else {
+        None
+    }
+}
+
+/// Per‐bar borrowable units from a `shortable` flag and/or an availability
+/// series (the smaller wins)
+pub fn borrow_availability(shortable: Option<&[bool]>, available: Option<&[f64]>, n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|t| {
+            let flag = shortable.map_or(f64::INFINITY, |s| if s[t] { f64::INFINITY } else { 0.0 });
+            let units = available.map_or(f64::INFINITY, |a| a[t]);
+            flag.min(units)
+        })
+        .collect()
+}
@@ -0,0 +1,247 @@
+// src/engine/bracket.rs
+
+//! Exit brackets.
+//!
+//! A position's take‐profit and stop‐loss are two sibling child orders of
+//! its entry, one‐cancels‐the‐other: the exit simulator asks the legs
+//! whether they trade on a bar, and whichever fills cancels its sibling.
+//! Each leg keeps its own order type, size and amendment history, so an
+//! exit rule moving only the stop amends only the stop leg, and partial
+//! entry fills resize both legs to the filled size.
+//!
+//! `Position::tp` / `Position::sl` stay the working prices the exit rules
+//! read and move; the legs follow them until they stop working.
+
+use crate::engine::{liquidity::Liquidity, position::Position};
+
+/// Order type of the stop‐loss leg
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StopType {
+    /// Stop‐market filling at the stop level once touched (the historical
+    /// behaviour)
+    #[default]
+    Stop,
+    /// Stop‐limit: touching the stop works a limit `offset` beyond it.  A bar
+    /// opening past the limit leaves the leg resting until price trades back
+    /// to the limit, which may be never
+    StopLimit { offset: f64 },
+}
+
+impl StopType {
+    pub fn parse(s: &str, limit_offset: Option<f64>) -> Result<Self, String> {
+        match (s, limit_offset) {
+            ("stop", _) => Ok(StopType::Stop),
+            ("stop_limit", Some(offset)) if offset.is_finite() && offset >= 0.0 => Ok(StopType::StopLimit { offset }),
+            ("stop_limit", _) => Err("sl_order_type=\"stop_limit\" needs a finite sl_limit_offset ≥ 0".into()),
+            (other, _) => Err(format!("sl_order_type must be \"stop\" or \"stop_limit\", got ‘{}’", other)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StopType::Stop => "stop",
+            StopType::StopLimit { .. } => "stop_limit",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegStatus {
+    Working,
+    /// Stop‐limit whose stop was touched; its limit is resting
+    Triggered,
+    Filled,
+    Canceled,
+}
+
+impl LegStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LegStatus::Working   => "open",
+            LegStatus::Triggered => "triggered",
+            LegStatus::Filled    => "filled",
+            LegStatus::Canceled  => "canceled",
+        }
+    }
+}
+
+/// Price and size of a leg after it was amended on `bar`
+#[derive(Clone, Debug)]
+pub struct LegAmendment {
+    pub bar:   usize,
+    pub price: f64,
+    pub size:  f64,
+}
+
+/// One child order of the bracket
+#[derive(Clone, Debug)]
+pub struct Leg {
+    /// "take_profit" or "stop_loss"
+    pub purpose:     &'static str,
+    /// "limit", "stop" or "stop_limit"
+    pub order_type:  &'static str,
+    /// Limit price of the take‐profit, trigger of the stop
+    pub price:       f64,
+    /// Limit of a stop‐limit (NaN otherwise)
+    pub limit_price: f64,
+    pub size:        f64,
+    pub status:      LegStatus,
+    pub amendments:  Vec<LegAmendment>,
+    /// Bar the leg filled or was cancelled on
+    pub done_bar:    Option<usize>,
+}
+
+/// Take‐profit and stop‐loss legs of one position
+#[derive(Clone, Debug)]
+pub struct Bracket {
+    pub take_profit: Leg,
+    pub stop_loss:   Leg,
+    long:            bool,
+    stop_type:       StopType,
+}
+
+impl Bracket {
+    /// Both legs working at the position's levels and size
+    pub fn new(pos: &Position, stop_type: StopType) -> Self {
+        let long = pos.position_type == "long";
+        let leg = |purpose, order_type, price| Leg {
+            purpose,
+            order_type,
+            price,
+            limit_price: f64::NAN,
+            size: pos.position_size,
+            status: LegStatus::Working,
+            amendments: Vec::new(),
+            done_bar: None,
+        };
+        let mut bracket = Bracket {
+            take_profit: leg("take_profit", "limit", pos.tp),
+            stop_loss:   leg("stop_loss", stop_type.name(), pos.sl),
+            long,
+            stop_type,
+        };
+        bracket.stop_loss.limit_price = bracket.limit_for(pos.sl);
+        bracket
+    }
+
+    fn limit_for(&self, stop: f64) -> f64 {
+        match self.stop_type {
+            StopType::Stop => f64::NAN,
+            StopType::StopLimit { offset } => if self.long { stop - offset } else { stop + offset },
+        }
+    }
+
+    /// Move working legs to `tp` / `sl`; only a leg whose price changed
+    /// records an amendment (a triggered stop‐limit no longer moves)
+    pub fn amend(&mut self, bar: usize, tp: f64, sl: f64) {
+        let sl_limit = self.limit_for(sl);
+        for (leg, price, limit) in [
+            (&mut self.take_profit, tp, f64::NAN),
+            (&mut self.stop_loss, sl, sl_limit),
+        ] {
+            if leg.status != LegStatus::Working || leg.price.to_bits() == price.to_bits() {
+                continue;
+            }
+            leg.price = price;
+            leg.limit_price = limit;
+            leg.amendments.push(LegAmendment { bar, price, size: leg.size });
+        }
+    }
+
+    /// Size both legs to the entry's cumulative fills: the first fill sets
+    /// the initial size and every later fill amends it
+    pub fn follow_fills(&mut self, fills: &[(usize, f64)]) {
+        for leg in [&mut self.take_profit, &mut self.stop_loss] {
+            let mut filled = 0.0;
+            for (k, &(bar, size)) in fills.iter().enumerate() {
+                filled += size;
+                if k == 0 {
+                    leg.size = filled;
+                } else {
+                    leg.amendments.push(LegAmendment { bar, price: leg.price, size: filled });
+                }
+            }
+            for a in &mut leg.amendments {
+                a.size = a.size.min(filled);
+            }
+            leg.amendments.sort_by_key(|a| a.bar);
+        }
+    }
+
+    /// Resize every leg (and its history) by `factor`
+    pub fn scale(&mut self, factor: f64) {
+        for leg in [&mut self.take_profit, &mut self.stop_loss] {
+            leg.size *= factor;
+            leg.amendments.iter_mut().for_each(|a| a.size *= factor);
+        }
+    }
+
+    /// Shift every price by −`delta` (back‐adjusted → contract prices)
+    pub fn shift(&mut self, delta: f64) {
+        for leg in [&mut self.take_profit, &mut self.stop_loss] {
+            leg.price -= delta;
+            leg.limit_price -= delta;
+            leg.amendments.iter_mut().for_each(|a| a.price -= delta);
+        }
+    }
+
+    /// Whether the take‐profit limit trades on a bar with this range
+    pub fn take_profit_touched(&self, high: f64, low: f64) -> bool {
+        let tp = self.take_profit.price;
+        if self.long { high >= tp } else { low <= tp }
+    }
+
+    /// Size the working take‐profit would close (0 without a take‐profit)
+    pub fn protected_size(&self) -> f64 {
+        let leg = &self.take_profit;
+        if leg.status == LegStatus::Working && leg.price.is_finite() { leg.size } else { 0.0 }
+    }
+
+    /// Fill price of the stop leg on a bar, if it trades.  A stop‐limit
+    /// touched on a bar that did not open past its limit fills at the stop
+    /// like a stop‐market; otherwise its limit rests and fills once a bar
+    /// trades back to it.
+    pub fn stop_fill(&mut self, open: f64, high: f64, low: f64) -> Option<f64> {
+        let long = self.long;
+        let leg = &mut self.stop_loss;
+        // `price` at or beyond `level` on the losing side
+        let reached = |price: f64, level: f64| if long { price <= level } else { price >= level };
+        let (adverse, favorable) = if long { (low, high) } else { (high, low) };
+        match self.stop_type {
+            StopType::Stop => reached(adverse, leg.price).then_some(leg.price),
+            StopType::StopLimit { .. } => {
+                if leg.status == LegStatus::Working {
+                    if !reached(adverse, leg.price) {
+                        return None;
+                    }
+                    leg.status = LegStatus::Triggered;
+                    let gapped = open != leg.limit_price && reached(open, leg.limit_price);
+                    if !gapped {
+                        return Some(leg.price);
+                    }
+                }
+                let traded_back = favorable == leg.limit_price || !reached(favorable, leg.limit_price);
+                traded_back.then_some(leg.limit_price)
+            }
+        }
+    }
+
+    /// Liquidity of a stop fill at `price`: a stop‐limit filling at its limit
+    /// rested there, anything else trades at market
+    pub fn stop_liquidity(&self, price: f64) -> Liquidity {
+        let leg = &self.stop_loss;
+        let rested = matches!(self.stop_type, StopType::StopLimit { offset } if offset > 0.0)
+            && price.to_bits() == leg.limit_price.to_bits();
+        if rested { Liquidity::Maker } else { Liquidity::Taker }
+    }
+
+    /// One‐cancels‐the‐other: a "TP" / "SL" exit fills its leg and cancels
+    /// the sibling; any other exit cancels both
+    pub fn settle(&mut self, condition: &str, bar: usize) {
+        for leg in [&mut self.take_profit, &mut self.stop_loss] {
+            let filled = matches!((condition, leg.purpose), ("TP", "take_profit") | ("SL", "stop_loss"));
+            leg.status = if filled { LegStatus::Filled } else { LegStatus::Canceled };
+            leg.done_bar = Some(bar);
+        }
+    }
+}
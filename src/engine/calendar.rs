@@ -0,0 +1,154 @@
+// src/engine/calendar.rs
+
+use crate::engine::{
+    bootstrap::span_years,
+    metrics::RatioPolicy,
+    stats::mean,
+};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Calendar period an equity curve is resampled to (UTC, timestamps in
+/// seconds since the epoch; weeks start on Monday)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 3] = [Frequency::Daily, Frequency::Weekly, Frequency::Monthly];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Frequency::Daily   => "daily",
+            Frequency::Weekly  => "weekly",
+            Frequency::Monthly => "monthly",
+        }
+    }
+
+    /// Period index of a timestamp
+    fn key(self, ts: f64) -> i64 {
+        let day = (ts / SECONDS_PER_DAY).floor() as i64;
+        match self {
+            Frequency::Daily   => day,
+            // 1970‐01‐01 was a Thursday
+            Frequency::Weekly  => (day + 3).div_euclid(7),
+            Frequency::Monthly => {
+                let (y, m, _) = civil_from_days(day);
+                y * 12 + (m as i64 - 1)
+            }
+        }
+    }
+
+    /// Timestamp at which period `key` starts
+    fn start(self, key: i64) -> f64 {
+        let day = match self {
+            Frequency::Daily   => key,
+            Frequency::Weekly  => key * 7 - 3,
+            Frequency::Monthly => days_from_civil(key.div_euclid(12), key.rem_euclid(12) as u32 + 1, 1),
+        };
+        day as f64 * SECONDS_PER_DAY
+    }
+}
+
+/// (year, month, day) of a day count since 1970‐01‐01 (proleptic Gregorian)
+pub fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp  = (5 * doy + 2) / 153;
+    let d   = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m   = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y   = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+/// Day count since 1970‐01‐01 of a (year, month, day)
+pub fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y   = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp  = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Account equity sampled at each period's last bar, with period returns
+/// and stats annualized by the periods observed per year of covered periods
+#[derive(Debug, Clone)]
+pub struct ResampledCurve {
+    pub frequency:             Frequency,
+    /// Start of each period
+    pub timestamps:            Vec<f64>,
+    /// Equity at the period's last bar
+    pub equity:                Vec<f64>,
+    /// Period return vs the previous period's close (the first vs the
+    /// initial equity)
+    pub returns:               Vec<f64>,
+    pub periods_per_year:      f64,
+    pub annualized_return:     f64,
+    pub annualized_volatility: f64,
+    pub sharpe_ratio:          f64,
+}
+
+pub fn resample(
+    timestamps: &[f64],
+    equity: &[f64],
+    initial_equity: f64,
+    frequency: Frequency,
+    policy: RatioPolicy,
+) -> ResampledCurve {
+    let mut starts = Vec::new();
+    let mut closes = Vec::new();
+    let mut last_key = None;
+    for (&ts, &eq) in timestamps.iter().zip(equity) {
+        let key = frequency.key(ts);
+        if last_key == Some(key) {
+            *closes.last_mut().unwrap() = eq;
+        } else {
+            starts.push(frequency.start(key));
+            closes.push(eq);
+            last_key = Some(key);
+        }
+    }
+
+    let mut prev = initial_equity;
+    let returns: Vec<f64> = closes
+        .iter()
+        .map(|&eq| {
+            let r = if prev != 0.0 { eq / prev - 1.0 } else { 0.0 };
+            prev = eq;
+            r
+        })
+        .collect();
+
+    // periods observed over the whole periods they cover (weekend‐free
+    // daily data gives ~260/year, 24/7 data ~365)
+    let years = match (starts.first(), last_key) {
+        (Some(&first), Some(key)) => span_years(&[first, frequency.start(key + 1)]),
+        _ => 0.0,
+    };
+    let periods_per_year = if years > 0.0 { returns.len() as f64 / years } else { 0.0 };
+    let mu  = mean(&returns);
+    let vol = if returns.len() > 1 {
+        (returns.iter().map(|r| (r - mu).powi(2)).sum::<f64>() / (returns.len() - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+
+    ResampledCurve {
+        frequency,
+        timestamps: starts,
+        equity: closes,
+        annualized_return:     mu * periods_per_year,
+        annualized_volatility: vol * periods_per_year.sqrt(),
+        sharpe_ratio:          policy.ratio(mu, vol, 0.0) * periods_per_year.sqrt(),
+        periods_per_year,
+        returns,
+    }
+}
@@ -0,0 +1,49 @@
+// src/engine/cash_flows.rs
+
+//! External deposits and withdrawals.
+//!
+//! `cash_flows[t]` (deposits positive) is booked at bar t's close.  Flows
+//! move the account value and, through it, equity‐dependent sizing
+//! (`compounding`, `cash_constrained`), but they are not performance: the
+//! PnL‐based equity curve and every return metric exclude them, and the
+//! time‐weighted return takes each bar's flow out before linking.
+
+/// Flows over a run, kept apart from trading PnL
+#[derive(Clone, Debug)]
+pub struct FlowSummary {
+    pub deposits:            f64,
+    /// Total withdrawn (a positive amount)
+    pub withdrawals:         f64,
+    pub net_flows:           f64,
+    pub final_account_value: f64,
+    /// Final account value − initial equity − net flows
+    pub trading_pnl:         f64,
+}
+
+/// Account value at every bar close: `equity` (initial equity + PnL) plus
+/// the flows booked so far
+pub fn account_value(equity: &[f64], flows: &[f64]) -> Vec<f64> {
+    let mut cum = 0.0;
+    equity
+        .iter()
+        .zip(flows)
+        .map(|(e, f)| {
+            cum += f;
+            e + cum
+        })
+        .collect()
+}
+
+pub fn summarize(account: &[f64], flows: &[f64], initial_equity: f64) -> FlowSummary {
+    let deposits: f64 = flows.iter().filter(|f| **f > 0.0).sum();
+    let withdrawals: f64 = -flows.iter().filter(|f| **f < 0.0).sum::<f64>();
+    let net_flows = deposits - withdrawals;
+    let final_account_value = account.last().copied().unwrap_or(initial_equity);
+    FlowSummary {
+        deposits,
+        withdrawals,
+        net_flows,
+        final_account_value,
+        trading_pnl: final_account_value - initial_equity - net_flows,
+    }
+}
@@ -0,0 +1,82 @@
+// src/engine/concurrency.rs
+
+use crate::engine::{exposure::ExposureSnapshot, position::Position};
+
+/// How many positions overlapped, and what the overlap earned
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyStats {
+    /// Most positions alive within one bar
+    pub max_concurrent:  usize,
+    /// Mean positions alive per bar
+    pub mean_concurrent: f64,
+    /// `(count, bars)`: bars with exactly `count` positions alive, ascending
+    pub distribution:    Vec<(usize, usize)>,
+    /// The K in `pnl_above_threshold`
+    pub threshold:       usize,
+    /// Equity change over bars with more than `threshold` positions alive
+    pub pnl_above_threshold:   f64,
+    /// That PnL as a share of the total (0 when the total is 0)
+    pub pnl_share_above_threshold: f64,
+    /// Share of bars with more than `threshold` positions alive
+    pub bar_share_above_threshold: f64,
+}
+
+/// Positions alive in each bar: entered at/before it and exiting at/after
+/// it (still‐open positions run to the end)
+pub fn concurrent_counts(positions: &[Position], n: usize) -> Vec<usize> {
+    let mut diff = vec![0i64; n + 1];
+    for pos in positions {
+        if pos.entry_index >= n {
+            continue;
+        }
+        let last = pos.exit_index.unwrap_or(n - 1).min(n - 1);
+        diff[pos.entry_index] += 1;
+        diff[last + 1] -= 1;
+    }
+    let mut alive = 0i64;
+    diff[..n]
+        .iter()
+        .map(|d| {
+            alive += d;
+            alive as usize
+        })
+        .collect()
+}
+
+/// Overlap statistics; each bar's equity change is attributed to the
+/// number of positions alive in that bar
+pub fn concurrency_stats(positions: &[Position], exposure: &[ExposureSnapshot], threshold: usize) -> ConcurrencyStats {
+    let n = exposure.len();
+    if n == 0 {
+        return ConcurrencyStats { threshold, ..Default::default() };
+    }
+    let counts = concurrent_counts(positions, n);
+    let max_concurrent = counts.iter().copied().max().unwrap_or(0);
+
+    let mut distribution = vec![0usize; max_concurrent + 1];
+    for &c in &counts {
+        distribution[c] += 1;
+    }
+
+    let mut prev = 0.0;
+    let mut pnl_above = 0.0;
+    let mut bars_above = 0;
+    for (snap, &c) in exposure.iter().zip(&counts) {
+        if c > threshold {
+            pnl_above  += snap.total_equity - prev;
+            bars_above += 1;
+        }
+        prev = snap.total_equity;
+    }
+    let total = exposure[n - 1].total_equity;
+
+    ConcurrencyStats {
+        max_concurrent,
+        mean_concurrent: counts.iter().sum::<usize>() as f64 / n as f64,
+        distribution: distribution.into_iter().enumerate().filter(|&(_, bars)| bars > 0).collect(),
+        threshold,
+        pnl_above_threshold: pnl_above,
+        pnl_share_above_threshold: if total != 0.0 { pnl_above / total } else { 0.0 },
+        bar_share_above_threshold: bars_above as f64 / n as f64,
+    }
+}
@@ -0,0 +1,608 @@
+// src/engine/config_file.rs
+
+use std::path::{Path, PathBuf};
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+use pyo3::wrap_pyfunction;
+
+/// `run_backtest` keyword arguments a config file may set (scalars and
+/// plain lists; arrays come from the data files)
+pub const RUN_KWARGS: &[&str] = &[
+    "entry_fee_rate", "exit_fee_rate", "slippage_rate", "initial_equity",
+    "bootstrap_samples", "bootstrap_block_length", "bootstrap_seed", "bootstrap_confidence",
+    "contract_type", "latency_bars", "latency_seconds",
+    "fill_probability", "fill_full_depth", "queue_volume_share", "fill_seed",
+    "max_trailing_drawdown", "trailing_drawdown_pct", "throttle_window", "throttle_scale",
+    "max_positions", "cash_constrained", "compounding",
+    "max_loss", "max_loss_mode", "tick_size", "lot_size", "min_notional", "size_mode",
+    "fill_rate", "participation_rate", "slippage_sl", "slippage_tp", "slippage_exp", "maker_fee_rate", "taker_fee_rate",
+    "leverage", "maintenance_margin", "liquidation_buffer", "signal_mode", "sl_decay_bars", "sl_decay_steps", "trail_mult",
+    "gross_metrics", "weighted_returns", "check_invariants", "undefined_ratios", "ratio_cap", "equity_basis", "return_type", "return_equity",
+    "cost_accrual", "net_floating", "expiry_fill", "expiry_policy", "expiry_extension", "exit_priority", "level_check", "sl_order_type", "sl_limit_offset", "beta_window",
+    "autocorr_lags", "duration_buckets", "warmup_bars", "start_time", "end_time",
+    "orders", "timeline", "overlap_threshold", "journal", "journal_label",
+];
+
+/// Per‐bar float arrays `run_backtest` accepts besides the base columns; a
+/// data‐file column with one of these names is passed through
+pub const ARRAY_KWARGS: &[&str] = &[
+    "volume", "roll_prices", "signal_strength", "trail_atr", "funding_rates",
+    "mark_price", "fx_rates", "dividends", "split_factors", "borrow_available",
+    "bid", "ask", "bid_size", "ask_size", "queue_volume", "benchmark",
+];
+
+/// Sections whose keys are `run_backtest` keyword arguments (the split is
+/// only for readability)
+const RUN_SECTIONS: &[&str] = &["costs", "account", "exits", "run"];
+
+/// Parsed config value (the subset shared by TOML and YAML)
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(i)   => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Validated experiment description
+#[derive(Clone, Debug)]
+pub struct ExperimentConfig {
+    /// `run_backtest` keyword arguments, in file order
+    pub run:       Vec<(String, Value)>,
+    /// Bar (+ signal) CSV, resolved against the config file's directory
+    pub bars:      PathBuf,
+    /// Optional separate signal CSV aligned row by row with the bars
+    pub signals:   Option<PathBuf>,
+    pub delimiter: char,
+    /// `grid_search` axes
+    pub grid:      Vec<(String, Vec<f64>)>,
+    /// `grid_search` options (rank_by, ascending, metrics, top_k, n_trials)
+    pub grid_options: Vec<(String, Value)>,
+}
+
+const GRID_OPTIONS: &[&str] = &["rank_by", "ascending", "metrics", "top_k", "n_trials"];
+
+/// Read and validate a `.toml`, `.yaml` or `.yml` experiment file
+pub fn load(path: &Path) -> Result<ExperimentConfig, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let root = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml")         => parse_toml(&text)?,
+        Some("yaml" | "yml") => parse_yaml(&text)?,
+        _ => return Err(format!("{}: config must be .toml, .yaml or .yml", path.display())),
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+    from_table(root, base).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn from_table(root: Vec<(String, Value)>, base: &Path) -> Result<ExperimentConfig, String> {
+    let mut run: Vec<(String, Value)> = Vec::new();
+    let mut data = None;
+    let mut grid = Vec::new();
+    let mut grid_options = Vec::new();
+    for (section, value) in root {
+        let Value::Table(entries) = value else {
+            return Err(format!("top‐level ‘{}’ must be a section", section));
+        };
+        match section.as_str() {
+            s if RUN_SECTIONS.contains(&s) => {
+                for (key, v) in entries {
+                    if !RUN_KWARGS.contains(&key.as_str()) {
+                        return Err(format!("[{}] ‘{}’ is not a run_backtest setting", section, key));
+                    }
+                    if run.iter().any(|(k, _)| *k == key) {
+                        return Err(format!("‘{}’ is set twice", key));
+                    }
+                    run.push((key, v));
+                }
+            }
+            "data" => data = Some(entries),
+            "grid" => {
+                for (key, v) in entries {
+                    if GRID_OPTIONS.contains(&key.as_str()) {
+                        grid_options.push((key, v));
+                        continue;
+                    }
+                    let values = match &v {
+                        Value::Array(items) => items.iter().map(Value::as_f64).collect::<Option<Vec<f64>>>(),
+                        _ => None,
+                    };
+                    grid.push((key.clone(), values.ok_or_else(|| format!("[grid] ‘{}’ must be a list of numbers", key))?));
+                }
+            }
+            other => {
+                return Err(format!(
+                    "unknown section [{}] (expected data, grid or one of {:?})",
+                    other, RUN_SECTIONS
+                ))
+            }
+        }
+    }
+
+    let data = data.ok_or("missing [data] section")?;
+    let mut bars = None;
+    let mut signals = None;
+    let mut delimiter = ',';
+    for (key, v) in data {
+        let s = v.as_str().ok_or_else(|| format!("[data] ‘{}’ must be a string", key))?;
+        match key.as_str() {
+            "bars"      => bars = Some(base.join(s)),
+            "signals"   => signals = Some(base.join(s)),
+            "delimiter" => {
+                let mut chars = s.chars();
+                delimiter = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err("[data] ‘delimiter’ must be one character".into()),
+                };
+            }
+            other => return Err(format!("[data] unknown key ‘{}’ (expected bars, signals, delimiter)", other)),
+        }
+    }
+    Ok(ExperimentConfig {
+        run,
+        bars: bars.ok_or("[data] needs ‘bars’")?,
+        signals,
+        delimiter,
+        grid,
+        grid_options,
+    })
+}
+
+/// Named numeric columns of a delimited file with a header row (`true` /
+/// `false` read as 1 / 0, empty cells as NaN)
+pub fn read_columns(path: &Path, delimiter: char) -> Result<Vec<(String, Vec<f64>)>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let header = lines.next().ok_or_else(|| format!("{} is empty", path.display()))?;
+    let mut cols: Vec<(String, Vec<f64>)> = header
+        .split(delimiter)
+        .map(|h| (h.trim().to_string(), Vec::new()))
+        .collect();
+    for (row, line) in lines.enumerate() {
+        let cells: Vec<&str> = line.split(delimiter).collect();
+        if cells.len() != cols.len() {
+            return Err(format!(
+                "{} row {}: {} cells, header has {}",
+                path.display(), row + 1, cells.len(), cols.len()
+            ));
+        }
+        for ((name, col), cell) in cols.iter_mut().zip(cells) {
+            let cell = cell.trim();
+            let v = match cell.to_ascii_lowercase().as_str() {
+                "" | "nan"    => f64::NAN,
+                "true"        => 1.0,
+                "false"       => 0.0,
+                "inf"         => f64::INFINITY,
+                _ => cell.parse().map_err(|_| {
+                    format!("{} row {} column ‘{}’: ‘{}’ is not a number", path.display(), row + 1, name, cell)
+                })?,
+            };
+            col.push(v);
+        }
+    }
+    Ok(cols)
+}
+
+// ---------------------------------------------------------------------
+// TOML subset: [section] / [section.sub] headers, key = value with basic
+// and literal strings, integers, floats, booleans and (multi‐line) arrays
+
+pub fn parse_toml(text: &str) -> Result<Vec<(String, Value)>, String> {
+    let mut root: Vec<(String, Value)> = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((no, raw)) = lines.next() {
+        let line = strip_comment(raw).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+        let err = |msg: &str| format!("line {}: {}", no + 1, msg);
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header.strip_suffix(']').ok_or_else(|| err("unterminated section header"))?;
+            path = header.split('.').map(|s| s.trim().to_string()).collect();
+            if path.iter().any(String::is_empty) {
+                return Err(err("empty section name"));
+            }
+            table_at(&mut root, &path).map_err(|e| err(&e))?;
+            continue;
+        }
+        let (key, rest) = line.split_once('=').ok_or_else(|| err("expected key = value"))?;
+        let key = unquote(key.trim());
+        let mut rest = rest.trim().to_string();
+        // arrays may span lines until their brackets balance
+        while rest.starts_with('[') && depth(&rest) > 0 {
+            let (_, more) = lines.next().ok_or_else(|| err("unterminated array"))?;
+            rest.push(' ');
+            rest.push_str(strip_comment(more).trim());
+        }
+        let value = parse_scalar_or_array(&rest).map_err(|e| err(&e))?;
+        let table = table_at(&mut root, &path).map_err(|e| err(&e))?;
+        if table.iter().any(|(k, _)| *k == key) {
+            return Err(err(&format!("duplicate key ‘{}’", key)));
+        }
+        table.push((key, value));
+    }
+    Ok(root)
+}
+
+/// The table at `path`, created on first use
+fn table_at<'a>(root: &'a mut Vec<(String, Value)>, path: &[String]) -> Result<&'a mut Vec<(String, Value)>, String> {
+    let mut table = root;
+    for name in path {
+        let idx = match table.iter().position(|(k, _)| k == name) {
+            Some(i) => i,
+            None => {
+                table.push((name.clone(), Value::Table(Vec::new())));
+                table.len() - 1
+            }
+        };
+        table = match &mut table[idx].1 {
+            Value::Table(t) => t,
+            _ => return Err(format!("‘{}’ is both a value and a section", name)),
+        };
+    }
+    Ok(table)
+}
+
+/// Drop a `#` comment outside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Unclosed `[` count outside quotes
+fn depth(s: &str) -> i32 {
+    let mut quote = None;
+    let mut d = 0;
+    for c in s.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '[') => d += 1,
+            (None, ']') => d -= 1,
+            _ => {}
+        }
+    }
+    d
+}
+
+fn unquote(s: &str) -> String {
+    let quoted = s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')));
+    if quoted { s[1..s.len() - 1].to_string() } else { s.to_string() }
+}
+
+/// Split a flow list body on commas outside quotes and brackets
+fn split_items(body: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut d = 0;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '[') => d += 1,
+            (None, ']') => d -= 1,
+            (None, ',') if d == 0 => {
+                items.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&body[start..]);
+    items.into_iter().map(str::trim).filter(|s| !s.is_empty()).collect()
+}
+
+fn parse_scalar_or_array(s: &str) -> Result<Value, String> {
+    let s = s.trim();
+    if let Some(body) = s.strip_prefix('[') {
+        let body = body.strip_suffix(']').ok_or("unterminated array")?;
+        return split_items(body)
+            .into_iter()
+            .map(parse_scalar_or_array)
+            .collect::<Result<_, _>>()
+            .map(Value::Array);
+    }
+    if s.len() >= 2 && (s.starts_with('"') || s.starts_with('\'')) {
+        if !s.ends_with(&s[..1]) {
+            return Err(format!("unterminated string {}", s));
+        }
+        return Ok(Value::Str(s[1..s.len() - 1].replace("\\\"", "\"").replace("\\\\", "\\")));
+    }
+    match s {
+        "true"  => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        "inf" | "+inf" => return Ok(Value::Float(f64::INFINITY)),
+        "nan" => return Ok(Value::Float(f64::NAN)),
+        _ => {}
+    }
+    let digits = s.replace('_', "");
+    if let Ok(i) = digits.parse::<i64>() {
+        return Ok(Value::Int(i));
+    }
+    digits
+        .parse::<f64>()
+        .map(Value::Float)
+        .map_err(|_| format!("cannot parse value ‘{}’", s))
+}
+
+// ---------------------------------------------------------------------
+// YAML subset: nested block mappings by indentation, `- item` block lists
+// of scalars, flow lists `[a, b]`, quoted / plain scalars and comments
+
+pub fn parse_yaml(text: &str) -> Result<Vec<(String, Value)>, String> {
+    let lines: Vec<(usize, usize, String)> = text
+        .lines()
+        .enumerate()
+        .filter_map(|(no, raw)| {
+            let line = strip_comment(raw).trim_end();
+            let body = line.trim_start();
+            if body.is_empty() || body == "---" {
+                None
+            } else {
+                Some((no + 1, line.len() - body.len(), body.to_string()))
+            }
+        })
+        .collect();
+    let mut pos = 0;
+    let root = yaml_mapping(&lines, &mut pos, 0)?;
+    if let Some((no, _, _)) = lines.get(pos) {
+        return Err(format!("line {}: unexpected indentation", no));
+    }
+    Ok(root)
+}
+
+fn yaml_mapping(lines: &[(usize, usize, String)], pos: &mut usize, indent: usize) -> Result<Vec<(String, Value)>, String> {
+    let mut table: Vec<(String, Value)> = Vec::new();
+    while let Some((no, ind, body)) = lines.get(*pos) {
+        if *ind < indent {
+            break;
+        }
+        if *ind > indent {
+            return Err(format!("line {}: unexpected indentation", no));
+        }
+        let (key, rest) = body
+            .split_once(':')
+            .ok_or_else(|| format!("line {}: expected key: value", no))?;
+        let key = unquote(key.trim());
+        let rest = rest.trim();
+        *pos += 1;
+        let value = if !rest.is_empty() {
+            yaml_scalar(rest).map_err(|e| format!("line {}: {}", no, e))?
+        } else {
+            match lines.get(*pos) {
+                Some((_, child, b)) if *child >= indent && (b.starts_with("- ") || b == "-") => {
+                    let child = *child;
+                    let mut items = Vec::new();
+                    while let Some((ino, ind, b)) = lines.get(*pos) {
+                        if *ind != child || !(b.starts_with("- ") || b == "-") {
+                            break;
+                        }
+                        items.push(yaml_scalar(b[1..].trim()).map_err(|e| format!("line {}: {}", ino, e))?);
+                        *pos += 1;
+                    }
+                    Value::Array(items)
+                }
+                Some((_, child, _)) if *child > indent => {
+                    let child = *child;
+                    Value::Table(yaml_mapping(lines, pos, child)?)
+                }
+                _ => Value::Table(Vec::new()),
+            }
+        };
+        if table.iter().any(|(k, _)| *k == key) {
+            return Err(format!("line {}: duplicate key ‘{}’", no, key));
+        }
+        table.push((key, value));
+    }
+    Ok(table)
+}
+
+fn yaml_scalar(s: &str) -> Result<Value, String> {
+    match s {
+        "true" | "True" | "yes"  => return Ok(Value::Bool(true)),
+        "false" | "False" | "no" => return Ok(Value::Bool(false)),
+        ".inf" => return Ok(Value::Float(f64::INFINITY)),
+        ".nan" => return Ok(Value::Float(f64::NAN)),
+        _ => {}
+    }
+    if let Some(body) = s.strip_prefix('[') {
+        let body = body.strip_suffix(']').ok_or("unterminated list")?;
+        return split_items(body)
+            .into_iter()
+            .map(yaml_scalar)
+            .collect::<Result<_, _>>()
+            .map(Value::Array);
+    }
+    if s.starts_with('"') || s.starts_with('\'') {
+        return parse_scalar_or_array(s);
+    }
+    Ok(parse_scalar_or_array(s).unwrap_or_else(|_| Value::Str(s.to_string())))
+}
+
+// ---------------------------------------------------------------------
+// Python entry points
+
+/// Base columns with their value when the data files leave them out
+const SIGNAL_DEFAULTS: &[(&str, f64)] = &[
+    ("long_signals",     0.0),
+    ("short_signals",    0.0),
+    ("long_tp",          f64::NAN),
+    ("long_sl",          f64::NAN),
+    ("short_tp",         f64::NAN),
+    ("short_sl",         f64::NAN),
+    ("long_size",        0.0),
+    ("short_size",       0.0),
+    ("expiration_times", f64::INFINITY),
+];
+
+const BAR_COLUMNS: &[&str] = &["timestamp", "open", "high", "low", "close"];
+
+fn value_to_py(py: Python<'_>, v: &Value) -> PyObject {
+    match v {
+        Value::Str(s)   => s.to_object(py),
+        Value::Int(i)   => i.to_object(py),
+        Value::Float(f) => f.to_object(py),
+        Value::Bool(b)  => b.to_object(py),
+        Value::Array(items) => {
+            let list: Vec<PyObject> = items.iter().map(|x| value_to_py(py, x)).collect();
+            list.to_object(py)
+        }
+        Value::Table(entries) => {
+            let d = PyDict::new(py);
+            for (k, x) in entries {
+                d.set_item(k, value_to_py(py, x)).unwrap();
+            }
+            d.to_object(py)
+        }
+    }
+}
+
+fn entries_to_py<'py>(py: Python<'py>, entries: &[(String, Value)]) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    for (k, v) in entries {
+        d.set_item(k, value_to_py(py, v))?;
+    }
+    Ok(d)
+}
+
+/// Parse and validate an experiment file without running it.
+///
+/// Returns `{"run": run_backtest kwargs, "data": {"bars", "signals",
+/// "delimiter"}, "grid": {axis: values}, "grid_options": {...}}`.
+#[pyfunction]
+pub fn load_config(py: Python<'_>, path: &str) -> PyResult<PyObject> {
+    let cfg = load(Path::new(path)).map_err(PyValueError::new_err)?;
+    let out = PyDict::new(py);
+    out.set_item("run", entries_to_py(py, &cfg.run)?)?;
+    let data = PyDict::new(py);
+    data.set_item("bars",      cfg.bars.to_string_lossy())?;
+    data.set_item("signals",   cfg.signals.as_ref().map(|p| p.to_string_lossy()))?;
+    data.set_item("delimiter", cfg.delimiter.to_string())?;
+    out.set_item("data", data)?;
+    let grid = PyDict::new(py);
+    for (k, values) in &cfg.grid {
+        grid.set_item(k, values.clone())?;
+    }
+    out.set_item("grid", grid)?;
+    out.set_item("grid_options", entries_to_py(py, &cfg.grid_options)?)?;
+    Ok(out.into())
+}
+
+/// Run the experiment an experiment file describes.
+///
+/// Loads the bar (and optional signal) CSVs, calls `run_backtest` with the
+/// configured settings and, when the file has a `[grid]`, adds
+/// `out["grid_search"]` from `grid_search` over the same inputs.
+#[pyfunction]
+pub fn run_config(py: Python<'_>, path: &str) -> PyResult<PyObject> {
+    let cfg = load(Path::new(path)).map_err(PyValueError::new_err)?;
+    let mut columns = read_columns(&cfg.bars, cfg.delimiter).map_err(PyValueError::new_err)?;
+    if let Some(signals) = &cfg.signals {
+        for (name, col) in read_columns(signals, cfg.delimiter).map_err(PyValueError::new_err)? {
+            if columns.iter().any(|(n, _)| *n == name) {
+                return Err(PyValueError::new_err(format!("column ‘{}’ is in both data files", name)));
+            }
+            columns.push((name, col));
+        }
+    }
+    let n = columns.first().map_or(0, |(_, c)| c.len());
+    if let Some((name, _)) = columns.iter().find(|(_, c)| c.len() != n) {
+        return Err(PyValueError::new_err(format!("column ‘{}’ length differs from the bars", name)));
+    }
+    if let Some((name, _)) = columns.iter().find(|(name, _)| {
+        let name = name.as_str();
+        !BAR_COLUMNS.contains(&name)
+            && !SIGNAL_DEFAULTS.iter().any(|(s, _)| *s == name)
+            && !ARRAY_KWARGS.contains(&name)
+    }) {
+        return Err(PyValueError::new_err(format!("unknown data column ‘{}’", name)));
+    }
+    let take = |name: &str| columns.iter().find(|(n, _)| n == name).map(|(_, c)| c.clone());
+
+    let kwargs = PyDict::new(py);
+    for name in BAR_COLUMNS {
+        let col = take(name).ok_or_else(|| PyValueError::new_err(format!("data has no ‘{}’ column", name)))?;
+        kwargs.set_item(name, PyArray1::from_vec(py, col))?;
+    }
+    for &(name, default) in SIGNAL_DEFAULTS {
+        let col = take(name).unwrap_or_else(|| vec![default; n]);
+        if name.ends_with("_signals") {
+            let flags: Vec<bool> = col.iter().map(|&v| v != 0.0 && !v.is_nan()).collect();
+            kwargs.set_item(name, PyArray1::from_vec(py, flags))?;
+        } else {
+            kwargs.set_item(name, PyArray1::from_vec(py, col))?;
+        }
+    }
+    for name in ARRAY_KWARGS {
+        if let Some(col) = take(name) {
+            kwargs.set_item(name, PyArray1::from_vec(py, col))?;
+        }
+    }
+    for key in ["entry_fee_rate", "exit_fee_rate", "slippage_rate"] {
+        kwargs.set_item(key, 0.0)?;
+    }
+    for (k, v) in &cfg.run {
+        kwargs.set_item(k, value_to_py(py, v))?;
+    }
+    if kwargs.get_item("initial_equity").is_none() {
+        return Err(PyValueError::new_err("config must set ‘initial_equity’"));
+    }
+
+    let run = wrap_pyfunction!(crate::engine::run_backtest, py)?;
+    let out: &PyDict = run.call((), Some(kwargs))?.downcast()?;
+
+    if !cfg.grid.is_empty() {
+        let gkw = PyDict::new(py);
+        for name in BAR_COLUMNS.iter().chain(SIGNAL_DEFAULTS.iter().map(|(s, _)| s)) {
+            gkw.set_item(name, kwargs.get_item(name))?;
+        }
+        for key in ["entry_fee_rate", "exit_fee_rate", "slippage_rate", "initial_equity"] {
+            gkw.set_item(key, kwargs.get_item(key))?;
+        }
+        let grid = PyDict::new(py);
+        for (k, values) in &cfg.grid {
+            grid.set_item(k, values.clone())?;
+        }
+        gkw.set_item("param_grid", grid)?;
+        for (k, v) in &cfg.grid_options {
+            gkw.set_item(k, value_to_py(py, v))?;
+        }
+        let search = wrap_pyfunction!(crate::engine::optimize::grid_search, py)?;
+        out.set_item("grid_search", search.call((), Some(gkw))?)?;
+    }
+    Ok(out.into())
+}
@@ -0,0 +1,84 @@
+// src/engine/contract.rs
+
+/// How PnL and notional are denominated
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ContractType {
+    /// Units of the base asset, PnL in quote currency: `size × Δprice`
+    #[default]
+    Linear,
+    /// Coin‐margined: size in quote (e.g. USD contracts), PnL and margin
+    /// in the base coin: `size × (1/entry − 1/exit)`
+    Inverse,
+}
+
+impl ContractType {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "linear"  => Ok(ContractType::Linear),
+            "inverse" => Ok(ContractType::Inverse),
+            other => Err(format!(
+                "unknown contract_type ‘{}’ (expected \"linear\" or \"inverse\")", other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ContractType::Linear  => "linear",
+            ContractType::Inverse => "inverse",
+        }
+    }
+
+    /// Value of `size` at `price`, in the PnL currency
+    pub fn notional(self, price: f64, size: f64) -> f64 {
+        match self {
+            ContractType::Linear  => size * price,
+            ContractType::Inverse => if price != 0.0 { size / price } else { 0.0 },
+        }
+    }
+
+    /// Size whose notional at `price` equals `notional`
+    pub fn size_for_notional(self, price: f64, notional: f64) -> f64 {
+        match self {
+            ContractType::Linear  => if price != 0.0 { notional / price } else { 0.0 },
+            ContractType::Inverse => notional * price,
+        }
+    }
+
+    /// Gross PnL of moving from `entry` to `exit`
+    pub fn pnl(self, long: bool, entry: f64, exit: f64, size: f64) -> f64 {
+        let dir = if long { 1.0 } else { -1.0 };
+        match self {
+            ContractType::Linear => dir * (exit - entry) * size,
+            ContractType::Inverse => {
+                if entry == 0.0 || exit == 0.0 {
+                    0.0
+                } else {
+                    dir * size * (1.0 / entry - 1.0 / exit)
+                }
+            }
+        }
+    }
+}
+
+/// What the `long_size` / `short_size` arrays are denominated in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SizeMode {
+    /// Units/contracts
+    #[default]
+    Units,
+    /// Notional in the PnL currency, converted to units at the fill price
+    Notional,
+}
+
+impl SizeMode {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "units"    => Ok(SizeMode::Units),
+            "notional" => Ok(SizeMode::Notional),
+            other => Err(format!(
+                "unknown size_mode ‘{}’ (expected \"units\" or \"notional\")", other
+            )),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// src/engine/corporate.rs
+
+use crate::engine::{
+    backtest::{MarketData, SignalSet},
+    contract::SizeMode,
+    position::Position,
+};
+
+/// Credit per‐bar dividends to every position held into the ex‐date.
+///
+/// `dividends[t]` is the cash per unit going ex on bar t: a position held
+/// over the previous close (entered before bar t, not exited before it)
+/// receives it when long and pays it when short.  NaN bars are skipped.
+/// As with funding, re‐running after an exit moved replaces the previous
+/// amount.
+pub fn apply_dividends(positions: &mut [Position], dividends: &[f64]) {
+    let n = dividends.len();
+    for pos in positions.iter_mut() {
+        let dir = if pos.position_type == "long" { 1.0 } else { -1.0 };
+        let last = pos.exit_index.unwrap_or(n - 1);
+        let income = (pos.entry_index + 1..=last)
+            .filter(|&t| !dividends[t].is_nan())
+            .fold(0.0, |acc, t| acc + dir * dividends[t] * pos.position_size);
+        if let Some(pnl) = pos.pnl.as_mut() {
+            *pnl += income - pos.dividend_income;
+            let notional = pos.contract.notional(pos.entry_price, pos.position_size);
+            pos.real_return = Some(if notional != 0.0 { *pnl / notional } else { 0.0 });
+        }
+        pos.dividend_income = income;
+    }
+}
+
+/// Product of the split factors after each bar: dividing bar t's prices by
+/// it puts them in post‐split units (NaN or non‐positive factors count as 1)
+pub fn split_adjustments(factors: &[f64]) -> Vec<f64> {
+    let mut adj = vec![1.0; factors.len()];
+    for t in (0..factors.len().saturating_sub(1)).rev() {
+        let f = factors[t + 1];
+        adj[t] = adj[t + 1] * if f.is_nan() || f <= 0.0 { 1.0 } else { f };
+    }
+    adj
+}
+
+/// Back‐adjust unadjusted prices, signal levels, unit sizes and dividends
+/// for splits (`factors[t]` = new shares per old share on bar t), so
+/// positions carried through a split keep their value.  Everything is then
+/// in post‐split units.
+pub fn adjust_for_splits(
+    market: &mut MarketData,
+    signals: &mut SignalSet,
+    dividends: Option<&mut Vec<f64>>,
+    factors: &[f64],
+    size_mode: SizeMode,
+) {
+    let adj = split_adjustments(factors);
+    for (t, &a) in adj.iter().enumerate() {
+        market.open[t]  /= a;
+        market.high[t]  /= a;
+        market.low[t]   /= a;
+        market.close[t] /= a;
+        signals.long_tp[t]  /= a;
+        signals.long_sl[t]  /= a;
+        signals.short_tp[t] /= a;
+        signals.short_sl[t] /= a;
+        if size_mode == SizeMode::Units {
+            signals.long_size[t]  *= a;
+            signals.short_size[t] *= a;
+        }
+    }
+    if let Some(divs) = dividends {
+        divs.iter_mut().zip(&adj).for_each(|(d, a)| *d /= a);
+    }
+}
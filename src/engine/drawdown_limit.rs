@@ -0,0 +1,59 @@
+// src/engine/drawdown_limit.rs
+
+use crate::engine::{
+    backtest::{BacktestConfig, MarketData},
+    exposure::ExposureSnapshot,
+    position::Position,
+    simulate_exits::close_position,
+};
+
+/// Funded‐account style trailing drawdown from the equity high‐water mark
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrailingDrawdown {
+    /// Allowed drop below the high‐water mark
+    pub limit:    f64,
+    /// `limit` is a fraction of the high‐water mark rather than an amount
+    pub relative: bool,
+}
+
+/// First bar whose closing equity broke the limit
+#[derive(Clone, Debug)]
+pub struct DrawdownBreach {
+    pub bar:             usize,
+    pub timestamp:       f64,
+    pub equity:          f64,
+    pub high_water_mark: f64,
+}
+
+/// Scan bar‐close equity (initial + realized + floating) for a breach
+pub fn find_breach(
+    exposure: &[ExposureSnapshot],
+    initial_equity: f64,
+    rule: &TrailingDrawdown,
+) -> Option<DrawdownBreach> {
+    let mut hwm = initial_equity;
+    for (bar, snap) in exposure.iter().enumerate() {
+        let equity = initial_equity + snap.total_equity;
+        hwm = hwm.max(equity);
+        let allowed = if rule.relative { rule.limit * hwm } else { rule.limit };
+        if hwm - equity > allowed {
+            return Some(DrawdownBreach { bar, timestamp: snap.timestamp, equity, high_water_mark: hwm });
+        }
+    }
+    None
+}
+
+/// Stop trading at the breach bar: later entries are dropped and positions
+/// still open are closed at that bar's close (exit condition "DD")
+pub fn stop_at_breach(positions: &mut Vec<Position>, bar: usize, market: &MarketData, config: &BacktestConfig) {
+    positions.retain(|p| p.entry_index <= bar);
+    for pos in positions.iter_mut() {
+        if pos.exit_index.is_none_or(|e| e > bar) {
+            pos.exit_delay = None;
+            close_position(
+                pos, bar, market.close[bar], "DD",
+                config.exit_fee_rate, config.exit_slippage().exp, config.filters,
+            );
+        }
+    }
+}
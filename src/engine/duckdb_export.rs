@@ -0,0 +1,154 @@
+// src/engine/duckdb_export.rs
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString};
+use pyo3::exceptions::PyValueError;
+
+/// Result lists exported as tables: (key in the `run_backtest` dict, table)
+const TABLES: &[(&str, &str)] = &[
+    ("closed_positions",     "trades"),
+    ("open_positions",       "open_positions"),
+    ("exposure_time_series", "exposure"),
+    ("trade_equity_curve",   "trade_equity"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Boolean,
+    BigInt,
+    Double,
+    Varchar,
+}
+
+impl Column {
+    fn of(value: &PyAny) -> Option<Column> {
+        if value.downcast::<PyBool>().is_ok() {
+            Some(Column::Boolean)
+        } else if value.downcast::<PyInt>().is_ok() {
+            Some(Column::BigInt)
+        } else if value.downcast::<PyFloat>().is_ok() {
+            Some(Column::Double)
+        } else if value.downcast::<PyString>().is_ok() {
+            Some(Column::Varchar)
+        } else {
+            None
+        }
+    }
+
+    fn sql(self) -> &'static str {
+        match self {
+            Column::Boolean => "BOOLEAN",
+            Column::BigInt  => "BIGINT",
+            Column::Double  => "DOUBLE",
+            Column::Varchar => "VARCHAR",
+        }
+    }
+
+    /// Ints mixed with floats widen to DOUBLE; any other clash is text
+    fn merge(self, other: Column) -> Column {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Column::BigInt, Column::Double) | (Column::Double, Column::BigInt) => Column::Double,
+            _ => Column::Varchar,
+        }
+    }
+}
+
+/// Scalar columns of a list of row dicts, in first‐seen order (nested
+/// dicts/lists are left out; all‐None columns become DOUBLE)
+fn columns(rows: &PyList) -> PyResult<Vec<(String, Column)>> {
+    let mut cols: Vec<(String, Option<Column>, bool)> = Vec::new();
+    for row in rows {
+        let row: &PyDict = row.downcast()?;
+        for (k, v) in row.iter() {
+            let name: String = k.extract()?;
+            let kind = Column::of(v);
+            let nested = !v.is_none() && kind.is_none();
+            match cols.iter_mut().find(|(n, _, _)| *n == name) {
+                Some((_, col, skip)) => {
+                    *skip |= nested;
+                    if let Some(k) = kind {
+                        *col = Some(col.map_or(k, |c| c.merge(k)));
+                    }
+                }
+                None => cols.push((name, kind, nested)),
+            }
+        }
+    }
+    Ok(cols
+        .into_iter()
+        .filter(|(_, _, nested)| !nested)
+        .map(|(name, col, _)| (name, col.unwrap_or(Column::Double)))
+        .collect())
+}
+
+/// A table or column name as a quoted SQL identifier
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// (Re)create one table from row dicts with a single columnar INSERT: each
+/// column ships as one typed list parameter and is unnested in SQL
+fn write_table(py: Python<'_>, conn: &PyAny, table: &str, rows: &PyList) -> PyResult<()> {
+    let cols = columns(rows)?;
+    if cols.is_empty() {
+        return Ok(());
+    }
+    let schema: Vec<String> = cols.iter().map(|(n, c)| format!("{} {}", quote_ident(n), c.sql())).collect();
+    conn.call_method1("execute", (format!("CREATE OR REPLACE TABLE {} ({})", quote_ident(table), schema.join(", ")),))?;
+
+    let params = PyList::empty(py);
+    for (name, col) in &cols {
+        let values = PyList::empty(py);
+        for row in rows {
+            let row: &PyDict = row.downcast()?;
+            let v = row.get_item(name.as_str()).unwrap_or_else(|| py.None().into_ref(py));
+            if *col == Column::Varchar && !v.is_none() {
+                values.append(v.str()?)?;
+            } else if *col == Column::Double && !v.is_none() {
+                values.append(v.extract::<f64>()?)?;
+            } else {
+                values.append(v)?;
+            }
+        }
+        params.append(values)?;
+    }
+    let select: Vec<String> = cols.iter().map(|(_, c)| format!("unnest(?::{}[])", c.sql())).collect();
+    conn.call_method1(
+        "execute",
+        (format!("INSERT INTO {} SELECT {}", quote_ident(table), select.join(", ")), params),
+    )?;
+    Ok(())
+}
+
+/// Load a `run_backtest` result into DuckDB tables for SQL analysis.
+///
+/// Writes `trades` (closed positions), `open_positions`, `exposure` and
+/// `trade_equity` (each name prefixed by `prefix`, replaced if present)
+/// into `connection`, or into a new connection on `database` (a `.duckdb`
+/// file; in‐memory when omitted).  Empty lists create no table.  Requires
+/// the `duckdb` Python package; returns the connection.
+#[pyfunction]
+#[pyo3(signature=(result, database=None, connection=None, prefix=""))]
+pub fn to_duckdb(
+    py: Python<'_>,
+    result:     &PyDict,
+    database:   Option<&str>,
+    connection: Option<&PyAny>,
+    prefix:     &str,
+) -> PyResult<PyObject> {
+    if database.is_some() && connection.is_some() {
+        return Err(PyValueError::new_err("pass either ‘database’ or ‘connection’, not both"));
+    }
+    let conn = match connection {
+        Some(c) => c,
+        None    => py.import("duckdb")?.call_method1("connect", (database.unwrap_or(":memory:"),))?,
+    };
+    for (key, table) in TABLES {
+        if let Some(rows) = result.get_item(key) {
+            let rows: &PyList = rows.downcast()?;
+            write_table(py, conn, &format!("{}{}", prefix, table), rows)?;
+        }
+    }
+    Ok(conn.into())
+}
@@ -0,0 +1,272 @@
+// src/engine/ensemble.rs
+
+//! Equity curves of separate runs on one timestamp index, blended into an
+//! ensemble curve
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    benchmark::{bar_returns, underwater},
+    metrics::RatioPolicy,
+    output::ratio_to_py,
+    stats::{correlation_matrix, mean},
+};
+
+/// Which timestamps the aligned index keeps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Every timestamp of any curve; a curve holds its last value between
+    /// its own points (and its first value before them)
+    #[default]
+    Union,
+    /// Only timestamps every curve has
+    Intersection,
+}
+
+impl Alignment {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "union"        => Ok(Alignment::Union),
+            "intersection" => Ok(Alignment::Intersection),
+            other => Err(format!("align must be \"union\" or \"intersection\", got ‘{}’", other)),
+        }
+    }
+}
+
+/// One run's equity at its own timestamps
+#[derive(Clone, Debug)]
+pub struct Curve {
+    pub timestamps: Vec<f64>,
+    pub equity:     Vec<f64>,
+}
+
+/// Summary of one equity curve over the aligned index
+#[derive(Clone, Debug, Default)]
+pub struct CurveStats {
+    pub total_return: f64,
+    pub mean_return:  f64,
+    pub volatility:   f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown: f64,
+}
+
+impl CurveStats {
+    pub fn of(equity: &[f64], policy: RatioPolicy) -> Self {
+        let returns = bar_returns(equity);
+        let mu = mean(&returns);
+        let vol = if returns.len() > 1 {
+            (returns.iter().map(|r| (r - mu).powi(2)).sum::<f64>() / (returns.len() - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        CurveStats {
+            total_return: match (equity.first(), equity.last()) {
+                (Some(&a), Some(&b)) if a != 0.0 => b / a - 1.0,
+                _ => 0.0,
+            },
+            mean_return:  mu,
+            volatility:   vol,
+            sharpe_ratio: policy.ratio(mu, vol, 0.0),
+            max_drawdown: underwater(equity).into_iter().fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Aligned curves, their blend and how they co‐move
+pub struct Ensemble {
+    pub timestamps:  Vec<f64>,
+    /// Each input curve on `timestamps`
+    pub curves:      Vec<Vec<f64>>,
+    /// Normalized weights (sum 1)
+    pub weights:     Vec<f64>,
+    pub combined:    Vec<f64>,
+    /// Pairwise correlation of the aligned curves' bar returns
+    pub correlation: Vec<Vec<f64>>,
+    pub stats:       Vec<CurveStats>,
+    pub combined_stats: CurveStats,
+    /// Weighted mean of the curves' volatilities over the blend's (> 1 when
+    /// blending diversifies)
+    pub diversification_ratio: f64,
+}
+
+/// Common index of `curves` and each curve sampled on it
+pub fn align(curves: &[Curve], mode: Alignment) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let mut index: Vec<f64> = curves.iter().flat_map(|c| c.timestamps.iter().copied()).collect();
+    index.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    index.dedup();
+    if mode == Alignment::Intersection {
+        index.retain(|t| curves.iter().all(|c| c.timestamps.binary_search_by(|x| x.partial_cmp(t).unwrap()).is_ok()));
+    }
+    let aligned = curves
+        .iter()
+        .map(|c| {
+            index
+                .iter()
+                .map(|&t| match c.timestamps.partition_point(|&x| x <= t) {
+                    0 => c.equity[0],
+                    k => c.equity[k - 1],
+                })
+                .collect()
+        })
+        .collect();
+    (index, aligned)
+}
+
+/// Align `curves`, blend them with `weights` (normalized) and compare them.
+/// With `rebalance` the blend holds the weights every bar (its return is
+/// the weighted return of the curves); without, it buys each curve once at
+/// its weight and lets the shares drift.  The blend starts at
+/// `initial_equity`, or at the weighted mean of the curves' first values.
+pub fn merge_curves(
+    curves: &[Curve],
+    weights: &[f64],
+    mode: Alignment,
+    rebalance: bool,
+    initial_equity: Option<f64>,
+    policy: RatioPolicy,
+) -> Result<Ensemble, String> {
+    if curves.is_empty() {
+        return Err("need at least one equity curve".into());
+    }
+    for (k, c) in curves.iter().enumerate() {
+        if c.timestamps.is_empty() || c.timestamps.len() != c.equity.len() {
+            return Err(format!("curve {} needs equally long, non‐empty timestamps and equity", k));
+        }
+        if !c.timestamps.windows(2).all(|w| w[1] > w[0]) {
+            return Err(format!("curve {} timestamps must be strictly increasing", k));
+        }
+        if c.equity.iter().any(|e| !e.is_finite() || *e <= 0.0) {
+            return Err(format!("curve {} equity must be positive and finite", k));
+        }
+    }
+    if weights.len() != curves.len() {
+        return Err(format!("{} weights for {} curves", weights.len(), curves.len()));
+    }
+    let total: f64 = weights.iter().sum();
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || total <= 0.0 {
+        return Err("weights must be ≥ 0 with a positive sum".into());
+    }
+    let weights: Vec<f64> = weights.iter().map(|w| w / total).collect();
+
+    let (timestamps, aligned) = align(curves, mode);
+    if timestamps.is_empty() {
+        return Err("the curves share no timestamps".into());
+    }
+    let n = timestamps.len();
+    let start = initial_equity.unwrap_or_else(|| weights.iter().zip(&aligned).map(|(w, c)| w * c[0]).sum());
+    let returns: Vec<Vec<f64>> = aligned.iter().map(|c| bar_returns(c)).collect();
+    let combined: Vec<f64> = if rebalance {
+        let mut eq = start;
+        std::iter::once(start)
+            .chain((0..n - 1).map(|i| {
+                eq *= 1.0 + weights.iter().zip(&returns).map(|(w, r)| w * r[i]).sum::<f64>();
+                eq
+            }))
+            .collect()
+    } else {
+        (0..n)
+            .map(|i| start * weights.iter().zip(&aligned).map(|(w, c)| w * c[i] / c[0]).sum::<f64>())
+            .collect()
+    };
+
+    let stats: Vec<CurveStats> = aligned.iter().map(|c| CurveStats::of(c, policy)).collect();
+    let combined_stats = CurveStats::of(&combined, policy);
+    let weighted_vol: f64 = weights.iter().zip(&stats).map(|(w, s)| w * s.volatility).sum();
+    Ok(Ensemble {
+        correlation: correlation_matrix(&returns),
+        diversification_ratio: policy.ratio(weighted_vol, combined_stats.volatility, 0.0),
+        timestamps,
+        curves: aligned,
+        weights,
+        combined,
+        stats,
+        combined_stats,
+    })
+}
+
+fn stats_to_py<'py>(py: Python<'py>, s: &CurveStats, policy: RatioPolicy) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("total_return", s.total_return)?;
+    d.set_item("mean_return",  s.mean_return)?;
+    d.set_item("volatility",   s.volatility)?;
+    d.set_item("sharpe_ratio", ratio_to_py(py, s.sharpe_ratio, policy))?;
+    d.set_item("max_drawdown", s.max_drawdown)?;
+    Ok(d)
+}
+
+/// Combine the equity curves of several runs.
+///
+/// `runs` are `run_backtest` results (or any mappings with `timestamps`
+/// and `equity`), `names` label them (default "0", "1", …).  The curves
+/// are aligned on the union (`align="union"`, holding each curve's last
+/// value) or intersection of their timestamps and blended with `weights`
+/// (equal by default, normalized to sum 1), rebalanced every bar or, with
+/// `rebalance=False`, bought once.  Returns the index, the aligned curves,
+/// the `combined` curve, per‐curve and combined stats, the correlation
+/// matrix of bar returns and the diversification ratio.
+#[pyfunction]
+#[pyo3(signature=(
+    runs,
+    weights=None,
+    names=None,
+    align="union",
+    rebalance=true,
+    initial_equity=None,
+    undefined_ratios="legacy",
+    ratio_cap=None
+))]
+pub fn merge_equity_curves(
+    py:               Python<'_>,
+    runs:             Vec<&PyAny>,
+    weights:          Option<Vec<f64>>,
+    names:            Option<Vec<String>>,
+    align:            &str,
+    rebalance:        bool,
+    initial_equity:   Option<f64>,
+    undefined_ratios: &str,
+    ratio_cap:        Option<f64>,
+) -> PyResult<PyObject> {
+    let mode = Alignment::parse(align).map_err(PyValueError::new_err)?;
+    let policy = RatioPolicy::parse(undefined_ratios, ratio_cap).map_err(PyValueError::new_err)?;
+    let names = names.unwrap_or_else(|| (0..runs.len()).map(|k| k.to_string()).collect());
+    if names.len() != runs.len() {
+        return Err(PyValueError::new_err(format!("{} names for {} runs", names.len(), runs.len())));
+    }
+    let curves = runs
+        .iter()
+        .map(|run| {
+            Ok(Curve {
+                timestamps: run.get_item("timestamps")?.extract()?,
+                equity:     run.get_item("equity")?.extract()?,
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let weights = weights.unwrap_or_else(|| vec![1.0; curves.len()]);
+    let ens = py
+        .allow_threads(|| merge_curves(&curves, &weights, mode, rebalance, initial_equity, policy))
+        .map_err(PyValueError::new_err)?;
+
+    let out = PyDict::new(py);
+    out.set_item("timestamps", PyArray1::from_slice(py, &ens.timestamps))?;
+    out.set_item("names",      names.clone())?;
+    let curves = PyDict::new(py);
+    let weights = PyDict::new(py);
+    let stats = PyDict::new(py);
+    for (k, name) in names.iter().enumerate() {
+        curves.set_item(name, PyArray1::from_slice(py, &ens.curves[k]))?;
+        weights.set_item(name, ens.weights[k])?;
+        stats.set_item(name, stats_to_py(py, &ens.stats[k], policy)?)?;
+    }
+    out.set_item("curves",  curves)?;
+    out.set_item("weights", weights)?;
+    out.set_item("stats",   stats)?;
+    let combined = stats_to_py(py, &ens.combined_stats, policy)?;
+    combined.set_item("equity", PyArray1::from_slice(py, &ens.combined))?;
+    out.set_item("combined",    combined)?;
+    out.set_item("correlation", ens.correlation.clone())?;
+    out.set_item("diversification_ratio", ratio_to_py(py, ens.diversification_ratio, policy))?;
+    Ok(out.into())
+}
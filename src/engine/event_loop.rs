@@ -0,0 +1,165 @@
+// src/engine/event_loop.rs
+
+//! Chronological account loop.
+//!
+//! The signal scan turns every signal into a pending order and the exit
+//! simulator resolves each order's path on its own (no exit rule looks at
+//! other positions).  Everything that depends on the account — free cash,
+//! the number of open positions, equity‐scaled sizing, the equity throttle —
+//! is decided here, one bar at a time:
+//!
+//! 1. positions that exited on earlier bars release their margin and book
+//!    their PnL;
+//! 2. orders filling at this bar's open are admitted in `entry_key` order
+//!    against the account as of the previous close, or skipped;
+//! 3. the bar closes: realized PnL of this bar's exits plus the floating
+//!    PnL of what is still open gives the closing equity the next bar's
+//!    entries see, together with any external cash flow booked at the close
+//!    (the throttle watches trading equity alone).
+//!
+//! This is an admission pass over already simulated positions, not a
+//! single event engine: an admitted order keeps the exit the simulator found
+//! for it (resized entries included), so a position's lifecycle never
+//! depends on which other orders were admitted.
+
+use crate::engine::{
+    backtest::{BacktestConfig, SkippedEntry},
+    borrow::borrow_refusal,
+    loss_cap::{LossCap, LossCapMode},
+    position::Position,
+    throttle::below_average,
+};
+
+/// Account limits checked at every entry
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AccountRules {
+    /// Most positions open at once (positions exiting this bar still count)
+    pub max_positions:    Option<usize>,
+    /// Entries need free margin (equity at the previous close minus the
+    /// margin of open positions) for their own margin plus entry fee
+    pub cash_constrained: bool,
+    /// Scale each entry by equity at the previous close / initial equity
+    pub compounding:      bool,
+}
+
+impl AccountRules {
+    pub fn is_active(&self) -> bool {
+        self.max_positions.is_some() || self.cash_constrained || self.compounding
+    }
+}
+
+/// Scale an entry by `factor` under the entry rules the scan applied to its
+/// original size: the loss cap (the stop is already simulated, so a larger
+/// size is cut back to the cap in either mode), the lot step, `min_notional`
+/// and the borrowable units.  Err with the skip reason when the entry can't
+/// be taken at the new size.
+fn resize(pos: &mut Position, factor: f64, config: &BacktestConfig) -> Result<(), &'static str> {
+    let f = config.filters.unwrap_or_default();
+    let mut size = pos.position_size * factor;
+    if size <= 0.0 || pos.position_size <= 0.0 {
+        return Err("cash");
+    }
+    if let Some(cap) = config.loss_cap {
+        let downsize = LossCap { mode: LossCapMode::Downsize, ..cap };
+        size = downsize.apply(pos.contract, pos.position_type == "long", pos.entry_price, pos.entry_sl, size).1;
+    }
+    let size = f.round_size(size);
+    if size <= 0.0 {
+        return Err("lot_size");
+    }
+    if f.min_notional.is_some_and(|m| pos.contract.notional(pos.entry_price, size) < m) {
+        return Err("min_notional");
+    }
+    pos.scale(size / pos.position_size);
+    if let Some(reason) = config.borrow_available.as_deref().and_then(|a| borrow_refusal(pos, a)) {
+        return Err(reason);
+    }
+    Ok(())
+}
+
+/// Admit simulated `positions` bar by bar under the config's account rules
+/// and equity throttle; refused entries are appended to `skipped`
+pub fn run_event_loop(
+    mut positions: Vec<Position>,
+    close: &[f64],
+    config: &BacktestConfig,
+    skipped: &mut Vec<SkippedEntry>,
+) -> Vec<Position> {
+    positions.sort_by_key(Position::entry_key);
+    let n = close.len();
+    let rules = config.account;
+    let initial_equity = config.initial_equity;
+    let leverage = config.leverage();
+
+    let mut pending = positions.into_iter().peekable();
+    let mut admitted: Vec<Position> = Vec::new();
+    // indices into `admitted` not yet past their exit bar
+    let mut open: Vec<usize> = Vec::new();
+    let mut realized_at = vec![0.0; n];
+    let mut cum_realized = 0.0;
+    let mut cum_flows = 0.0;
+    // closing equity of every finished bar
+    let mut equity: Vec<f64> = Vec::with_capacity(n);
+
+    for t in 0..n {
+        // 1) exits of earlier bars
+        open.retain(|&k| admitted[k].exit_index.is_none_or(|x| x >= t));
+
+        // 2) entries at this bar's open
+        let prev_equity = equity.last().copied().unwrap_or(initial_equity) + cum_flows;
+        while let Some(mut pos) = pending.next_if(|p| p.entry_index <= t) {
+            let mut factor = 1.0;
+            if rules.compounding && initial_equity > 0.0 {
+                factor = prev_equity.max(0.0) / initial_equity;
+            }
+            if let Some(rule) = &config.throttle {
+                if below_average(&equity, t, rule.window) {
+                    if rule.scale <= 0.0 {
+                        skipped.push(SkippedEntry::from_position(&pos, "equity_throttle"));
+                        continue;
+                    }
+                    factor *= rule.scale;
+                    pos.throttled = true;
+                }
+            }
+            if factor != 1.0 {
+                if let Err(reason) = resize(&mut pos, factor, config) {
+                    skipped.push(SkippedEntry::from_position(&pos, reason));
+                    continue;
+                }
+            }
+            let margin = |p: &Position| p.contract.notional(p.entry_price, p.position_size) / leverage;
+            let reason = if rules.max_positions.is_some_and(|cap| open.len() >= cap) {
+                Some("max_positions")
+            } else if rules.cash_constrained && {
+                let used: f64 = open.iter().map(|&k| margin(&admitted[k])).sum();
+                margin(&pos) + pos.fee_entry > prev_equity - used
+            } {
+                Some("cash")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                skipped.push(SkippedEntry::from_position(&pos, reason));
+                continue;
+            }
+            if let Some(x) = pos.exit_index {
+                realized_at[x] += pos.pnl.unwrap_or(0.0);
+            }
+            open.push(admitted.len());
+            admitted.push(pos);
+        }
+
+        // 3) close of the bar
+        cum_realized += realized_at[t];
+        let floating: f64 = open
+            .iter()
+            .map(|&k| &admitted[k])
+            .filter(|p| p.exit_index.is_none_or(|x| x > t))
+            .map(|p| p.contract.pnl(p.position_type == "long", p.entry_price, close[t], p.position_size))
+            .sum();
+        equity.push(initial_equity + cum_realized + floating);
+        cum_flows += config.cash_flows.as_ref().map_or(0.0, |f| f[t]);
+    }
+    admitted
+}
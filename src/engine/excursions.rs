@@ -0,0 +1,75 @@
+// src/engine/excursions.rs
+
+use crate::engine::par::*;
+
+use crate::engine::position::Position;
+
+/// Per‐trade efficiencies: how close to the best price over the trade's
+/// range it entered and exited, and how much of the range it captured
+#[derive(Debug, Clone, Copy)]
+pub struct Efficiency {
+    pub entry: f64,
+    pub exit:  f64,
+    pub total: f64,
+}
+
+/// Record each position's maximum favorable / adverse excursion (price
+/// distance from its entry to the best / worst high or low of the bars it
+/// was held, exit bar included; open positions through the last bar)
+pub fn record_excursions(positions: &mut [Position], high: &[f64], low: &[f64]) {
+    let n = high.len();
+    positions.par_iter_mut().for_each(|pos| {
+        if pos.entry_index >= n {
+            return;
+        }
+        let last = pos.exit_index.unwrap_or(n - 1).min(n - 1);
+        let range = pos.entry_index..=last;
+        let hi = high[range.clone()].iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let lo = low[range].iter().copied().fold(f64::INFINITY, f64::min);
+        let (favorable, adverse) = if pos.position_type == "long" {
+            (hi - pos.entry_price, pos.entry_price - lo)
+        } else {
+            (pos.entry_price - lo, hi - pos.entry_price)
+        };
+        pos.mfe = favorable.max(0.0);
+        pos.mae = adverse.max(0.0);
+    });
+}
+
+/// Entry/exit/total efficiency over the trade's range (closed trades with a
+/// non‐zero range only)
+pub fn efficiency(pos: &Position) -> Option<Efficiency> {
+    let exit = pos.exit_price?;
+    let range = pos.mfe + pos.mae;
+    if range <= 0.0 {
+        return None;
+    }
+    let gain = if pos.position_type == "long" { exit - pos.entry_price } else { pos.entry_price - exit };
+    Some(Efficiency {
+        entry: pos.mfe / range,
+        exit:  (gain + pos.mae) / range,
+        total: gain / range,
+    })
+}
+
+/// Best open profit of a closed trade and how much of it the exit gave back
+#[derive(Debug, Clone, Copy)]
+pub struct Runup {
+    /// Price PnL (before costs) at the trade's most favorable high / low
+    pub max_runup: f64,
+    /// Share of `max_runup` not kept at the exit: 0 exited at the best
+    /// price, above 1 closed below entry (NaN when never in profit)
+    pub giveback:  f64,
+}
+
+pub fn runup(pos: &Position) -> Option<Runup> {
+    let exit = pos.exit_price?;
+    let long = pos.position_type == "long";
+    let best = if long { pos.entry_price + pos.mfe } else { pos.entry_price - pos.mfe };
+    let max_runup = pos.contract.pnl(long, pos.entry_price, best, pos.position_size).max(0.0);
+    let kept = pos.contract.pnl(long, pos.entry_price, exit, pos.position_size);
+    Some(Runup {
+        max_runup,
+        giveback: if max_runup > 0.0 { (max_runup - kept) / max_runup } else { f64::NAN },
+    })
+}
@@ -0,0 +1,82 @@
+// src/engine/exit_policies.rs
+
+use crate::engine::par::*;
+use crate::engine::{
+    backtest::{run_engine, run_scanned, scan, BacktestConfig, BacktestResult, MarketData, SignalSet},
+    warnings::collect_warnings,
+};
+
+/// One exit parameterization: TP and SL distances from the signal‐bar close
+/// scaled by these multiples
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExitPolicy {
+    pub tp_mult: f64,
+    pub sl_mult: f64,
+}
+
+impl Default for ExitPolicy {
+    fn default() -> Self {
+        ExitPolicy { tp_mult: 1.0, sl_mult: 1.0 }
+    }
+}
+
+/// Scale each level's distance from the signal‐bar close by `mult`
+pub fn scale_levels(levels: &mut [f64], close: &[f64], mult: f64) {
+    for (lvl, &c) in levels.iter_mut().zip(close) {
+        *lvl = c + (*lvl - c) * mult;
+    }
+}
+
+impl ExitPolicy {
+    /// `signals` with this policy's levels
+    pub fn apply(&self, signals: &SignalSet, close: &[f64]) -> SignalSet {
+        let mut s = signals.clone();
+        scale_levels(&mut s.long_tp,  close, self.tp_mult);
+        scale_levels(&mut s.short_tp, close, self.tp_mult);
+        scale_levels(&mut s.long_sl,  close, self.sl_mult);
+        scale_levels(&mut s.short_sl, close, self.sl_mult);
+        s
+    }
+}
+
+/// One run per exit policy over a single entry scan.
+///
+/// Entries are scanned once with the unscaled levels; each policy re‐levels
+/// copies of those pending positions and simulates only their exits,
+/// accounting and metrics, the policies in parallel.  Runs with a
+/// `loss_cap` (which sizes or tightens each entry from its stop) and futures
+/// runs (`rolls`, re‐levelled on a back‐adjusted series) fall back to one
+/// full run per policy.
+pub fn run_exit_policies(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    policies: &[ExitPolicy],
+) -> Vec<BacktestResult> {
+    if config.loss_cap.is_some() || config.rolls.as_ref().is_some_and(|r| !r.roll_bars.is_empty()) {
+        return policies
+            .par_iter()
+            .map(|p| run_engine(market, &p.apply(signals, &market.close), config))
+            .collect();
+    }
+
+    let mut skipped = Vec::new();
+    let entries = scan(market, signals, config, &mut skipped);
+    let filters = config.filters.unwrap_or_default();
+    policies
+        .par_iter()
+        .map(|p| {
+            let mut positions = entries.clone();
+            for pos in &mut positions {
+                let c = market.close[pos.signal_index];
+                pos.tp = filters.round_level(c + (pos.tp - c) * p.tp_mult);
+                pos.sl = filters.round_level(c + (pos.sl - c) * p.sl_mult);
+                pos.entry_sl = pos.sl;
+            }
+            let signals = p.apply(signals, &market.close);
+            let mut result = run_scanned(market, &signals, config, positions, skipped.clone());
+            result.warnings = collect_warnings(&result, market, &signals, config);
+            result
+        })
+        .collect()
+}
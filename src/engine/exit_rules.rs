@@ -0,0 +1,164 @@
+// src/engine/exit_rules.rs
+
+use crate::engine::position::Position;
+
+/// A TP/SL change replayed at the start of `bar` (before its exit checks),
+/// for one position (by signal bar) or every position open on that bar
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Amendment {
+    pub bar:          usize,
+    pub signal_index: Option<usize>,
+    pub tp:           Option<f64>,
+    pub sl:           Option<f64>,
+}
+
+/// Stop tightening by trade age: the stop moves from its level at entry
+/// toward the entry price by a fraction of the distance (1 = breakeven)
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeStop {
+    /// Fraction `age / bars`, capped at 1
+    Linear { bars: usize },
+    /// `(age_bars, fraction)` steps; the last step reached applies
+    Steps(Vec<(usize, f64)>),
+}
+
+impl TimeStop {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            TimeStop::Linear { bars: 0 } => Err("sl_decay_bars must be ≥ 1".into()),
+            TimeStop::Linear { .. }      => Ok(()),
+            TimeStop::Steps(steps) => {
+                if steps.iter().any(|&(_, f)| f.is_nan() || f < 0.0) {
+                    return Err("sl_decay_steps fractions must be ≥ 0".into());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn fraction(&self, age: usize) -> f64 {
+        match self {
+            TimeStop::Linear { bars } => (age as f64 / *bars as f64).min(1.0),
+            TimeStop::Steps(steps) => steps
+                .iter()
+                .rev()
+                .find(|&&(a, _)| a <= age)
+                .map_or(0.0, |&(_, f)| f),
+        }
+    }
+}
+
+/// Chandelier trailing stop: `mult × atr` below the highest high (above the
+/// lowest low for shorts) since entry, from completed bars only
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chandelier {
+    /// Per‐bar volatility (e.g. ATR), read at the previous bar
+    pub atr:  Vec<f64>,
+    pub mult: f64,
+}
+
+/// Per‐position memory of the rules, taken at entry
+#[derive(Clone, Copy, Debug)]
+pub struct RuleState {
+    initial_sl: f64,
+    /// Highest high (long) / lowest low (short) since entry; starts at the fill
+    extreme:    f64,
+}
+
+/// Stateful TP/SL management applied bar by bar inside the exit loop
+#[derive(Clone, Debug, Default)]
+pub struct ExitRules {
+    /// Sorted by `bar`
+    pub amendments: Vec<Amendment>,
+    pub time_stop:  Option<TimeStop>,
+    pub chandelier: Option<Chandelier>,
+}
+
+impl ExitRules {
+    pub fn new(
+        mut amendments: Vec<Amendment>,
+        mut time_stop: Option<TimeStop>,
+        chandelier: Option<Chandelier>,
+    ) -> Self {
+        amendments.sort_by_key(|a| a.bar);
+        if let Some(TimeStop::Steps(steps)) = &mut time_stop {
+            steps.sort_by_key(|&(a, _)| a);
+        }
+        ExitRules { amendments, time_stop, chandelier }
+    }
+
+    pub fn start(&self, pos: &Position) -> RuleState {
+        RuleState { initial_sl: pos.sl, extreme: pos.entry_price }
+    }
+
+    pub fn validate(&self, n: usize) -> Result<(), String> {
+        if let Some(ts) = &self.time_stop {
+            ts.validate()?;
+        }
+        if let Some(ch) = &self.chandelier {
+            if ch.atr.len() != n {
+                return Err(format!("‘trail_atr’ length {} != expected {}", ch.atr.len(), n));
+            }
+            if ch.mult.is_nan() || ch.mult <= 0.0 {
+                return Err("trail_mult must be > 0".into());
+            }
+        }
+        for a in &self.amendments {
+            if a.bar >= n {
+                return Err(format!("amendment bar {} out of range ({} bars)", a.bar, n));
+            }
+            if a.signal_index.is_some_and(|s| s >= a.bar) {
+                return Err(format!(
+                    "amendment at bar {} targets signal bar {}, which has not filled yet", a.bar, a.signal_index.unwrap()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Update `pos`'s levels for bar `j`; true if anything changed.
+    /// Amendments apply first; the time and trailing stops only ever tighten.
+    pub fn apply(&self, pos: &mut Position, state: &mut RuleState, j: usize, high: &[f64], low: &[f64]) -> bool {
+        let start = self.amendments.partition_point(|a| a.bar < j);
+        let mut changed = false;
+        for a in self.amendments[start..].iter().take_while(|a| a.bar == j) {
+            if a.signal_index.is_some_and(|s| s != pos.signal_index) {
+                continue;
+            }
+            if let Some(tp) = a.tp {
+                pos.tp  = tp;
+                changed = true;
+            }
+            if let Some(sl) = a.sl {
+                pos.sl  = sl;
+                changed = true;
+            }
+        }
+        if let Some(ts) = &self.time_stop {
+            let target = state.initial_sl
+                + (pos.entry_price - state.initial_sl) * ts.fraction(j - pos.entry_index);
+            changed |= tighten(pos, target);
+        }
+        if let Some(ch) = &self.chandelier {
+            if j > pos.entry_index {
+                let long = pos.position_type == "long";
+                state.extreme = if long { state.extreme.max(high[j - 1]) } else { state.extreme.min(low[j - 1]) };
+                let atr = ch.atr[j - 1];
+                if !atr.is_nan() {
+                    let target = if long { state.extreme - ch.mult * atr } else { state.extreme + ch.mult * atr };
+                    changed |= tighten(pos, target);
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Move the stop to `target` if that is closer to (or past) the entry
+fn tighten(pos: &mut Position, target: f64) -> bool {
+    let tighter = if pos.position_type == "long" { target > pos.sl } else { target < pos.sl };
+    if tighter {
+        pos.sl = target;
+    }
+    tighter
+}
@@ -1,6 +1,6 @@
 // src/engine/exposure.rs
 
-use crate::engine::position::Position;
+use crate::engine::{contract::ContractType, position::Position};
 
 /// One snapshot of bar-level exposure + PnL
 pub struct ExposureSnapshot {
@@ -11,6 +11,112 @@ pub struct ExposureSnapshot {
     pub realized_equity: f64,
     pub floating_pnl:    f64,
     pub total_equity:    f64,
+    /// Open long / short positions at this bar
+    pub open_longs:      usize,
+    pub open_shorts:     usize,
+    /// Mean age (bars since entry) of the open positions; 0 when flat
+    pub avg_open_age:    f64,
+    /// Account balance: initial equity + realized PnL
+    pub cash:            f64,
+    /// Initial margin of open positions (entry notional / leverage)
+    pub margin_used:     f64,
+    /// Equity (cash + floating) not tied up as margin
+    pub free_margin:     f64,
+    /// Open size × the bar's close per side (comparable across instruments)
+    pub long_notional:   f64,
+    pub short_notional:  f64,
+    pub total_notional:  f64,
+    /// Notional as a fraction of account equity (initial + total equity)
+    pub long_pct_of_equity:  f64,
+    pub short_pct_of_equity: f64,
+    pub total_pct_of_equity: f64,
+    /// Size‐weighted average entry price of the open (filled) long / short
+    /// units (harmonic for inverse contracts); NaN when flat
+    pub long_avg_entry:  f64,
+    pub short_avg_entry: f64,
+}
+
+impl ExposureSnapshot {
+    /// Recompute the `*_pct_of_equity` fields from the notionals and equity
+    pub fn set_equity_shares(&mut self, initial_equity: f64) {
+        let equity = initial_equity + self.total_equity;
+        let share = |x: f64| if equity != 0.0 { x / equity } else { 0.0 };
+        self.long_pct_of_equity  = share(self.long_notional);
+        self.short_pct_of_equity = share(self.short_notional);
+        self.total_pct_of_equity = share(self.total_notional);
+    }
+}
+
+/// Running size‐weighted entry price of the open units on one side
+#[derive(Clone, Copy, Default)]
+struct EntryBook {
+    size:     f64,
+    /// Σ size × price (Σ size / price for inverse contracts)
+    weighted: f64,
+    contract: ContractType,
+}
+
+impl EntryBook {
+    fn add(&mut self, contract: ContractType, price: f64, size: f64) {
+        self.contract = contract;
+        self.size += size;
+        self.weighted += match contract {
+            ContractType::Linear  => size * price,
+            ContractType::Inverse => size / price,
+        };
+    }
+
+    fn average(&self) -> f64 {
+        if self.size <= 0.0 || self.weighted == 0.0 {
+            return f64::NAN;
+        }
+        match self.contract {
+            ContractType::Linear  => self.weighted / self.size,
+            ContractType::Inverse => self.size / self.weighted,
+        }
+    }
+}
+
+/// Average entry of one side across series on different instruments: the
+/// one series holding that side, NaN when none or several do (prices of
+/// different instruments don't average)
+pub(crate) fn single_average(values: impl Iterator<Item = f64>) -> f64 {
+    let mut held = values.filter(|v| !v.is_nan());
+    match (held.next(), held.next()) {
+        (Some(v), None) => v,
+        _ => f64::NAN,
+    }
+}
+
+/// When trading costs reach realized equity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CostAccrual {
+    /// With the trade's PnL at its exit (the historical behaviour)
+    #[default]
+    Exit,
+    /// Entry fees at each fill bar, exit fees at the exit bar
+    Incurred,
+}
+
+impl CostAccrual {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "exit"     => Ok(CostAccrual::Exit),
+            "incurred" => Ok(CostAccrual::Incurred),
+            other => Err(format!("cost_accrual must be \"exit\" or \"incurred\", got ‘{}’", other)),
+        }
+    }
+}
+
+/// How costs enter the bar‐by‐bar equity
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExposureOptions<'a> {
+    pub accrual:       CostAccrual,
+    /// Net open positions' paid entry fees and accrued funding out of
+    /// floating PnL
+    pub net_floating:  bool,
+    /// Per‐bar funding rates (as in `apply_funding`) to accrue while open
+    pub funding_rates: Option<&'a [f64]>,
 }
 
 /// O(N + M) exposure/PnL via prefix-sums + small per-bar loops
@@ -18,9 +124,23 @@ pub fn compute_exposure_series(
     positions: &[Position],
     price: &[f64],
     timestamps: &[f64],
-    _initial_equity: f64,
+    initial_equity: f64,
+    leverage: f64,
+) -> Vec<ExposureSnapshot> {
+    compute_exposure_series_with(positions, price, timestamps, initial_equity, leverage, ExposureOptions::default())
+}
+
+/// `compute_exposure_series` with explicit cost handling
+pub fn compute_exposure_series_with(
+    positions: &[Position],
+    price: &[f64],
+    timestamps: &[f64],
+    initial_equity: f64,
+    leverage: f64,
+    opts: ExposureOptions<'_>,
 ) -> Vec<ExposureSnapshot> {
     let n = price.len();
+    let accrual = opts.accrual;
 
     // 1) Build event arrays
     let mut realized_events = vec![0.0; n];
@@ -37,11 +157,29 @@ pub fn compute_exposure_series(
                 short_delta[exit_i] -= pos.position_size;
             }
         }
-        // At entry, add exposure
-        if pos.position_type=="long" {
-            long_delta[pos.entry_index] += pos.position_size;
+        // Entry fees leave the account as they are paid (and come back out
+        // of the exit PnL, which already nets them)
+        if accrual == CostAccrual::Incurred {
+            if pos.fills.is_empty() {
+                realized_events[pos.entry_index] -= pos.fee_entry;
+            } else {
+                let filled: f64 = pos.fills.iter().map(|f| f.size).sum();
+                for fill in &pos.fills {
+                    realized_events[fill.bar] -= pos.fee_entry * fill.size / filled;
+                }
+            }
+            if let Some(exit_i) = pos.exit_index {
+                realized_events[exit_i] += pos.fee_entry;
+            }
+        }
+        // At entry (or at each partial fill), add exposure
+        let side = if pos.position_type=="long" { &mut long_delta } else { &mut short_delta };
+        if pos.fills.is_empty() {
+            side[pos.entry_index] += pos.position_size;
         } else {
-            short_delta[pos.entry_index] += pos.position_size;
+            for fill in &pos.fills {
+                side[fill.bar] += fill.size;
+            }
         }
     }
 
@@ -50,6 +188,8 @@ pub fn compute_exposure_series(
     let mut cum_realized = 0.0;
     let mut long_exp     = 0.0;
     let mut short_exp    = 0.0;
+    // funding each position has accrued so far (net_floating only)
+    let mut funding_paid = vec![0.0; positions.len()];
 
     for i in 0..n {
         cum_realized += realized_events[i];
@@ -58,17 +198,50 @@ pub fn compute_exposure_series(
 
         // Only **open** positions contribute to floating
         let mut float_pnl = 0.0;
-        for pos in positions.iter().filter(|p| {
-            p.entry_index <= i && p.exit_index.map_or(true, |ei| ei > i)
+        let mut margin    = 0.0;
+        let (mut long_notional, mut short_notional) = (0.0, 0.0);
+        let (mut open_longs, mut open_shorts, mut age_sum) = (0usize, 0usize, 0usize);
+        let (mut long_book, mut short_book) = (EntryBook::default(), EntryBook::default());
+        for (k, pos) in positions.iter().enumerate().filter(|(_, p)| {
+            p.entry_index <= i && p.exit_index.is_none_or(|ei| ei > i)
         }) {
-            if pos.position_type=="long" {
-                float_pnl += (price[i] - pos.entry_price) * pos.position_size;
+            let long = pos.position_type=="long";
+            if long { open_longs += 1 } else { open_shorts += 1 }
+            age_sum += i - pos.entry_index;
+            let book = if long { &mut long_book } else { &mut short_book };
+            // share of the entry fee paid so far
+            let (held, fee_share) = if pos.fills.is_empty() {
+                float_pnl += pos.contract.pnl(long, pos.entry_price, price[i], pos.position_size);
+                margin    += pos.contract.notional(pos.entry_price, pos.position_size);
+                book.add(pos.contract, pos.entry_price, pos.position_size);
+                (pos.position_size, 1.0)
             } else {
-                float_pnl += (pos.entry_price - price[i]) * pos.position_size;
+                let mut filled = 0.0;
+                for fill in pos.fills.iter().filter(|f| f.bar <= i) {
+                    float_pnl += pos.contract.pnl(long, fill.price, price[i], fill.size);
+                    margin    += pos.contract.notional(fill.price, fill.size);
+                    book.add(pos.contract, fill.price, fill.size);
+                    filled    += fill.size;
+                }
+                let total: f64 = pos.fills.iter().map(|f| f.size).sum();
+                (filled, if total > 0.0 { filled / total } else { 0.0 })
+            };
+            let notional = pos.contract.notional(price[i], held);
+            if long { long_notional += notional } else { short_notional += notional }
+            if opts.net_floating {
+                // with `Incurred` the fee is already out of realized equity
+                if accrual == CostAccrual::Exit {
+                    float_pnl -= pos.fee_entry * fee_share;
+                }
+                if let Some(rate) = opts.funding_rates.map(|r| r[i]).filter(|r| !r.is_nan()) {
+                    let dir = if long { 1.0 } else { -1.0 };
+                    funding_paid[k] += dir * rate * pos.contract.notional(price[i], pos.position_size);
+                }
+                float_pnl -= funding_paid[k];
             }
         }
 
-        snapshots.push(ExposureSnapshot {
+        let mut snap = ExposureSnapshot {
             timestamp:       timestamps[i],
             long_exposure:   long_exp,
             short_exposure:  short_exp,
@@ -76,8 +249,223 @@ pub fn compute_exposure_series(
             realized_equity: cum_realized,
             floating_pnl:    float_pnl,
             total_equity:    cum_realized + float_pnl,
-        });
+            open_longs,
+            open_shorts,
+            avg_open_age:    if open_longs + open_shorts > 0 {
+                age_sum as f64 / (open_longs + open_shorts) as f64
+            } else { 0.0 },
+            cash:            initial_equity + cum_realized,
+            margin_used:     margin / leverage,
+            free_margin:     initial_equity + cum_realized + float_pnl - margin / leverage,
+            long_notional,
+            short_notional,
+            total_notional:  long_notional + short_notional,
+            long_pct_of_equity:  0.0,
+            short_pct_of_equity: 0.0,
+            total_pct_of_equity: 0.0,
+            long_avg_entry:  long_book.average(),
+            short_avg_entry: short_book.average(),
+        };
+        snap.set_equity_shares(initial_equity);
+        snapshots.push(snap);
     }
 
     snapshots
 }
+
+/// Mark‐to‐market of a position still open at the last bar
+pub struct OpenMark {
+    pub mark_price:        f64,
+    /// At the mark, before exit costs (as in `floating_pnl`)
+    pub unrealized_pnl:    f64,
+    /// Unrealized PnL / entry notional
+    pub unrealized_return: f64,
+    pub bars_held:         usize,
+}
+
+/// `OpenMark` of every open position in `positions` (in order) at the last
+/// bar of `price`
+pub fn mark_open_positions(positions: &[Position], price: &[f64]) -> Vec<OpenMark> {
+    positions.iter().filter(|p| !p.is_closed).map(|p| mark_open_position(p, price)).collect()
+}
+
+pub fn mark_open_position(pos: &Position, price: &[f64]) -> OpenMark {
+    let last = price.len() - 1;
+    let mark = price[last];
+    let long = pos.position_type == "long";
+    let (pnl, notional) = if pos.fills.is_empty() {
+        (pos.contract.pnl(long, pos.entry_price, mark, pos.position_size),
+         pos.contract.notional(pos.entry_price, pos.position_size))
+    } else {
+        pos.fills.iter().fold((0.0, 0.0), |(pnl, notional), f| {
+            (pnl + pos.contract.pnl(long, f.price, mark, f.size),
+             notional + pos.contract.notional(f.price, f.size))
+        })
+    };
+    OpenMark {
+        mark_price:        mark,
+        unrealized_pnl:    pnl,
+        unrealized_return: if notional != 0.0 { pnl / notional } else { 0.0 },
+        bars_held:         last.saturating_sub(pos.entry_index),
+    }
+}
+
+/// Realized equity right after one trade close
+pub struct TradeClosePoint {
+    pub exit_index:  usize,
+    pub timestamp:   f64,
+    pub position_id: u64,
+    pub pnl:         f64,
+    pub equity:      f64,
+}
+
+/// Realized‐only equity sampled at every exit (in exit order), for
+/// trade‐based statistics
+pub fn compute_trade_close_curve(
+    closed: &[Position],
+    timestamps: &[f64],
+    initial_equity: f64,
+) -> Vec<TradeClosePoint> {
+    let mut ordered: Vec<&Position> = closed.iter().filter(|p| p.exit_index.is_some()).collect();
+    ordered.sort_by(|a, b| a.cmp_exit(b));
+    let mut equity = initial_equity;
+    ordered
+        .into_iter()
+        .map(|p| {
+            let exit_i = p.exit_index.unwrap_or(0);
+            let pnl = p.pnl.unwrap_or(0.0);
+            equity += pnl;
+            TradeClosePoint {
+                exit_index:  exit_i,
+                timestamp:   timestamps[exit_i],
+                position_id: p.position_id,
+                pnl,
+                equity,
+            }
+        })
+        .collect()
+}
+
+/// Exposure snapshots from an account equity curve: realized equity from
+/// the trades' exits, the rest of the curve counted as floating
+pub fn exposure_from_equity(
+    positions: &[Position],
+    timestamps: &[f64],
+    equity: &[f64],
+    initial_equity: f64,
+) -> Vec<ExposureSnapshot> {
+    let n = equity.len();
+    let mut realized = vec![0.0; n];
+    let mut longs = vec![0i64; n + 1];
+    let mut shorts = vec![0i64; n + 1];
+    let mut long_size = vec![0.0; n + 1];
+    let mut short_size = vec![0.0; n + 1];
+    // entry books at each bar boundary, as deltas like the sizes
+    let mut long_value = vec![0.0; n + 1];
+    let mut short_value = vec![0.0; n + 1];
+    let contract = positions.first().map(|p| p.contract).unwrap_or_default();
+    for pos in positions {
+        let exit = pos.exit_index.unwrap_or(n);
+        if let (Some(i), Some(pnl)) = (pos.exit_index, pos.pnl) {
+            realized[i] += pnl;
+        }
+        let (count, size, value) = if pos.position_type == "long" {
+            (&mut longs, &mut long_size, &mut long_value)
+        } else {
+            (&mut shorts, &mut short_size, &mut short_value)
+        };
+        count[pos.entry_index] += 1;
+        count[exit] -= 1;
+        size[pos.entry_index] += pos.position_size;
+        size[exit] -= pos.position_size;
+        let mut book = EntryBook::default();
+        book.add(pos.contract, pos.entry_price, pos.position_size);
+        value[pos.entry_index] += book.weighted;
+        value[exit] -= book.weighted;
+    }
+
+    let (mut cum, mut nl, mut ns, mut sl, mut ss) = (0.0, 0, 0, 0.0, 0.0);
+    let mut long_book = EntryBook { contract, ..EntryBook::default() };
+    let mut short_book = long_book;
+    (0..n)
+        .map(|i| {
+            cum += realized[i];
+            nl += longs[i];
+            ns += shorts[i];
+            sl += long_size[i];
+            ss += short_size[i];
+            long_book.size = sl;
+            long_book.weighted += long_value[i];
+            short_book.size = ss;
+            short_book.weighted += short_value[i];
+            let total = equity[i] - initial_equity;
+            ExposureSnapshot {
+                timestamp:       timestamps[i],
+                long_exposure:   sl,
+                short_exposure:  ss,
+                total_exposure:  sl + ss,
+                realized_equity: cum,
+                floating_pnl:    total - cum,
+                total_equity:    total,
+                open_longs:      nl as usize,
+                open_shorts:     ns as usize,
+                avg_open_age:    0.0,
+                cash:            initial_equity + cum,
+                margin_used:     0.0,
+                free_margin:     equity[i],
+                long_notional:   0.0,
+                short_notional:  0.0,
+                total_notional:  0.0,
+                long_pct_of_equity:  0.0,
+                short_pct_of_equity: 0.0,
+                total_pct_of_equity: 0.0,
+                long_avg_entry:  if nl > 0 { long_book.average() } else { f64::NAN },
+                short_avg_entry: if ns > 0 { short_book.average() } else { f64::NAN },
+            }
+        })
+        .collect()
+}
+
+/// Bar‐by‐bar sum of exposure series priced on different instruments
+/// (all on the same timestamps; `series` must not be empty)
+pub fn sum_exposure_series(series: &[&[ExposureSnapshot]], initial_equity: f64) -> Vec<ExposureSnapshot> {
+    (0..series[0].len())
+        .map(|i| {
+            let bars = series.iter().map(|s| &s[i]);
+            let sum = |f: fn(&ExposureSnapshot) -> f64| bars.clone().map(f).sum::<f64>();
+            let open_longs:  usize = bars.clone().map(|s| s.open_longs).sum();
+            let open_shorts: usize = bars.clone().map(|s| s.open_shorts).sum();
+            let open = open_longs + open_shorts;
+            let realized    = sum(|s| s.realized_equity);
+            let floating    = sum(|s| s.floating_pnl);
+            let margin_used = sum(|s| s.margin_used);
+            let mut snap = ExposureSnapshot {
+                timestamp:       series[0][i].timestamp,
+                long_exposure:   sum(|s| s.long_exposure),
+                short_exposure:  sum(|s| s.short_exposure),
+                total_exposure:  sum(|s| s.total_exposure),
+                realized_equity: realized,
+                floating_pnl:    floating,
+                total_equity:    realized + floating,
+                open_longs,
+                open_shorts,
+                avg_open_age:    if open > 0 {
+                    sum(|s| s.avg_open_age * (s.open_longs + s.open_shorts) as f64) / open as f64
+                } else { 0.0 },
+                cash:            initial_equity + realized,
+                margin_used,
+                free_margin:     initial_equity + realized + floating - margin_used,
+                long_notional:   sum(|s| s.long_notional),
+                short_notional:  sum(|s| s.short_notional),
+                total_notional:  sum(|s| s.total_notional),
+                long_pct_of_equity:  0.0,
+                short_pct_of_equity: 0.0,
+                total_pct_of_equity: 0.0,
+                long_avg_entry:  single_average(bars.clone().map(|s| s.long_avg_entry)),
+                short_avg_entry: single_average(bars.clone().map(|s| s.short_avg_entry)),
+            };
+            snap.set_equity_shares(initial_equity);
+            snap
+        })
+        .collect()
+}
@@ -0,0 +1,273 @@
+// src/engine/external.rs
+
+//! Metrics of trades produced outside the engine (live fills, another
+//! simulator), computed exactly as for a `run_backtest` result
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    array_to_vec,
+    backtest::{run_metrics, BacktestConfig},
+    bootstrap::BootstrapOptions,
+    benchmark::account_equity,
+    concurrency::concurrency_stats,
+    config_file::RUN_KWARGS,
+    contract::ContractType,
+    exposure::{compute_exposure_series_with, exposure_from_equity, CostAccrual},
+    metrics::{EquityBasis, RatioPolicy, ReturnEquity, ReturnKind, ReturnSpec},
+    position::Position,
+    liquidity::Liquidity,
+    stages::{liquidity_from_py, optional, required},
+    output::summary_metrics_to_py,
+};
+
+/// Bar holding `time`: the last bar starting at or before it
+fn bar_at(timestamps: &[f64], time: f64, what: &str) -> PyResult<usize> {
+    match timestamps.partition_point(|&t| t <= time) {
+        0 => Err(PyValueError::new_err(format!("{} {} is before the first bar", what, time))),
+        k => Ok(k - 1),
+    }
+}
+
+/// One external trade → Position; `pnl` and fees are derived from the
+/// prices and the config's fee rates when the dict doesn't carry them
+fn trade_from_py(d: &PyDict, id: usize, timestamps: &[f64], config: &BacktestConfig) -> PyResult<Position> {
+    const WHAT: &str = "trade";
+    let position_type: String = required(d, WHAT, "position_type")?;
+    if position_type != "long" && position_type != "short" {
+        return Err(PyValueError::new_err(format!(
+            "position_type must be \"long\" or \"short\", got ‘{}’", position_type
+        )));
+    }
+    let long = position_type == "long";
+    let entry_time: f64 = required(d, WHAT, "entry_time")?;
+    let entry_price: f64 = required(d, WHAT, "entry_price")?;
+    let size: f64 = required(d, WHAT, "position_size")?;
+    if entry_price.is_nan() || entry_price <= 0.0 || !size.is_finite() || size <= 0.0 {
+        return Err(PyValueError::new_err(format!("trade {} needs a positive entry_price and position_size", id)));
+    }
+    let exit_time: Option<f64> = optional(d, "exit_time")?;
+    let exit_price: Option<f64> = optional(d, "exit_price")?;
+    if exit_time.is_some() != exit_price.is_some() {
+        return Err(PyValueError::new_err(format!("trade {} needs both exit_time and exit_price, or neither", id)));
+    }
+    if exit_time.is_some_and(|t| t < entry_time) {
+        return Err(PyValueError::new_err(format!("trade {} exits before it enters", id)));
+    }
+    let contract = config.contract;
+    let entry_index = bar_at(timestamps, entry_time, "entry_time")?;
+    let exit_index = exit_time.map(|t| bar_at(timestamps, t, "exit_time")).transpose()?;
+
+    let notional = contract.notional(entry_price, size);
+    let fee_entry = optional(d, "fee_entry")?.unwrap_or(notional * config.entry_fee_rate);
+    let exit_condition: Option<String> = optional(d, "exit_condition")?;
+    // take‐profits rest, every other exit takes unless the trade says otherwise
+    let exit_liquidity = match (liquidity_from_py(d, "exit_liquidity")?, exit_price) {
+        (_, None)            => None,
+        (Some(l), Some(_))   => Some(l),
+        (None, Some(_)) if exit_condition.as_deref() == Some("TP") => Some(Liquidity::Maker),
+        (None, Some(_))      => Some(Liquidity::Taker),
+    };
+    let fee_exit = match (exit_price, exit_liquidity) {
+        (Some(px), Some(l)) => optional(d, "fee_exit")?
+            .unwrap_or(contract.notional(px, size) * config.exit_fees().for_liquidity(l)),
+        _ => 0.0,
+    };
+    let pnl = match (optional::<f64>(d, "pnl")?, exit_price) {
+        (Some(pnl), Some(_)) => Some(pnl),
+        (None, Some(px))     => Some(contract.pnl(long, entry_price, px, size) - fee_entry - fee_exit),
+        (_, None)            => None,
+    };
+    let sl = optional(d, "sl")?.unwrap_or(f64::NAN);
+    Ok(Position {
+        position_id:      optional(d, "position_id")?.unwrap_or(id as u64),
+        entry_time,
+        position_type,
+        signal_index:     entry_index,
+        entry_index,
+        entry_price,
+        tp:               optional(d, "tp")?.unwrap_or(f64::NAN),
+        sl,
+        expiration_time:  None,
+        exit_index,
+        exit_time,
+        expiry_slippage:  None,
+        expiry_action:    None,
+        exit_price,
+        exit_condition,
+        position_size:    size,
+        contract,
+        fee_entry,
+        fee_exit,
+        entry_liquidity:  liquidity_from_py(d, "entry_liquidity")?.unwrap_or_default(),
+        exit_liquidity,
+        slippage_entry:   optional(d, "slippage_entry")?.unwrap_or(0.0),
+        slippage_exit:    optional(d, "slippage_exit")?.unwrap_or(0.0),
+        absolute_return:  exit_price.map(|px| px / entry_price - 1.0),
+        real_return:      pnl.map(|p| p / notional),
+        pnl,
+        is_closed:        exit_index.is_some(),
+        roll_count:       0,
+        roll_cost:        0.0,
+        funding_cost:     optional(d, "funding_cost")?.unwrap_or(0.0),
+        dividend_income:  0.0,
+        strategy_id:      optional(d, "strategy_id")?,
+        symbol:           optional(d, "symbol")?,
+        tag:              optional(d, "tag")?,
+        entry_delay:      None,
+        exit_delay:       None,
+        latency_cost:     0.0,
+        tp_attempts:      0,
+        tp_rejections:    0,
+        throttled:        false,
+        entry_sl:         sl,
+        original_sl:      None,
+        original_size:    None,
+        fills:            Vec::new(),
+        unfilled_size:    0.0,
+        level_changes:    Vec::new(),
+        bracket:          None,
+        mfe:              0.0,
+        mae:              0.0,
+        liquidation_price: None,
+        near_liquidation: Vec::new(),
+        liquidation_bar:  None,
+    })
+}
+
+/// The metric options of a `run_backtest` keyword dict
+pub(crate) fn config_from_py(config: Option<&PyDict>) -> PyResult<BacktestConfig> {
+    fn get<'a, T: FromPyObject<'a>>(d: Option<&'a PyDict>, key: &str, default: T) -> PyResult<T> {
+        Ok(match d {
+            Some(d) => optional(d, key)?.unwrap_or(default),
+            None    => default,
+        })
+    }
+    if let Some(d) = config {
+        for key in d.keys() {
+            let key: &str = key.extract()?;
+            if !RUN_KWARGS.contains(&key) {
+                return Err(PyValueError::new_err(format!("unknown config key ‘{}’", key)));
+            }
+        }
+    }
+    let initial_equity: f64 = match config.and_then(|d| d.get_item("initial_equity")) {
+        Some(v) => v.extract()?,
+        None    => return Err(PyValueError::new_err("config has no ‘initial_equity’")),
+    };
+    let leverage: f64 = get(config, "leverage", 1.0)?;
+    if leverage.is_nan() || leverage <= 0.0 {
+        return Err(PyValueError::new_err("leverage must be > 0"));
+    }
+    let bootstrap_samples: usize = get(config, "bootstrap_samples", 0)?;
+    let bootstrap_confidence: f64 = get(config, "bootstrap_confidence", 0.95)?;
+    if !(0.0..1.0).contains(&bootstrap_confidence) {
+        return Err(PyValueError::new_err("bootstrap_confidence must be in [0, 1)"));
+    }
+    let duration_edges: Option<Vec<f64>> = get(config, "duration_buckets", None)?;
+    if let Some(edges) = &duration_edges {
+        if edges.iter().any(|e| e.is_nan() || *e <= 0.0) || edges.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(PyValueError::new_err("duration_buckets must be increasing positive seconds"));
+        }
+    }
+    let taker_fee_rate: Option<f64> = get(config, "taker_fee_rate", None)?;
+    let config = BacktestConfig {
+        initial_equity,
+        entry_fee_rate: taker_fee_rate.unwrap_or(get(config, "entry_fee_rate", 0.0)?),
+        exit_fee_rate:  taker_fee_rate.unwrap_or(get(config, "exit_fee_rate", 0.0)?),
+        maker_fee_rate: get(config, "maker_fee_rate", None)?,
+        contract: ContractType::parse(get(config, "contract_type", "linear")?).map_err(PyValueError::new_err)?,
+        leverage: Some(leverage),
+        bootstrap: (bootstrap_samples > 0).then_some(BootstrapOptions {
+            n_samples:    bootstrap_samples,
+            block_length: get(config, "bootstrap_block_length", None)?,
+            seed:         get(config, "bootstrap_seed", 0)?,
+            confidence:   bootstrap_confidence,
+        }),
+        weighted_returns: get(config, "weighted_returns", false)?,
+        ratio_policy: RatioPolicy::parse(get(config, "undefined_ratios", "legacy")?, get(config, "ratio_cap", None)?)
+            .map_err(PyValueError::new_err)?,
+        returns: ReturnSpec {
+            basis:  EquityBasis::parse(get(config, "equity_basis", "pnl")?).map_err(PyValueError::new_err)?,
+            kind:   ReturnKind::parse(get(config, "return_type", "simple")?).map_err(PyValueError::new_err)?,
+            equity: ReturnEquity::parse(get(config, "return_equity", "marked")?).map_err(PyValueError::new_err)?,
+        },
+        cost_accrual: CostAccrual::parse(get(config, "cost_accrual", "exit")?).map_err(PyValueError::new_err)?,
+        net_floating: get(config, "net_floating", false)?,
+        overlap_threshold: Some(get(config, "overlap_threshold", 1)?),
+        autocorr_lags: get(config, "autocorr_lags", None)?,
+        duration_edges,
+        ..BacktestConfig::default()
+    };
+    config.validate_fees().map_err(PyValueError::new_err)?;
+    Ok(config)
+}
+
+/// The `metrics` dict of `run_backtest` for trades produced elsewhere.
+///
+/// `trades` are dicts with `position_type`, `entry_time`, `entry_price`,
+/// `position_size` and, once closed, `exit_time` and `exit_price`; `pnl`,
+/// `fee_entry`, `fee_exit`, `sl`, `tag` and the other trade fields are
+/// optional.  Missing fees are charged at the config's rates by liquidity
+/// (`entry_liquidity` / `exit_liquidity`, by default taker except for "TP"
+/// exits).  Fills are placed on the last bar of `timestamp` starting at
+/// or before them.  The equity comes from `prices` (trades marked at each
+/// bar's price, as in a run) or from an account `equity_curve`; pass one.
+/// `config` takes `run_backtest` keyword arguments (e.g. the `run` table of
+/// `load_config`), of which only `initial_equity`, the fee rates,
+/// `contract_type`, `leverage`, the cost accrual and the metric options are
+/// used.
+#[pyfunction]
+#[pyo3(signature=(trades, timestamp, equity_curve=None, prices=None, config=None))]
+pub fn compute_metrics<'py>(
+    py:           Python<'py>,
+    trades:       Vec<&PyDict>,
+    timestamp:    &PyArray1<f64>,
+    equity_curve: Option<&PyArray1<f64>>,
+    prices:       Option<&PyArray1<f64>>,
+    config:       Option<&PyDict>,
+) -> PyResult<&'py PyDict> {
+    let config = config_from_py(config)?;
+    let timestamps = array_to_vec(timestamp)?;
+    if timestamps.is_empty() {
+        return Err(PyValueError::new_err("timestamp must not be empty"));
+    }
+    if !timestamps.windows(2).all(|w| w[1] > w[0]) {
+        return Err(PyValueError::new_err("timestamps must be strictly increasing"));
+    }
+    let positions = trades
+        .into_iter()
+        .enumerate()
+        .map(|(id, d)| trade_from_py(d, id, &timestamps, &config))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let exposure = match (prices, equity_curve) {
+        (Some(prices), None) => {
+            let prices = array_to_vec(prices)?;
+            if prices.len() != timestamps.len() {
+                return Err(PyValueError::new_err("‘prices’ length must match timestamp"));
+            }
+            compute_exposure_series_with(
+                &positions, &prices, &timestamps, config.initial_equity, config.leverage(), config.exposure_options(),
+            )
+        }
+        (None, Some(equity)) => {
+            let equity = array_to_vec(equity)?;
+            if equity.len() != timestamps.len() {
+                return Err(PyValueError::new_err("‘equity_curve’ length must match timestamp"));
+            }
+            exposure_from_equity(&positions, &timestamps, &equity, config.initial_equity)
+        }
+        _ => return Err(PyValueError::new_err("pass either prices or equity_curve")),
+    };
+
+    let concurrency = concurrency_stats(&positions, &exposure, config.overlap_threshold.unwrap_or(1));
+    let closed: Vec<Position> = positions.into_iter().filter(|p| p.is_closed).collect();
+    let equity = account_equity(&exposure, config.initial_equity);
+    let mut metrics = run_metrics(&timestamps, &closed, &exposure, &equity, None, &config);
+    metrics.concurrency = Some(concurrency);
+    summary_metrics_to_py(py, &metrics)
+}
@@ -0,0 +1,60 @@
+// src/engine/factors.rs
+
+/// Beta of the strategy's bar returns to one factor return series
+#[derive(Debug, Clone)]
+pub struct FactorBeta {
+    pub name:    String,
+    /// Full‐sample OLS beta (NaN if the factor is flat)
+    pub beta:    f64,
+    /// Beta over the trailing `window` bars ending at each bar; NaN until
+    /// the window fills or when the factor is flat within it
+    pub rolling: Vec<f64>,
+}
+
+fn ols_beta(sx: f64, sy: f64, sxx: f64, sxy: f64, m: f64) -> f64 {
+    let var = sxx - sx * sx / m;
+    if m < 2.0 || var <= f64::EPSILON * sxx.abs().max(1.0) {
+        return f64::NAN;
+    }
+    (sxy - sx * sy / m) / var
+}
+
+/// Betas of `returns` (bar i's return at `returns[i-1]`) to a per‐bar
+/// factor return series (`factor[i]` is bar i's factor return; bar 0's is
+/// unused).  Rolling sums keep this O(n) per factor.
+pub fn factor_beta(name: &str, returns: &[f64], factor: &[f64], window: usize) -> FactorBeta {
+    let n = factor.len();
+    let pairs: Vec<(f64, f64)> = (1..n)
+        .map(|i| (factor[i], returns.get(i - 1).copied().unwrap_or(0.0)))
+        .collect();
+
+    let sums = |ps: &[(f64, f64)]| {
+        ps.iter().filter(|(x, y)| !x.is_nan() && !y.is_nan()).fold(
+            (0.0, 0.0, 0.0, 0.0, 0.0),
+            |(sx, sy, sxx, sxy, m), &(x, y)| (sx + x, sy + y, sxx + x * x, sxy + x * y, m + 1.0),
+        )
+    };
+    let (sx, sy, sxx, sxy, m) = sums(&pairs);
+    let beta = ols_beta(sx, sy, sxx, sxy, m);
+
+    let mut rolling = vec![f64::NAN; n];
+    if window >= 2 {
+        let (mut sx, mut sy, mut sxx, mut sxy, mut m) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for (k, &(x, y)) in pairs.iter().enumerate() {
+            if !x.is_nan() && !y.is_nan() {
+                sx += x; sy += y; sxx += x * x; sxy += x * y; m += 1.0;
+            }
+            if k >= window {
+                let (ox, oy) = pairs[k - window];
+                if !ox.is_nan() && !oy.is_nan() {
+                    sx -= ox; sy -= oy; sxx -= ox * ox; sxy -= ox * oy; m -= 1.0;
+                }
+            }
+            if k + 1 >= window {
+                rolling[k + 1] = ols_beta(sx, sy, sxx, sxy, m);
+            }
+        }
+    }
+
+    FactorBeta { name: name.to_string(), beta, rolling }
+}
@@ -0,0 +1,86 @@
+// src/engine/fill_model.rs
+
+/// Probabilistic TP (limit) fills: a bar that only touches the level fills
+/// with `touch_probability`, rising linearly to certainty once price trades
+/// `full_fill_depth` (fraction of the level) through it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FillModel {
+    pub touch_probability: f64,
+    pub full_fill_depth:   f64,
+    pub seed:              u64,
+}
+
+impl FillModel {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.touch_probability) {
+            return Err("fill_probability must be in [0, 1]".into());
+        }
+        if self.full_fill_depth.is_nan() || self.full_fill_depth < 0.0 {
+            return Err("fill_full_depth must be ≥ 0".into());
+        }
+        Ok(())
+    }
+
+    /// Fill probability for a bar that traded `through` price units past `level`
+    pub fn probability(&self, level: f64, through: f64) -> f64 {
+        let depth = if level != 0.0 { through / level.abs() } else { 0.0 };
+        let frac = if self.full_fill_depth > 0.0 {
+            (depth / self.full_fill_depth).min(1.0)
+        } else if depth > 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+        self.touch_probability + (1.0 - self.touch_probability) * frac
+    }
+}
+
+/// Queue‐position TP (limit) fills from per‐bar L1 quotes and volume.
+///
+/// A TP order joins the back of the displayed queue on its side when it
+/// starts resting (the best ask's size for a sell at or above the ask, none
+/// when it improves the quote) and is re‐queued whenever its level moves.
+/// A bar whose opposite quote crossed the level (bid above a sell, ask below
+/// a buy) fills it outright; a bar that only traded at the level works off
+/// `level_share` of the bar's volume, filling once the queue ahead and the
+/// order's own size are consumed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueueModel {
+    pub bid:         Vec<f64>,
+    pub ask:         Vec<f64>,
+    pub bid_size:    Vec<f64>,
+    pub ask_size:    Vec<f64>,
+    pub volume:      Vec<f64>,
+    pub level_share: f64,
+}
+
+impl QueueModel {
+    pub fn validate(&self, n: usize) -> Result<(), String> {
+        let lens = [self.bid.len(), self.ask.len(), self.bid_size.len(), self.ask_size.len(), self.volume.len()];
+        if lens.iter().any(|&l| l != n) {
+            return Err("bid, ask, bid_size, ask_size and volume must match the bar series".into());
+        }
+        if !(self.level_share > 0.0 && self.level_share <= 1.0) {
+            return Err("queue_volume_share must be in (0, 1]".into());
+        }
+        Ok(())
+    }
+
+    /// Displayed size ahead of a new order at `level` on bar `j`
+    pub fn queue_ahead(&self, j: usize, sell: bool, level: f64) -> f64 {
+        let (best, size) = if sell { (self.ask[j], self.ask_size[j]) } else { (self.bid[j], self.bid_size[j]) };
+        let joins = if sell { level >= best } else { level <= best };
+        if joins && !size.is_nan() { size.max(0.0) } else { 0.0 }
+    }
+
+    /// Whether bar `j`'s opposite quote crossed the level
+    pub fn traded_through(&self, j: usize, sell: bool, level: f64) -> bool {
+        if sell { self.bid[j] > level } else { self.ask[j] < level }
+    }
+
+    /// Volume assumed to trade at a touched level on bar `j`
+    pub fn level_volume(&self, j: usize) -> f64 {
+        let v = self.volume[j];
+        if v.is_nan() { 0.0 } else { v.max(0.0) * self.level_share }
+    }
+}
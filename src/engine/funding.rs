@@ -0,0 +1,27 @@
+// src/engine/funding.rs
+
+use crate::engine::position::Position;
+
+/// Charge per‐bar funding to every position open at that bar's close.
+///
+/// `rates[t]` is a fraction of the notional at `close[t]`: longs pay a
+/// positive rate and shorts receive it (perpetual‐swap convention), NaN
+/// bars are skipped.  Closed positions pay for the closes from their entry
+/// bar up to (not including) their exit bar and their PnL is adjusted;
+/// open positions accrue through the last bar.  Re‐running after an exit
+/// moved replaces the previous charge.
+pub fn apply_funding(positions: &mut [Position], close: &[f64], rates: &[f64]) {
+    for pos in positions.iter_mut() {
+        let dir = if pos.position_type == "long" { 1.0 } else { -1.0 };
+        let end = pos.exit_index.unwrap_or(close.len());
+        let funding = (pos.entry_index..end)
+            .filter(|&t| !rates[t].is_nan())
+            .fold(0.0, |acc, t| acc + dir * rates[t] * pos.contract.notional(close[t], pos.position_size));
+        if let Some(pnl) = pos.pnl.as_mut() {
+            *pnl += pos.funding_cost - funding;
+            let notional = pos.contract.notional(pos.entry_price, pos.position_size);
+            pos.real_return = Some(if notional != 0.0 { *pnl / notional } else { 0.0 });
+        }
+        pos.funding_cost = funding;
+    }
+}
@@ -0,0 +1,57 @@
+// src/engine/fx.rs
+
+use crate::engine::{
+    exposure::ExposureSnapshot,
+    position::Position,
+};
+
+/// Convert booked PnL and costs from the instrument's quote currency into
+/// the account currency.
+///
+/// `rates[t]` is account currency per unit of quote currency at bar t.  The
+/// entry fee converts at the entry bar, the exit fee, funding and roll cost
+/// at the exit bar (open positions: entry fee only), and PnL is the exit‐bar
+/// conversion of its quote amount with the entry fee at its own rate.
+/// Slippage and latency stay price distances in the quote currency.
+pub fn convert_positions(positions: &mut [Position], rates: &[f64]) {
+    for pos in positions.iter_mut() {
+        let entry_fx = rates[pos.entry_index];
+        let fee_entry = pos.fee_entry * entry_fx;
+        if let Some(exit_i) = pos.exit_index {
+            let exit_fx = rates[exit_i];
+            if let Some(pnl) = pos.pnl.as_mut() {
+                *pnl = *pnl * exit_fx + pos.fee_entry * exit_fx - fee_entry;
+            }
+            pos.fee_exit     *= exit_fx;
+            pos.funding_cost *= exit_fx;
+            pos.roll_cost    *= exit_fx;
+        }
+        pos.fee_entry = fee_entry;
+    }
+}
+
+/// Mark the floating PnL and margin of a curve built from converted
+/// positions at each bar's rate (realized PnL is already converted)
+pub fn convert_exposure(exposure: &mut [ExposureSnapshot], rates: &[f64], initial_equity: f64) {
+    for (s, &fx) in exposure.iter_mut().zip(rates) {
+        s.floating_pnl *= fx;
+        s.margin_used  *= fx;
+        s.total_equity  = s.realized_equity + s.floating_pnl;
+        s.free_margin   = initial_equity + s.total_equity - s.margin_used;
+        s.long_notional  *= fx;
+        s.short_notional *= fx;
+        s.total_notional *= fx;
+        s.set_equity_shares(initial_equity);
+    }
+}
+
+/// Per‐bar rates must be finite and positive
+pub fn validate_rates(rates: &[f64], n: usize) -> Result<(), String> {
+    if rates.len() != n {
+        return Err("‘fx_rates’ length must match the bar series".into());
+    }
+    if rates.iter().any(|r| !r.is_finite() || *r <= 0.0) {
+        return Err("fx_rates must be finite and > 0".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+// src/engine/generators.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    backtest::MarketData,
+    synthetic::{gbm, ornstein_uhlenbeck, regime_switching, BarLayout},
+};
+
+fn layout_from_args(
+    n_bars: usize,
+    start_price: f64,
+    start_time: f64,
+    bar_seconds: f64,
+    steps_per_bar: usize,
+) -> PyResult<BarLayout> {
+    if start_price.is_nan() || start_price <= 0.0 {
+        return Err(PyValueError::new_err("start_price must be > 0"));
+    }
+    if bar_seconds.is_nan() || bar_seconds <= 0.0 {
+        return Err(PyValueError::new_err("bar_seconds must be > 0"));
+    }
+    Ok(BarLayout { n_bars, start_price, start_time, bar_seconds, steps_per_bar })
+}
+
+fn market_to_py<'py>(py: Python<'py>, m: MarketData) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("timestamp", PyArray1::from_vec(py, m.timestamps))?;
+    d.set_item("open",      PyArray1::from_vec(py, m.open))?;
+    d.set_item("high",      PyArray1::from_vec(py, m.high))?;
+    d.set_item("low",       PyArray1::from_vec(py, m.low))?;
+    d.set_item("close",     PyArray1::from_vec(py, m.close))?;
+    Ok(d)
+}
+
+/// GBM bars as `{"timestamp", "open", "high", "low", "close"}` arrays
+#[pyfunction]
+#[pyo3(signature=(
+    n_bars, mu=0.0, sigma=0.01,
+    start_price=100.0, start_time=0.0, bar_seconds=60.0, steps_per_bar=10, seed=0
+))]
+pub fn generate_gbm(
+    py: Python<'_>,
+    n_bars:        usize,
+    mu:            f64,
+    sigma:         f64,
+    start_price:   f64,
+    start_time:    f64,
+    bar_seconds:   f64,
+    steps_per_bar: usize,
+    seed:          u64,
+) -> PyResult<PyObject> {
+    let layout = layout_from_args(n_bars, start_price, start_time, bar_seconds, steps_per_bar)?;
+    let m = py.allow_threads(|| gbm(&layout, mu, sigma, seed));
+    Ok(market_to_py(py, m)?.into())
+}
+
+/// Mean‐reverting (log‐OU) bars; `mean` defaults to `start_price`
+#[pyfunction]
+#[pyo3(signature=(
+    n_bars, theta=0.05, sigma=0.01, mean=None,
+    start_price=100.0, start_time=0.0, bar_seconds=60.0, steps_per_bar=10, seed=0
+))]
+pub fn generate_ou(
+    py: Python<'_>,
+    n_bars:        usize,
+    theta:         f64,
+    sigma:         f64,
+    mean:          Option<f64>,
+    start_price:   f64,
+    start_time:    f64,
+    bar_seconds:   f64,
+    steps_per_bar: usize,
+    seed:          u64,
+) -> PyResult<PyObject> {
+    let layout = layout_from_args(n_bars, start_price, start_time, bar_seconds, steps_per_bar)?;
+    let mean = mean.unwrap_or(start_price);
+    if mean.is_nan() || mean <= 0.0 {
+        return Err(PyValueError::new_err("mean must be > 0"));
+    }
+    let m = py.allow_threads(|| ornstein_uhlenbeck(&layout, mean, theta, sigma, seed));
+    Ok(market_to_py(py, m)?.into())
+}
+
+/// Regime‐switching GBM; also returns the per‐bar `regime` index.
+///
+/// `regimes` is a list of `(mu, sigma)` pairs.  Without a `transition`
+/// matrix, each bar leaves its regime with `switch_probability`, moving to
+/// one of the others uniformly.
+#[pyfunction]
+#[pyo3(signature=(
+    n_bars, regimes, transition=None, switch_probability=0.01,
+    start_price=100.0, start_time=0.0, bar_seconds=60.0, steps_per_bar=10, seed=0
+))]
+pub fn generate_regime_switching(
+    py: Python<'_>,
+    n_bars:             usize,
+    regimes:            Vec<(f64, f64)>,
+    transition:         Option<Vec<Vec<f64>>>,
+    switch_probability: f64,
+    start_price:        f64,
+    start_time:         f64,
+    bar_seconds:        f64,
+    steps_per_bar:      usize,
+    seed:               u64,
+) -> PyResult<PyObject> {
+    let layout = layout_from_args(n_bars, start_price, start_time, bar_seconds, steps_per_bar)?;
+    let k = regimes.len();
+    if k == 0 {
+        return Err(PyValueError::new_err("regimes must not be empty"));
+    }
+    let transition = match transition {
+        Some(t) => {
+            if t.len() != k || t.iter().any(|row| row.len() != k) {
+                return Err(PyValueError::new_err(format!("transition must be {0}×{0}", k)));
+            }
+            if t.iter().any(|row| (row.iter().sum::<f64>() - 1.0).abs() > 1e-6) {
+                return Err(PyValueError::new_err("transition rows must sum to 1"));
+            }
+            t
+        }
+        None => {
+            if !(0.0..=1.0).contains(&switch_probability) {
+                return Err(PyValueError::new_err("switch_probability must be in [0, 1]"));
+            }
+            let off = if k > 1 { switch_probability / (k - 1) as f64 } else { 0.0 };
+            (0..k)
+                .map(|i| {
+                    (0..k)
+                        .map(|j| if i == j { if k > 1 { 1.0 - switch_probability } else { 1.0 } } else { off })
+                        .collect()
+                })
+                .collect()
+        }
+    };
+    let (m, path) = py.allow_threads(|| regime_switching(&layout, &regimes, &transition, seed));
+    let d = market_to_py(py, m)?;
+    d.set_item("regime", PyArray1::from_vec(py, path))?;
+    Ok(d.into())
+}
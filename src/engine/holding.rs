@@ -0,0 +1,86 @@
+// src/engine/holding.rs
+
+use crate::engine::position::Position;
+
+/// Closed trades whose holding time falls in `[min_secs, max_secs)`
+#[derive(Debug, Clone)]
+pub struct DurationBucket {
+    pub label:            String,
+    pub min_secs:         f64,
+    pub max_secs:         f64,
+    pub number_of_trades: usize,
+    pub win_rate:         f64,
+    pub average_pnl:      f64,
+    /// Mean R‐multiple of the bucket's trades with a usable entry stop
+    pub average_r:        f64,
+    /// Trades that entered `average_r`
+    pub r_trades:         usize,
+}
+
+/// Bucket edges as multiples of the median bar interval (1h bars give
+/// <1h, 1–4h, 4h–1d, ≥1d)
+pub fn default_edges(timestamps: &[f64]) -> Vec<f64> {
+    let mut steps: Vec<f64> = timestamps.windows(2).map(|w| w[1] - w[0]).collect();
+    if steps.is_empty() {
+        return Vec::new();
+    }
+    steps.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let bar = steps[steps.len() / 2];
+    [1.0, 4.0, 24.0].iter().map(|k| k * bar).collect()
+}
+
+/// Seconds as the largest whole unit ("90m", "4h", "1d")
+fn fmt_secs(s: f64) -> String {
+    for (unit, secs) in [("d", 86_400.0), ("h", 3_600.0), ("m", 60.0)] {
+        if s >= secs && (s / secs).fract() == 0.0 {
+            return format!("{}{}", s / secs, unit);
+        }
+    }
+    format!("{}s", s)
+}
+
+/// Holding time of a closed trade: entry to the exit (sub‐bar) time
+pub fn holding_secs(pos: &Position, timestamps: &[f64]) -> f64 {
+    let exit = pos.exit_time.or(pos.exit_index.map(|j| timestamps[j])).unwrap_or(pos.entry_time);
+    exit - pos.entry_time
+}
+
+/// Trade performance per holding‐time bucket between ascending `edges`
+pub fn duration_buckets(closed: &[Position], timestamps: &[f64], edges: &[f64]) -> Vec<DurationBucket> {
+    let mut bounds = vec![f64::NEG_INFINITY];
+    bounds.extend_from_slice(edges);
+    bounds.push(f64::INFINITY);
+
+    bounds
+        .windows(2)
+        .map(|w| {
+            let (lo, hi) = (w[0], w[1]);
+            let label = match (lo.is_finite(), hi.is_finite()) {
+                (false, true)  => format!("<{}", fmt_secs(hi)),
+                (true, true)   => format!("{}–{}", fmt_secs(lo), fmt_secs(hi)),
+                (true, false)  => format!("≥{}", fmt_secs(lo)),
+                (false, false) => "all".to_string(),
+            };
+            let trades: Vec<&Position> = closed
+                .iter()
+                .filter(|p| {
+                    let h = holding_secs(p, timestamps);
+                    h >= lo && h < hi
+                })
+                .collect();
+            let n = trades.len();
+            let pnls: Vec<f64> = trades.iter().map(|p| p.pnl.unwrap_or(0.0)).collect();
+            let rs: Vec<f64> = trades.iter().filter_map(|p| p.r_multiple()).collect();
+            DurationBucket {
+                label,
+                min_secs:         lo.max(0.0),
+                max_secs:         hi,
+                number_of_trades: n,
+                win_rate:         if n > 0 { pnls.iter().filter(|&&x| x > 0.0).count() as f64 / n as f64 } else { 0.0 },
+                average_pnl:      if n > 0 { pnls.iter().sum::<f64>() / n as f64 } else { 0.0 },
+                average_r:        if rs.is_empty() { 0.0 } else { rs.iter().sum::<f64>() / rs.len() as f64 },
+                r_trades:         rs.len(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,51 @@
+// src/engine/instrument.rs
+
+/// Exchange filters: price tick, quantity step and minimum order notional
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InstrumentFilters {
+    pub tick_size:    Option<f64>,
+    pub lot_size:     Option<f64>,
+    pub min_notional: Option<f64>,
+}
+
+/// Tolerance for values already on the grid
+const GRID_EPS: f64 = 1e-9;
+
+impl InstrumentFilters {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, v) in [("tick_size", self.tick_size), ("lot_size", self.lot_size)] {
+            if v.is_some_and(|x| x.is_nan() || x <= 0.0) {
+                return Err(format!("{} must be > 0", name));
+            }
+        }
+        if self.min_notional.is_some_and(|x| x.is_nan() || x < 0.0) {
+            return Err("min_notional must be ≥ 0".into());
+        }
+        Ok(())
+    }
+
+    /// Level rounded to the nearest tick
+    pub fn round_level(&self, px: f64) -> f64 {
+        match self.tick_size {
+            Some(t) => (px / t).round() * t,
+            None    => px,
+        }
+    }
+
+    /// Fill rounded to the tick against the trader: buys up, sells down
+    pub fn round_fill(&self, px: f64, buy: bool) -> f64 {
+        match self.tick_size {
+            Some(t) if buy => (px / t - GRID_EPS).ceil() * t,
+            Some(t)        => (px / t + GRID_EPS).floor() * t,
+            None           => px,
+        }
+    }
+
+    /// Size floored to the quantity step
+    pub fn round_size(&self, size: f64) -> f64 {
+        match self.lot_size {
+            Some(l) => (size / l + GRID_EPS).floor() * l,
+            None    => size,
+        }
+    }
+}
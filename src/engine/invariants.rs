@@ -0,0 +1,155 @@
+// src/engine/invariants.rs
+
+use std::collections::HashSet;
+
+use crate::engine::{
+    backtest::{BacktestConfig, BacktestResult, MarketData},
+    exposure::CostAccrual,
+    position::Position,
+    warnings::push_listed,
+};
+
+/// Conservation properties every run should satisfy, as readable lines
+/// describing each violation (empty when the run is consistent).  Cheap
+/// enough to leave on while exploring unusual option combinations.
+pub fn check_invariants(result: &BacktestResult, market: &MarketData, config: &BacktestConfig) -> Vec<String> {
+    let mut out = Vec::new();
+    let n = market.close.len();
+    let closed_pnl: f64 = result.closed.iter().filter_map(|p| p.pnl).sum();
+    // absolute tolerance for sums of PnL
+    let scale = config.initial_equity.abs()
+        + result.closed.iter().filter_map(|p| p.pnl).map(f64::abs).sum::<f64>()
+        + 1.0;
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-9 * scale;
+
+    // 1) Each position
+    let mut ids = HashSet::new();
+    let mut bad_positions = Vec::new();
+    let mut bad_fees = Vec::new();
+    for pos in result.closed.iter().chain(&result.open) {
+        if !ids.insert(pos.position_id) {
+            bad_positions.push(format!("position_id {} appears more than once", pos.position_id));
+        }
+        if let Some(problem) = position_problem(pos, n) {
+            bad_positions.push(format!("{} #{} from bar {}: {}", pos.position_type, pos.position_id, pos.entry_index, problem));
+        }
+        let fees = [
+            ("entry", pos.fee_entry, config.entry_fee_rate),
+            ("exit", pos.fee_exit, config.exit_fees().for_liquidity(pos.exit_liquidity.unwrap_or_default())),
+        ];
+        for (what, fee, rate) in fees {
+            // a fee carries its rate's sign: rebates (rate < 0) are income
+            if !fee.is_finite() || (rate >= 0.0 && fee < 0.0) || (rate < 0.0 && fee > 0.0) {
+                bad_fees.push(format!("{} fee {} of {} #{} with rate {}", what, fee, pos.position_type, pos.position_id, rate));
+            }
+        }
+    }
+    push_listed(&mut out, bad_positions, "inconsistent positions");
+    push_listed(&mut out, bad_fees, "mis‐signed or non‐finite fees");
+
+    // 2) Equity curve against the trades
+    let mut bad_bars = Vec::new();
+    if result.exposure.len() != n || result.equity.len() != n {
+        out.push(format!(
+            "{} exposure snapshots and {} equity points for {} bars",
+            result.exposure.len(), result.equity.len(), n,
+        ));
+    }
+    for (i, (s, &equity)) in result.exposure.iter().zip(&result.equity).enumerate() {
+        if !close(s.total_equity, s.realized_equity + s.floating_pnl)
+            || !close(s.cash, config.initial_equity + s.realized_equity)
+            || !close(equity, config.initial_equity + s.total_equity)
+        {
+            bad_bars.push(format!(
+                "bar {}: equity {} / total {} ≠ realized {} + floating {}",
+                i, equity, s.total_equity, s.realized_equity, s.floating_pnl,
+            ));
+        }
+        if s.long_exposure < -1e-9 || s.short_exposure < -1e-9 {
+            bad_bars.push(format!("bar {}: negative exposure (long {}, short {})", i, s.long_exposure, s.short_exposure));
+        }
+    }
+    push_listed(&mut out, bad_bars, "bars with inconsistent equity");
+
+    if let Some(last) = result.exposure.last() {
+        // entry fees of open positions are already out of realized equity
+        // when costs accrue as incurred
+        let open_fees: f64 = match config.cost_accrual {
+            CostAccrual::Incurred => result.open.iter().map(|p| p.fee_entry).sum(),
+            CostAccrual::Exit     => 0.0,
+        };
+        if !close(last.realized_equity, closed_pnl - open_fees) {
+            out.push(format!(
+                "realized equity {} at the last bar ≠ closed trade PnL {}",
+                last.realized_equity, closed_pnl - open_fees,
+            ));
+        }
+        let open_count = last.open_longs + last.open_shorts;
+        if open_count != result.open.len() {
+            out.push(format!("{} positions open at the last bar but {} reported open", open_count, result.open.len()));
+        }
+    }
+    if let Some(last) = result.trade_equity.last() {
+        if !close(last.equity, config.initial_equity + closed_pnl) {
+            out.push(format!(
+                "trade‐close equity {} ≠ initial equity + closed trade PnL {}",
+                last.equity, config.initial_equity + closed_pnl,
+            ));
+        }
+    }
+    if result.open_marks.len() != result.open.len() {
+        out.push(format!("{} marks for {} open positions", result.open_marks.len(), result.open.len()));
+    }
+
+    // 3) Metrics against the trades
+    let m = &result.metrics;
+    let trades = m.overall.trade_metrics.number_of_trades;
+    if trades != result.closed.len() {
+        out.push(format!("{} trades in the metrics for {} closed positions", trades, result.closed.len()));
+    }
+    if trades != m.longs.trade_metrics.number_of_trades + m.shorts.trade_metrics.number_of_trades {
+        out.push(format!(
+            "long ({}) + short ({}) trades ≠ {} overall",
+            m.longs.trade_metrics.number_of_trades, m.shorts.trade_metrics.number_of_trades, trades,
+        ));
+    }
+    let side_pnl = m.longs.total_pnl + m.shorts.total_pnl;
+    if !close(side_pnl, closed_pnl) {
+        out.push(format!("long + short total_pnl {} ≠ closed trade PnL {}", side_pnl, closed_pnl));
+    }
+    if let Some(last) = result.exposure.last() {
+        if !close(m.overall.total_pnl, last.total_equity) {
+            out.push(format!("total_pnl {} ≠ final total equity {}", m.overall.total_pnl, last.total_equity));
+        }
+    }
+    out
+}
+
+/// First broken property of one position's bookkeeping
+fn position_problem(pos: &Position, n: usize) -> Option<String> {
+    if pos.entry_index < pos.signal_index || pos.entry_index >= n {
+        return Some(format!("entry bar {} for signal bar {} of {} bars", pos.entry_index, pos.signal_index, n));
+    }
+    if !(pos.position_size.is_finite() && pos.position_size > 0.0) {
+        return Some(format!("size {}", pos.position_size));
+    }
+    if !(pos.entry_price.is_finite() && pos.entry_price > 0.0) {
+        return Some(format!("entry price {}", pos.entry_price));
+    }
+    if pos.is_closed != pos.exit_index.is_some() {
+        return Some(format!("is_closed {} with exit bar {:?}", pos.is_closed, pos.exit_index));
+    }
+    if let Some(exit_i) = pos.exit_index {
+        if exit_i < pos.entry_index || exit_i >= n {
+            return Some(format!("exit bar {} before entry or past the last bar", exit_i));
+        }
+        match pos.exit_price {
+            Some(price) if price.is_finite() && price > 0.0 => {}
+            price => return Some(format!("exit price {:?}", price)),
+        }
+        if !pos.pnl.is_some_and(f64::is_finite) {
+            return Some(format!("PnL {:?} of a closed position", pos.pnl));
+        }
+    }
+    None
+}
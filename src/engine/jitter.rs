@@ -0,0 +1,186 @@
+// src/engine/jitter.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    market_from_py, signals_from_py,
+    backtest::{run_engine, validate_signals, BacktestConfig},
+    latency::Latency,
+    metrics::lookup_metric,
+    output::distribution_to_py,
+    rng::Rng,
+    significance::metric_selection,
+    stats::summarize,
+};
+
+/// Bounds the execution assumptions are drawn from on every repetition
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExecutionJitter {
+    /// Slippage moves uniformly within ± this many basis points (floored at 0)
+    pub slippage_bps:   f64,
+    /// Entries and exits fill 0‥=k bars late, uniformly
+    pub max_delay_bars: usize,
+}
+
+/// Assumptions one repetition ran with
+#[derive(Clone, Copy, Debug)]
+pub struct JitterDraw {
+    pub seed:          u64,
+    pub slippage_rate: f64,
+    pub delay_bars:    usize,
+}
+
+impl ExecutionJitter {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.slippage_bps.is_finite() || self.slippage_bps < 0.0 {
+            return Err("slippage_jitter_bps must be ≥ 0".into());
+        }
+        Ok(())
+    }
+
+    /// Repetition `k` of a study seeded with `seed`: its own seed, and the
+    /// config with the assumptions drawn from it
+    pub fn draw(&self, base: &BacktestConfig, seed: u64, k: u64) -> (JitterDraw, BacktestConfig) {
+        let run_seed = Rng::fork(seed, k).next_u64();
+        let mut rng = Rng::new(run_seed);
+        let shift = (2.0 * rng.next_f64() - 1.0) * self.slippage_bps / 10_000.0;
+        let draw = JitterDraw {
+            seed:          run_seed,
+            slippage_rate: (base.slippage_rate + shift).max(0.0),
+            delay_bars:    rng.below(self.max_delay_bars + 1),
+        };
+        let config = BacktestConfig {
+            slippage_rate: draw.slippage_rate,
+            latency: (draw.delay_bars > 0).then_some(Latency::Bars(draw.delay_bars)),
+            ..base.clone()
+        };
+        (draw, config)
+    }
+}
+
+/// Execution‐assumption sensitivity.
+///
+/// Re‐runs the same signals `repetitions` times, each with the slippage
+/// rate moved uniformly within ±`slippage_jitter_bps` basis points and
+/// entries/exits filled 0‥`max_delay_bars` bars late.  Every repetition
+/// draws from its own seed (reported with the drawn values, so any run can
+/// be reproduced with `run_backtest`).  Returns the unjittered baseline,
+/// the runs and each metric's distribution with a `confidence` interval.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    repetitions=100,
+    slippage_jitter_bps=0.0,
+    max_delay_bars=0,
+    seed=0,
+    confidence=0.95,
+    metrics=None
+))]
+pub fn execution_jitter(
+    py: Python<'_>,
+    timestamp:           &PyArray1<f64>,
+    open:                &PyArray1<f64>,
+    high:                &PyArray1<f64>,
+    low:                 &PyArray1<f64>,
+    close:               &PyArray1<f64>,
+    long_signals:        &PyArray1<bool>,
+    short_signals:       &PyArray1<bool>,
+    long_tp:             &PyArray1<f64>,
+    long_sl:             &PyArray1<f64>,
+    short_tp:            &PyArray1<f64>,
+    short_sl:            &PyArray1<f64>,
+    long_size:           &PyArray1<f64>,
+    short_size:          &PyArray1<f64>,
+    expiration_times:    &PyArray1<f64>,
+    entry_fee_rate:      f64,
+    exit_fee_rate:       f64,
+    slippage_rate:       f64,
+    initial_equity:      f64,
+    repetitions:         usize,
+    slippage_jitter_bps: f64,
+    max_delay_bars:      usize,
+    seed:                u64,
+    confidence:          f64,
+    metrics:             Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let jitter = ExecutionJitter { slippage_bps: slippage_jitter_bps, max_delay_bars };
+    jitter.validate().map_err(PyValueError::new_err)?;
+    if repetitions == 0 {
+        return Err(PyValueError::new_err("repetitions must be > 0"));
+    }
+    if !(0.0..1.0).contains(&confidence) {
+        return Err(PyValueError::new_err("confidence must be in [0, 1)"));
+    }
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let metric_names = metric_selection(metrics)?;
+
+    let score = |config: &BacktestConfig| -> Vec<f64> {
+        let summary = run_engine(&market, &signals, config).metrics;
+        metric_names
+            .iter()
+            .map(|name| lookup_metric(&summary, name).unwrap_or(f64::NAN))
+            .collect()
+    };
+    let (baseline, runs) = py.allow_threads(|| {
+        let baseline = score(&config);
+        let runs: Vec<(JitterDraw, Vec<f64>)> = (0..repetitions as u64)
+            .into_par_iter()
+            .map(|k| {
+                let (draw, jittered) = jitter.draw(&config, seed, k);
+                (draw, score(&jittered))
+            })
+            .collect();
+        (baseline, runs)
+    });
+
+    let values_to_py = |values: &[f64]| -> PyResult<&PyDict> {
+        let d = PyDict::new(py);
+        for (name, v) in metric_names.iter().zip(values) {
+            d.set_item(name, v)?;
+        }
+        Ok(d)
+    };
+    let rows = PyList::empty(py);
+    for (draw, values) in &runs {
+        let row = PyDict::new(py);
+        row.set_item("seed",          draw.seed)?;
+        row.set_item("slippage_rate", draw.slippage_rate)?;
+        row.set_item("delay_bars",    draw.delay_bars)?;
+        row.set_item("metrics",       values_to_py(values)?)?;
+        rows.append(row)?;
+    }
+    let dists = PyDict::new(py);
+    for (m, name) in metric_names.iter().enumerate() {
+        let values: Vec<f64> = runs.iter().map(|(_, v)| v[m]).collect();
+        dists.set_item(name, distribution_to_py(py, &summarize(values, confidence))?)?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("baseline",      values_to_py(&baseline)?)?;
+    out.set_item("runs",          rows)?;
+    out.set_item("distributions", dists)?;
+    Ok(out.into())
+}
@@ -0,0 +1,119 @@
+// src/engine/journal.rs
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+
+use crate::engine::{
+    backtest::BacktestResult,
+    metrics::{lookup_metric, SCALAR_METRICS},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at  REAL NOT NULL,
+    label       TEXT,
+    version     TEXT NOT NULL,
+    params      TEXT NOT NULL,
+    inputs      TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS trades (
+    run_id          INTEGER NOT NULL REFERENCES runs(run_id),
+    position_id     INTEGER NOT NULL,
+    position_type   TEXT NOT NULL,
+    tag             TEXT,
+    entry_time      REAL NOT NULL,
+    entry_index     INTEGER NOT NULL,
+    entry_price     REAL NOT NULL,
+    exit_index      INTEGER,
+    exit_price      REAL,
+    exit_condition  TEXT,
+    position_size   REAL NOT NULL,
+    fee_entry       REAL NOT NULL,
+    fee_exit        REAL NOT NULL,
+    pnl             REAL,
+    real_return     REAL
+);
+CREATE TABLE IF NOT EXISTS metrics (
+    run_id  INTEGER NOT NULL REFERENCES runs(run_id),
+    name    TEXT NOT NULL,
+    value   REAL
+);
+";
+
+/// Append one run (manifest, closed trades, overall scalar metrics) to the
+/// SQLite journal at `path`, creating the tables on first use.  Goes through
+/// Python's bundled `sqlite3`, so the extension needs no SQLite of its own.
+/// Returns the new `run_id`.
+pub fn append_run(
+    py: Python<'_>,
+    path: &str,
+    label: Option<&str>,
+    run_info: &PyDict,
+    result: &BacktestResult,
+) -> PyResult<i64> {
+    let json = py.import("json")?;
+    let dumps = |obj: &PyAny| -> PyResult<String> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("sort_keys", true)?;
+        kwargs.set_item("default", py.eval("str", None, None)?)?;
+        json.call_method("dumps", (obj,), Some(kwargs))?.extract()
+    };
+    let version = run_info.get_item("version").map_or(Ok(String::new()), |v| v.extract())?;
+    let params  = run_info.get_item("params").map_or(Ok("{}".to_string()), dumps)?;
+    let inputs  = run_info.get_item("inputs").map_or(Ok("{}".to_string()), dumps)?;
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+
+    let conn = py.import("sqlite3")?.call_method1("connect", (path,))?;
+    let written = (|| -> PyResult<i64> {
+        conn.call_method1("executescript", (SCHEMA,))?;
+        let cur = conn.call_method1(
+            "execute",
+            (
+                "INSERT INTO runs (created_at, label, version, params, inputs) VALUES (?, ?, ?, ?, ?)",
+                (created_at, label, version, params, inputs),
+            ),
+        )?;
+        let run_id: i64 = cur.getattr("lastrowid")?.extract()?;
+
+        let trades = PyList::empty(py);
+        for pos in &result.closed {
+            trades.append(PyTuple::new(py, [
+                run_id.to_object(py),
+                pos.position_id.to_object(py),
+                pos.position_type.to_object(py),
+                pos.tag.to_object(py),
+                pos.entry_time.to_object(py),
+                pos.entry_index.to_object(py),
+                pos.entry_price.to_object(py),
+                pos.exit_index.to_object(py),
+                pos.exit_price.to_object(py),
+                pos.exit_condition.to_object(py),
+                pos.position_size.to_object(py),
+                pos.fee_entry.to_object(py),
+                pos.fee_exit.to_object(py),
+                pos.pnl.to_object(py),
+                pos.real_return.to_object(py),
+            ]))?;
+        }
+        conn.call_method1(
+            "executemany",
+            ("INSERT INTO trades VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)", trades),
+        )?;
+
+        let metrics = PyList::empty(py);
+        for name in SCALAR_METRICS {
+            let value = lookup_metric(&result.metrics, name).filter(|v| v.is_finite());
+            metrics.append((run_id, *name, value))?;
+        }
+        conn.call_method1("executemany", ("INSERT INTO metrics VALUES (?, ?, ?)", metrics))?;
+        conn.call_method0("commit")?;
+        Ok(run_id)
+    })();
+    conn.call_method0("close")?;
+    written
+}
@@ -0,0 +1,304 @@
+// src/engine/json.rs
+
+//! Minimal JSON for the Python‐free front ends (wasm, HTTP server): the
+//! result document they return and a parser for request bodies.
+
+use std::fmt::Write;
+
+use crate::engine::{
+    backtest::BacktestResult,
+    benchmark::account_equity,
+    metrics::{lookup_metric, SCALAR_METRICS},
+    position::Position,
+    run_info::SCHEMA_VERSION,
+};
+
+/// Parsed JSON value
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Deepest array/object nesting `parse` accepts (it recurses per level)
+const MAX_DEPTH: usize = 64;
+
+pub fn parse(text: &str) -> Result<Json, String> {
+    let mut p = Parser { s: text.as_bytes(), i: 0, depth: 0 };
+    let v = p.value()?;
+    p.ws();
+    if p.i != p.s.len() {
+        return Err(format!("trailing characters at byte {}", p.i));
+    }
+    Ok(v)
+}
+
+struct Parser<'a> {
+    s:     &'a [u8],
+    i:     usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn ws(&mut self) {
+        while self.i < self.s.len() && self.s[self.i].is_ascii_whitespace() {
+            self.i += 1;
+        }
+    }
+
+    fn err<T>(&self, msg: &str) -> Result<T, String> {
+        Err(format!("{} at byte {}", msg, self.i))
+    }
+
+    fn eat(&mut self, lit: &str) -> bool {
+        if self.s[self.i..].starts_with(lit.as_bytes()) {
+            self.i += lit.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        if self.depth > MAX_DEPTH {
+            return self.err("nesting too deep");
+        }
+        self.depth += 1;
+        let v = self.node();
+        self.depth -= 1;
+        v
+    }
+
+    fn node(&mut self) -> Result<Json, String> {
+        self.ws();
+        match self.s.get(self.i) {
+            None => self.err("unexpected end"),
+            Some(b'{') => {
+                self.i += 1;
+                let mut entries = Vec::new();
+                self.ws();
+                if self.eat("}") {
+                    return Ok(Json::Object(entries));
+                }
+                loop {
+                    self.ws();
+                    let key = match self.value()? {
+                        Json::Str(k) => k,
+                        _ => return self.err("object key must be a string"),
+                    };
+                    self.ws();
+                    if !self.eat(":") {
+                        return self.err("expected ‘:’");
+                    }
+                    entries.push((key, self.value()?));
+                    self.ws();
+                    if self.eat("}") {
+                        return Ok(Json::Object(entries));
+                    }
+                    if !self.eat(",") {
+                        return self.err("expected ‘,’ or ‘}’");
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.i += 1;
+                let mut items = Vec::new();
+                self.ws();
+                if self.eat("]") {
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.ws();
+                    if self.eat("]") {
+                        return Ok(Json::Array(items));
+                    }
+                    if !self.eat(",") {
+                        return self.err("expected ‘,’ or ‘]’");
+                    }
+                }
+            }
+            Some(b'"') => {
+                self.i += 1;
+                let mut out = String::new();
+                loop {
+                    let start = self.i;
+                    while self.i < self.s.len() && self.s[self.i] != b'"' && self.s[self.i] != b'\\' {
+                        self.i += 1;
+                    }
+                    out.push_str(std::str::from_utf8(&self.s[start..self.i]).map_err(|e| e.to_string())?);
+                    match self.s.get(self.i) {
+                        None => return self.err("unterminated string"),
+                        Some(b'"') => {
+                            self.i += 1;
+                            return Ok(Json::Str(out));
+                        }
+                        Some(_) => {
+                            let esc = self.s.get(self.i + 1).copied();
+                            self.i += 2;
+                            match esc {
+                                Some(b'"')  => out.push('"'),
+                                Some(b'\\') => out.push('\\'),
+                                Some(b'/')  => out.push('/'),
+                                Some(b'n')  => out.push('\n'),
+                                Some(b't')  => out.push('\t'),
+                                Some(b'r')  => out.push('\r'),
+                                Some(b'b')  => out.push('\u{8}'),
+                                Some(b'f')  => out.push('\u{c}'),
+                                Some(b'u')  => {
+                                    let hex = self.s.get(self.i..self.i + 4).ok_or("truncated \\u escape")?;
+                                    let code = u32::from_str_radix(std::str::from_utf8(hex).map_err(|e| e.to_string())?, 16)
+                                        .map_err(|e| e.to_string())?;
+                                    out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                                    self.i += 4;
+                                }
+                                _ => return self.err("bad escape"),
+                            }
+                        }
+                    }
+                }
+            }
+            Some(_) => {
+                if self.eat("null") {
+                    return Ok(Json::Null);
+                }
+                if self.eat("true") {
+                    return Ok(Json::Bool(true));
+                }
+                if self.eat("false") {
+                    return Ok(Json::Bool(false));
+                }
+                let start = self.i;
+                while self.i < self.s.len() && matches!(self.s[self.i], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') {
+                    self.i += 1;
+                }
+                std::str::from_utf8(&self.s[start..self.i])
+                    .ok()
+                    .and_then(|t| t.parse().ok())
+                    .map(Json::Num)
+                    .map_or_else(|| self.err("invalid value"), Ok)
+            }
+        }
+    }
+}
+
+/// JSON number (NaN / ±∞ have no JSON form and become null)
+pub fn push_num(out: &mut String, v: f64) {
+    if v.is_finite() {
+        write!(out, "{}", v).unwrap();
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn push_opt(out: &mut String, v: Option<f64>) {
+    push_num(out, v.unwrap_or(f64::NAN));
+}
+
+pub fn push_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"'  => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// `{"error": msg}`
+pub fn error_json(msg: &str) -> String {
+    let mut out = String::from("{\"error\":");
+    push_str(&mut out, msg);
+    out.push('}');
+    out
+}
+
+fn push_position(out: &mut String, pos: &Position) {
+    write!(out, "{{\"position_id\":{},\"position_type\":", pos.position_id).unwrap();
+    push_str(out, &pos.position_type);
+    out.push_str(",\"entry_time\":");
+    push_num(out, pos.entry_time);
+    write!(out, ",\"entry_index\":{},\"entry_price\":", pos.entry_index).unwrap();
+    push_num(out, pos.entry_price);
+    out.push_str(",\"tp\":");
+    push_num(out, pos.tp);
+    out.push_str(",\"sl\":");
+    push_num(out, pos.sl);
+    out.push_str(",\"position_size\":");
+    push_num(out, pos.position_size);
+    out.push_str(",\"exit_index\":");
+    match pos.exit_index {
+        Some(i) => write!(out, "{}", i).unwrap(),
+        None    => out.push_str("null"),
+    }
+    out.push_str(",\"exit_price\":");
+    push_opt(out, pos.exit_price);
+    out.push_str(",\"exit_condition\":");
+    match &pos.exit_condition {
+        Some(c) => push_str(out, c),
+        None    => out.push_str("null"),
+    }
+    out.push_str(",\"fee_entry\":");
+    push_num(out, pos.fee_entry);
+    out.push_str(",\"fee_exit\":");
+    push_num(out, pos.fee_exit);
+    out.push_str(",\"pnl\":");
+    push_opt(out, pos.pnl);
+    out.push_str(",\"real_return\":");
+    push_opt(out, pos.real_return);
+    out.push('}');
+}
+
+fn push_positions(out: &mut String, key: &str, positions: &[Position]) {
+    write!(out, "\"{}\":[", key).unwrap();
+    for (i, pos) in positions.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_position(out, pos);
+    }
+    out.push(']');
+}
+
+/// `{"schema_version", "closed_positions", "open_positions", "equity",
+/// "metrics"}`
+pub fn result_json(result: &BacktestResult, initial_equity: f64) -> String {
+    let mut out = format!("{{\"schema_version\":{},", SCHEMA_VERSION);
+    push_positions(&mut out, "closed_positions", &result.closed);
+    out.push(',');
+    push_positions(&mut out, "open_positions", &result.open);
+    out.push_str(",\"equity\":[");
+    for (i, eq) in account_equity(&result.exposure, initial_equity).into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_num(&mut out, eq);
+    }
+    out.push_str("],\"metrics\":{");
+    for (i, name) in SCALAR_METRICS.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_str(&mut out, name);
+        out.push(':');
+        push_opt(&mut out, lookup_metric(&result.metrics, name));
+    }
+    out.push_str("}}");
+    out
+}
@@ -0,0 +1,26 @@
+// src/engine/latency.rs
+
+/// Delay between a signal/trigger and its fill
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Latency {
+    /// Fill this many bars later
+    Bars(usize),
+    /// Fill at the first bar opening at least this many seconds later
+    Seconds(f64),
+}
+
+impl Latency {
+    /// Bar the delayed order fills on: at least `earliest`, and `Bars(k)` /
+    /// `Seconds(s)` after bar `from`.  None if that lies past the data.
+    pub fn fill_bar(self, timestamps: &[f64], from: usize, earliest: usize) -> Option<usize> {
+        let n = timestamps.len();
+        let bar = match self {
+            Latency::Bars(k) => (from + k).max(earliest),
+            Latency::Seconds(s) => {
+                let due = timestamps[from] + s;
+                (earliest..n).find(|&j| timestamps[j] >= due)?
+            }
+        };
+        (bar < n).then_some(bar)
+    }
+}
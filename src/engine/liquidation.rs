@@ -0,0 +1,91 @@
+// src/engine/liquidation.rs
+
+use crate::engine::par::*;
+use crate::engine::{contract::ContractType, position::Position};
+
+/// Isolated‐margin liquidation of each position (reporting only: positions
+/// are not force‐closed)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LiquidationModel {
+    /// Maintenance margin as a fraction of the entry notional
+    pub maintenance_margin: f64,
+    /// A bar is flagged when its adverse extreme comes within this fraction
+    /// of the liquidation price
+    pub buffer:             f64,
+}
+
+impl LiquidationModel {
+    pub fn validate(&self, leverage: f64) -> Result<(), String> {
+        if !(0.0..1.0).contains(&self.maintenance_margin) {
+            return Err("maintenance_margin must be in [0, 1)".into());
+        }
+        if self.maintenance_margin >= 1.0 / leverage {
+            return Err(format!(
+                "maintenance_margin {} is not below the initial margin 1/leverage = {}",
+                self.maintenance_margin, 1.0 / leverage,
+            ));
+        }
+        if !self.buffer.is_finite() || self.buffer < 0.0 {
+            return Err("liquidation_buffer must be ≥ 0".into());
+        }
+        Ok(())
+    }
+
+    /// Price at which the position's loss eats its initial margin
+    /// (notional / leverage) down to the maintenance margin, before fees and
+    /// funding; None when no price gets there
+    pub fn price(&self, pos: &Position, leverage: f64) -> Option<f64> {
+        let cushion = 1.0 / leverage - self.maintenance_margin;
+        let long = pos.position_type == "long";
+        let price = match (pos.contract, long) {
+            (ContractType::Linear, true)   => pos.entry_price * (1.0 - cushion),
+            (ContractType::Linear, false)  => pos.entry_price * (1.0 + cushion),
+            // inverse PnL is linear in 1/price
+            (ContractType::Inverse, true)  => pos.entry_price / (1.0 + cushion),
+            (ContractType::Inverse, false) => pos.entry_price / (1.0 - cushion),
+        };
+        (price.is_finite() && price > 0.0).then_some(price)
+    }
+}
+
+/// Set each position's liquidation price and the bars it was held through
+/// (exit bar included; open positions through the last bar) whose low (long)
+/// or high (short) came within the buffer of it or crossed it
+pub fn record_liquidation(positions: &mut [Position], high: &[f64], low: &[f64], leverage: f64, model: &LiquidationModel) {
+    let n = high.len();
+    positions.par_iter_mut().for_each(|pos| {
+        pos.liquidation_price = model.price(pos, leverage);
+        pos.near_liquidation.clear();
+        pos.liquidation_bar = None;
+        let Some(liq) = pos.liquidation_price else { return };
+        if pos.entry_index >= n {
+            return;
+        }
+        let long = pos.position_type == "long";
+        let last = pos.exit_index.unwrap_or(n - 1).min(n - 1);
+        for j in pos.entry_index..=last {
+            let (near, crossed) = if long {
+                (low[j] <= liq * (1.0 + model.buffer), low[j] <= liq)
+            } else {
+                (high[j] >= liq * (1.0 - model.buffer), high[j] >= liq)
+            };
+            if near {
+                pos.near_liquidation.push(j);
+            }
+            if crossed && pos.liquidation_bar.is_none() {
+                pos.liquidation_bar = Some(j);
+            }
+        }
+    });
+}
+
+/// Per bar: whether any position was near (or through) its liquidation price
+pub fn near_liquidation_flags(positions: &[&Position], n: usize) -> Vec<bool> {
+    let mut flags = vec![false; n];
+    for pos in positions {
+        for &j in pos.near_liquidation.iter().filter(|&&j| j < n) {
+            flags[j] = true;
+        }
+    }
+    flags
+}
@@ -0,0 +1,66 @@
+// src/engine/liquidity.rs
+
+use crate::engine::position::Position;
+
+/// Whether a fill took liquidity (market and stop orders, marketable
+/// limits) or provided it (a limit that rested before trading)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Liquidity {
+    #[default]
+    Taker,
+    Maker,
+}
+
+impl Liquidity {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "taker" => Ok(Liquidity::Taker),
+            "maker" => Ok(Liquidity::Maker),
+            other => Err(format!("liquidity must be \"maker\" or \"taker\", got ‘{}’", other)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Liquidity::Taker => "taker",
+            Liquidity::Maker => "maker",
+        }
+    }
+}
+
+/// Maker/taker mix of the closed trades' fills (each entry fill and the exit)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExecutionSummary {
+    pub maker_fills: usize,
+    pub taker_fills: usize,
+    /// maker_fills / all fills (0 without fills)
+    pub maker_share: f64,
+    /// $ fees paid on maker / taker fills
+    pub maker_fees:  f64,
+    pub taker_fees:  f64,
+}
+
+pub fn execution_summary(positions: &[&Position]) -> ExecutionSummary {
+    let mut s = ExecutionSummary::default();
+    let mut book = |liquidity: Liquidity, fills: usize, fee: f64| match liquidity {
+        Liquidity::Maker => {
+            s.maker_fills += fills;
+            s.maker_fees  += fee;
+        }
+        Liquidity::Taker => {
+            s.taker_fills += fills;
+            s.taker_fees  += fee;
+        }
+    };
+    for pos in positions {
+        book(pos.entry_liquidity, pos.fills.len().max(1), pos.fee_entry);
+        if let Some(liquidity) = pos.exit_liquidity {
+            book(liquidity, 1, pos.fee_exit);
+        }
+    }
+    let total = s.maker_fills + s.taker_fills;
+    if total > 0 {
+        s.maker_share = s.maker_fills as f64 / total as f64;
+    }
+    s
+}
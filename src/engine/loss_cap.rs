@@ -0,0 +1,55 @@
+// src/engine/loss_cap.rs
+
+use crate::engine::contract::ContractType;
+
+/// How an entry whose stop risks more than the cap is brought within it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LossCapMode {
+    /// Move the stop closer to the entry
+    Tighten,
+    /// Keep the stop, cut the size
+    Downsize,
+}
+
+impl LossCapMode {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "tighten"  => Ok(LossCapMode::Tighten),
+            "downsize" => Ok(LossCapMode::Downsize),
+            other => Err(format!(
+                "unknown max_loss_mode ‘{}’ (expected \"tighten\" or \"downsize\")", other
+            )),
+        }
+    }
+}
+
+/// Hard per‐trade loss cap: the stop distance × size may not exceed `amount`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LossCap {
+    pub amount: f64,
+    pub mode:   LossCapMode,
+}
+
+impl LossCap {
+    /// (sl, size) after applying the cap to a fill at `entry`; unchanged when
+    /// the stop already risks no more than `amount`
+    pub fn apply(&self, contract: ContractType, long: bool, entry: f64, sl: f64, size: f64) -> (f64, f64) {
+        let risk = -contract.pnl(long, entry, sl, size);
+        if risk.is_nan() || risk <= self.amount {
+            return (sl, size);
+        }
+        match self.mode {
+            LossCapMode::Downsize => (sl, size * self.amount / risk),
+            LossCapMode::Tighten  => (stop_for_loss(contract, long, entry, size, self.amount), size),
+        }
+    }
+}
+
+/// Stop level at which `size` loses exactly `loss` from `entry`
+fn stop_for_loss(contract: ContractType, long: bool, entry: f64, size: f64, loss: f64) -> f64 {
+    let dir = if long { 1.0 } else { -1.0 };
+    match contract {
+        ContractType::Linear  => entry - dir * loss / size,
+        ContractType::Inverse => 1.0 / (1.0 / entry + dir * loss / size),
+    }
+}
@@ -1,5 +1,170 @@
 use crate::engine::position::Position;
 use crate::engine::exposure::ExposureSnapshot;
+use crate::engine::bootstrap::BootstrapIntervals;
+use crate::engine::concurrency::ConcurrencyStats;
+use crate::engine::regimes::RegimeMetrics;
+use crate::engine::benchmark::BenchmarkMetrics;
+use crate::engine::factors::FactorBeta;
+use crate::engine::holding::DurationBucket;
+use crate::engine::weighted_returns::WeightedReturns;
+use crate::engine::cash_flows::FlowSummary;
+use crate::engine::liquidity::{execution_summary, ExecutionSummary};
+use crate::engine::excursions::{efficiency, runup, Efficiency, Runup};
+use crate::engine::stats::{autocorrelation, moments, probabilistic_sharpe, runs_test, RunsTest};
+
+/// What a ratio with a zero denominator (no losing trades, flat returns)
+/// reports
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RatioPolicy {
+    /// Profit factor +inf, Sharpe 0 (the historical behaviour)
+    #[default]
+    Legacy,
+    /// ±inf by the numerator's sign; 0/0 is NaN
+    Infinity,
+    Nan,
+    /// NaN in Rust, `None` in Python
+    Null,
+    /// ±cap by the numerator's sign; 0/0 is 0
+    Cap(f64),
+}
+
+impl RatioPolicy {
+    pub fn parse(s: &str, cap: Option<f64>) -> Result<Self, String> {
+        match (s, cap) {
+            ("legacy", _) => Ok(RatioPolicy::Legacy),
+            ("inf", _)    => Ok(RatioPolicy::Infinity),
+            ("nan", _)    => Ok(RatioPolicy::Nan),
+            ("none", _)   => Ok(RatioPolicy::Null),
+            ("cap", Some(c)) if c > 0.0 => Ok(RatioPolicy::Cap(c)),
+            ("cap", _)    => Err("undefined_ratios=‘cap’ needs ratio_cap > 0".into()),
+            (other, _) => Err(format!(
+                "unknown undefined_ratios ‘{}’ (expected legacy, inf, nan, none or cap)", other
+            )),
+        }
+    }
+
+    /// `num / den`, or the policy's value when `den` is 0 (`legacy` is the
+    /// metric's historical value)
+    pub fn ratio(&self, num: f64, den: f64, legacy: f64) -> f64 {
+        if den != 0.0 {
+            return num / den;
+        }
+        let sign = if num > 0.0 { 1.0 } else if num < 0.0 { -1.0 } else { 0.0 };
+        match self {
+            RatioPolicy::Legacy   => legacy,
+            RatioPolicy::Infinity => if sign == 0.0 { f64::NAN } else { sign * f64::INFINITY },
+            RatioPolicy::Nan | RatioPolicy::Null => f64::NAN,
+            RatioPolicy::Cap(c)   => sign * c,
+        }
+    }
+}
+
+/// Curve the time‐series metrics (returns, drawdown, cumulative return)
+/// are computed on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EquityBasis {
+    /// Cumulative PnL, starting at 0 (the historical behaviour)
+    #[default]
+    Pnl,
+    /// Account value: initial equity + PnL, with the cumulative return and
+    /// drawdown peak measured from the initial equity
+    Account,
+}
+
+impl EquityBasis {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "pnl"     => Ok(EquityBasis::Pnl),
+            "account" => Ok(EquityBasis::Account),
+            other => Err(format!("equity_basis must be \"pnl\" or \"account\", got ‘{}’", other)),
+        }
+    }
+
+    /// Offset added to the PnL curve (`total_equity`)
+    pub fn base(self, initial_equity: f64) -> f64 {
+        match self {
+            EquityBasis::Pnl     => 0.0,
+            EquityBasis::Account => initial_equity,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EquityBasis::Pnl     => "pnl",
+            EquityBasis::Account => "account",
+        }
+    }
+}
+
+/// Per‐bar return of the equity curve
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReturnKind {
+    /// `E_t / E_{t-1} − 1`
+    #[default]
+    Simple,
+    /// `ln(E_t / E_{t-1})` (0 when either side is not positive)
+    Log,
+}
+
+/// Which equity the returns are taken on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReturnEquity {
+    /// Realized + floating PnL at each close
+    #[default]
+    Marked,
+    /// Realized PnL only (moves on exit bars)
+    Realized,
+}
+
+impl ReturnKind {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "simple" => Ok(ReturnKind::Simple),
+            "log"    => Ok(ReturnKind::Log),
+            other => Err(format!("return_type must be \"simple\" or \"log\", got ‘{}’", other)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ReturnKind::Simple => "simple",
+            ReturnKind::Log    => "log",
+        }
+    }
+
+    pub(crate) fn of(self, prev: f64, cur: f64) -> f64 {
+        match self {
+            ReturnKind::Simple if prev != 0.0 => (cur - prev) / prev,
+            ReturnKind::Log if prev > 0.0 && cur > 0.0 => (cur / prev).ln(),
+            _ => 0.0,
+        }
+    }
+}
+
+impl ReturnEquity {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "marked"   => Ok(ReturnEquity::Marked),
+            "realized" => Ok(ReturnEquity::Realized),
+            other => Err(format!("return_equity must be \"marked\" or \"realized\", got ‘{}’", other)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ReturnEquity::Marked   => "marked",
+            ReturnEquity::Realized => "realized",
+        }
+    }
+}
+
+/// The curve and return convention time‐series metrics were computed with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReturnSpec {
+    pub basis:  EquityBasis,
+    pub kind:   ReturnKind,
+    pub equity: ReturnEquity,
+}
 
 /// Per‐trade metrics (notional‐normalized returns)
 #[derive(Debug)]
@@ -12,6 +177,16 @@ pub struct SideTradeMetrics {
     pub profit_factor:        f64,
     pub expectancy:           f64,
     pub average_duration:     f64,
+    /// Mean entry / exit / total efficiency of trades with a price range
+    pub average_entry_efficiency: f64,
+    pub average_exit_efficiency:  f64,
+    pub average_total_efficiency: f64,
+    /// Mean best open profit, mean giveback of the trades that were ever in
+    /// profit, and the share of trades in profit at some point that still
+    /// closed at a loss
+    pub average_max_runup:    f64,
+    pub average_giveback:     f64,
+    pub profit_to_loss_rate:  f64,
     pub trade_returns:        Vec<f64>,
     pub trade_pnls:           Vec<f64>,
     pub durations:            Vec<f64>,
@@ -26,6 +201,115 @@ pub struct TimeSeriesMetrics {
     pub sharpe_ratio:      f64,
     pub cumulative_return: f64,
     pub max_drawdown:      f64,
+    /// Block‐bootstrap CIs (only when requested)
+    pub bootstrap:         Option<BootstrapIntervals>,
+    /// `(lag, autocorrelation)` of `returns`
+    pub autocorrelation:   Vec<(usize, f64)>,
+    /// Runs test on the signs of `returns` (flat bars skipped)
+    pub runs_test:         RunsTest,
+    /// New equity highs and the stretches between them
+    pub high_water:        HighWaterStats,
+    /// Skewness / kurtosis of `returns`
+    pub skewness:          f64,
+    pub kurtosis:          f64,
+    /// Probability the true per‐bar Sharpe is above zero given the sample
+    /// length and return moments (NaN on a flat curve)
+    pub probabilistic_sharpe: f64,
+}
+
+/// How often the equity curve made new highs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HighWaterStats {
+    /// Bars closing strictly above every earlier bar
+    pub new_highs:                  usize,
+    /// Mean bars from the start / previous high to each new high
+    pub average_bars_between_highs: f64,
+    /// Most bars without a new high (including the current stretch)
+    pub longest_without_high:       usize,
+    /// Bars since the last high (or the start) at the end of the run
+    pub bars_since_high:            usize,
+}
+
+pub fn high_water_stats(curve: &[f64]) -> HighWaterStats {
+    let Some(&first) = curve.first() else { return HighWaterStats::default() };
+    let mut peak = first;
+    let mut last_high = 0;
+    let mut gaps = Vec::new();
+    for (t, &eq) in curve.iter().enumerate().skip(1) {
+        if eq > peak {
+            peak = eq;
+            gaps.push(t - last_high);
+            last_high = t;
+        }
+    }
+    let bars_since_high = curve.len() - 1 - last_high;
+    HighWaterStats {
+        new_highs:                  gaps.len(),
+        average_bars_between_highs: if gaps.is_empty() { 0.0 } else { gaps.iter().sum::<usize>() as f64 / gaps.len() as f64 },
+        longest_without_high:       gaps.iter().copied().max().unwrap_or(0).max(bars_since_high),
+        bars_since_high,
+    }
+}
+
+/// Return autocorrelation lags reported unless a run asks for others
+pub const DEFAULT_AUTOCORR_LAGS: &[usize] = &[1, 2, 5, 10];
+
+/// `(lag, autocorrelation)` for each lag
+pub fn autocorrelations(returns: &[f64], lags: &[usize]) -> Vec<(usize, f64)> {
+    lags.iter().map(|&lag| (lag, autocorrelation(returns, lag))).collect()
+}
+
+/// Where closed‐trade PnL went: `gross_pnl` (at raw, pre‐cost prices) minus
+/// every cost term is `net_pnl`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CostBreakdown {
+    pub gross_pnl: f64,
+    /// Fees net of rebates (negative when rebates exceed fees)
+    pub fees:      f64,
+    /// Rebates earned on negative‐rate fills, as a positive amount (already
+    /// netted in `fees`)
+    pub rebates:   f64,
+    pub slippage:  f64,
+    pub funding:   f64,
+    pub rolls:     f64,
+    pub latency:   f64,
+    /// Dividend income (not a cost; excluded from `gross_pnl`)
+    pub dividends: f64,
+    pub net_pnl:   f64,
+}
+
+impl CostBreakdown {
+    pub fn of(pos: &Position) -> Self {
+        let net_pnl  = pos.pnl.unwrap_or(0.0);
+        let fees     = pos.fee_entry + pos.fee_exit;
+        let slippage = pos.slippage_cost();
+        CostBreakdown {
+            gross_pnl: net_pnl + fees + slippage + pos.funding_cost + pos.roll_cost + pos.latency_cost
+                - pos.dividend_income,
+            fees,
+            rebates:   -pos.fee_entry.min(0.0) - pos.fee_exit.min(0.0),
+            slippage,
+            funding:   pos.funding_cost,
+            rolls:     pos.roll_cost,
+            latency:   pos.latency_cost,
+            dividends: pos.dividend_income,
+            net_pnl,
+        }
+    }
+
+    fn total(trades: &[&Position]) -> Self {
+        trades.iter().map(|p| Self::of(p)).fold(Self::default(), |a, c| CostBreakdown {
+            gross_pnl: a.gross_pnl + c.gross_pnl,
+            fees:      a.fees + c.fees,
+            rebates:   a.rebates + c.rebates,
+            slippage:  a.slippage + c.slippage,
+            funding:   a.funding + c.funding,
+            rolls:     a.rolls + c.rolls,
+            latency:   a.latency + c.latency,
+            dividends: a.dividends + c.dividends,
+            net_pnl:   a.net_pnl + c.net_pnl,
+        })
+    }
 }
 
 /// Combined side metrics
@@ -33,6 +317,8 @@ pub struct TimeSeriesMetrics {
 pub struct SideMetrics {
     pub total_return:  f64,
     pub total_pnl:     f64,
+    /// Cost split over the side's closed trades
+    pub costs:         CostBreakdown,
     pub trade_metrics: SideTradeMetrics,
     pub time_metrics:  TimeSeriesMetrics,
 }
@@ -43,14 +329,88 @@ pub struct SummaryMetrics {
     pub overall: SideMetrics,
     pub longs:   SideMetrics,
     pub shorts:  SideMetrics,
+    /// Trade metrics per signal tag (tagged runs only), in first‐seen order
+    pub by_tag:  Vec<(String, SideTradeMetrics)>,
+    /// Headline metrics of the same trades without costs (when requested)
+    pub gross:   Option<HeadlineMetrics>,
+    /// How zero‐denominator ratios were reported
+    pub ratio_policy: RatioPolicy,
+    /// How the time‐series returns were defined
+    pub returns:      ReturnSpec,
+    /// Position overlap over the run (set by the runners)
+    pub concurrency: Option<ConcurrencyStats>,
+    /// Bars and trades per regime label (runs with `regimes` only)
+    pub by_regime:   Vec<RegimeMetrics>,
+    /// Strategy vs benchmark (runs with `benchmark` only)
+    pub benchmark:   Option<BenchmarkMetrics>,
+    /// Betas to each factor series (runs with `factors` only)
+    pub factor_betas: Vec<FactorBeta>,
+    /// Closed‐trade performance by holding time (set by the runners)
+    pub by_duration: Vec<DurationBucket>,
+    /// Time‐ vs money‐weighted account returns (when requested)
+    pub weighted:    Option<WeightedReturns>,
+    /// External deposits / withdrawals and the PnL net of them
+    pub cash_flows:  Option<FlowSummary>,
+    /// Maker/taker mix of the closed trades' fills
+    pub execution:   ExecutionSummary,
+}
+
+/// The headline numbers compared between cost bases
+#[derive(Debug, Clone, Copy)]
+pub struct HeadlineMetrics {
+    pub total_pnl:     f64,
+    pub total_return:  f64,
+    pub sharpe_ratio:  f64,
+    pub profit_factor: f64,
+    pub win_rate:      f64,
+    pub max_drawdown:  f64,
+}
+
+impl HeadlineMetrics {
+    pub fn of(side: &SideMetrics) -> Self {
+        HeadlineMetrics {
+            total_pnl:     side.total_pnl,
+            total_return:  side.total_return,
+            sharpe_ratio:  side.time_metrics.sharpe_ratio,
+            profit_factor: side.trade_metrics.profit_factor,
+            win_rate:      side.trade_metrics.win_rate,
+            max_drawdown:  side.time_metrics.max_drawdown,
+        }
+    }
+}
+
+/// The positions as if traded at raw (pre‐slippage) prices with no fees,
+/// funding, roll or latency costs: PnL becomes `CostBreakdown::gross_pnl`
+pub fn without_costs(positions: &[Position]) -> Vec<Position> {
+    positions
+        .iter()
+        .map(|p| {
+            let long = p.position_type == "long";
+            let mut g = p.clone();
+            if p.pnl.is_some() {
+                g.pnl = Some(CostBreakdown::of(p).gross_pnl + p.dividend_income);
+            }
+            g.entry_price    = if long { p.entry_price - p.slippage_entry } else { p.entry_price + p.slippage_entry };
+            g.exit_price     = p.exit_price.map(|x| if long { x + p.slippage_exit } else { x - p.slippage_exit });
+            g.fee_entry      = 0.0;
+            g.fee_exit       = 0.0;
+            g.slippage_entry = 0.0;
+            g.slippage_exit  = 0.0;
+            g.funding_cost   = 0.0;
+            g.roll_cost      = 0.0;
+            g.latency_cost   = 0.0;
+            g
+        })
+        .collect()
 }
 
 /// Build just the trade‐level slice
-fn compute_trade_metrics(
+pub(crate) fn compute_trade_metrics(
     trades: Vec<&Position>,
+    policy: RatioPolicy,
 ) -> SideTradeMetrics {
     let mut ordered = trades;
-    ordered.sort_by_key(|p| p.exit_index.unwrap_or(usize::MAX));
+    ordered.sort_by(|a, b| a.cmp_exit(b));
 
     let n = ordered.len();
     let mut trade_returns = Vec::with_capacity(n);
@@ -66,8 +426,8 @@ fn compute_trade_metrics(
         let pnl = pos.pnl.unwrap_or(0.0);
         trade_pnls.push(pnl);
 
-        // r_i = PnL_i / (entry_price * position_size)
-        let notional = pos.entry_price * pos.position_size;
+        // r_i = PnL_i / entry notional
+        let notional = pos.contract.notional(pos.entry_price, pos.position_size);
         let r = if notional != 0.0 {
             pnl / notional
         } else {
@@ -93,10 +453,21 @@ fn compute_trade_metrics(
     let loss_rate = if nf > 0.0 { losses as f64 / nf } else { 0.0 };
     let avg_ret   = if nf > 0.0 { trade_returns.iter().sum::<f64>() / nf } else { 0.0 };
     let avg_pnl   = if nf > 0.0 { trade_pnls.iter().sum::<f64>() / nf } else { 0.0 };
-    let profit_factor = 
-        if sum_losses > 0.0 { sum_wins / sum_losses } else { f64::INFINITY };
+    let profit_factor = policy.ratio(sum_wins, sum_losses, f64::INFINITY);
     let expectancy    = avg_ret;
     let avg_dur       = if nf > 0.0 { durations.iter().sum::<f64>() / nf } else { 0.0 };
+    let effs: Vec<Efficiency> = ordered.iter().filter_map(|p| efficiency(p)).collect();
+    let avg_eff = |f: fn(&Efficiency) -> f64| {
+        if effs.is_empty() { 0.0 } else { effs.iter().map(f).sum::<f64>() / effs.len() as f64 }
+    };
+    let runups: Vec<(Runup, f64)> = ordered
+        .iter()
+        .filter_map(|p| runup(p).map(|r| (r, p.pnl.unwrap_or(0.0))))
+        .collect();
+    let avg_runup = if nf > 0.0 { runups.iter().map(|(r, _)| r.max_runup).sum::<f64>() / nf } else { 0.0 };
+    let givebacks: Vec<f64> = runups.iter().map(|(r, _)| r.giveback).filter(|g| g.is_finite()).collect();
+    let avg_giveback = if givebacks.is_empty() { 0.0 } else { givebacks.iter().sum::<f64>() / givebacks.len() as f64 };
+    let profit_to_loss = runups.iter().filter(|(r, pnl)| r.max_runup > 0.0 && *pnl < 0.0).count();
 
     SideTradeMetrics {
         number_of_trades:     n,
@@ -107,28 +478,40 @@ fn compute_trade_metrics(
         profit_factor,
         expectancy,
         average_duration:     avg_dur,
+        average_entry_efficiency: avg_eff(|e| e.entry),
+        average_exit_efficiency:  avg_eff(|e| e.exit),
+        average_total_efficiency: avg_eff(|e| e.total),
+        average_max_runup:    avg_runup,
+        average_giveback:     avg_giveback,
+        profit_to_loss_rate:  if nf > 0.0 { profit_to_loss as f64 / nf } else { 0.0 },
         trade_returns,
         trade_pnls,
         durations,
     }
 }
 
-/// Build bar‐by‐bar metrics from the **full** exposure curve
+/// Build bar‐by‐bar metrics from the **full** exposure curve, shifted up
+/// by `base` (the initial equity for account‐value metrics, else 0)
 fn compute_time_metrics(
     exposure: &[ExposureSnapshot],
+    base: f64,
+    spec: ReturnSpec,
+    policy: RatioPolicy,
 ) -> TimeSeriesMetrics {
     let n = exposure.len();
+    let curve: Vec<f64> = exposure
+        .iter()
+        .map(|s| base + match spec.equity {
+            ReturnEquity::Marked   => s.total_equity,
+            ReturnEquity::Realized => s.realized_equity,
+        })
+        .collect();
+    // account curves start from the initial equity, PnL curves from bar 0
+    let start = if base != 0.0 { base } else { curve[0] };
     let mut returns = Vec::with_capacity(n.saturating_sub(1));
 
     for i in 1..n {
-        let prev = exposure[i - 1].total_equity;
-        let cur  = exposure[i].total_equity;
-        let r    = if prev != 0.0 {
-            (cur - prev) / prev
-        } else {
-            0.0
-        };
-        returns.push(r);
+        returns.push(spec.kind.of(curve[i - 1], curve[i]));
     }
 
     let m = returns.len() as f64;
@@ -139,40 +522,67 @@ fn compute_time_metrics(
     } else {
         0.0
     };
-    let sharpe_ratio = if volatility != 0.0 { mean_return / volatility } else { 0.0 };
+    let sharpe_ratio = policy.ratio(mean_return, volatility, 0.0);
+    let (skewness, kurtosis) = moments(&returns);
+    let probabilistic_sharpe = if volatility > 0.0 {
+        probabilistic_sharpe(mean_return / volatility, 0.0, returns.len(), skewness, kurtosis)
+    } else {
+        f64::NAN
+    };
 
-    // cumulative = (E_final / E_initial) - 1
-    let cum_return = if exposure[0].total_equity != 0.0 {
-        (exposure[n - 1].total_equity / exposure[0].total_equity) - 1.0
+    // cumulative = (E_final / E_initial) - 1, or its log
+    let cum_return = if start != 0.0 {
+        match spec.kind {
+            ReturnKind::Simple => (curve[n - 1] / start) - 1.0,
+            ReturnKind::Log    => ReturnKind::Log.of(start, curve[n - 1]),
+        }
     } else {
         0.0
     };
 
     // max drawdown
-    let mut peak: f64   = exposure[0].total_equity;
+    let mut peak: f64   = start;
     let mut max_dd: f64 = 0.0;
-    for snap in exposure {
-        let eq = snap.total_equity;
+    for &eq in &curve {
         peak = peak.max(eq);
         let dd = if peak != 0.0 { (peak - eq) / peak } else { 0.0 };
         max_dd = max_dd.max(dd);
     }
 
     TimeSeriesMetrics {
+        autocorrelation:   autocorrelations(&returns, DEFAULT_AUTOCORR_LAGS),
+        runs_test:         runs_test(&returns),
+        high_water:        high_water_stats(&curve),
+        skewness,
+        kurtosis,
+        probabilistic_sharpe,
         returns,
         mean_return,
         volatility,
         sharpe_ratio,
         cumulative_return: cum_return,
         max_drawdown:      max_dd,
+        bootstrap:         None,
     }
 }
 
 /// Top‐level: per‐trade + time‐series for overall, longs, shorts
 pub fn compute_summary_metrics(
-    _initial_equity: f64,
+    initial_equity: f64,
     closed: &[Position],
     exposure: &[ExposureSnapshot],
+) -> SummaryMetrics {
+    compute_summary_metrics_with(initial_equity, closed, exposure, RatioPolicy::default(), ReturnSpec::default())
+}
+
+/// `compute_summary_metrics` with an explicit zero‐denominator policy and
+/// return definition
+pub fn compute_summary_metrics_with(
+    initial_equity: f64,
+    closed: &[Position],
+    exposure: &[ExposureSnapshot],
+    policy: RatioPolicy,
+    spec: ReturnSpec,
 ) -> SummaryMetrics {
     // partition the closed trades
     let all:   Vec<&Position> = closed.iter().collect();
@@ -180,12 +590,29 @@ pub fn compute_summary_metrics(
     let shorts:Vec<&Position> = closed.iter().filter(|p| p.position_type == "short").collect();
 
     // trade metrics
-    let tm_all   = compute_trade_metrics(all.clone());
-    let tm_long  = compute_trade_metrics(longs.clone());
-    let tm_short = compute_trade_metrics(shorts.clone());
+    let tm_all   = compute_trade_metrics(all.clone(), policy);
+    let tm_long  = compute_trade_metrics(longs.clone(), policy);
+    let tm_short = compute_trade_metrics(shorts.clone(), policy);
+
+    // per‐tag trade metrics
+    let mut tags: Vec<&str> = Vec::new();
+    for p in closed {
+        if let Some(t) = p.tag.as_deref() {
+            if !tags.contains(&t) {
+                tags.push(t);
+            }
+        }
+    }
+    let by_tag = tags
+        .into_iter()
+        .map(|t| {
+            let trades: Vec<&Position> = closed.iter().filter(|p| p.tag.as_deref() == Some(t)).collect();
+            (t.to_string(), compute_trade_metrics(trades, policy))
+        })
+        .collect();
 
     // time metrics (one full exposure curve)
-    let ts_all = compute_time_metrics(exposure);
+    let ts_all = compute_time_metrics(exposure, spec.basis.base(initial_equity), spec, policy);
 
     // total PnL from exposure
     let final_snap = exposure.last().unwrap();
@@ -196,20 +623,91 @@ pub fn compute_summary_metrics(
         overall: SideMetrics {
             total_return:  total_ret,
             total_pnl,
+            costs:         CostBreakdown::total(&all),
             trade_metrics: tm_all,
             time_metrics:  ts_all.clone(),
         },
         longs: SideMetrics {
             total_return:  total_ret,
             total_pnl:     tm_long.trade_pnls.iter().sum(),
+            costs:         CostBreakdown::total(&longs),
             trade_metrics: tm_long,
             time_metrics:  ts_all.clone(),
         },
         shorts: SideMetrics {
             total_return:  total_ret,
             total_pnl:     tm_short.trade_pnls.iter().sum(),
+            costs:         CostBreakdown::total(&shorts),
             trade_metrics: tm_short,
             time_metrics:  ts_all.clone(),
         },
+        by_tag,
+        gross: None,
+        ratio_policy: policy,
+        returns:      spec,
+        concurrency:  None,
+        by_regime:    Vec::new(),
+        benchmark:    None,
+        factor_betas: Vec::new(),
+        by_duration:  Vec::new(),
+        weighted:     None,
+        cash_flows:   None,
+        execution:    execution_summary(&all),
     }
 }
+
+/// Scalar metrics addressable by name (overall side) in ranking APIs
+pub const SCALAR_METRICS: &[&str] = &[
+    "total_return",
+    "total_pnl",
+    "number_of_trades",
+    "win_rate",
+    "loss_rate",
+    "average_trade_return",
+    "average_trade_pnl",
+    "profit_factor",
+    "expectancy",
+    "average_duration",
+    "mean_return",
+    "volatility",
+    "sharpe_ratio",
+    "cumulative_return",
+    "max_drawdown",
+    "skewness",
+    "kurtosis",
+    "probabilistic_sharpe",
+];
+
+/// Look up one overall scalar metric by name
+pub fn lookup_metric(summary: &SummaryMetrics, name: &str) -> Option<f64> {
+    let o  = &summary.overall;
+    let tm = &o.trade_metrics;
+    let ts = &o.time_metrics;
+    let v = match name {
+        "total_return"         => o.total_return,
+        "total_pnl"            => o.total_pnl,
+        "number_of_trades"     => tm.number_of_trades as f64,
+        "win_rate"             => tm.win_rate,
+        "loss_rate"            => tm.loss_rate,
+        "average_trade_return" => tm.average_trade_return,
+        "average_trade_pnl"    => tm.average_trade_pnl,
+        "profit_factor"        => tm.profit_factor,
+        "expectancy"           => tm.expectancy,
+        "average_duration"     => tm.average_duration,
+        "mean_return"          => ts.mean_return,
+        "volatility"           => ts.volatility,
+        "sharpe_ratio"         => ts.sharpe_ratio,
+        "cumulative_return"    => ts.cumulative_return,
+        "max_drawdown"         => ts.max_drawdown,
+        "skewness"             => ts.skewness,
+        "kurtosis"             => ts.kurtosis,
+        "probabilistic_sharpe" => ts.probabilistic_sharpe,
+        _ => return None,
+    };
+    Some(v)
+}
+
+/// Metrics where a smaller value is the better outcome
+pub fn lower_is_better(name: &str) -> bool {
+    matches!(name, "max_drawdown" | "volatility" | "loss_rate")
+}
@@ -0,0 +1,188 @@
+// src/engine/monte_carlo.rs
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    output::distribution_to_py,
+    rng::Rng,
+    stats::summarize,
+};
+
+/// How each simulated trade sequence is drawn from the observed trades
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResampleMethod {
+    /// Draw N trades with replacement
+    Bootstrap,
+    /// Permute the observed trades (same set, new order)
+    Shuffle,
+}
+
+impl ResampleMethod {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "bootstrap" => Ok(ResampleMethod::Bootstrap),
+            "shuffle"   => Ok(ResampleMethod::Shuffle),
+            other => Err(format!(
+                "unknown method ‘{}’ (expected \"bootstrap\" or \"shuffle\")", other
+            )),
+        }
+    }
+}
+
+/// Path statistics of one trade‐close equity sequence
+#[derive(Clone, Copy, Debug)]
+pub struct PathStats {
+    pub final_equity:          f64,
+    /// Fractional peak‐to‐trough decline
+    pub max_drawdown:          f64,
+    /// Longest run of trades spent below the running peak
+    pub max_drawdown_duration: f64,
+}
+
+/// Walk `initial_equity + cumsum(pnls)` and collect its path statistics
+pub fn path_stats(initial_equity: f64, pnls: &[f64]) -> PathStats {
+    let mut equity   = initial_equity;
+    let mut peak     = initial_equity;
+    let mut max_dd   = 0.0_f64;
+    let mut under    = 0usize;
+    let mut max_dur  = 0usize;
+
+    for &pnl in pnls {
+        equity += pnl;
+        if equity >= peak {
+            peak  = equity;
+            under = 0;
+        } else {
+            under += 1;
+            max_dur = max_dur.max(under);
+        }
+        let dd = if peak != 0.0 { (peak - equity) / peak } else { 0.0 };
+        max_dd = max_dd.max(dd);
+    }
+
+    PathStats {
+        final_equity:          equity,
+        max_drawdown:          max_dd,
+        max_drawdown_duration: max_dur as f64,
+    }
+}
+
+/// Run `n_simulations` resampled paths in parallel (one RNG stream each)
+pub fn simulate_paths(
+    pnls: &[f64],
+    initial_equity: f64,
+    n_simulations: usize,
+    method: ResampleMethod,
+    seed: u64,
+) -> Vec<PathStats> {
+    (0..n_simulations)
+        .into_par_iter()
+        .map(|k| {
+            let mut rng = Rng::fork(seed, k as u64);
+            let sample: Vec<f64> = match method {
+                ResampleMethod::Bootstrap => {
+                    (0..pnls.len()).map(|_| pnls[rng.below(pnls.len())]).collect()
+                }
+                ResampleMethod::Shuffle => {
+                    let mut v = pnls.to_vec();
+                    rng.shuffle(&mut v);
+                    v
+                }
+            };
+            path_stats(initial_equity, &sample)
+        })
+        .collect()
+}
+
+/// Trade PnLs from either a flat numeric sequence (taken as already in exit
+/// order) or `closed_positions` dicts (re‐ordered by `exit_index`)
+pub(crate) fn extract_trade_pnls(trades: &PyAny) -> PyResult<Vec<f64>> {
+    let mut keyed: Vec<(usize, f64)> = Vec::new();
+    for (i, item) in trades.iter()?.enumerate() {
+        let item = item?;
+        let entry = match item.downcast::<PyDict>() {
+            Ok(d) => {
+                let pnl = d
+                    .get_item("pnl")
+                    .ok_or_else(|| PyValueError::new_err("trade dict has no ‘pnl’ key"))?
+                    .extract::<f64>()?;
+                let key = match d.get_item("exit_index") {
+                    Some(v) if !v.is_none() => v.extract::<usize>()?,
+                    _ => i,
+                };
+                (key, pnl)
+            }
+            Err(_) => (i, item.extract::<f64>()?),
+        };
+        keyed.push(entry);
+    }
+    keyed.sort_by_key(|&(k, _)| k);
+    Ok(keyed.into_iter().map(|(_, pnl)| pnl).collect())
+}
+
+/// Monte Carlo resampling of closed trades.
+///
+/// `trades` is either `result["closed_positions"]` or a sequence of trade
+/// PnLs (in exit order).  Returns the distribution and confidence interval of
+/// final equity, max drawdown and max drawdown duration (in trades), plus the
+/// same statistics for the observed order.
+#[pyfunction]
+#[pyo3(signature=(
+    trades,
+    initial_equity,
+    n_simulations=1000,
+    method="bootstrap",
+    seed=0,
+    confidence=0.95
+))]
+pub fn monte_carlo_trades(
+    py: Python<'_>,
+    trades:         &PyAny,
+    initial_equity: f64,
+    n_simulations:  usize,
+    method:         &str,
+    seed:           u64,
+    confidence:     f64,
+) -> PyResult<PyObject> {
+    let method = ResampleMethod::parse(method).map_err(PyValueError::new_err)?;
+    if !(0.0..1.0).contains(&confidence) {
+        return Err(PyValueError::new_err("confidence must be in [0, 1)"));
+    }
+    let pnls = extract_trade_pnls(trades)?;
+    if pnls.is_empty() {
+        return Err(PyValueError::new_err("no trades to resample"));
+    }
+
+    let paths = py.allow_threads(|| {
+        simulate_paths(&pnls, initial_equity, n_simulations, method, seed)
+    });
+    let observed = path_stats(initial_equity, &pnls);
+
+    let final_eq = summarize(paths.iter().map(|p| p.final_equity).collect(), confidence);
+    let max_dd   = summarize(paths.iter().map(|p| p.max_drawdown).collect(), confidence);
+    let max_dur  = summarize(paths.iter().map(|p| p.max_drawdown_duration).collect(), confidence);
+
+    let out = PyDict::new(py);
+    out.set_item("n_simulations", n_simulations)?;
+    out.set_item("n_trades",      pnls.len())?;
+    out.set_item("method",        match method {
+        ResampleMethod::Bootstrap => "bootstrap",
+        ResampleMethod::Shuffle   => "shuffle",
+    })?;
+    out.set_item("seed",          seed)?;
+    out.set_item("confidence",    confidence)?;
+    out.set_item("final_equity",          distribution_to_py(py, &final_eq)?)?;
+    out.set_item("max_drawdown",          distribution_to_py(py, &max_dd)?)?;
+    out.set_item("max_drawdown_duration", distribution_to_py(py, &max_dur)?)?;
+
+    let obs = PyDict::new(py);
+    obs.set_item("final_equity",          observed.final_equity)?;
+    obs.set_item("max_drawdown",          observed.max_drawdown)?;
+    obs.set_item("max_drawdown_duration", observed.max_drawdown_duration)?;
+    out.set_item("observed", obs)?;
+
+    Ok(out.into())
+}
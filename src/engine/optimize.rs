@@ -0,0 +1,633 @@
+// src/engine/optimize.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    array_to_vec, market_from_py, signals_from_py, tags_from_py,
+    backtest::{run_engine, validate_signals, BacktestConfig, MarketData, SignalSet},
+    exit_policies::{run_exit_policies, scale_levels, ExitPolicy},
+    metrics::{lookup_metric, lower_is_better, SummaryMetrics, SCALAR_METRICS},
+    output::result_to_py,
+    stats::{expected_max_sharpe, mean, probabilistic_sharpe},
+};
+
+/// Grid axes the engine applies itself (no `signal_fn` needed)
+pub const BUILTIN_PARAMS: &[&str] = &[
+    "tp_mult",
+    "sl_mult",
+    "size_fraction",
+    "entry_fee_rate",
+    "exit_fee_rate",
+    "slippage_rate",
+];
+
+/// Metrics reported per configuration when the caller does not choose
+const DEFAULT_METRICS: &[&str] = &[
+    "total_return",
+    "total_pnl",
+    "sharpe_ratio",
+    "max_drawdown",
+    "win_rate",
+    "profit_factor",
+    "number_of_trades",
+];
+
+/// One grid point: (name, value) pairs in axis order
+pub type ParamSet = Vec<(String, f64)>;
+
+/// Cartesian product of the axes; the last axis varies fastest
+pub fn expand_grid(axes: &[(String, Vec<f64>)]) -> Vec<ParamSet> {
+    let mut combos: Vec<ParamSet> = vec![Vec::new()];
+    for (name, values) in axes {
+        let mut next = Vec::with_capacity(combos.len() * values.len());
+        for combo in &combos {
+            for &v in values {
+                let mut c = combo.clone();
+                c.push((name.clone(), v));
+                next.push(c);
+            }
+        }
+        combos = next;
+    }
+    combos
+}
+
+/// Apply the builtin parameters of `params` onto a signal set + config
+pub fn apply_builtin_params(
+    signals: &mut SignalSet,
+    config: &mut BacktestConfig,
+    close: &[f64],
+    params: &[(String, f64)],
+) {
+    for (name, v) in params {
+        match name.as_str() {
+            "tp_mult" => {
+                scale_levels(&mut signals.long_tp,  close, *v);
+                scale_levels(&mut signals.short_tp, close, *v);
+            }
+            "sl_mult" => {
+                scale_levels(&mut signals.long_sl,  close, *v);
+                scale_levels(&mut signals.short_sl, close, *v);
+            }
+            "size_fraction" => {
+                signals.long_size.iter_mut().for_each(|s| *s *= v);
+                signals.short_size.iter_mut().for_each(|s| *s *= v);
+            }
+            "entry_fee_rate" => config.entry_fee_rate = *v,
+            "exit_fee_rate"  => config.exit_fee_rate  = *v,
+            "slippage_rate"  => config.slippage_rate  = *v,
+            _ => {}
+        }
+    }
+}
+
+/// Replace the arrays named in a `signal_fn` result
+pub(crate) fn override_signals(base: &SignalSet, d: &PyDict) -> PyResult<SignalSet> {
+    let mut s = base.clone();
+    for (k, v) in d.iter() {
+        let key: &str = k.extract()?;
+        match key {
+            "long_signals"     => s.long_signals     = array_to_vec(v.extract::<&PyArray1<bool>>()?)?,
+            "short_signals"    => s.short_signals    = array_to_vec(v.extract::<&PyArray1<bool>>()?)?,
+            "long_tp"          => s.long_tp          = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "long_sl"          => s.long_sl          = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "short_tp"         => s.short_tp         = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "short_sl"         => s.short_sl         = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "long_size"        => s.long_size        = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "short_size"       => s.short_size       = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "expiration_times" => s.expiration_times = array_to_vec(v.extract::<&PyArray1<f64>>()?)?,
+            "tags"             => s.tags             = Some(tags_from_py(v)?),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "signal_fn returned unknown key ‘{}’", other
+                )))
+            }
+        }
+    }
+    Ok(s)
+}
+
+/// (name → values) axes from a Python dict, in insertion order
+pub(crate) fn axes_from_py(grid: &PyDict) -> PyResult<Vec<(String, Vec<f64>)>> {
+    let mut axes = Vec::with_capacity(grid.len());
+    for (k, v) in grid.iter() {
+        let name: String = k.extract()?;
+        let values: Vec<f64> = v.extract()?;
+        if values.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "param_grid axis ‘{}’ is empty", name
+            )));
+        }
+        axes.push((name, values));
+    }
+    Ok(axes)
+}
+
+pub(crate) fn params_to_py<'py>(py: Python<'py>, params: &[(String, f64)]) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    for (name, v) in params {
+        d.set_item(name, v)?;
+    }
+    Ok(d)
+}
+
+/// Reject metric names the ranking APIs cannot resolve
+pub(crate) fn check_metric_name(name: &str) -> PyResult<()> {
+    if SCALAR_METRICS.contains(&name) {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "unknown metric ‘{}’ (expected one of {:?})", name, SCALAR_METRICS
+        )))
+    }
+}
+
+/// Build one (signals, config) job per grid point, calling `signal_fn` under the GIL
+pub(crate) fn build_jobs(
+    py: Python<'_>,
+    market: &MarketData,
+    base_signals: &SignalSet,
+    base_config: &BacktestConfig,
+    combos: &[ParamSet],
+    signal_fn: Option<&PyAny>,
+) -> PyResult<Vec<(SignalSet, BacktestConfig)>> {
+    let mut jobs = Vec::with_capacity(combos.len());
+    for params in combos {
+        let mut signals = match signal_fn {
+            Some(f) => {
+                let ret = f.call1((params_to_py(py, params)?,))?;
+                override_signals(base_signals, ret.downcast::<PyDict>()?)?
+            }
+            None => base_signals.clone(),
+        };
+        let mut config = base_config.clone();
+        apply_builtin_params(&mut signals, &mut config, &market.close, params);
+        validate_signals(&signals, &market.timestamps).map_err(|e| {
+            PyValueError::new_err(format!("{} (params {:?})", e, params))
+        })?;
+        jobs.push((signals, config));
+    }
+    Ok(jobs)
+}
+
+/// Sort indices by score; NaN always ranks last
+pub fn rank_indices(scores: &[f64], ascending: bool) -> Vec<usize> {
+    let mut idx: Vec<usize> = (0..scores.len()).collect();
+    idx.sort_by(|&a, &b| {
+        let (x, y) = (scores[a], scores[b]);
+        match (x.is_nan(), y.is_nan()) {
+            (true, true)   => std::cmp::Ordering::Equal,
+            (true, false)  => std::cmp::Ordering::Greater,
+            (false, true)  => std::cmp::Ordering::Less,
+            (false, false) => {
+                let o = x.partial_cmp(&y).unwrap();
+                if ascending { o } else { o.reverse() }
+            }
+        }
+    });
+    idx
+}
+
+/// Evaluate every job in parallel, keeping only the requested scalars
+pub fn evaluate_jobs(
+    market: &MarketData,
+    jobs: &[(SignalSet, BacktestConfig)],
+    metric_names: &[String],
+) -> Vec<Vec<f64>> {
+    jobs.par_iter()
+        .map(|(signals, config)| {
+            let summary: SummaryMetrics = run_engine(market, signals, config).metrics;
+            metric_names
+                .iter()
+                .map(|m| lookup_metric(&summary, m).unwrap_or(f64::NAN))
+                .collect()
+        })
+        .collect()
+}
+
+/// Exhaustive grid search.
+///
+/// `param_grid` maps parameter names to candidate values.  The builtin axes
+/// (`tp_mult`, `sl_mult`, `size_fraction`, `entry_fee_rate`, `exit_fee_rate`,
+/// `slippage_rate`) are applied by the engine; any other name requires a
+/// `signal_fn(params: dict) -> dict` that returns replacement arrays (any of
+/// the `run_backtest` signal/level/size keys).  Builtin axes are applied on
+/// top of whatever `signal_fn` returns.
+///
+/// Each row also carries the probabilistic Sharpe (vs 0) and the deflated
+/// Sharpe: the probability its Sharpe beats the best expected from
+/// `n_trials` zero‐skill trials (default: the grid size) with the grid's
+/// Sharpe dispersion.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    param_grid,
+    signal_fn=None,
+    rank_by="sharpe_ratio",
+    ascending=false,
+    metrics=None,
+    top_k=0,
+    n_trials=None
+))]
+pub fn grid_search(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    param_grid:       &PyDict,
+    signal_fn:        Option<&PyAny>,
+    rank_by:          &str,
+    ascending:        bool,
+    metrics:          Option<Vec<String>>,
+    top_k:            usize,
+    n_trials:         Option<usize>,
+) -> PyResult<PyObject> {
+    // 1) Inputs
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let base_signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    let base_config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+
+    // 2) Grid + metric selection
+    let axes = axes_from_py(param_grid)?;
+    if signal_fn.is_none() {
+        if let Some((name, _)) = axes.iter().find(|(n, _)| !BUILTIN_PARAMS.contains(&n.as_str())) {
+            return Err(PyValueError::new_err(format!(
+                "param ‘{}’ is not builtin ({:?}); pass a signal_fn to handle it",
+                name, BUILTIN_PARAMS
+            )));
+        }
+    }
+    check_metric_name(rank_by)?;
+    let mut metric_names: Vec<String> = metrics
+        .unwrap_or_else(|| DEFAULT_METRICS.iter().map(|s| s.to_string()).collect());
+    for m in &metric_names {
+        check_metric_name(m)?;
+    }
+    if !metric_names.iter().any(|m| m == rank_by) {
+        metric_names.push(rank_by.to_string());
+    }
+    let rank_col = metric_names.iter().position(|m| m == rank_by).unwrap();
+    let reported = metric_names.len();
+    metric_names.extend(["mean_return", "volatility", "skewness", "kurtosis"].map(String::from));
+
+    // 3) Jobs (serial, GIL held for signal_fn) → parallel evaluation
+    let combos = expand_grid(&axes);
+    let jobs = build_jobs(py, &market, &base_signals, &base_config, &combos, signal_fn)?;
+    let values = py.allow_threads(|| evaluate_jobs(&market, &jobs, &metric_names));
+
+    // Deflated Sharpe: per‐bar Sharpes against the expected best of the trials
+    let bars = market.timestamps.len().saturating_sub(1);
+    let sharpes: Vec<f64> = values
+        .iter()
+        .map(|v| if v[reported + 1] > 0.0 { v[reported] / v[reported + 1] } else { f64::NAN })
+        .collect();
+    let finite: Vec<f64> = sharpes.iter().copied().filter(|s| s.is_finite()).collect();
+    let sr_var = if finite.len() > 1 {
+        let mu = mean(&finite);
+        finite.iter().map(|s| (s - mu).powi(2)).sum::<f64>() / (finite.len() - 1) as f64
+    } else {
+        0.0
+    };
+    let sr_star = expected_max_sharpe(sr_var, n_trials.unwrap_or(combos.len()));
+
+    // 4) Rank
+    let scores: Vec<f64> = values.iter().map(|v| v[rank_col]).collect();
+    let order = rank_indices(&scores, ascending);
+
+    // 5) Re-run the top‐k for full results
+    let top: Vec<usize> = order.iter().copied().take(top_k).collect();
+    let full = py.allow_threads(|| {
+        top.par_iter()
+            .map(|&i| run_engine(&market, &jobs[i].0, &jobs[i].1))
+            .collect::<Vec<_>>()
+    });
+
+    // 6) Ranked table
+    let table = PyList::empty(py);
+    for (rank, &i) in order.iter().enumerate() {
+        let row = PyDict::new(py);
+        row.set_item("rank",   rank + 1)?;
+        row.set_item("params", params_to_py(py, &combos[i])?)?;
+        let md = PyDict::new(py);
+        for (name, v) in metric_names.iter().zip(&values[i]).take(reported) {
+            md.set_item(name, v)?;
+        }
+        row.set_item("metrics", md)?;
+        let (skew, kurt) = (values[i][reported + 2], values[i][reported + 3]);
+        row.set_item("probabilistic_sharpe", probabilistic_sharpe(sharpes[i], 0.0, bars, skew, kurt))?;
+        row.set_item("deflated_sharpe",      probabilistic_sharpe(sharpes[i], sr_star, bars, skew, kurt))?;
+        if rank < full.len() {
+            row.set_item("result", result_to_py(py, &full[rank])?)?;
+        }
+        table.append(row)?;
+    }
+    Ok(table.into())
+}
+
+/// Compare exit policies over one shared entry scan.
+///
+/// `policies` is a list of dicts with `tp_mult` / `sl_mult` (default 1):
+/// each scales the TP / SL distances from the signal‐bar close like the
+/// `grid_search` axes.  Entries are scanned once and every policy only
+/// re‐simulates exits and metrics, in parallel.  Returns one row per policy,
+/// in the given order, with its `rank` by `rank_by`, its `policy` and
+/// `metrics`; `top_k` attaches full results to the best rows.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    policies,
+    rank_by="sharpe_ratio",
+    ascending=false,
+    metrics=None,
+    top_k=0
+))]
+pub fn compare_exit_policies(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    policies:         &PyList,
+    rank_by:          &str,
+    ascending:        bool,
+    metrics:          Option<Vec<String>>,
+    top_k:            usize,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+
+    let mut parsed = Vec::with_capacity(policies.len());
+    for item in policies {
+        let d: &PyDict = item
+            .downcast()
+            .map_err(|_| PyValueError::new_err("each policy must be a dict"))?;
+        let mut policy = ExitPolicy::default();
+        for (k, v) in d.iter() {
+            match k.extract::<&str>()? {
+                "tp_mult" => policy.tp_mult = v.extract()?,
+                "sl_mult" => policy.sl_mult = v.extract()?,
+                other => {
+                    return Err(PyValueError::new_err(format!(
+                        "unknown policy key ‘{}’ (expected \"tp_mult\" or \"sl_mult\")", other
+                    )))
+                }
+            }
+        }
+        if !(policy.tp_mult.is_finite() && policy.sl_mult.is_finite()) {
+            return Err(PyValueError::new_err("policy multiples must be finite"));
+        }
+        validate_signals(&policy.apply(&signals, &market.close), &market.timestamps)
+            .map_err(|e| PyValueError::new_err(format!("{} (policy {:?})", e, policy)))?;
+        parsed.push(policy);
+    }
+    if parsed.is_empty() {
+        return Err(PyValueError::new_err("policies must not be empty"));
+    }
+    check_metric_name(rank_by)?;
+    let mut metric_names: Vec<String> = metrics
+        .unwrap_or_else(|| DEFAULT_METRICS.iter().map(|s| s.to_string()).collect());
+    for m in &metric_names {
+        check_metric_name(m)?;
+    }
+    if !metric_names.iter().any(|m| m == rank_by) {
+        metric_names.push(rank_by.to_string());
+    }
+
+    let results = py.allow_threads(|| run_exit_policies(&market, &signals, &config, &parsed));
+    let values: Vec<Vec<f64>> = results
+        .iter()
+        .map(|r| metric_names.iter().map(|m| lookup_metric(&r.metrics, m).unwrap_or(f64::NAN)).collect())
+        .collect();
+    let rank_col = metric_names.iter().position(|m| m == rank_by).unwrap();
+    let scores: Vec<f64> = values.iter().map(|v| v[rank_col]).collect();
+    let mut rank = vec![0; parsed.len()];
+    for (r, i) in rank_indices(&scores, ascending).into_iter().enumerate() {
+        rank[i] = r + 1;
+    }
+
+    let table = PyList::empty(py);
+    for (i, policy) in parsed.iter().enumerate() {
+        let row = PyDict::new(py);
+        row.set_item("rank", rank[i])?;
+        let pd = PyDict::new(py);
+        pd.set_item("tp_mult", policy.tp_mult)?;
+        pd.set_item("sl_mult", policy.sl_mult)?;
+        row.set_item("policy", pd)?;
+        let md = PyDict::new(py);
+        for (name, v) in metric_names.iter().zip(&values[i]) {
+            md.set_item(name, v)?;
+        }
+        row.set_item("metrics", md)?;
+        if rank[i] <= top_k {
+            row.set_item("result", result_to_py(py, &results[i])?)?;
+        }
+        table.append(row)?;
+    }
+    Ok(table.into())
+}
+
+/// Mean of the cells adjacent (8‐neighbourhood) to `(row, col)`, NaNs skipped
+pub fn neighbourhood_mean(surface: &[Vec<f64>], row: usize, col: usize) -> f64 {
+    let mut sum = 0.0;
+    let mut cnt = 0usize;
+    for dr in -1isize..=1 {
+        for dc in -1isize..=1 {
+            if dr == 0 && dc == 0 {
+                continue;
+            }
+            let (r, c) = (row as isize + dr, col as isize + dc);
+            if r < 0 || c < 0 {
+                continue;
+            }
+            if let Some(&v) = surface.get(r as usize).and_then(|line| line.get(c as usize)) {
+                if !v.is_nan() {
+                    sum += v;
+                    cnt += 1;
+                }
+            }
+        }
+    }
+    if cnt > 0 { sum / cnt as f64 } else { f64::NAN }
+}
+
+/// Metric surface over one or two parameters.
+///
+/// Returns `surface[y][x]` (a single row when `y_param` is omitted) along
+/// with the best cell and the mean of its neighbours: a best value far above
+/// its neighbourhood indicates a knife‐edge optimum.  Parameters follow the
+/// `grid_search` rules (builtin names or a `signal_fn`).
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    x_param, x_values,
+    y_param=None, y_values=None,
+    metric="sharpe_ratio",
+    signal_fn=None
+))]
+pub fn sensitivity_surface(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    x_param:          String,
+    x_values:         Vec<f64>,
+    y_param:          Option<String>,
+    y_values:         Option<Vec<f64>>,
+    metric:           &str,
+    signal_fn:        Option<&PyAny>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let base_signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    let base_config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    check_metric_name(metric)?;
+
+    // y is the outer axis so rows of the flat grid are rows of the surface
+    let mut axes = Vec::with_capacity(2);
+    match (&y_param, &y_values) {
+        (Some(name), Some(values)) => axes.push((name.clone(), values.clone())),
+        (None, None) => {}
+        _ => return Err(PyValueError::new_err("y_param and y_values must be given together")),
+    }
+    axes.push((x_param.clone(), x_values.clone()));
+    if axes.iter().any(|(_, v)| v.is_empty()) {
+        return Err(PyValueError::new_err("parameter value lists must not be empty"));
+    }
+    if signal_fn.is_none() {
+        if let Some((name, _)) = axes.iter().find(|(n, _)| !BUILTIN_PARAMS.contains(&n.as_str())) {
+            return Err(PyValueError::new_err(format!(
+                "param ‘{}’ is not builtin ({:?}); pass a signal_fn to handle it",
+                name, BUILTIN_PARAMS
+            )));
+        }
+    }
+
+    let combos = expand_grid(&axes);
+    let jobs = build_jobs(py, &market, &base_signals, &base_config, &combos, signal_fn)?;
+    let names = vec![metric.to_string()];
+    let values = py.allow_threads(|| evaluate_jobs(&market, &jobs, &names));
+
+    let width = x_values.len();
+    let surface: Vec<Vec<f64>> = values
+        .chunks(width)
+        .map(|row| row.iter().map(|v| v[0]).collect())
+        .collect();
+
+    let flat: Vec<f64> = values.iter().map(|v| v[0]).collect();
+    let best = rank_indices(&flat, lower_is_better(metric))[0];
+    let (best_row, best_col) = (best / width, best % width);
+
+    let out = PyDict::new(py);
+    out.set_item("metric",   metric)?;
+    out.set_item("x_param",  &x_param)?;
+    out.set_item("x_values", &x_values)?;
+    out.set_item("y_param",  &y_param)?;
+    out.set_item("y_values", &y_values)?;
+    out.set_item("surface",  surface.clone())?;
+    let b = PyDict::new(py);
+    b.set_item("x", x_values[best_col])?;
+    b.set_item("y", y_values.as_ref().map(|ys| ys[best_row]))?;
+    b.set_item("value", flat[best])?;
+    b.set_item("neighbourhood_mean", neighbourhood_mean(&surface, best_row, best_col))?;
+    out.set_item("best", b)?;
+    Ok(out.into())
+}
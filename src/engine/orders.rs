@@ -0,0 +1,132 @@
+// src/engine/orders.rs
+
+use crate::engine::{bracket::LegStatus, position::Position};
+
+/// One order of the broker‐style log derived from a simulated position
+#[derive(Clone, Debug)]
+pub struct Order {
+    /// Sequential in log order
+    pub order_id:    u64,
+    pub position_id: u64,
+    /// Submission time
+    pub timestamp:   f64,
+    /// "buy" or "sell"
+    pub side:        &'static str,
+    /// "market", "limit" (take‐profit), "stop" or "stop_limit" (stop‐loss)
+    pub order_type:  &'static str,
+    /// Limit / stop price (None for market orders)
+    pub price:       Option<f64>,
+    /// Limit of a stop‐limit
+    pub limit_price: Option<f64>,
+    /// Times the order was amended (price or size) while working
+    pub amendments:  usize,
+    pub size:        f64,
+    /// Closes (part of) a position rather than opening one
+    pub reduce_only: bool,
+    /// "entry", "take_profit", "stop_loss" or the exit condition that
+    /// triggered a market close ("expiry", "signal", "drawdown")
+    pub purpose:     &'static str,
+    /// "filled", "canceled", "open" (still working after the last bar) or
+    /// "triggered" (a stop‐limit resting at its limit after the last bar)
+    pub status:      &'static str,
+    /// Fill or cancel time (None while open)
+    pub update_time: Option<f64>,
+    pub fill_price:  Option<f64>,
+}
+
+/// Expand positions into the orders a broker would have seen: a market
+/// entry, the bracket's take‐profit limit and stop (one‐cancels‐the‐other)
+/// working from the fill, and a market close for expiry, signal and
+/// drawdown exits.  Each leg carries its price and size in force at the exit
+/// (after any amendments).
+/// Returns the log sorted by submission time, then position.
+pub fn expand_orders(closed: &[Position], open: &[Position], timestamps: &[f64]) -> Vec<Order> {
+    let mut orders = Vec::new();
+    for pos in closed.iter().chain(open) {
+        let long = pos.position_type == "long";
+        let (side, close_side) = if long { ("buy", "sell") } else { ("sell", "buy") };
+        let exit_time = pos.exit_index.map(|i| pos.exit_time.unwrap_or(timestamps[i]));
+        let condition = pos.exit_condition.as_deref();
+        let order = |timestamp, side, order_type, price: Option<f64>, reduce_only, purpose| Order {
+            order_id: 0,
+            position_id: pos.position_id,
+            timestamp,
+            side,
+            order_type,
+            price,
+            limit_price: None,
+            amendments: 0,
+            size: pos.position_size,
+            reduce_only,
+            purpose,
+            status: "open",
+            update_time: None,
+            fill_price: None,
+        };
+
+        orders.push(Order {
+            status:      "filled",
+            update_time: Some(pos.entry_time),
+            fill_price:  Some(pos.entry_price),
+            ..order(pos.entry_time, side, "market", None, false, "entry")
+        });
+        if let Some(b) = &pos.bracket {
+            for leg in [&b.take_profit, &b.stop_loss] {
+                if !leg.price.is_finite() {
+                    continue;
+                }
+                let mut o = order(pos.entry_time, close_side, leg.order_type, Some(leg.price), true, leg.purpose);
+                o.size        = leg.size;
+                o.limit_price = leg.limit_price.is_finite().then_some(leg.limit_price);
+                o.amendments  = leg.amendments.len();
+                o.status      = leg.status.name();
+                if leg.status == LegStatus::Filled {
+                    o.fill_price = pos.exit_price;
+                }
+                if leg.done_bar.is_some() {
+                    o.update_time = exit_time;
+                }
+                orders.push(o);
+            }
+        }
+        // positions built outside the exit simulation (pairs) have no bracket
+        for (level, order_type, purpose, hit) in [
+            (pos.tp, "limit", "take_profit", "TP"),
+            (pos.sl, "stop",  "stop_loss",   "SL"),
+        ] {
+            if pos.bracket.is_some() || !level.is_finite() {
+                continue;
+            }
+            let mut o = order(pos.entry_time, close_side, order_type, Some(level), true, purpose);
+            if let Some(t) = exit_time {
+                o.update_time = Some(t);
+                if condition == Some(hit) {
+                    o.status = "filled";
+                    o.fill_price = pos.exit_price;
+                } else {
+                    o.status = "canceled";
+                }
+            }
+            orders.push(o);
+        }
+        let market_close = match condition {
+            Some("EXP")    => Some("expiry"),
+            Some("SIGNAL") => Some("signal"),
+            Some("DD")     => Some("drawdown"),
+            _              => None,
+        };
+        if let (Some(purpose), Some(t)) = (market_close, exit_time) {
+            orders.push(Order {
+                status:      "filled",
+                update_time: Some(t),
+                fill_price:  pos.exit_price,
+                ..order(t, close_side, "market", None, true, purpose)
+            });
+        }
+    }
+    orders.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp).then(a.position_id.cmp(&b.position_id)));
+    for (i, o) in orders.iter_mut().enumerate() {
+        o.order_id = i as u64;
+    }
+    orders
+}
@@ -0,0 +1,696 @@
+// src/engine/output.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::engine::{
+    backtest::{BacktestResult, SkippedEntry},
+    benchmark::{bar_returns, underwater},
+    bootstrap::BootstrapIntervals,
+    contract::ContractType,
+    drawdown_limit::DrawdownBreach,
+    stats::Distribution,
+    exposure::{ExposureSnapshot, OpenMark},
+    concurrency::ConcurrencyStats,
+    excursions::{efficiency, runup},
+    orders::Order,
+    metrics::{CostBreakdown, HeadlineMetrics, RatioPolicy, SideMetrics, SideTradeMetrics, SummaryMetrics, TimeSeriesMetrics},
+    position::Position,
+    timeline::{position_events, TimelineLayout},
+    run_info::{RunInfo, SCHEMA_VERSION, VERSION},
+};
+
+/// Liquidation fields of a position (runs with `maintenance_margin` only)
+fn set_liquidation(pd: &PyDict, pos: &Position) -> PyResult<()> {
+    if let Some(price) = pos.liquidation_price {
+        pd.set_item("liquidation_price",      price)?;
+        pd.set_item("near_liquidation_bars",  pos.near_liquidation.len())?;
+        pd.set_item("liquidation_bar",        pos.liquidation_bar)?;
+    }
+    Ok(())
+}
+
+/// Closed trade → dict
+pub fn closed_position_to_py<'py>(py: Python<'py>, pos: &Position) -> PyResult<&'py PyDict> {
+    let pd = PyDict::new(py);
+    pd.set_item("position_id",     pos.position_id)?;
+    pd.set_item("entry_time",      pos.entry_time)?;
+    pd.set_item("position_type",   &pos.position_type)?;
+    pd.set_item("entry_index",     pos.entry_index)?;
+    pd.set_item("entry_price",     pos.entry_price)?;
+    pd.set_item("tp",              pos.tp)?;
+    pd.set_item("sl",              pos.sl)?;
+    pd.set_item("expiration_time", pos.expiration_time)?;
+    pd.set_item("exit_index",      pos.exit_index)?;
+    pd.set_item("exit_price",      pos.exit_price)?;
+    pd.set_item("exit_condition",  &pos.exit_condition)?;
+    if pos.exit_time.is_some() {
+        pd.set_item("exit_time", pos.exit_time)?;
+    }
+    pd.set_item("mfe", pos.mfe)?;
+    pd.set_item("mae", pos.mae)?;
+    set_liquidation(pd, pos)?;
+    if let Some(e) = efficiency(pos) {
+        pd.set_item("entry_efficiency", e.entry)?;
+        pd.set_item("exit_efficiency",  e.exit)?;
+        pd.set_item("total_efficiency", e.total)?;
+    }
+    if let Some(r) = runup(pos) {
+        pd.set_item("max_runup", r.max_runup)?;
+        pd.set_item("giveback",  r.giveback)?;
+    }
+    if let Some(r) = pos.r_multiple() {
+        pd.set_item("r_multiple", r)?;
+    }
+    if pos.expiry_slippage.is_some() {
+        pd.set_item("expiry_slippage", pos.expiry_slippage)?;
+    }
+    if pos.expiry_action.is_some() {
+        pd.set_item("expiry_action", &pos.expiry_action)?;
+    }
+    pd.set_item("position_size",   pos.position_size)?;
+    pd.set_item("fee_entry",       pos.fee_entry)?;
+    pd.set_item("slippage_entry",  pos.slippage_entry)?;
+    pd.set_item("fee_exit",        pos.fee_exit)?;
+    pd.set_item("slippage_exit",   pos.slippage_exit)?;
+    pd.set_item("entry_liquidity", pos.entry_liquidity.name())?;
+    pd.set_item("exit_liquidity",  pos.exit_liquidity.map(|l| l.name()))?;
+    pd.set_item("absolute_return", pos.absolute_return)?;
+    pd.set_item("real_return",     pos.real_return)?;
+    pd.set_item("pnl",             pos.pnl)?;
+    pd.set_item("is_closed",       pos.is_closed)?;
+    let costs = CostBreakdown::of(pos);
+    pd.set_item("gross_pnl",       costs.gross_pnl)?;
+    pd.set_item("slippage_cost",   costs.slippage)?;
+    if pos.funding_cost != 0.0 {
+        pd.set_item("funding_cost", pos.funding_cost)?;
+    }
+    if pos.dividend_income != 0.0 {
+        pd.set_item("dividend_income", pos.dividend_income)?;
+    }
+    if pos.roll_count > 0 {
+        pd.set_item("roll_count", pos.roll_count)?;
+        pd.set_item("roll_cost",  pos.roll_cost)?;
+    }
+    if pos.contract == ContractType::Inverse {
+        // initial margin at 1× leverage, in the base coin
+        pd.set_item("margin", pos.contract.notional(pos.entry_price, pos.position_size))?;
+    }
+    if let Some(id) = &pos.strategy_id {
+        pd.set_item("strategy_id", id)?;
+    }
+    if let Some(symbol) = &pos.symbol {
+        pd.set_item("symbol", symbol)?;
+    }
+    if let Some(tag) = &pos.tag {
+        pd.set_item("tag", tag)?;
+    }
+    if !pos.fills.is_empty() {
+        let fills = PyList::empty(py);
+        for fill in &pos.fills {
+            let fd = PyDict::new(py);
+            fd.set_item("bar",   fill.bar)?;
+            fd.set_item("size",  fill.size)?;
+            fd.set_item("price", fill.price)?;
+            fd.set_item("fee",   fill.fee)?;
+            fills.append(fd)?;
+        }
+        pd.set_item("fills",         fills)?;
+        pd.set_item("unfilled_size", pos.unfilled_size)?;
+    }
+    if let Some(sl) = pos.original_sl {
+        pd.set_item("original_sl", sl)?;
+    }
+    if let Some(size) = pos.original_size {
+        pd.set_item("original_size", size)?;
+    }
+    if pos.throttled {
+        pd.set_item("throttled", true)?;
+    }
+    if pos.tp_attempts > 0 {
+        pd.set_item("tp_attempts",   pos.tp_attempts)?;
+        pd.set_item("tp_rejections", pos.tp_rejections)?;
+    }
+    if pos.entry_delay.is_some() {
+        pd.set_item("entry_delay",  pos.entry_delay)?;
+        pd.set_item("exit_delay",   pos.exit_delay)?;
+        pd.set_item("latency_cost", pos.latency_cost)?;
+    }
+    Ok(pd)
+}
+
+/// Still‐open trade → dict
+pub fn open_position_to_py<'py>(py: Python<'py>, pos: &Position, mark: Option<&OpenMark>) -> PyResult<&'py PyDict> {
+    let pd = PyDict::new(py);
+    pd.set_item("position_id",     pos.position_id)?;
+    pd.set_item("entry_time",      pos.entry_time)?;
+    pd.set_item("position_type",   &pos.position_type)?;
+    pd.set_item("entry_index",     pos.entry_index)?;
+    pd.set_item("entry_price",     pos.entry_price)?;
+    pd.set_item("tp",              pos.tp)?;
+    pd.set_item("sl",              pos.sl)?;
+    pd.set_item("expiration_time", pos.expiration_time)?;
+    pd.set_item("position_size",   pos.position_size)?;
+    pd.set_item("fee_entry",       pos.fee_entry)?;
+    pd.set_item("slippage_entry",  pos.slippage_entry)?;
+    pd.set_item("entry_liquidity", pos.entry_liquidity.name())?;
+    pd.set_item("is_closed",       pos.is_closed)?;
+    if pos.expiry_action.is_some() {
+        pd.set_item("expiry_action", &pos.expiry_action)?;
+    }
+    set_liquidation(pd, pos)?;
+    if let Some(m) = mark {
+        pd.set_item("mark_price",        m.mark_price)?;
+        pd.set_item("unrealized_pnl",    m.unrealized_pnl)?;
+        pd.set_item("unrealized_return", m.unrealized_return)?;
+        pd.set_item("bars_held",         m.bars_held)?;
+    }
+    if pos.roll_count > 0 {
+        pd.set_item("roll_count", pos.roll_count)?;
+        pd.set_item("roll_cost",  pos.roll_cost)?;
+    }
+    if pos.contract == ContractType::Inverse {
+        // initial margin at 1× leverage, in the base coin
+        pd.set_item("margin", pos.contract.notional(pos.entry_price, pos.position_size))?;
+    }
+    if let Some(id) = &pos.strategy_id {
+        pd.set_item("strategy_id", id)?;
+    }
+    if let Some(symbol) = &pos.symbol {
+        pd.set_item("symbol", symbol)?;
+    }
+    if let Some(tag) = &pos.tag {
+        pd.set_item("tag", tag)?;
+    }
+    if !pos.fills.is_empty() {
+        let fills = PyList::empty(py);
+        for fill in &pos.fills {
+            let fd = PyDict::new(py);
+            fd.set_item("bar",   fill.bar)?;
+            fd.set_item("size",  fill.size)?;
+            fd.set_item("price", fill.price)?;
+            fd.set_item("fee",   fill.fee)?;
+            fills.append(fd)?;
+        }
+        pd.set_item("fills",         fills)?;
+        pd.set_item("unfilled_size", pos.unfilled_size)?;
+    }
+    if let Some(sl) = pos.original_sl {
+        pd.set_item("original_sl", sl)?;
+    }
+    if let Some(size) = pos.original_size {
+        pd.set_item("original_size", size)?;
+    }
+    if pos.throttled {
+        pd.set_item("throttled", true)?;
+    }
+    if pos.tp_attempts > 0 {
+        pd.set_item("tp_attempts",   pos.tp_attempts)?;
+        pd.set_item("tp_rejections", pos.tp_rejections)?;
+    }
+    if pos.entry_delay.is_some() {
+        pd.set_item("entry_delay",  pos.entry_delay)?;
+        pd.set_item("exit_delay",   pos.exit_delay)?;
+        pd.set_item("latency_cost", pos.latency_cost)?;
+    }
+    Ok(pd)
+}
+
+/// One exposure snapshot → dict
+pub fn snapshot_to_py<'py>(py: Python<'py>, snap: &ExposureSnapshot) -> PyResult<&'py PyDict> {
+    let pd = PyDict::new(py);
+    pd.set_item("timestamp",       snap.timestamp)?;
+    pd.set_item("long_exposure",   snap.long_exposure)?;
+    pd.set_item("short_exposure",  snap.short_exposure)?;
+    pd.set_item("total_exposure",  snap.total_exposure)?;
+    pd.set_item("realized_equity", snap.realized_equity)?;
+    pd.set_item("floating_pnl",    snap.floating_pnl)?;
+    pd.set_item("total_equity",    snap.total_equity)?;
+    pd.set_item("open_longs",      snap.open_longs)?;
+    pd.set_item("open_shorts",     snap.open_shorts)?;
+    pd.set_item("avg_open_age",    snap.avg_open_age)?;
+    pd.set_item("cash",            snap.cash)?;
+    pd.set_item("margin_used",     snap.margin_used)?;
+    pd.set_item("free_margin",     snap.free_margin)?;
+    pd.set_item("long_notional",   snap.long_notional)?;
+    pd.set_item("short_notional",  snap.short_notional)?;
+    pd.set_item("total_notional",  snap.total_notional)?;
+    pd.set_item("long_pct_of_equity",  snap.long_pct_of_equity)?;
+    pd.set_item("short_pct_of_equity", snap.short_pct_of_equity)?;
+    pd.set_item("total_pct_of_equity", snap.total_pct_of_equity)?;
+    pd.set_item("long_avg_entry",  snap.long_avg_entry)?;
+    pd.set_item("short_avg_entry", snap.short_avg_entry)?;
+    Ok(pd)
+}
+
+/// Sampled distribution + CI → dict
+pub fn distribution_to_py<'py>(py: Python<'py>, d: &Distribution) -> PyResult<&'py PyDict> {
+    let out = PyDict::new(py);
+    out.set_item("values",   d.values.clone())?;
+    out.set_item("mean",     d.mean)?;
+    out.set_item("median",   d.median)?;
+    out.set_item("ci_lower", d.ci_lower)?;
+    out.set_item("ci_upper", d.ci_upper)?;
+    Ok(out)
+}
+
+pub fn bootstrap_to_py<'py>(py: Python<'py>, b: &BootstrapIntervals) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("n_samples",    b.n_samples)?;
+    d.set_item("block_length", b.block_length)?;
+    d.set_item("seed",         b.seed)?;
+    d.set_item("confidence",   b.confidence)?;
+    d.set_item("sharpe_ratio", distribution_to_py(py, &b.sharpe_ratio)?)?;
+    d.set_item("cagr",         distribution_to_py(py, &b.cagr)?)?;
+    d.set_item("max_drawdown", distribution_to_py(py, &b.max_drawdown)?)?;
+    Ok(d)
+}
+
+/// A ratio metric; undefined values become `None` under `RatioPolicy::Null`
+pub(crate) fn ratio_to_py(py: Python<'_>, v: f64, policy: RatioPolicy) -> PyObject {
+    if policy == RatioPolicy::Null && v.is_nan() { py.None() } else { v.into_py(py) }
+}
+
+pub fn trade_metrics_to_py<'py>(py: Python<'py>, tm: &SideTradeMetrics, policy: RatioPolicy) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("number_of_trades",     tm.number_of_trades)?;
+    d.set_item("win_rate",             tm.win_rate)?;
+    d.set_item("loss_rate",            tm.loss_rate)?;
+    d.set_item("average_trade_return", tm.average_trade_return)?;
+    d.set_item("average_trade_pnl",    tm.average_trade_pnl)?;
+    d.set_item("profit_factor",        ratio_to_py(py, tm.profit_factor, policy))?;
+    d.set_item("expectancy",           tm.expectancy)?;
+    d.set_item("average_duration",     tm.average_duration)?;
+    d.set_item("average_entry_efficiency", tm.average_entry_efficiency)?;
+    d.set_item("average_exit_efficiency",  tm.average_exit_efficiency)?;
+    d.set_item("average_total_efficiency", tm.average_total_efficiency)?;
+    d.set_item("average_max_runup",    tm.average_max_runup)?;
+    d.set_item("average_giveback",     tm.average_giveback)?;
+    d.set_item("profit_to_loss_rate",  tm.profit_to_loss_rate)?;
+    d.set_item("trade_returns", PyList::new(py, &tm.trade_returns))?;
+    d.set_item("trade_pnls",    PyList::new(py, &tm.trade_pnls))?;
+    d.set_item("durations",     PyList::new(py, &tm.durations))?;
+    Ok(d)
+}
+
+pub fn time_metrics_to_py<'py>(py: Python<'py>, tsm: &TimeSeriesMetrics, policy: RatioPolicy) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("returns",           PyList::new(py, &tsm.returns))?;
+    d.set_item("mean_return",       tsm.mean_return)?;
+    d.set_item("volatility",        tsm.volatility)?;
+    d.set_item("sharpe_ratio",      ratio_to_py(py, tsm.sharpe_ratio, policy))?;
+    d.set_item("cumulative_return", tsm.cumulative_return)?;
+    d.set_item("max_drawdown",      tsm.max_drawdown)?;
+    d.set_item("skewness",          tsm.skewness)?;
+    d.set_item("kurtosis",          tsm.kurtosis)?;
+    d.set_item("probabilistic_sharpe", tsm.probabilistic_sharpe)?;
+    if let Some(b) = &tsm.bootstrap {
+        d.set_item("bootstrap", bootstrap_to_py(py, b)?)?;
+    }
+    let ac = PyDict::new(py);
+    for (lag, rho) in &tsm.autocorrelation {
+        ac.set_item(lag, rho)?;
+    }
+    d.set_item("autocorrelation",   ac)?;
+    let rt = PyDict::new(py);
+    rt.set_item("runs",          tsm.runs_test.runs)?;
+    rt.set_item("expected_runs", tsm.runs_test.expected_runs)?;
+    rt.set_item("z_score",       tsm.runs_test.z_score)?;
+    rt.set_item("p_value",       tsm.runs_test.p_value)?;
+    d.set_item("runs_test",         rt)?;
+    let hw = PyDict::new(py);
+    hw.set_item("new_highs",                  tsm.high_water.new_highs)?;
+    hw.set_item("average_bars_between_highs", tsm.high_water.average_bars_between_highs)?;
+    hw.set_item("longest_without_high",       tsm.high_water.longest_without_high)?;
+    hw.set_item("bars_since_high",            tsm.high_water.bars_since_high)?;
+    d.set_item("high_water",        hw)?;
+    Ok(d)
+}
+
+/// Headline metrics, or their difference `h − base` when `base` is given
+fn headline_to_py<'py>(
+    py: Python<'py>,
+    h: &HeadlineMetrics,
+    base: Option<&HeadlineMetrics>,
+    policy: RatioPolicy,
+) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    let rows = |m: &HeadlineMetrics| [
+        ("total_pnl",     m.total_pnl),
+        ("total_return",  m.total_return),
+        ("sharpe_ratio",  m.sharpe_ratio),
+        ("profit_factor", m.profit_factor),
+        ("win_rate",      m.win_rate),
+        ("max_drawdown",  m.max_drawdown),
+    ];
+    match base {
+        Some(b) => {
+            for ((k, v), (_, bv)) in rows(h).into_iter().zip(rows(b)) {
+                d.set_item(k, ratio_to_py(py, v - bv, policy))?;
+            }
+        }
+        None => {
+            for (k, v) in rows(h) {
+                d.set_item(k, ratio_to_py(py, v, policy))?;
+            }
+        }
+    }
+    Ok(d)
+}
+
+pub fn costs_to_py<'py>(py: Python<'py>, c: &CostBreakdown) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("gross_pnl", c.gross_pnl)?;
+    d.set_item("fees",      c.fees)?;
+    d.set_item("rebates",   c.rebates)?;
+    d.set_item("slippage",  c.slippage)?;
+    d.set_item("funding",   c.funding)?;
+    d.set_item("rolls",     c.rolls)?;
+    d.set_item("latency",   c.latency)?;
+    d.set_item("dividends", c.dividends)?;
+    d.set_item("net_pnl",   c.net_pnl)?;
+    Ok(d)
+}
+
+pub fn side_metrics_to_py<'py>(py: Python<'py>, sm: &SideMetrics, policy: RatioPolicy) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("total_return",  sm.total_return)?;
+    d.set_item("total_pnl",     sm.total_pnl)?;
+    d.set_item("costs",         costs_to_py(py, &sm.costs)?)?;
+    d.set_item("trade_metrics", trade_metrics_to_py(py, &sm.trade_metrics, policy)?)?;
+    d.set_item("time_metrics",  time_metrics_to_py(py, &sm.time_metrics, policy)?)?;
+    Ok(d)
+}
+
+pub fn summary_metrics_to_py<'py>(py: Python<'py>, summary: &SummaryMetrics) -> PyResult<&'py PyDict> {
+    let pm = PyDict::new(py);
+    let policy = summary.ratio_policy;
+    pm.set_item("overall", side_metrics_to_py(py, &summary.overall, policy)?)?;
+    pm.set_item("long",    side_metrics_to_py(py, &summary.longs, policy)?)?;
+    pm.set_item("short",   side_metrics_to_py(py, &summary.shorts, policy)?)?;
+    let rd = PyDict::new(py);
+    rd.set_item("equity_basis",  summary.returns.basis.name())?;
+    rd.set_item("return_type",   summary.returns.kind.name())?;
+    rd.set_item("return_equity", summary.returns.equity.name())?;
+    pm.set_item("return_definition", rd)?;
+    if !summary.by_tag.is_empty() {
+        let bt = PyDict::new(py);
+        for (tag, tm) in &summary.by_tag {
+            let d = trade_metrics_to_py(py, tm, policy)?;
+            d.set_item("total_pnl", tm.trade_pnls.iter().sum::<f64>())?;
+            bt.set_item(tag, d)?;
+        }
+        pm.set_item("by_tag", bt)?;
+    }
+    if let Some(gross) = &summary.gross {
+        let net = HeadlineMetrics::of(&summary.overall);
+        let gn = PyDict::new(py);
+        gn.set_item("gross",     headline_to_py(py, gross, None, policy)?)?;
+        gn.set_item("net",       headline_to_py(py, &net, None, policy)?)?;
+        gn.set_item("cost_drag", headline_to_py(py, &net, Some(gross), policy)?)?;
+        pm.set_item("gross_vs_net", gn)?;
+    }
+    if !summary.by_regime.is_empty() {
+        let br = PyDict::new(py);
+        for rm in &summary.by_regime {
+            let d = PyDict::new(py);
+            d.set_item("bars",         rm.bars)?;
+            d.set_item("bar_share",    rm.bar_share)?;
+            d.set_item("pnl",          rm.pnl)?;
+            d.set_item("mean_return",  rm.mean_return)?;
+            d.set_item("volatility",   rm.volatility)?;
+            d.set_item("sharpe_ratio", ratio_to_py(py, rm.sharpe_ratio, policy))?;
+            d.set_item("trades",       trade_metrics_to_py(py, &rm.trades, policy)?)?;
+            br.set_item(&rm.label, d)?;
+        }
+        pm.set_item("by_regime", br)?;
+    }
+    if let Some(b) = &summary.benchmark {
+        let d = PyDict::new(py);
+        d.set_item("benchmark_return",      b.benchmark_return)?;
+        d.set_item("strategy_return",       b.strategy_return)?;
+        d.set_item("excess_return",         b.excess_return)?;
+        d.set_item("up_capture",            ratio_to_py(py, b.up_capture, policy))?;
+        d.set_item("down_capture",          ratio_to_py(py, b.down_capture, policy))?;
+        d.set_item("up_bars",               b.up_bars)?;
+        d.set_item("down_bars",             b.down_bars)?;
+        d.set_item("max_relative_drawdown", b.max_relative_drawdown)?;
+        d.set_item("max_underwater_gap",    b.max_underwater_gap)?;
+        pm.set_item("benchmark", d)?;
+    }
+    if !summary.factor_betas.is_empty() {
+        let fb = PyDict::new(py);
+        for f in &summary.factor_betas {
+            let d = PyDict::new(py);
+            d.set_item("beta",         f.beta)?;
+            d.set_item("rolling_beta", PyArray1::from_slice(py, &f.rolling))?;
+            fb.set_item(&f.name, d)?;
+        }
+        pm.set_item("factor_betas", fb)?;
+    }
+    if !summary.by_duration.is_empty() {
+        let bd = PyList::empty(py);
+        for b in &summary.by_duration {
+            let d = PyDict::new(py);
+            d.set_item("label",            &b.label)?;
+            d.set_item("min_secs",         b.min_secs)?;
+            d.set_item("max_secs",         b.max_secs)?;
+            d.set_item("number_of_trades", b.number_of_trades)?;
+            d.set_item("win_rate",         b.win_rate)?;
+            d.set_item("average_pnl",      b.average_pnl)?;
+            d.set_item("average_r",        b.average_r)?;
+            d.set_item("r_trades",         b.r_trades)?;
+            bd.append(d)?;
+        }
+        pm.set_item("by_duration", bd)?;
+    }
+    if let Some(c) = &summary.concurrency {
+        pm.set_item("concurrency", concurrency_to_py(py, c)?)?;
+    }
+    if let Some(w) = &summary.weighted {
+        let d = PyDict::new(py);
+        d.set_item("time_weighted_return",            w.time_weighted)?;
+        d.set_item("time_weighted_return_annualized", w.time_weighted_annualized)?;
+        d.set_item("money_weighted_return",           w.money_weighted)?;
+        pm.set_item("weighted_returns", d)?;
+    }
+    if let Some(f) = &summary.cash_flows {
+        let d = PyDict::new(py);
+        d.set_item("deposits",            f.deposits)?;
+        d.set_item("withdrawals",         f.withdrawals)?;
+        d.set_item("net_flows",           f.net_flows)?;
+        d.set_item("final_account_value", f.final_account_value)?;
+        d.set_item("trading_pnl",         f.trading_pnl)?;
+        pm.set_item("cash_flows", d)?;
+    }
+    let e = &summary.execution;
+    let d = PyDict::new(py);
+    d.set_item("maker_fills", e.maker_fills)?;
+    d.set_item("taker_fills", e.taker_fills)?;
+    d.set_item("maker_share", e.maker_share)?;
+    d.set_item("maker_fees",  e.maker_fees)?;
+    d.set_item("taker_fees",  e.taker_fees)?;
+    pm.set_item("execution", d)?;
+    Ok(pm)
+}
+
+fn concurrency_to_py<'py>(py: Python<'py>, c: &ConcurrencyStats) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("max_concurrent",  c.max_concurrent)?;
+    d.set_item("mean_concurrent", c.mean_concurrent)?;
+    let dist = PyDict::new(py);
+    for (count, bars) in &c.distribution {
+        dist.set_item(count, bars)?;
+    }
+    d.set_item("distribution",    dist)?;
+    d.set_item("threshold",       c.threshold)?;
+    d.set_item("pnl_above_threshold",       c.pnl_above_threshold)?;
+    d.set_item("pnl_share_above_threshold", c.pnl_share_above_threshold)?;
+    d.set_item("bar_share_above_threshold", c.bar_share_above_threshold)?;
+    Ok(d)
+}
+
+/// Full result → the dict returned by `run_backtest`
+pub fn result_to_py<'py>(py: Python<'py>, result: &BacktestResult) -> PyResult<&'py PyDict> {
+    let out = PyDict::new(py);
+    out.set_item("schema_version", SCHEMA_VERSION)?;
+
+    let py_closed = PyList::empty(py);
+    for pos in &result.closed {
+        py_closed.append(closed_position_to_py(py, pos)?)?;
+    }
+    out.set_item("closed_positions", py_closed)?;
+
+    let py_open = PyList::empty(py);
+    for (i, pos) in result.open.iter().enumerate() {
+        py_open.append(open_position_to_py(py, pos, result.open_marks.get(i))?)?;
+    }
+    out.set_item("open_positions", py_open)?;
+
+    let py_expo = PyList::empty(py);
+    for snap in &result.exposure {
+        let d = snapshot_to_py(py, snap)?;
+        if result.equity_base != 0.0 {
+            d.set_item("total_equity", result.equity_base + snap.total_equity)?;
+        }
+        py_expo.append(d)?;
+    }
+    out.set_item("exposure_time_series", py_expo)?;
+
+    // the same curve as flat arrays, aligned with the bars
+    let timestamps: Vec<f64> = result.exposure.iter().map(|s| s.timestamp).collect();
+    let mut returns = vec![0.0];
+    returns.extend(bar_returns(&result.equity));
+    out.set_item("timestamps",  PyArray1::from_vec(py, timestamps))?;
+    out.set_item("equity",      PyArray1::from_slice(py, &result.equity))?;
+    out.set_item("drawdown",    PyArray1::from_vec(py, underwater(&result.equity)))?;
+    out.set_item("bar_returns", PyArray1::from_vec(py, returns))?;
+    if let Some(account) = &result.account_value {
+        out.set_item("account_value", PyArray1::from_slice(py, account))?;
+    }
+
+    out.set_item("warnings", PyList::new(py, &result.warnings))?;
+    if let Some(violations) = &result.invariant_violations {
+        out.set_item("invariant_violations", PyList::new(py, violations))?;
+    }
+
+    let policy = result.metrics.ratio_policy;
+    let py_resampled = PyDict::new(py);
+    for curve in &result.resampled {
+        let d = PyDict::new(py);
+        d.set_item("timestamps",            PyList::new(py, &curve.timestamps))?;
+        d.set_item("equity",                PyList::new(py, &curve.equity))?;
+        d.set_item("returns",               PyList::new(py, &curve.returns))?;
+        d.set_item("periods_per_year",      curve.periods_per_year)?;
+        d.set_item("annualized_return",     curve.annualized_return)?;
+        d.set_item("annualized_volatility", curve.annualized_volatility)?;
+        d.set_item("sharpe_ratio",          ratio_to_py(py, curve.sharpe_ratio, policy))?;
+        py_resampled.set_item(curve.frequency.name(), d)?;
+    }
+    out.set_item("resampled", py_resampled)?;
+
+    let py_trade_eq = PyList::empty(py);
+    for pt in &result.trade_equity {
+        let d = PyDict::new(py);
+        d.set_item("exit_index",  pt.exit_index)?;
+        d.set_item("timestamp",   pt.timestamp)?;
+        d.set_item("position_id", pt.position_id)?;
+        d.set_item("pnl",         pt.pnl)?;
+        d.set_item("equity",      pt.equity)?;
+        py_trade_eq.append(d)?;
+    }
+    out.set_item("trade_equity_curve", py_trade_eq)?;
+
+    out.set_item("metrics", summary_metrics_to_py(py, &result.metrics)?)?;
+
+    if !result.skipped.is_empty() {
+        let py_skipped = PyList::empty(py);
+        for s in &result.skipped {
+            py_skipped.append(skipped_entry_to_py(py, s)?)?;
+        }
+        out.set_item("skipped_entries", py_skipped)?;
+    }
+    Ok(out)
+}
+
+/// Convert a declined entry → Python dict
+pub fn skipped_entry_to_py<'py>(py: Python<'py>, s: &SkippedEntry) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("signal_index",  s.signal_index)?;
+    d.set_item("entry_index",   s.entry_index)?;
+    d.set_item("position_type", &s.position_type)?;
+    d.set_item("reason",        s.reason)?;
+    Ok(d)
+}
+
+/// Reproducibility manifest → Python dict (`params` as recorded by the caller)
+pub fn run_info_to_py<'py>(py: Python<'py>, info: &RunInfo, params: &PyDict) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("version", VERSION)?;
+    d.set_item("params",  params)?;
+    let inputs = PyDict::new(py);
+    for input in &info.inputs {
+        let id = PyDict::new(py);
+        id.set_item("length", input.length)?;
+        id.set_item("hash",   format!("{:016x}", input.hash))?;
+        inputs.set_item(&input.name, id)?;
+    }
+    d.set_item("inputs",  inputs)?;
+    d.set_item("threads", info.threads)?;
+    let seeds = PyDict::new(py);
+    for (name, seed) in &info.seeds {
+        seeds.set_item(name, seed)?;
+    }
+    d.set_item("seeds",   seeds)?;
+    Ok(d)
+}
+
+/// Convert a trailing‐drawdown breach → Python dict
+pub fn breach_to_py<'py>(py: Python<'py>, b: &DrawdownBreach) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("bar",             b.bar)?;
+    d.set_item("timestamp",       b.timestamp)?;
+    d.set_item("equity",          b.equity)?;
+    d.set_item("high_water_mark", b.high_water_mark)?;
+    Ok(d)
+}
+
+/// Broker‐style order log → list of Python dicts
+pub fn orders_to_py<'py>(py: Python<'py>, orders: &[Order]) -> PyResult<&'py PyList> {
+    let out = PyList::empty(py);
+    for o in orders {
+        let d = PyDict::new(py);
+        d.set_item("order_id",    o.order_id)?;
+        d.set_item("position_id", o.position_id)?;
+        d.set_item("timestamp",   o.timestamp)?;
+        d.set_item("side",        o.side)?;
+        d.set_item("order_type",  o.order_type)?;
+        d.set_item("price",       o.price)?;
+        d.set_item("limit_price", o.limit_price)?;
+        d.set_item("amendments",  o.amendments)?;
+        d.set_item("size",        o.size)?;
+        d.set_item("reduce_only", o.reduce_only)?;
+        d.set_item("purpose",     o.purpose)?;
+        d.set_item("status",      o.status)?;
+        d.set_item("update_time", o.update_time)?;
+        d.set_item("fill_price",  o.fill_price)?;
+        out.append(d)?;
+    }
+    Ok(out)
+}
+
+/// Per‐position lifecycle events (`timeline`), nested by position id or as
+/// one flat table
+pub fn timeline_to_py(py: Python<'_>, positions: &[&Position], timestamps: &[f64], layout: TimelineLayout) -> PyResult<PyObject> {
+    let nested = PyDict::new(py);
+    let flat = PyList::empty(py);
+    for pos in positions {
+        let events = PyList::empty(py);
+        for e in position_events(pos, timestamps) {
+            let d = PyDict::new(py);
+            if layout == TimelineLayout::Flat {
+                d.set_item("position_id", e.position_id)?;
+            }
+            d.set_item("event",     e.kind)?;
+            d.set_item("bar",       e.bar)?;
+            d.set_item("timestamp", e.timestamp)?;
+            d.set_item("price",     e.price)?;
+            d.set_item("size",      e.size)?;
+            d.set_item("tp",        e.tp)?;
+            d.set_item("sl",        e.sl)?;
+            d.set_item("condition", e.condition)?;
+            match layout {
+                TimelineLayout::Nested => events.append(d)?,
+                TimelineLayout::Flat   => flat.append(d)?,
+            }
+        }
+        if layout == TimelineLayout::Nested {
+            nested.set_item(pos.position_id, events)?;
+        }
+    }
+    Ok(match layout {
+        TimelineLayout::Nested => nested.into(),
+        TimelineLayout::Flat   => flat.into(),
+    })
+}
@@ -0,0 +1,446 @@
+// src/engine/pairs.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    array_to_vec, signals_from_py,
+    backtest::{validate_signals, SignalSet},
+    contract::ContractType,
+    exposure::{compute_exposure_series, single_average, ExposureSnapshot},
+    liquidity::Liquidity,
+    metrics::{compute_summary_metrics, SummaryMetrics},
+    output::{snapshot_to_py, summary_metrics_to_py},
+    position::Position,
+    prepare_inputs::prepare_inputs,
+};
+
+/// Two legs sharing one timeline; spread = a − hedge_ratio × b
+#[derive(Clone, Debug)]
+pub struct PairMarket {
+    pub timestamps:  Vec<f64>,
+    pub open_a:      Vec<f64>,
+    pub close_a:     Vec<f64>,
+    pub open_b:      Vec<f64>,
+    pub close_b:     Vec<f64>,
+    pub hedge_ratio: Vec<f64>,
+}
+
+/// One logical spread position (long spread = long A, short h×B)
+#[derive(Clone, Debug)]
+pub struct PairTrade {
+    /// Unique within a run, increasing in signal order
+    pub position_id:    u64,
+    pub entry_time:     f64,
+    /// "long" or "short" the spread
+    pub position_type:  String,
+    pub signal_index:   usize,
+    pub entry_index:    usize,
+    /// Hedge ratio frozen at the signal bar
+    pub hedge_ratio:    f64,
+    pub entry_price_a:  f64,
+    pub entry_price_b:  f64,
+    pub entry_spread:   f64,
+    /// Spread take‐profit / stop‐loss levels
+    pub tp:             f64,
+    pub sl:             f64,
+    pub expiration_time: Option<f64>,
+    pub exit_index:     Option<usize>,
+    pub exit_price_a:   Option<f64>,
+    pub exit_price_b:   Option<f64>,
+    pub exit_spread:    Option<f64>,
+    pub exit_condition: Option<String>,
+    /// Units of leg A (leg B trades hedge_ratio × this)
+    pub position_size:  f64,
+    pub fee_entry_a:    f64,
+    pub fee_entry_b:    f64,
+    pub fee_exit_a:     f64,
+    pub fee_exit_b:     f64,
+    pub pnl_a:          Option<f64>,
+    pub pnl_b:          Option<f64>,
+    pub pnl:            Option<f64>,
+    pub is_closed:      bool,
+}
+
+pub struct PairResult {
+    pub trades:   Vec<PairTrade>,
+    pub exposure: Vec<ExposureSnapshot>,
+    pub metrics:  SummaryMetrics,
+}
+
+pub fn validate_pair_market(m: &mut PairMarket) -> Result<usize, String> {
+    if !m.timestamps.windows(2).all(|w| w[1] > w[0]) {
+        return Err("timestamps must be strictly increasing".into());
+    }
+    let n = prepare_inputs(&mut [
+        &mut m.timestamps,
+        &mut m.open_a, &mut m.close_a,
+        &mut m.open_b, &mut m.close_b,
+        &mut m.hedge_ratio,
+    ])?;
+    if n == 0 {
+        return Err("input arrays must not be empty".into());
+    }
+    Ok(n)
+}
+
+/// Fill price after adverse slippage for a buy (`buy = true`) or sell
+fn slipped(price: f64, buy: bool, slippage_rate: f64) -> f64 {
+    if buy { price * (1.0 + slippage_rate) } else { price * (1.0 - slippage_rate) }
+}
+
+/// Entries at the next bar's open of both legs; exits checked on the
+/// close‐to‐close spread (leg highs/lows are not simultaneous, so the
+/// spread's intrabar range is unknown) with priority SL → TP → EXP.
+pub fn simulate_pairs(
+    m: &PairMarket,
+    signals: &SignalSet,
+    entry_fee_rate: f64,
+    exit_fee_rate: f64,
+    slippage_rate: f64,
+) -> Vec<PairTrade> {
+    let n = m.timestamps.len();
+    let mut trades: Vec<PairTrade> = Vec::new();
+
+    for i in 0..n {
+        let long = signals.long_signals[i];
+        if !(long || signals.short_signals[i]) {
+            continue;
+        }
+        let entry_idx = if i + 1 < n { i + 1 } else { i };
+        let h         = m.hedge_ratio[i];
+        let (tp, sl, size) = if long {
+            (signals.long_tp[i], signals.long_sl[i], signals.long_size[i])
+        } else {
+            (signals.short_tp[i], signals.short_sl[i], signals.short_size[i])
+        };
+        // long spread buys A / sells h·B; short spread the reverse (a
+        // negative hedge ratio flips leg B's side)
+        let pa = slipped(m.open_a[entry_idx], long, slippage_rate);
+        let pb = slipped(m.open_b[entry_idx], buys_b(long, h), slippage_rate);
+
+        trades.push(PairTrade {
+            position_id:     trades.len() as u64,
+            entry_time:      m.timestamps[entry_idx],
+            position_type:   if long { "long" } else { "short" }.into(),
+            signal_index:    i,
+            entry_index:     entry_idx,
+            hedge_ratio:     h,
+            entry_price_a:   pa,
+            entry_price_b:   pb,
+            entry_spread:    pa - h * pb,
+            tp,
+            sl,
+            expiration_time: Some(signals.expiration_times[i]),
+            exit_index:      None,
+            exit_price_a:    None,
+            exit_price_b:    None,
+            exit_spread:     None,
+            exit_condition:  None,
+            position_size:   size,
+            fee_entry_a:     size * pa * entry_fee_rate,
+            fee_entry_b:     (h * size).abs() * pb * entry_fee_rate,
+            fee_exit_a:      0.0,
+            fee_exit_b:      0.0,
+            pnl_a:           None,
+            pnl_b:           None,
+            pnl:             None,
+            is_closed:       false,
+        });
+    }
+
+    trades.par_iter_mut().for_each(|t| {
+        let long = t.position_type == "long";
+        for j in t.entry_index..n {
+            let spread  = m.close_a[j] - t.hedge_ratio * m.close_b[j];
+            let hit_sl  = if long { spread <= t.sl } else { spread >= t.sl };
+            let hit_tp  = if long { spread >= t.tp } else { spread <= t.tp };
+            let expired = t.expiration_time.is_some_and(|et| m.timestamps[j] >= et);
+            if !(hit_sl || hit_tp || expired) {
+                continue;
+            }
+
+            let xa = slipped(m.close_a[j], !long, slippage_rate);
+            let xb = slipped(m.close_b[j], !buys_b(long, t.hedge_ratio), slippage_rate);
+            let units_b = t.hedge_ratio * t.position_size;
+            let dir     = if long { 1.0 } else { -1.0 };
+
+            t.fee_exit_a = t.position_size * xa * exit_fee_rate;
+            t.fee_exit_b = units_b.abs() * xb * exit_fee_rate;
+            let pnl_a = dir * (xa - t.entry_price_a) * t.position_size - t.fee_entry_a - t.fee_exit_a;
+            let pnl_b = -dir * (xb - t.entry_price_b) * units_b - t.fee_entry_b - t.fee_exit_b;
+
+            t.exit_index     = Some(j);
+            t.exit_price_a   = Some(xa);
+            t.exit_price_b   = Some(xb);
+            t.exit_spread    = Some(xa - t.hedge_ratio * xb);
+            t.exit_condition = Some(
+                if hit_sl { "SL" } else if hit_tp { "TP" } else { "EXP" }.to_string(),
+            );
+            t.pnl_a     = Some(pnl_a);
+            t.pnl_b     = Some(pnl_b);
+            t.pnl       = Some(pnl_a + pnl_b);
+            t.is_closed = true;
+            break;
+        }
+    });
+
+    trades
+}
+
+/// Whether entering the spread buys leg B: the spread holds −h units of B
+/// per unit of A
+fn buys_b(long_spread: bool, hedge_ratio: f64) -> bool {
+    long_spread == (hedge_ratio < 0.0)
+}
+
+/// A single‐leg `Position` view of one side of a pair trade
+fn leg_position(t: &PairTrade, leg_a: bool) -> Position {
+    let long_spread = t.position_type == "long";
+    let (is_long, entry, size, pnl, fee_entry, fee_exit, exit_price) = if leg_a {
+        (long_spread, t.entry_price_a, t.position_size, t.pnl_a,
+         t.fee_entry_a, t.fee_exit_a, t.exit_price_a)
+    } else {
+        let units = t.hedge_ratio * t.position_size;
+        (buys_b(long_spread, t.hedge_ratio), t.entry_price_b, units.abs(), t.pnl_b,
+         t.fee_entry_b, t.fee_exit_b, t.exit_price_b)
+    };
+    Position {
+        position_id:     t.position_id,
+        entry_time:      t.entry_time,
+        position_type:   if is_long { "long" } else { "short" }.into(),
+        signal_index:    t.signal_index,
+        entry_index:     t.entry_index,
+        entry_price:     entry,
+        tp:              t.tp,
+        sl:              t.sl,
+        expiration_time: t.expiration_time,
+        exit_index:      t.exit_index,
+        exit_time:       None,
+        expiry_slippage: None,
+        expiry_action:   None,
+        exit_price,
+        exit_condition:  t.exit_condition.clone(),
+        position_size:   size,
+        contract:        ContractType::Linear,
+        fee_entry,
+        fee_exit,
+        entry_liquidity: Liquidity::Taker,
+        exit_liquidity:  t.exit_index.map(|_| Liquidity::Taker),
+        slippage_entry:  0.0,
+        slippage_exit:   0.0,
+        absolute_return: None,
+        real_return:     None,
+        pnl,
+        is_closed:       t.is_closed,
+        roll_count:      0,
+        roll_cost:       0.0,
+        funding_cost:    0.0,
+        dividend_income: 0.0,
+        strategy_id:     None,
+        symbol:          None,
+        tag:             None,
+        entry_delay:     None,
+        exit_delay:      None,
+        latency_cost:    0.0,
+        tp_attempts:     0,
+        tp_rejections:   0,
+        throttled:       false,
+        entry_sl:        f64::NAN,
+        original_sl:     None,
+        original_size:   None,
+        fills:           Vec::new(),
+        unfilled_size:   0.0,
+        level_changes:   Vec::new(),
+        bracket:         None,
+        mfe:             0.0,
+        mae:             0.0,
+        liquidation_price: None,
+        near_liquidation: Vec::new(),
+        liquidation_bar: None,
+    }
+}
+
+/// Leg‐by‐leg exposure summed into one series
+fn combined_exposure(m: &PairMarket, trades: &[PairTrade], initial_equity: f64) -> Vec<ExposureSnapshot> {
+    let legs_a: Vec<Position> = trades.iter().map(|t| leg_position(t, true)).collect();
+    let legs_b: Vec<Position> = trades.iter().map(|t| leg_position(t, false)).collect();
+    let ea = compute_exposure_series(&legs_a, &m.close_a, &m.timestamps, initial_equity, 1.0);
+    let eb = compute_exposure_series(&legs_b, &m.close_b, &m.timestamps, initial_equity, 1.0);
+    ea.into_iter()
+        .zip(eb)
+        .map(|(a, b)| {
+            let mut snap = ExposureSnapshot {
+                timestamp:       a.timestamp,
+                long_exposure:   a.long_exposure + b.long_exposure,
+                short_exposure:  a.short_exposure + b.short_exposure,
+                total_exposure:  a.total_exposure + b.total_exposure,
+                realized_equity: a.realized_equity + b.realized_equity,
+                floating_pnl:    a.floating_pnl + b.floating_pnl,
+                total_equity:    a.total_equity + b.total_equity,
+                // one pair trade = one position; leg A carries the spread side
+                open_longs:      a.open_longs,
+                open_shorts:     a.open_shorts,
+                avg_open_age:    a.avg_open_age,
+                cash:            a.cash + b.realized_equity,
+                margin_used:     a.margin_used + b.margin_used,
+                free_margin:     a.cash + b.realized_equity + a.floating_pnl + b.floating_pnl
+                    - a.margin_used - b.margin_used,
+                long_notional:   a.long_notional + b.long_notional,
+                short_notional:  a.short_notional + b.short_notional,
+                total_notional:  a.total_notional + b.total_notional,
+                long_pct_of_equity:  0.0,
+                short_pct_of_equity: 0.0,
+                total_pct_of_equity: 0.0,
+                long_avg_entry:  single_average([a.long_avg_entry, b.long_avg_entry].into_iter()),
+                short_avg_entry: single_average([a.short_avg_entry, b.short_avg_entry].into_iter()),
+            };
+            snap.set_equity_shares(initial_equity);
+            snap
+        })
+        .collect()
+}
+
+/// Pair trade as one `Position` for trade metrics: returns are normalised by
+/// the gross notional of both legs
+fn metrics_position(t: &PairTrade) -> Position {
+    let mut p = leg_position(t, true);
+    p.position_type = t.position_type.clone();
+    p.entry_price   = t.entry_price_a + (t.hedge_ratio * t.entry_price_b).abs();
+    p.position_size = t.position_size;
+    p.pnl           = t.pnl;
+    p
+}
+
+pub fn run_pairs(
+    m: &PairMarket,
+    signals: &SignalSet,
+    entry_fee_rate: f64,
+    exit_fee_rate: f64,
+    slippage_rate: f64,
+    initial_equity: f64,
+) -> PairResult {
+    let trades   = simulate_pairs(m, signals, entry_fee_rate, exit_fee_rate, slippage_rate);
+    let exposure = combined_exposure(m, &trades, initial_equity);
+    let closed: Vec<Position> = trades
+        .iter()
+        .filter(|t| t.is_closed)
+        .map(metrics_position)
+        .collect();
+    let metrics = compute_summary_metrics(initial_equity, &closed, &exposure);
+    PairResult { trades, exposure, metrics }
+}
+
+fn pair_trade_to_py<'py>(py: Python<'py>, t: &PairTrade) -> PyResult<&'py PyDict> {
+    let d = PyDict::new(py);
+    d.set_item("position_id",     t.position_id)?;
+    d.set_item("entry_time",      t.entry_time)?;
+    d.set_item("position_type",   &t.position_type)?;
+    d.set_item("signal_index",    t.signal_index)?;
+    d.set_item("entry_index",     t.entry_index)?;
+    d.set_item("hedge_ratio",     t.hedge_ratio)?;
+    d.set_item("entry_price_a",   t.entry_price_a)?;
+    d.set_item("entry_price_b",   t.entry_price_b)?;
+    d.set_item("entry_spread",    t.entry_spread)?;
+    d.set_item("tp",              t.tp)?;
+    d.set_item("sl",              t.sl)?;
+    d.set_item("expiration_time", t.expiration_time)?;
+    d.set_item("exit_index",      t.exit_index)?;
+    d.set_item("exit_price_a",    t.exit_price_a)?;
+    d.set_item("exit_price_b",    t.exit_price_b)?;
+    d.set_item("exit_spread",     t.exit_spread)?;
+    d.set_item("exit_condition",  &t.exit_condition)?;
+    d.set_item("position_size",   t.position_size)?;
+    d.set_item("fee_entry_a",     t.fee_entry_a)?;
+    d.set_item("fee_entry_b",     t.fee_entry_b)?;
+    d.set_item("fee_exit_a",      t.fee_exit_a)?;
+    d.set_item("fee_exit_b",      t.fee_exit_b)?;
+    d.set_item("pnl_a",           t.pnl_a)?;
+    d.set_item("pnl_b",           t.pnl_b)?;
+    d.set_item("pnl",             t.pnl)?;
+    d.set_item("is_closed",       t.is_closed)?;
+    Ok(d)
+}
+
+/// Spread (pair) backtest.
+///
+/// A long signal buys `size` units of leg A and sells `hedge_ratio × size`
+/// of leg B at the next bar's opens; a short signal does the reverse.  TP/SL
+/// arrays are levels of the spread `A − hedge_ratio × B` and are checked on
+/// bar closes.  Fees and slippage apply to each leg separately.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open_a, close_a, open_b, close_b, hedge_ratio,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity
+))]
+pub fn run_pair_backtest(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open_a:           &PyArray1<f64>,
+    close_a:          &PyArray1<f64>,
+    open_b:           &PyArray1<f64>,
+    close_b:          &PyArray1<f64>,
+    hedge_ratio:      &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+) -> PyResult<PyObject> {
+    let mut market = PairMarket {
+        timestamps:  array_to_vec(timestamp)?,
+        open_a:      array_to_vec(open_a)?,
+        close_a:     array_to_vec(close_a)?,
+        open_b:      array_to_vec(open_b)?,
+        close_b:     array_to_vec(close_b)?,
+        hedge_ratio: array_to_vec(hedge_ratio)?,
+    };
+    validate_pair_market(&mut market).map_err(PyValueError::new_err)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+
+    let result = py.allow_threads(|| {
+        run_pairs(&market, &signals, entry_fee_rate, exit_fee_rate, slippage_rate, initial_equity)
+    });
+
+    let out = PyDict::new(py);
+    let py_closed = PyList::empty(py);
+    let py_open   = PyList::empty(py);
+    for t in &result.trades {
+        if t.is_closed {
+            py_closed.append(pair_trade_to_py(py, t)?)?;
+        } else {
+            py_open.append(pair_trade_to_py(py, t)?)?;
+        }
+    }
+    out.set_item("closed_positions", py_closed)?;
+    out.set_item("open_positions",   py_open)?;
+    let py_expo = PyList::empty(py);
+    for snap in &result.exposure {
+        py_expo.append(snapshot_to_py(py, snap)?)?;
+    }
+    out.set_item("exposure_time_series", py_expo)?;
+    out.set_item("metrics", summary_metrics_to_py(py, &result.metrics)?)?;
+    Ok(out.into())
+}
@@ -0,0 +1,332 @@
+// src/engine/paper.rs
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    backtest::{finish, BacktestConfig, BacktestResult, MarketData, SignalSet, SkippedEntry},
+    excursions::record_excursions,
+    exposure::{compute_exposure_series_with, mark_open_position, mark_open_positions},
+    output::{closed_position_to_py, open_position_to_py, result_to_py},
+    position::Position,
+    scan_entries::scan_entries,
+    simulate_exits::simulate_position_exits,
+    warnings::collect_warnings,
+};
+
+/// Live bar feed driven through the backtest engine.
+///
+/// Each `on_bar` appends one completed bar (and the signal decided on its
+/// close), enters the previous bar's signal at its open and advances the
+/// open positions, so positions and costs are exactly what `run_backtest`
+/// reports on the same bars.  The newest bar's signal stays pending until
+/// the next bar supplies its fill open.  Like a chunked run with one‐bar
+/// chunks, closed trades are final and only the positions still open are
+/// re‐simulated (from their entry, so a callback costs their holding time,
+/// not the history); `result()` rebuilds the curve and metrics over every
+/// bar on request.
+#[pyclass]
+pub struct PaperSession {
+    market:  MarketData,
+    signals: SignalSet,
+    config:  BacktestConfig,
+    /// Trades closed so far, in exit order
+    closed:  Vec<Position>,
+    /// Positions still open, simulated up to the last bar, and the entries
+    /// they are re‐simulated from
+    open:    Vec<Position>,
+    entries: Vec<Position>,
+    skipped: Vec<SkippedEntry>,
+    next_id: u64,
+}
+
+impl PaperSession {
+    /// Enter the signal of bar `t − 1` at the open of bar `t` and carry
+    /// every open position through bar `t`; returns the ids entered and
+    /// exited on it
+    fn advance(&mut self, t: usize) -> (Vec<u64>, Vec<u64>) {
+        let (m, s, c) = (&self.market, &self.signals, &self.config);
+        let mut fresh = scan_entries(
+            t - 1..t,
+            &m.timestamps,
+            &m.open,
+            &s.long_signals, &s.short_signals,
+            &s.long_tp, &s.long_sl,
+            &s.short_tp, &s.short_sl,
+            &s.long_size, &s.short_size,
+            &s.expiration_times,
+            c.entry_fee_rate,
+            c.slippage_rate,
+            c.contract,
+            c.size_mode,
+            c.latency,
+            c.loss_cap,
+            c.filters,
+            &mut self.skipped,
+        );
+        for pos in &mut fresh {
+            pos.position_id += self.next_id;
+        }
+        self.next_id += fresh.len() as u64;
+        let entered = fresh.iter().map(|p| p.position_id).collect();
+        self.entries.extend(fresh);
+
+        // an open position's path so far is unchanged by the new bar, so
+        // re‐simulating it only decides whether it exits there
+        let mut held = self.entries.clone();
+        simulate_position_exits(
+            &mut held,
+            &m.timestamps, &m.open, &m.high, &m.low, &m.close,
+            c.exit_fees(), c.exit_slippage(),
+            c.latency, c.fill_model, c.queue_model.as_ref(), c.filters, c.exit_rules.as_ref(),
+            c.sub_bars.as_ref(), c.expiry_fill, c.expiry_policy, c.exit_priority, c.level_check, c.stop_type,
+        );
+        record_excursions(&mut held, &m.high, &m.low);
+
+        let mut exited = Vec::new();
+        let entries = std::mem::take(&mut self.entries);
+        self.open.clear();
+        for (entry, pos) in entries.into_iter().zip(held) {
+            if pos.is_closed {
+                exited.push(pos.position_id);
+                self.closed.push(pos);
+            } else {
+                self.entries.push(entry);
+                self.open.push(pos);
+            }
+        }
+        (entered, exited)
+    }
+
+    /// Closed trades in position order, as `run_backtest` lists them
+    fn closed_by_id(&self) -> Vec<&Position> {
+        let mut closed: Vec<&Position> = self.closed.iter().collect();
+        closed.sort_by_key(|p| p.position_id);
+        closed
+    }
+
+    /// The `run_backtest` result over every bar so far
+    fn full_result(&self) -> BacktestResult {
+        let (m, c) = (&self.market, &self.config);
+        let mut positions: Vec<Position> = self.closed.iter().chain(&self.open).cloned().collect();
+        positions.sort_by_key(|p| p.position_id);
+        let exposure = compute_exposure_series_with(
+            &positions, &m.close, &m.timestamps, c.initial_equity, c.leverage(), c.exposure_options(),
+        );
+        let marks = mark_open_positions(&positions, &m.close);
+        let mut result = finish(m, positions, marks, exposure, c);
+        result.skipped = self.skipped.clone();
+        result.warnings = collect_warnings(&result, m, &self.signals, c);
+        result
+    }
+}
+
+#[pymethods]
+impl PaperSession {
+    #[new]
+    #[pyo3(signature=(entry_fee_rate, exit_fee_rate, slippage_rate, initial_equity))]
+    fn new(entry_fee_rate: f64, exit_fee_rate: f64, slippage_rate: f64, initial_equity: f64) -> Self {
+        PaperSession {
+            market: MarketData {
+                timestamps: Vec::new(),
+                open:       Vec::new(),
+                high:       Vec::new(),
+                low:        Vec::new(),
+                close:      Vec::new(),
+            },
+            signals: SignalSet {
+                long_signals:     Vec::new(),
+                short_signals:    Vec::new(),
+                long_tp:          Vec::new(),
+                long_sl:          Vec::new(),
+                short_tp:         Vec::new(),
+                short_sl:         Vec::new(),
+                long_size:        Vec::new(),
+                short_size:       Vec::new(),
+                expiration_times: Vec::new(),
+                tags:             None,
+                direction:        None,
+            },
+            config: BacktestConfig {
+                entry_fee_rate,
+                exit_fee_rate,
+                slippage_rate,
+                initial_equity,
+                ..BacktestConfig::default()
+            },
+            closed:  Vec::new(),
+            open:    Vec::new(),
+            entries: Vec::new(),
+            skipped: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Feed one completed bar and the signal decided on its close.
+    ///
+    /// Returns the events the bar produced, oldest first: `{"event":
+    /// "entry", ...open position}` and `{"event": "exit", ...closed
+    /// position}`.  `expiration_time=None` never expires.
+    #[pyo3(signature=(
+        timestamp, open, high, low, close,
+        long_signal=false, short_signal=false,
+        long_tp=f64::NAN, long_sl=f64::NAN, short_tp=f64::NAN, short_sl=f64::NAN,
+        long_size=0.0, short_size=0.0,
+        expiration_time=None
+    ))]
+    fn on_bar(
+        &mut self,
+        py:              Python<'_>,
+        timestamp:       f64,
+        open:            f64,
+        high:            f64,
+        low:             f64,
+        close:           f64,
+        long_signal:     bool,
+        short_signal:    bool,
+        long_tp:         f64,
+        long_sl:         f64,
+        short_tp:        f64,
+        short_sl:        f64,
+        long_size:       f64,
+        short_size:      f64,
+        expiration_time: Option<f64>,
+    ) -> PyResult<PyObject> {
+        if [timestamp, open, high, low, close].iter().any(|v| !v.is_finite()) {
+            return Err(PyValueError::new_err("bar values must be finite"));
+        }
+        if self.market.timestamps.last().is_some_and(|&t| timestamp <= t) {
+            return Err(PyValueError::new_err("timestamps must be strictly increasing"));
+        }
+        if long_signal && short_signal {
+            return Err(PyValueError::new_err("both long and short signals true"));
+        }
+        let expiration_time = expiration_time.unwrap_or(f64::INFINITY);
+        if expiration_time < timestamp {
+            return Err(PyValueError::new_err(format!(
+                "expiration_time {} < timestamp {}", expiration_time, timestamp
+            )));
+        }
+
+        self.market.timestamps.push(timestamp);
+        self.market.open.push(open);
+        self.market.high.push(high);
+        self.market.low.push(low);
+        self.market.close.push(close);
+        let s = &mut self.signals;
+        s.long_signals.push(long_signal);
+        s.short_signals.push(short_signal);
+        s.long_tp.push(long_tp);
+        s.long_sl.push(long_sl);
+        s.short_tp.push(short_tp);
+        s.short_sl.push(short_sl);
+        s.long_size.push(long_size);
+        s.short_size.push(short_size);
+        s.expiration_times.push(expiration_time);
+
+        let t = self.market.timestamps.len() - 1;
+        if t == 0 {
+            return Ok(PyList::empty(py).into());
+        }
+        let (entered, exited) = py.allow_threads(|| self.advance(t));
+
+        // entries before exits, each in position order
+        let events = PyList::empty(py);
+        let fresh = entered.iter().map(|&id| (id, "entry")).chain(exited.iter().map(|&id| (id, "exit")));
+        for (id, kind) in fresh {
+            let d = match self.open.iter().find(|p| p.position_id == id) {
+                Some(pos) => open_position_to_py(py, pos, Some(&mark_open_position(pos, &self.market.close)))?,
+                None      => {
+                    let pos = self.closed.iter().rev().find(|p| p.position_id == id).unwrap();
+                    closed_position_to_py(py, pos)?
+                }
+            };
+            d.set_item("event", kind)?;
+            events.append(d)?;
+        }
+        Ok(events.into())
+    }
+
+    /// Number of bars fed so far
+    #[getter]
+    fn bars(&self) -> usize {
+        self.market.timestamps.len()
+    }
+
+    /// True when the last bar's signal is waiting for the next bar's open
+    #[getter]
+    fn pending_entry(&self) -> bool {
+        let n = self.signals.long_signals.len();
+        n > 0 && (self.signals.long_signals[n - 1] || self.signals.short_signals[n - 1])
+    }
+
+    /// Current positions, costs and metrics in the `run_backtest` layout
+    /// (None before the first bar); rebuilt over every bar on each call
+    fn result(&self, py: Python<'_>) -> PyResult<PyObject> {
+        if self.market.timestamps.is_empty() {
+            Ok(py.None())
+        } else {
+            let result = py.allow_threads(|| self.full_result());
+            Ok(result_to_py(py, &result)?.into())
+        }
+    }
+
+    /// Positions still open after the last bar, marked at its close
+    fn open_positions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let out = PyList::empty(py);
+        for pos in &self.open {
+            out.append(open_position_to_py(py, pos, Some(&mark_open_position(pos, &self.market.close)))?)?;
+        }
+        Ok(out.into())
+    }
+
+    /// Closed positions so far
+    fn closed_positions(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let out = PyList::empty(py);
+        for pos in self.closed_by_id() {
+            out.append(closed_position_to_py(py, pos)?)?;
+        }
+        Ok(out.into())
+    }
+
+    /// Compare this session's closed trades with another run's (e.g. live
+    /// fills or a `run_backtest` result): the first mismatching trade, or
+    /// None when every shared trade agrees on entry/exit index and prices
+    /// within `tolerance`
+    #[pyo3(signature=(closed_positions, tolerance=1e-9))]
+    fn parity(&self, py: Python<'_>, closed_positions: &PyList, tolerance: f64) -> PyResult<PyObject> {
+        let ours = self.closed_by_id();
+        for (i, (pos, other)) in ours.iter().zip(closed_positions.iter()).enumerate() {
+            let other: &PyDict = other.downcast()?;
+            let get = |key: &str| -> PyResult<f64> {
+                other.get_item(key)
+                    .ok_or_else(|| PyValueError::new_err(format!("closed position {} has no ‘{}’", i, key)))?
+                    .extract()
+            };
+            let checks = [
+                ("entry_index", pos.entry_index as f64, get("entry_index")?),
+                ("exit_index",  pos.exit_index.map_or(f64::NAN, |e| e as f64), get("exit_index")?),
+                ("entry_price", pos.entry_price, get("entry_price")?),
+                ("exit_price",  pos.exit_price.unwrap_or(f64::NAN), get("exit_price")?),
+            ];
+            if let Some((field, a, b)) = checks.iter().find(|(_, a, b)| (a - b).abs() > tolerance || a.is_nan() != b.is_nan()) {
+                let d = PyDict::new(py);
+                d.set_item("trade", i)?;
+                d.set_item("field", field)?;
+                d.set_item("paper", a)?;
+                d.set_item("other", b)?;
+                return Ok(d.into());
+            }
+        }
+        if ours.len() != closed_positions.len() {
+            let d = PyDict::new(py);
+            d.set_item("trade", ours.len().min(closed_positions.len()))?;
+            d.set_item("field", "count")?;
+            d.set_item("paper", ours.len())?;
+            d.set_item("other", closed_positions.len())?;
+            return Ok(d.into());
+        }
+        Ok(py.None())
+    }
+}
@@ -0,0 +1,52 @@
+// src/engine/par.rs
+
+//! rayon's parallel iterators in the Python build; the same method names
+//! over plain sequential iterators without it (the wasm build), so the
+//! core engine reads the same either way.
+
+#[cfg(feature = "python")]
+pub use rayon::prelude::*;
+
+#[cfg(feature = "python")]
+pub fn current_num_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+#[cfg(not(feature = "python"))]
+pub fn current_num_threads() -> usize {
+    1
+}
+
+#[cfg(not(feature = "python"))]
+pub trait IntoParallelIterator: IntoIterator + Sized {
+    fn into_par_iter(self) -> Self::IntoIter {
+        self.into_iter()
+    }
+}
+
+#[cfg(not(feature = "python"))]
+impl<I: IntoIterator> IntoParallelIterator for I {}
+
+#[cfg(not(feature = "python"))]
+pub trait ParallelSliceMut<T> {
+    fn par_iter_mut(&mut self) -> std::slice::IterMut<'_, T>;
+}
+
+#[cfg(not(feature = "python"))]
+impl<T> ParallelSliceMut<T> for [T] {
+    fn par_iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(not(feature = "python"))]
+pub trait ParallelSlice<T> {
+    fn par_iter(&self) -> std::slice::Iter<'_, T>;
+}
+
+#[cfg(not(feature = "python"))]
+impl<T> ParallelSlice<T> for [T] {
+    fn par_iter(&self) -> std::slice::Iter<'_, T> {
+        self.iter()
+    }
+}
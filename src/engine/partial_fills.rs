@@ -0,0 +1,114 @@
+// src/engine/partial_fills.rs
+
+use crate::engine::{
+    backtest::{BacktestConfig, MarketData, SkippedEntry},
+    contract::ContractType,
+    position::{Fill, Position},
+    simulate_exits::book_exit,
+};
+
+/// Entries fill progressively: each bar fills at most `fill_rate` × the
+/// ordered size and/or `participation_rate` × that bar's volume
+#[derive(Clone, Debug, Default)]
+pub struct PartialFills {
+    pub fill_rate:          Option<f64>,
+    pub volume:             Option<Vec<f64>>,
+    pub participation_rate: f64,
+}
+
+impl PartialFills {
+    fn bar_capacity(&self, bar: usize, ordered: f64) -> f64 {
+        let mut cap = f64::INFINITY;
+        if let Some(rate) = self.fill_rate {
+            cap = cap.min(rate * ordered);
+        }
+        if let Some(volume) = &self.volume {
+            cap = cap.min(self.participation_rate * volume[bar]);
+        }
+        cap.max(0.0)
+    }
+}
+
+/// Size‐weighted entry price with the same PnL as the individual fills
+fn average_price(contract: ContractType, fills: &[Fill]) -> f64 {
+    let size: f64 = fills.iter().map(|f| f.size).sum();
+    match contract {
+        ContractType::Linear => fills.iter().map(|f| f.size * f.price).sum::<f64>() / size,
+        // inverse PnL is linear in 1/price
+        ContractType::Inverse => size / fills.iter().map(|f| f.size / f.price).sum::<f64>(),
+    }
+}
+
+/// Replace each single entry fill by per‐bar fills at the open, from the
+/// entry bar until the position exits (or the order expires); the unfilled
+/// remainder is cancelled.  Exits only depend on levels, so the exit bar and
+/// price are kept and fees/PnL re‐booked on the filled size.
+pub fn apply_partial_fills(
+    positions: Vec<Position>,
+    market: &MarketData,
+    model: &PartialFills,
+    config: &BacktestConfig,
+    skipped: &mut Vec<SkippedEntry>,
+) -> Vec<Position> {
+    let n = market.open.len();
+    let filters = config.filters.unwrap_or_default();
+    let mut out = Vec::with_capacity(positions.len());
+
+    for mut pos in positions {
+        let long    = pos.position_type == "long";
+        let ordered = pos.position_size;
+        if ordered <= 0.0 {
+            out.push(pos);
+            continue;
+        }
+        let last    = pos.exit_index.unwrap_or(n - 1);
+        let mut remaining = ordered;
+        let mut fills = Vec::new();
+
+        for j in pos.entry_index..=last {
+            if remaining <= 0.0 {
+                break;
+            }
+            if j > pos.entry_index && pos.expiration_time.is_some_and(|et| market.timestamps[j] >= et) {
+                break;
+            }
+            let size = filters.round_size(remaining.min(model.bar_capacity(j, ordered)));
+            if size <= 0.0 {
+                continue;
+            }
+            let raw   = market.open[j];
+            let price = filters.round_fill(
+                if long { raw * (1.0 + config.slippage_rate) } else { raw * (1.0 - config.slippage_rate) },
+                long,
+            );
+            let fee = pos.contract.notional(price, size) * config.entry_fee_rate;
+            fills.push(Fill { bar: j, size, price, fee });
+            remaining -= size;
+        }
+
+        if fills.is_empty() {
+            skipped.push(SkippedEntry::from_position(&pos, "unfilled"));
+            continue;
+        }
+        let filled: f64 = fills.iter().map(|f| f.size).sum();
+        pos.entry_price    = average_price(pos.contract, &fills);
+        pos.slippage_entry = fills
+            .iter()
+            .map(|f| f.size * (f.price - market.open[f.bar]).abs())
+            .sum::<f64>() / filled;
+        pos.fee_entry      = fills.iter().map(|f| f.fee).sum();
+        pos.latency_cost  *= filled / ordered;
+        pos.position_size  = filled;
+        pos.unfilled_size  = ordered - filled;
+        if let Some(b) = &mut pos.bracket {
+            b.follow_fills(&fills.iter().map(|f| (f.bar, f.size)).collect::<Vec<_>>());
+        }
+        pos.fills          = fills;
+        if pos.is_closed {
+            let rate = config.exit_fees().for_liquidity(pos.exit_liquidity.unwrap_or_default());
+            book_exit(&mut pos, rate);
+        }
+        out.push(pos);
+    }
+    out
+}
@@ -0,0 +1,798 @@
+// src/engine/portfolio.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    array_to_vec, market_from_py, signals_from_dict,
+    backtest::{validate_signals, BacktestConfig, BacktestResult, MarketData, SignalSet},
+    exposure::{
+        compute_exposure_series_with, compute_trade_close_curve, mark_open_position, sum_exposure_series,
+        ExposureSnapshot,
+    },
+    metrics::{compute_summary_metrics, SummaryMetrics},
+    output::{result_to_py, summary_metrics_to_py},
+    position::Position,
+    scan_entries::scan_entries,
+    simulate_exits::simulate_position_exits,
+    concurrency::concurrency_stats,
+    excursions::record_excursions,
+    fx::{convert_exposure, convert_positions, validate_rates},
+    stats::{correlation_matrix, mean},
+};
+
+/// Prices of one traded instrument (all on the portfolio's timestamps)
+#[derive(Clone, Debug)]
+pub struct SymbolMarket {
+    pub symbol: String,
+    pub market: MarketData,
+    /// Account currency per unit of the symbol's quote currency, per bar
+    /// (`None` when it is quoted in the account currency)
+    pub fx:     Option<Vec<f64>>,
+}
+
+/// One tagged signal set inside a portfolio run
+#[derive(Clone, Debug)]
+pub struct StrategyInput {
+    pub id:           String,
+    pub signals:      SignalSet,
+    /// Index into the run's `SymbolMarket`s
+    pub market:       usize,
+    /// Index into the run's `SubAccount`s
+    pub account:      usize,
+    /// Cap on this strategy's open notional (entry price × units)
+    pub max_exposure: Option<f64>,
+}
+
+/// Capital rules of one account, shared by its strategies
+#[derive(Clone, Debug, Default)]
+pub struct PortfolioRules {
+    /// Entries must be funded from free cash (notional fully reserved)
+    pub shared_cash:        bool,
+    /// Cap on total open notional across strategies
+    pub max_gross_exposure: Option<f64>,
+}
+
+/// A named slice of the portfolio's capital: its strategies' entries are
+/// admitted against its own equity and rules
+#[derive(Clone, Debug)]
+pub struct SubAccount {
+    pub id:             String,
+    pub initial_equity: f64,
+    pub rules:          PortfolioRules,
+}
+
+/// An entry the capital rules refused
+#[derive(Clone, Debug)]
+pub struct RejectedEntry {
+    pub strategy_id:   String,
+    pub entry_index:   usize,
+    pub position_type: String,
+    pub reason:        &'static str,
+}
+
+/// Per‐strategy attribution against the combined portfolio
+#[derive(Clone, Debug)]
+pub struct StrategyAttribution {
+    pub id:                    String,
+    pub number_of_trades:      usize,
+    pub rejected_entries:      usize,
+    pub total_pnl:             f64,
+    /// Sharpe of the strategy's contribution to portfolio bar returns
+    pub sharpe_ratio:          f64,
+    /// Drawdown of `initial_equity + strategy PnL` on its own
+    pub max_drawdown:          f64,
+    /// Strategy PnL over the portfolio's max‐drawdown window
+    pub drawdown_pnl:          f64,
+    /// Share of the portfolio's max drawdown caused by this strategy
+    pub drawdown_contribution: f64,
+    pub pnl_curve:             Vec<f64>,
+}
+
+/// Stand‐alone metrics of one symbol plus its share of the portfolio
+#[derive(Debug)]
+pub struct SymbolBreakdown {
+    pub symbol:           String,
+    pub metrics:          SummaryMetrics,
+    pub number_of_trades: usize,
+    pub total_pnl:        f64,
+    /// Symbol PnL / portfolio PnL
+    pub pnl_share:        f64,
+    /// Symbol open entry notional / portfolio open entry notional, summed over bars
+    pub exposure_share:   f64,
+    /// Symbol closed trades / portfolio closed trades
+    pub trade_share:      f64,
+    pub pnl_curve:        Vec<f64>,
+}
+
+/// Metrics of one sub‐account on its own capital plus its share of the
+/// portfolio
+#[derive(Debug)]
+pub struct AccountBreakdown {
+    pub id:               String,
+    pub initial_equity:   f64,
+    /// Ids of the strategies trading from this account
+    pub strategies:       Vec<String>,
+    pub metrics:          SummaryMetrics,
+    pub number_of_trades: usize,
+    pub rejected_entries: usize,
+    pub total_pnl:        f64,
+    /// Account PnL / portfolio PnL
+    pub pnl_share:        f64,
+    pub pnl_curve:        Vec<f64>,
+}
+
+pub struct PortfolioResult {
+    pub combined:    BacktestResult,
+    pub rejected:    Vec<RejectedEntry>,
+    pub attribution: Vec<StrategyAttribution>,
+    pub symbols:     Vec<SymbolBreakdown>,
+    pub accounts:    Vec<AccountBreakdown>,
+    /// Correlation of the strategies' contributions to portfolio bar returns
+    pub strategy_correlation: Vec<Vec<f64>>,
+    /// Same, per symbol
+    pub symbol_correlation:   Vec<Vec<f64>>,
+    /// Same, per sub‐account
+    pub account_correlation:  Vec<Vec<f64>>,
+}
+
+/// Index of the market a position's strategy trades
+fn strategy_market(strategies: &[StrategyInput], p: &Position) -> usize {
+    strategies
+        .iter()
+        .find(|s| p.strategy_id.as_deref() == Some(s.id.as_str()))
+        .map_or(0, |s| s.market)
+}
+
+/// Entries + exits for one strategy, tagged with its id and symbol
+fn simulate_strategy(markets: &[SymbolMarket], strat: &StrategyInput, config: &BacktestConfig) -> Vec<Position> {
+    let SymbolMarket { symbol, market, .. } = &markets[strat.market];
+    let s = &strat.signals;
+    let mut positions = scan_entries(
+        0..market.open.len(),
+        &market.timestamps,
+        &market.open,
+        &s.long_signals, &s.short_signals,
+        &s.long_tp, &s.long_sl,
+        &s.short_tp, &s.short_sl,
+        &s.long_size, &s.short_size,
+        &s.expiration_times,
+        config.entry_fee_rate,
+        config.slippage_rate,
+        config.contract,
+        config.size_mode,
+        config.latency,
+        config.loss_cap,
+        config.filters,
+        &mut Vec::new(),
+    );
+    simulate_position_exits(
+        &mut positions,
+        &market.timestamps, &market.open, &market.high, &market.low, &market.close,
+        config.exit_fees(), config.exit_slippage(),
+        config.latency, config.fill_model, config.queue_model.as_ref(), config.filters, config.exit_rules.as_ref(),
+        config.sub_bars.as_ref(), config.expiry_fill, config.expiry_policy, config.exit_priority, config.level_check, config.stop_type,
+    );
+    record_excursions(&mut positions, &market.high, &market.low);
+    for p in &mut positions {
+        p.strategy_id = Some(strat.id.clone());
+        p.symbol      = Some(symbol.clone());
+        if let Some(tags) = &s.tags {
+            p.tag = tags[p.signal_index].clone();
+        }
+    }
+    positions
+}
+
+/// Admit candidates chronologically under the capital rules: each
+/// account's cash and gross cap, then `max_portfolio_exposure` over the
+/// open notional of all accounts.
+///
+/// Exits never depend on other positions, so admission can run after exit
+/// simulation.  A position exiting on bar j frees its capital from bar j+1
+/// (the exit happens intrabar, after bar j's open where new entries fill).
+pub fn allocate(
+    candidates: Vec<(usize, Position)>,
+    strategies: &[StrategyInput],
+    accounts: &[SubAccount],
+    max_portfolio_exposure: Option<f64>,
+) -> (Vec<Position>, Vec<RejectedEntry>) {
+    let mut accepted: Vec<Position> = Vec::with_capacity(candidates.len());
+    let mut rejected = Vec::new();
+    // (exit_index, strategy, notional, pnl) of admitted, still‐reserved positions
+    let mut open: Vec<(usize, usize, f64, f64)> = Vec::new();
+    let mut strat_expo = vec![0.0_f64; strategies.len()];
+    let mut gross: Vec<f64> = vec![0.0; accounts.len()];
+    let mut cash:  Vec<f64> = accounts.iter().map(|a| a.initial_equity).collect();
+
+    for (k, pos) in candidates {
+        let j = pos.entry_index;
+        open.retain(|&(exit_i, s, notional, pnl)| {
+            if exit_i < j {
+                let a = strategies[s].account;
+                cash[a]       += notional + pnl;
+                gross[a]      -= notional;
+                strat_expo[s] -= notional;
+                false
+            } else {
+                true
+            }
+        });
+
+        let a = strategies[k].account;
+        let rules = &accounts[a].rules;
+        let notional = pos.entry_price * pos.position_size;
+        let reason = if rules.shared_cash && notional + pos.fee_entry > cash[a] {
+            Some("cash")
+        } else if rules.max_gross_exposure.is_some_and(|cap| gross[a] + notional > cap) {
+            Some("gross_exposure")
+        } else if max_portfolio_exposure.is_some_and(|cap| gross.iter().sum::<f64>() + notional > cap) {
+            Some("portfolio_exposure")
+        } else if strategies[k].max_exposure.is_some_and(|cap| strat_expo[k] + notional > cap) {
+            Some("strategy_exposure")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => rejected.push(RejectedEntry {
+                strategy_id:   strategies[k].id.clone(),
+                entry_index:   j,
+                position_type: pos.position_type.clone(),
+                reason,
+            }),
+            None => {
+                cash[a]       -= notional + pos.fee_entry;
+                gross[a]      += notional;
+                strat_expo[k] += notional;
+                // the entry fee was reserved above and is part of pnl at exit
+                let exit_i = pos.exit_index.unwrap_or(usize::MAX);
+                open.push((exit_i, k, notional, pos.pnl.unwrap_or(0.0) + pos.fee_entry));
+                accepted.push(pos);
+            }
+        }
+    }
+    (accepted, rejected)
+}
+
+/// (peak, trough) bar indices of the deepest drawdown of `equity`
+fn max_drawdown_window(equity: &[f64]) -> (usize, usize) {
+    let (mut peak_i, mut best) = (0usize, (0usize, 0usize));
+    let mut max_dd = 0.0_f64;
+    for (i, &e) in equity.iter().enumerate() {
+        if e > equity[peak_i] {
+            peak_i = i;
+        }
+        let peak = equity[peak_i];
+        let dd = if peak != 0.0 { (peak - e) / peak } else { 0.0 };
+        if dd > max_dd {
+            max_dd = dd;
+            best   = (peak_i, i);
+        }
+    }
+    best
+}
+
+fn curve_drawdown(curve: &[f64]) -> f64 {
+    let mut peak   = f64::NEG_INFINITY;
+    let mut max_dd = 0.0_f64;
+    for &e in curve {
+        peak = peak.max(e);
+        let dd = if peak != 0.0 { (peak - e) / peak } else { 0.0 };
+        max_dd = max_dd.max(dd);
+    }
+    max_dd
+}
+
+/// A component's PnL change per bar as a share of the previous bar's
+/// portfolio equity (components sum to the portfolio's bar returns)
+fn contribution_returns(curve: &[f64], equity: &[f64]) -> Vec<f64> {
+    (1..curve.len())
+        .map(|t| {
+            let prev = equity[t - 1];
+            if prev != 0.0 { (curve[t] - curve[t - 1]) / prev } else { 0.0 }
+        })
+        .collect()
+}
+
+fn share(part: f64, total: f64) -> f64 {
+    if total != 0.0 { part / total } else { 0.0 }
+}
+
+/// Run several strategies against the sub‐accounts they trade from (one
+/// account holding `config.initial_equity` for a single‐account run), with
+/// `max_portfolio_exposure` capping the open notional of all of them.
+/// `markets[0]` is the primary instrument; every market shares its
+/// timestamps.  The combined report starts from `config.initial_equity`, so
+/// capital no account was given sits idle in it.
+pub fn run_portfolio(
+    markets: &[SymbolMarket],
+    strategies: &[StrategyInput],
+    config: &BacktestConfig,
+    accounts: &[SubAccount],
+    max_portfolio_exposure: Option<f64>,
+) -> PortfolioResult {
+    let timestamps = &markets[0].market.timestamps;
+
+    // 1) Independent simulation per strategy
+    let per_strategy: Vec<Vec<Position>> = strategies
+        .par_iter()
+        .map(|s| simulate_strategy(markets, s, config))
+        .collect();
+
+    // 2) Chronological admission (ties: strategy order, then signal order)
+    let mut candidates: Vec<(usize, Position)> = per_strategy
+        .into_iter()
+        .enumerate()
+        .flat_map(|(k, ps)| ps.into_iter().map(move |p| (k, p)))
+        .collect();
+    candidates.sort_by_key(|(k, p)| (p.entry_index, *k, p.entry_key()));
+    let (mut accepted, rejected) = allocate(candidates, strategies, accounts, max_portfolio_exposure);
+    // ids restart per strategy; renumber in admission order
+    for (id, p) in accepted.iter_mut().enumerate() {
+        p.position_id = id as u64;
+    }
+    // booked amounts into the account currency, per symbol
+    for p in accepted.iter_mut() {
+        if let Some(fx) = &markets[strategy_market(strategies, p)].fx {
+            convert_positions(std::slice::from_mut(p), fx);
+        }
+    }
+
+    // 3) Combined curve + metrics, each strategy marked on its own prices
+    let own_positions: Vec<Vec<Position>> = strategies
+        .iter()
+        .map(|strat| {
+            accepted
+                .iter()
+                .filter(|p| p.strategy_id.as_deref() == Some(strat.id.as_str()))
+                .cloned()
+                .collect()
+        })
+        .collect();
+    let own_exposure: Vec<Vec<ExposureSnapshot>> = strategies
+        .iter()
+        .zip(&own_positions)
+        .map(|(strat, own)| {
+            let mut expo = compute_exposure_series_with(
+                own, &markets[strat.market].market.close, timestamps,
+                config.initial_equity, config.leverage(), config.exposure_options(),
+            );
+            if let Some(fx) = &markets[strat.market].fx {
+                convert_exposure(&mut expo, fx, config.initial_equity);
+            }
+            expo
+        })
+        .collect();
+    let all: Vec<&[ExposureSnapshot]> = own_exposure.iter().map(Vec::as_slice).collect();
+    let exposure = sum_exposure_series(&all, config.initial_equity);
+    let equity: Vec<f64> = exposure
+        .iter()
+        .map(|s| config.initial_equity + s.total_equity)
+        .collect();
+    let (dd_peak, dd_trough) = max_drawdown_window(&equity);
+    let dd_total = equity[dd_trough] - equity[dd_peak];
+
+    // 4) Attribution
+    let attribution: Vec<StrategyAttribution> = strategies
+        .iter()
+        .zip(own_positions.iter().zip(&own_exposure))
+        .map(|(strat, (own, own_expo))| {
+            let curve: Vec<f64> = own_expo.iter().map(|s| s.total_equity).collect();
+
+            let contrib = contribution_returns(&curve, &equity);
+            let mu  = mean(&contrib);
+            let m   = contrib.len() as f64;
+            let vol = if m > 1.0 {
+                (contrib.iter().map(|&x| (x - mu).powi(2)).sum::<f64>() / (m - 1.0)).sqrt()
+            } else {
+                0.0
+            };
+            let standalone: Vec<f64> = curve.iter().map(|p| config.initial_equity + p).collect();
+            let dd_pnl = curve[dd_trough] - curve[dd_peak];
+
+            StrategyAttribution {
+                id:                    strat.id.clone(),
+                number_of_trades:      own.iter().filter(|p| p.is_closed).count(),
+                rejected_entries:      rejected.iter().filter(|r| r.strategy_id == strat.id).count(),
+                total_pnl:             *curve.last().unwrap_or(&0.0),
+                sharpe_ratio:          if vol != 0.0 { mu / vol } else { 0.0 },
+                max_drawdown:          curve_drawdown(&standalone),
+                drawdown_pnl:          dd_pnl,
+                drawdown_contribution: if dd_total != 0.0 { dd_pnl / dd_total } else { 0.0 },
+                pnl_curve:             curve,
+            }
+        })
+        .collect();
+
+    let concurrency = concurrency_stats(&accepted, &exposure, config.overlap_threshold.unwrap_or(1));
+    let (closed, open): (Vec<Position>, Vec<Position>) =
+        accepted.into_iter().partition(|p| p.is_closed);
+    let mut metrics = compute_summary_metrics(config.initial_equity, &closed, &exposure);
+    metrics.concurrency = Some(concurrency);
+    let trade_equity = compute_trade_close_curve(&closed, timestamps, config.initial_equity);
+    let open_marks = open
+        .iter()
+        .map(|p| {
+            let m = markets.iter().position(|sm| p.symbol.as_deref() == Some(sm.symbol.as_str())).unwrap_or(0);
+            let mut mark = mark_open_position(p, &markets[m].market.close);
+            if let Some(fx) = markets[m].fx.as_ref().and_then(|r| r.last()) {
+                mark.unrealized_pnl *= fx;
+            }
+            mark
+        })
+        .collect();
+
+    // 5) Per‐symbol breakdown
+    let total_pnl      = exposure.last().map_or(0.0, |s| s.total_equity);
+    let total_notional = exposure.iter().map(|s| s.margin_used).sum::<f64>();
+    let symbols: Vec<SymbolBreakdown> = markets
+        .iter()
+        .enumerate()
+        .filter(|(m, _)| strategies.iter().any(|s| s.market == *m))
+        .map(|(m, sm)| {
+            let series: Vec<&[ExposureSnapshot]> = strategies
+                .iter()
+                .zip(&own_exposure)
+                .filter(|(s, _)| s.market == m)
+                .map(|(_, e)| e.as_slice())
+                .collect();
+            let sym_exposure = sum_exposure_series(&series, config.initial_equity);
+            let sym_closed: Vec<Position> = closed
+                .iter()
+                .filter(|p| p.symbol.as_deref() == Some(sm.symbol.as_str()))
+                .cloned()
+                .collect();
+            let pnl      = sym_exposure.last().map_or(0.0, |s| s.total_equity);
+            let notional = sym_exposure.iter().map(|s| s.margin_used).sum::<f64>();
+            SymbolBreakdown {
+                symbol:           sm.symbol.clone(),
+                metrics:          compute_summary_metrics(config.initial_equity, &sym_closed, &sym_exposure),
+                number_of_trades: sym_closed.len(),
+                total_pnl:        pnl,
+                pnl_share:        share(pnl, total_pnl),
+                exposure_share:   share(notional, total_notional),
+                trade_share:      share(sym_closed.len() as f64, closed.len() as f64),
+                pnl_curve:        sym_exposure.iter().map(|s| s.total_equity).collect(),
+            }
+        })
+        .collect();
+
+    // 6) Per‐account breakdown, each on its own capital
+    let accounts: Vec<AccountBreakdown> = accounts
+        .iter()
+        .enumerate()
+        .map(|(a, acct)| {
+            let own: Vec<&StrategyInput> = strategies.iter().filter(|s| s.account == a).collect();
+            let series: Vec<&[ExposureSnapshot]> = strategies
+                .iter()
+                .zip(&own_exposure)
+                .filter(|(s, _)| s.account == a)
+                .map(|(_, e)| e.as_slice())
+                .collect();
+            let acct_exposure = sum_exposure_series(&series, acct.initial_equity);
+            let acct_closed: Vec<Position> = closed
+                .iter()
+                .filter(|p| own.iter().any(|s| p.strategy_id.as_deref() == Some(s.id.as_str())))
+                .cloned()
+                .collect();
+            let pnl = acct_exposure.last().map_or(0.0, |s| s.total_equity);
+            AccountBreakdown {
+                id:               acct.id.clone(),
+                initial_equity:   acct.initial_equity,
+                strategies:       own.iter().map(|s| s.id.clone()).collect(),
+                metrics:          compute_summary_metrics(acct.initial_equity, &acct_closed, &acct_exposure),
+                number_of_trades: acct_closed.len(),
+                rejected_entries: rejected.iter().filter(|r| own.iter().any(|s| s.id == r.strategy_id)).count(),
+                total_pnl:        pnl,
+                pnl_share:        share(pnl, total_pnl),
+                pnl_curve:        acct_exposure.iter().map(|s| s.total_equity).collect(),
+            }
+        })
+        .collect();
+
+    let returns = |curves: Vec<&Vec<f64>>| -> Vec<Vec<f64>> {
+        curves.into_iter().map(|c| contribution_returns(c, &equity)).collect()
+    };
+    let strategy_correlation = correlation_matrix(&returns(attribution.iter().map(|a| &a.pnl_curve).collect()));
+    let symbol_correlation   = correlation_matrix(&returns(symbols.iter().map(|s| &s.pnl_curve).collect()));
+    let account_correlation  = correlation_matrix(&returns(accounts.iter().map(|a| &a.pnl_curve).collect()));
+
+    PortfolioResult {
+        combined: BacktestResult {
+            closed, open, open_marks, exposure, equity, account_value: None, metrics, equity_base: 0.0, breach: None, skipped: Vec::new(), trade_equity,
+            warnings: Vec::new(), invariant_violations: None, resampled: Vec::new(),
+        },
+        rejected,
+        attribution,
+        symbols,
+        accounts,
+        strategy_correlation,
+        symbol_correlation,
+        account_correlation,
+    }
+}
+
+/// Multi‐strategy portfolio backtest.
+///
+/// `strategies` is a list of dicts, each with an `"id"` and the nine
+/// `run_backtest` signal arrays (plus an optional `"max_exposure"` notional
+/// cap).  Positions from all strategies share one account: with
+/// `shared_cash` an entry needs free cash for its full notional, and
+/// `max_gross_exposure` caps total open notional.  Returns the combined
+/// result (positions tagged with `strategy_id`), the rejected entries, and
+/// per‐strategy attribution.
+///
+/// A strategy may trade another instrument: `"symbol"` names it and the
+/// first strategy naming it supplies its `"open"`, `"high"`, `"low"` and
+/// `"close"` arrays (on the shared `timestamp`).  Strategies without a
+/// symbol trade the top‐level prices as `"default"`.  `out["symbols"]`
+/// holds each symbol's full metrics block and its contribution shares, and
+/// `out["correlation"]` the correlation matrices (`labels`, `matrix`) of the
+/// strategies' and symbols' contributions to portfolio bar returns.
+///
+/// Symbols quoted in another currency set `"quote_currency"` (alongside
+/// their prices; `quote_currency` for the top‐level prices) and `fx_rates`
+/// maps each such currency to a per‐bar array of account currency per unit;
+/// their PnL, costs and curves are converted before aggregation (capital
+/// rules still compare quote‐currency notionals).
+///
+/// `accounts` splits the capital into named sub‐accounts: dicts with an
+/// `"id"`, their `"initial_equity"` (together at most `initial_equity`; the
+/// rest stays idle) and optionally their own `"shared_cash"` and
+/// `"max_gross_exposure"`.  Every strategy then names its `"account"`, and
+/// its entries are admitted against that account's capital and rules, while
+/// the top‐level `max_gross_exposure` caps the open notional of all
+/// accounts together (reason `"portfolio_exposure"`).  The combined result covers all of them; `out["accounts"]` holds
+/// each account's metrics on its own capital and its strategies.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    strategies,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    shared_cash=true,
+    max_gross_exposure=None,
+    quote_currency=None,
+    account_currency=None,
+    fx_rates=None,
+    accounts=None
+))]
+pub fn run_portfolio_backtest(
+    py: Python<'_>,
+    timestamp:          &PyArray1<f64>,
+    open:               &PyArray1<f64>,
+    high:               &PyArray1<f64>,
+    low:                &PyArray1<f64>,
+    close:              &PyArray1<f64>,
+    strategies:         Vec<&PyDict>,
+    entry_fee_rate:     f64,
+    exit_fee_rate:      f64,
+    slippage_rate:      f64,
+    initial_equity:     f64,
+    shared_cash:        bool,
+    max_gross_exposure: Option<f64>,
+    quote_currency:     Option<String>,
+    account_currency:   Option<String>,
+    fx_rates:           Option<&PyDict>,
+    accounts:           Option<Vec<&PyDict>>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let n = market.close.len();
+    // quote → account rates of a symbol's currency (None: no conversion)
+    let fx_for = |currency: Option<String>| -> PyResult<Option<Vec<f64>>> {
+        let Some(c) = currency.filter(|c| Some(c) != account_currency.as_ref()) else {
+            return Ok(None);
+        };
+        let arr = fx_rates
+            .and_then(|d| d.get_item(&c))
+            .ok_or_else(|| PyValueError::new_err(format!("fx_rates has no series for ‘{}’", c)))?;
+        let rates = array_to_vec(arr.extract::<&PyArray1<f64>>()?)?;
+        validate_rates(&rates, n).map_err(|e| PyValueError::new_err(format!("{} (‘{}’)", e, c)))?;
+        Ok(Some(rates))
+    };
+    let sub_accounts = match &accounts {
+        None => vec![SubAccount {
+            id: "default".to_string(),
+            initial_equity,
+            rules: PortfolioRules { shared_cash, max_gross_exposure },
+        }],
+        Some(list) => {
+            let mut out: Vec<SubAccount> = Vec::with_capacity(list.len());
+            for d in list {
+                let id: String = d
+                    .get_item("id")
+                    .ok_or_else(|| PyValueError::new_err("account dict has no ‘id’"))?
+                    .extract()?;
+                if out.iter().any(|a| a.id == id) {
+                    return Err(PyValueError::new_err(format!("duplicate account id ‘{}’", id)));
+                }
+                let equity: f64 = d
+                    .get_item("initial_equity")
+                    .ok_or_else(|| PyValueError::new_err(format!("account ‘{}’ has no ‘initial_equity’", id)))?
+                    .extract()?;
+                if !equity.is_finite() || equity <= 0.0 {
+                    return Err(PyValueError::new_err(format!("account ‘{}’: initial_equity must be > 0", id)));
+                }
+                let shared_cash = match d.get_item("shared_cash") {
+                    Some(v) if !v.is_none() => v.extract()?,
+                    _ => shared_cash,
+                };
+                let max_gross_exposure = match d.get_item("max_gross_exposure") {
+                    Some(v) if !v.is_none() => Some(v.extract::<f64>()?),
+                    _ => None,
+                };
+                out.push(SubAccount { id, initial_equity: equity, rules: PortfolioRules { shared_cash, max_gross_exposure } });
+            }
+            if out.is_empty() {
+                return Err(PyValueError::new_err("accounts must not be empty"));
+            }
+            let allocated: f64 = out.iter().map(|a| a.initial_equity).sum();
+            if allocated > initial_equity {
+                return Err(PyValueError::new_err(format!(
+                    "accounts hold {} in total, more than initial_equity {}", allocated, initial_equity
+                )));
+            }
+            out
+        }
+    };
+    let mut markets = vec![SymbolMarket { symbol: "default".to_string(), market, fx: fx_for(quote_currency)? }];
+    let mut inputs = Vec::with_capacity(strategies.len());
+    for d in strategies {
+        let id: String = d
+            .get_item("id")
+            .ok_or_else(|| PyValueError::new_err("strategy dict has no ‘id’"))?
+            .extract()?;
+        if inputs.iter().any(|s: &StrategyInput| s.id == id) {
+            return Err(PyValueError::new_err(format!("duplicate strategy id ‘{}’", id)));
+        }
+        let symbol: String = match d.get_item("symbol") {
+            Some(v) if !v.is_none() => v.extract()?,
+            _ => "default".to_string(),
+        };
+        let m = match markets.iter().position(|sm| sm.symbol == symbol) {
+            Some(m) => {
+                if d.get_item("close").is_some() || d.get_item("quote_currency").is_some() {
+                    return Err(PyValueError::new_err(format!(
+                        "strategy ‘{}’: prices given for ‘{}’, which already has them", id, symbol
+                    )));
+                }
+                m
+            }
+            None => {
+                let get = |key: &str| -> PyResult<&PyArray1<f64>> {
+                    d.get_item(key)
+                        .ok_or_else(|| PyValueError::new_err(format!(
+                            "strategy ‘{}’: symbol ‘{}’ has no prices yet (missing ‘{}’)", id, symbol, key
+                        )))?
+                        .extract()
+                };
+                let own = market_from_py(timestamp, get("open")?, get("high")?, get("low")?, get("close")?)
+                    .map_err(|e| PyValueError::new_err(format!("strategy ‘{}’: {}", id, e)))?;
+                let currency = match d.get_item("quote_currency") {
+                    Some(v) if !v.is_none() => Some(v.extract::<String>()?),
+                    _ => None,
+                };
+                markets.push(SymbolMarket { symbol, market: own, fx: fx_for(currency)? });
+                markets.len() - 1
+            }
+        };
+        let signals = signals_from_dict(d)?;
+        validate_signals(&signals, &markets[0].market.timestamps)
+            .map_err(|e| PyValueError::new_err(format!("strategy ‘{}’: {}", id, e)))?;
+        let max_exposure = match d.get_item("max_exposure") {
+            Some(v) if !v.is_none() => Some(v.extract::<f64>()?),
+            _ => None,
+        };
+        let account = match (d.get_item("account"), &accounts) {
+            (Some(v), Some(_)) if !v.is_none() => {
+                let name: String = v.extract()?;
+                sub_accounts.iter().position(|a| a.id == name).ok_or_else(|| PyValueError::new_err(format!(
+                    "strategy ‘{}’: unknown account ‘{}’", id, name
+                )))?
+            }
+            (_, Some(_)) => {
+                return Err(PyValueError::new_err(format!("strategy ‘{}’ names no ‘account’", id)));
+            }
+            (Some(v), None) if !v.is_none() => {
+                return Err(PyValueError::new_err(format!("strategy ‘{}’ names an account, but no accounts were given", id)));
+            }
+            _ => 0,
+        };
+        inputs.push(StrategyInput { id, signals, market: m, account, max_exposure });
+    }
+    if inputs.is_empty() {
+        return Err(PyValueError::new_err("at least one strategy is required"));
+    }
+    if let Some(a) = sub_accounts.iter().enumerate().find(|(a, _)| !inputs.iter().any(|s| s.account == *a)) {
+        return Err(PyValueError::new_err(format!("account ‘{}’ has no strategies", a.1.id)));
+    }
+
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    // with sub‐accounts the top‐level cap spans all of them
+    let max_portfolio_exposure = accounts.as_ref().and(max_gross_exposure);
+    let result = py.allow_threads(|| run_portfolio(&markets, &inputs, &config, &sub_accounts, max_portfolio_exposure));
+
+    let out = result_to_py(py, &result.combined)?;
+
+    let py_rej = PyList::empty(py);
+    for r in &result.rejected {
+        let d = PyDict::new(py);
+        d.set_item("strategy_id",   &r.strategy_id)?;
+        d.set_item("entry_index",   r.entry_index)?;
+        d.set_item("position_type", &r.position_type)?;
+        d.set_item("reason",        r.reason)?;
+        py_rej.append(d)?;
+    }
+    out.set_item("rejected_entries", py_rej)?;
+
+    let py_attr = PyDict::new(py);
+    for a in &result.attribution {
+        let d = PyDict::new(py);
+        d.set_item("number_of_trades",      a.number_of_trades)?;
+        d.set_item("rejected_entries",      a.rejected_entries)?;
+        d.set_item("total_pnl",             a.total_pnl)?;
+        d.set_item("sharpe_ratio",          a.sharpe_ratio)?;
+        d.set_item("max_drawdown",          a.max_drawdown)?;
+        d.set_item("drawdown_pnl",          a.drawdown_pnl)?;
+        d.set_item("drawdown_contribution", a.drawdown_contribution)?;
+        d.set_item("pnl_curve",             a.pnl_curve.clone())?;
+        py_attr.set_item(&a.id, d)?;
+    }
+    out.set_item("strategies", py_attr)?;
+
+    let py_sym = PyDict::new(py);
+    for s in &result.symbols {
+        let d = PyDict::new(py);
+        d.set_item("metrics",          summary_metrics_to_py(py, &s.metrics)?)?;
+        d.set_item("number_of_trades", s.number_of_trades)?;
+        d.set_item("total_pnl",        s.total_pnl)?;
+        d.set_item("pnl_share",        s.pnl_share)?;
+        d.set_item("exposure_share",   s.exposure_share)?;
+        d.set_item("trade_share",      s.trade_share)?;
+        d.set_item("pnl_curve",        s.pnl_curve.clone())?;
+        py_sym.set_item(&s.symbol, d)?;
+    }
+    out.set_item("symbols", py_sym)?;
+
+    let py_acct = PyDict::new(py);
+    for a in &result.accounts {
+        let d = PyDict::new(py);
+        d.set_item("initial_equity",   a.initial_equity)?;
+        d.set_item("strategies",       a.strategies.clone())?;
+        d.set_item("metrics",          summary_metrics_to_py(py, &a.metrics)?)?;
+        d.set_item("number_of_trades", a.number_of_trades)?;
+        d.set_item("rejected_entries", a.rejected_entries)?;
+        d.set_item("total_pnl",        a.total_pnl)?;
+        d.set_item("pnl_share",        a.pnl_share)?;
+        d.set_item("pnl_curve",        a.pnl_curve.clone())?;
+        py_acct.set_item(&a.id, d)?;
+    }
+    out.set_item("accounts", py_acct)?;
+
+    let corr = PyDict::new(py);
+    let ids: Vec<&str> = result.attribution.iter().map(|a| a.id.as_str()).collect();
+    let syms: Vec<&str> = result.symbols.iter().map(|s| s.symbol.as_str()).collect();
+    let accts: Vec<&str> = result.accounts.iter().map(|a| a.id.as_str()).collect();
+    for (key, labels, matrix) in [
+        ("strategies", ids, &result.strategy_correlation),
+        ("symbols", syms, &result.symbol_correlation),
+        ("accounts", accts, &result.account_correlation),
+    ] {
+        let d = PyDict::new(py);
+        d.set_item("labels", labels)?;
+        d.set_item("matrix", matrix.clone())?;
+        corr.set_item(key, d)?;
+    }
+    out.set_item("correlation", corr)?;
+    Ok(out.into())
+}
@@ -1,11 +1,37 @@
 // src/engine/position.rs
 
+use std::cmp::Ordering;
+
+use crate::engine::{bracket::Bracket, contract::ContractType, liquidity::Liquidity};
+
+/// One slice of a progressively filled entry
+#[derive(Clone, Debug)]
+pub struct Fill {
+    pub bar:   usize,
+    pub size:  f64,
+    /// Fill price (includes slippage)
+    pub price: f64,
+    pub fee:   f64,
+}
+
+/// Levels in force after an exit rule or amendment moved them on `bar`
+#[derive(Clone, Debug)]
+pub struct LevelChange {
+    pub bar: usize,
+    pub tp:  f64,
+    pub sl:  f64,
+}
+
 #[derive(Clone, Debug)]
 pub struct Position {
+    /// Unique within a run, increasing in signal order
+    pub position_id:        u64,
     /// The entry timestamp (UNIX seconds) of this position
-    pub position_id:        f64,
+    pub entry_time:         f64,
     /// "long" or "short"
     pub position_type:      String,
+    /// Bar‐index of the signal that opened this position
+    pub signal_index:       usize,
     /// Bar‐index at which this position was filled
     pub entry_index:        usize,
     /// Fill price (includes slippage)
@@ -14,30 +40,262 @@ pub struct Position {
     pub tp:                 f64,
     /// Absolute stop‐loss level
     pub sl:                 f64,
-    /// Optional expiration timestamp (must be ≥ position_id)
+    /// Stop level at entry (after any loss cap, before amendments/trailing)
+    pub entry_sl:           f64,
+    /// Optional expiration timestamp (must be ≥ entry_time)
     pub expiration_time:    Option<f64>,
     /// Bar‐index at which this position was closed
     pub exit_index:         Option<usize>,
+    /// Sub‐bar timestamp of the SL/TP touch (sub‐bar runs only)
+    pub exit_time:          Option<f64>,
+    /// Seconds from the expiry to the exit fill (EXP exits only)
+    pub expiry_slippage:    Option<f64>,
+    /// Expiry policy applied when the expiry was reached ("close",
+    /// "remainder" or "extend")
+    pub expiry_action:      Option<String>,
     /// Fill price at exit (includes slippage)
     pub exit_price:         Option<f64>,
     /// "TP", "SL", or "EXP"
     pub exit_condition:     Option<String>,
     /// Number of units/contracts
     pub position_size:      f64,
+    /// Linear or inverse (coin‐margined) PnL math
+    pub contract:           ContractType,
     /// $ fee charged at entry
     pub fee_entry:          f64,
     /// $ fee charged at exit
     pub fee_exit:           f64,
+    /// Liquidity of the entry fills (market entries take)
+    pub entry_liquidity:    Liquidity,
+    /// Liquidity of the exit fill: resting take‐profits (and stop‐limits
+    /// filling at their limit) make, market and stop exits take
+    pub exit_liquidity:     Option<Liquidity>,
     /// Price‐delta slippage at entry (reporting only)
     pub slippage_entry:     f64,
     /// Price‐delta slippage at exit (reporting only)
     pub slippage_exit:      f64,
     /// (exit_price/entry_price − 1)
     pub absolute_return:    Option<f64>,
-    /// net $ PnL / (entry_price×units)
+    /// net $ PnL / entry notional
     pub real_return:        Option<f64>,
     /// net $ PnL
     pub pnl:                Option<f64>,
     /// true once closed
     pub is_closed:          bool,
+    /// Futures rolls this position was carried through
+    pub roll_count:         usize,
+    /// $ cost of those rolls (fees + slippage on both contracts)
+    pub roll_cost:          f64,
+    /// $ funding paid while open (negative when received; included in PnL)
+    pub funding_cost:       f64,
+    /// $ dividends received while held (negative when paid on a short;
+    /// included in PnL)
+    pub dividend_income:    f64,
+    /// Owning strategy (portfolio runs only)
+    pub strategy_id:        Option<String>,
+    /// Traded instrument (portfolio runs only)
+    pub symbol:             Option<String>,
+    /// Setup label copied from the signal bar's tag
+    pub tag:                Option<String>,
+    /// Seconds from the intended to the actual entry fill (latency runs)
+    pub entry_delay:        Option<f64>,
+    /// Seconds from the exit trigger bar to the actual exit fill (latency runs)
+    pub exit_delay:         Option<f64>,
+    /// $ adverse price drift caused by the delays (included in PnL)
+    pub latency_cost:       f64,
+    /// Bars whose TP touch was tried under the probabilistic fill model
+    pub tp_attempts:        usize,
+    /// …of which were rejected
+    pub tp_rejections:      usize,
+    /// Entered at reduced size by the equity‐curve throttle
+    pub throttled:          bool,
+    /// Stop level before the loss cap / tick rounding changed it
+    pub original_sl:        Option<f64>,
+    /// Size before the loss cap / lot rounding changed it
+    pub original_size:      Option<f64>,
+    /// Entry fills when entries are filled over several bars (entry_price is
+    /// their average, position_size their sum)
+    pub fills:              Vec<Fill>,
+    /// Ordered size cancelled because it never filled
+    pub unfilled_size:      f64,
+    /// Every TP/SL move made while the position was open
+    pub level_changes:      Vec<LevelChange>,
+    /// TP/SL child orders (set by the exit simulation)
+    pub bracket:            Option<Bracket>,
+    /// Max favorable / adverse price excursion from entry while held
+    pub mfe:                f64,
+    pub mae:                f64,
+    /// Isolated‐margin liquidation price (runs with `maintenance_margin`)
+    pub liquidation_price:  Option<f64>,
+    /// Held bars whose adverse extreme came within the buffer of it
+    pub near_liquidation:   Vec<usize>,
+    /// First held bar whose adverse extreme crossed it
+    pub liquidation_bar:    Option<usize>,
+}
+
+impl Position {
+    /// Resize the position; every cost and PnL term is linear in size
+    pub fn scale(&mut self, factor: f64) {
+        self.position_size *= factor;
+        self.fee_entry     *= factor;
+        self.fee_exit      *= factor;
+        self.latency_cost  *= factor;
+        self.roll_cost     *= factor;
+        self.funding_cost  *= factor;
+        self.dividend_income *= factor;
+        self.unfilled_size *= factor;
+        self.pnl            = self.pnl.map(|p| p * factor);
+        for f in &mut self.fills {
+            f.size *= factor;
+            f.fee  *= factor;
+        }
+        if let Some(b) = &mut self.bracket {
+            b.scale(factor);
+        }
+    }
+
+    /// Loss if the entry stop were hit at its level (NaN without a stop
+    /// on the losing side)
+    pub fn initial_risk(&self) -> f64 {
+        let loss = -self.contract.pnl(self.position_type == "long", self.entry_price, self.entry_sl, self.position_size);
+        if loss > 0.0 { loss } else { f64::NAN }
+    }
+
+    /// PnL in units of the initial risk (None without a usable stop)
+    pub fn r_multiple(&self) -> Option<f64> {
+        let risk = self.initial_risk();
+        match self.pnl {
+            Some(pnl) if risk.is_finite() => Some(pnl / risk),
+            _ => None,
+        }
+    }
+
+    /// $ lost to entry + exit slippage (the price deltas times the size)
+    pub fn slippage_cost(&self) -> f64 {
+        let long = self.position_type == "long";
+        let raw_entry = if long { self.entry_price - self.slippage_entry } else { self.entry_price + self.slippage_entry };
+        let mut cost = self.contract.pnl(true, raw_entry, self.entry_price, self.position_size).abs();
+        if let Some(exit) = self.exit_price {
+            let raw_exit = if long { exit + self.slippage_exit } else { exit - self.slippage_exit };
+            cost += self.contract.pnl(true, exit, raw_exit, self.position_size).abs();
+        }
+        cost
+    }
+
+    /// Admission order of same‐bar entries: fill bar, then signal bar,
+    /// then `position_id` (signal order)
+    pub fn entry_key(&self) -> (usize, usize, u64) {
+        (self.entry_index, self.signal_index, self.position_id)
+    }
+
+    /// Booking order of realized PnL: exit bar, then exit time (sub‐bar or
+    /// expiry fills inside the bar), then `position_id`; open positions last
+    pub fn cmp_exit(&self, other: &Position) -> Ordering {
+        let time = |p: &Position| p.exit_time.unwrap_or(f64::INFINITY);
+        self.exit_index
+            .unwrap_or(usize::MAX)
+            .cmp(&other.exit_index.unwrap_or(usize::MAX))
+            .then(time(self).total_cmp(&time(other)))
+            .then(self.position_id.cmp(&other.position_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        backtest::{run_engine, BacktestConfig, BacktestResult, MarketData, SignalSet},
+        event_loop::AccountRules,
+        latency::Latency,
+    };
+
+    /// Longs on bars 1‥=4 that all expire at 1500; the gap before bar 5
+    /// makes the 100 s latency fill bars 3 and 4 together on bar 5
+    fn run(config: &BacktestConfig) -> BacktestResult {
+        let ts = vec![0.0, 60.0, 120.0, 180.0, 240.0, 1000.0, 1060.0, 1120.0, 1500.0, 1560.0];
+        let n = ts.len();
+        let close: Vec<f64> = (0..n).map(|i| 100.0 + i as f64).collect();
+        let market = MarketData {
+            timestamps: ts,
+            open:  close.clone(),
+            high:  close.iter().map(|c| c + 0.5).collect(),
+            low:   close.iter().map(|c| c - 0.5).collect(),
+            close,
+        };
+        let signals = SignalSet {
+            long_signals:     (0..n).map(|i| (1..=4).contains(&i)).collect(),
+            short_signals:    vec![false; n],
+            long_tp:          vec![f64::NAN; n],
+            long_sl:          vec![f64::NAN; n],
+            short_tp:         vec![f64::NAN; n],
+            short_sl:         vec![f64::NAN; n],
+            long_size:        vec![1.0; n],
+            short_size:       vec![1.0; n],
+            expiration_times: vec![1500.0; n],
+            tags:             None,
+            direction:        None,
+        };
+        run_engine(&market, &signals, config)
+    }
+
+    fn config() -> BacktestConfig {
+        BacktestConfig {
+            initial_equity: 1000.0,
+            latency: Some(Latency::Seconds(100.0)),
+            ..BacktestConfig::default()
+        }
+    }
+
+    #[test]
+    fn same_bar_entries_and_exits_follow_the_keys() {
+        let result = run(&config());
+        let mut closed = result.closed.clone();
+        assert_eq!(closed.len(), 4);
+        assert!(closed.iter().all(|p| p.exit_index == closed[0].exit_index));
+        assert_eq!(closed.iter().filter(|p| p.entry_index == 5).count(), 2);
+
+        // one total order, whatever order the positions come in
+        closed.sort_by(|a, b| a.cmp_exit(b));
+        let forward: Vec<u64> = closed.iter().map(|p| p.position_id).collect();
+        closed.reverse();
+        closed.sort_by(|a, b| a.cmp_exit(b));
+        assert_eq!(closed.iter().map(|p| p.position_id).collect::<Vec<_>>(), forward);
+        assert!(forward.windows(2).all(|w| w[0] < w[1]));
+        closed.reverse();
+        closed.sort_by_key(Position::entry_key);
+        assert!(closed.windows(2).all(|w| w[0].entry_key() < w[1].entry_key()));
+
+        // of the two bar‐5 entries, the earlier signal is admitted
+        let limited = run(&BacktestConfig {
+            account: AccountRules { max_positions: Some(3), ..AccountRules::default() },
+            ..config()
+        });
+        let admitted: Vec<(usize, usize)> = limited.closed.iter().map(|p| (p.signal_index, p.entry_index)).collect();
+        assert!(admitted.contains(&(3, 5)) && !admitted.contains(&(4, 5)));
+        assert_eq!(limited.skipped.len(), 1);
+        assert_eq!(limited.skipped[0].signal_index, 4);
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn results_do_not_depend_on_thread_count() {
+        let fingerprint = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let result = pool.install(|| run(&BacktestConfig {
+                account: AccountRules { max_positions: Some(3), ..AccountRules::default() },
+                ..config()
+            }));
+            let trades: Vec<(u64, usize, Option<usize>, u64)> = result
+                .closed
+                .iter()
+                .map(|p| (p.position_id, p.entry_index, p.exit_index, p.pnl.unwrap_or(0.0).to_bits()))
+                .collect();
+            let equity: Vec<u64> = result.equity.iter().map(|e| e.to_bits()).collect();
+            (trades, equity)
+        };
+        let single = fingerprint(1);
+        for threads in [2, 4, 8] {
+            assert_eq!(fingerprint(threads), single);
+        }
+    }
 }
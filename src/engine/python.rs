@@ -0,0 +1,912 @@
+// src/engine/python.rs
+
+//! `run_backtest` and the numpy ↔ Rust conversions shared by the Python
+//! entry points
+
+use numpy::{Element, PyArray1};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    backtest::{
+        run_engine, validate_market, validate_signals,
+        BacktestConfig, MarketData, SignalSet,
+    },
+    bootstrap::BootstrapOptions,
+    contract::{ContractType, SizeMode},
+    drawdown_limit::TrailingDrawdown,
+    exit_rules::{Amendment, Chandelier, ExitRules, TimeStop},
+    throttle::EquityThrottle,
+    event_loop::AccountRules,
+    loss_cap::{LossCap, LossCapMode},
+    instrument::InstrumentFilters,
+    partial_fills::PartialFills,
+    fill_model::{FillModel, QueueModel},
+    latency::Latency,
+    liquidation::{near_liquidation_flags, LiquidationModel},
+    metrics::{EquityBasis, RatioPolicy, ReturnEquity, ReturnKind, ReturnSpec},
+    exposure::CostAccrual,
+    rolls::build_schedule,
+    signal_input::{apply_direction, apply_strength, restrict_time_range, skip_warmup, SignalMode},
+    sub_bars::SubBars,
+    fx::validate_rates,
+    corporate::adjust_for_splits,
+    borrow::borrow_availability,
+    simulate_exits::{ExitPriority, ExpiryFill, ExpiryPolicy, LevelCheck},
+    bracket::StopType,
+    run_info::{InputDigest, RunInfo, Word},
+    orders::expand_orders,
+    position::Position,
+    timeline::TimelineLayout,
+    journal::append_run,
+    output::{breach_to_py, orders_to_py, result_to_py, run_info_to_py, timeline_to_py},
+};
+
+/// Copy a 1‐D numpy array into an owned Vec
+pub(crate) fn array_to_vec<T: Element + Copy>(arr: &PyArray1<T>) -> PyResult<Vec<T>> {
+    Ok(unsafe { arr.as_slice()? }.to_vec())
+}
+
+/// Length + hash of a 1‐D numpy array for `run_info`
+pub(crate) fn digest<T: Element + Word>(name: &str, arr: &PyArray1<T>) -> PyResult<InputDigest> {
+    Ok(InputDigest::of(name, unsafe { arr.as_slice()? }))
+}
+
+/// Pull and validate the OHLC + timestamp arrays
+pub(crate) fn market_from_py(
+    timestamp: &PyArray1<f64>,
+    open:      &PyArray1<f64>,
+    high:      &PyArray1<f64>,
+    low:       &PyArray1<f64>,
+    close:     &PyArray1<f64>,
+) -> PyResult<MarketData> {
+    let mut market = MarketData {
+        timestamps: array_to_vec(timestamp)?,
+        open:       array_to_vec(open)?,
+        high:       array_to_vec(high)?,
+        low:        array_to_vec(low)?,
+        close:      array_to_vec(close)?,
+    };
+    validate_market(&mut market).map_err(PyValueError::new_err)?;
+    Ok(market)
+}
+
+/// Pull the per‐bar signal arrays (validated separately)
+pub(crate) fn signals_from_py(
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+) -> PyResult<SignalSet> {
+    Ok(SignalSet {
+        long_signals:     array_to_vec(long_signals)?,
+        short_signals:    array_to_vec(short_signals)?,
+        long_tp:          array_to_vec(long_tp)?,
+        long_sl:          array_to_vec(long_sl)?,
+        short_tp:         array_to_vec(short_tp)?,
+        short_sl:         array_to_vec(short_sl)?,
+        long_size:        array_to_vec(long_size)?,
+        short_size:       array_to_vec(short_size)?,
+        expiration_times: array_to_vec(expiration_times)?,
+        tags:             None,
+        direction:        None,
+    })
+}
+
+/// Per‐bar labels (strings, ints, or None for untagged bars)
+pub(crate) fn tags_from_py(obj: &PyAny) -> PyResult<Vec<Option<String>>> {
+    let mut tags = Vec::new();
+    for item in obj.iter()? {
+        let item = item?;
+        tags.push(if item.is_none() { None } else { Some(item.str()?.to_string()) });
+    }
+    Ok(tags)
+}
+
+/// TP/SL amendments from `{"bar", "signal_index"?, "tp"?, "sl"?}` dicts
+pub(crate) fn amendments_from_py(list: Vec<&PyDict>) -> PyResult<Vec<Amendment>> {
+    fn opt<'a, T: FromPyObject<'a>>(d: &'a PyDict, key: &str) -> PyResult<Option<T>> {
+        match d.get_item(key) {
+            Some(v) if !v.is_none() => Ok(Some(v.extract()?)),
+            _ => Ok(None),
+        }
+    }
+    list.into_iter()
+        .map(|d| {
+            Ok(Amendment {
+                bar: opt(d, "bar")?.ok_or_else(|| PyValueError::new_err("amendment has no ‘bar’"))?,
+                signal_index: opt(d, "signal_index")?,
+                tp: opt(d, "tp")?,
+                sl: opt(d, "sl")?,
+            })
+        })
+        .collect()
+}
+
+/// Signal arrays from a dict keyed like the `run_backtest` arguments
+pub(crate) fn signals_from_dict(d: &PyDict) -> PyResult<SignalSet> {
+    fn get<'a, T: Element>(d: &'a PyDict, key: &str) -> PyResult<&'a PyArray1<T>> {
+        d.get_item(key)
+            .ok_or_else(|| PyValueError::new_err(format!("missing array ‘{}’", key)))?
+            .extract()
+    }
+    let mut signals = signals_from_py(
+        get(d, "long_signals")?, get(d, "short_signals")?,
+        get(d, "long_tp")?, get(d, "long_sl")?,
+        get(d, "short_tp")?, get(d, "short_sl")?,
+        get(d, "long_size")?, get(d, "short_size")?,
+        get(d, "expiration_times")?,
+    )?;
+    if let Some(tags) = d.get_item("tags") {
+        if !tags.is_none() {
+            signals.tags = Some(tags_from_py(tags)?);
+        }
+    }
+    if let Some(strength) = d.get_item("signal_strength") {
+        if !strength.is_none() {
+            let strength: &PyArray1<f64> = strength.extract()?;
+            apply_strength(&mut signals, &array_to_vec(strength)?).map_err(PyValueError::new_err)?;
+        }
+    }
+    Ok(signals)
+}
+
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    bootstrap_samples=0,
+    bootstrap_block_length=None,
+    bootstrap_seed=0,
+    bootstrap_confidence=0.95,
+    tags=None,
+    contract_ids=None,
+    roll_prices=None,
+    contract_type="linear",
+    latency_bars=0,
+    latency_seconds=None,
+    fill_probability=None,
+    fill_full_depth=0.001,
+    fill_seed=0,
+    max_trailing_drawdown=None,
+    trailing_drawdown_pct=false,
+    throttle_window=None,
+    throttle_scale=0.0,
+    max_positions=None,
+    cash_constrained=false,
+    compounding=false,
+    max_loss=None,
+    max_loss_mode="tighten",
+    tick_size=None,
+    lot_size=None,
+    min_notional=None,
+    size_mode="units",
+    fill_rate=None,
+    volume=None,
+    participation_rate=0.1,
+    slippage_sl=None,
+    slippage_tp=None,
+    slippage_exp=None,
+    maker_fee_rate=None,
+    taker_fee_rate=None,
+    leverage=1.0,
+    maintenance_margin=None,
+    liquidation_buffer=0.05,
+    signal_strength=None,
+    signal=None,
+    signal_mode="independent",
+    amendments=None,
+    sl_decay_bars=None,
+    sl_decay_steps=None,
+    trail_atr=None,
+    trail_mult=3.0,
+    funding_rates=None,
+    mark_price=None,
+    fx_rates=None,
+    dividends=None,
+    split_factors=None,
+    shortable=None,
+    borrow_available=None,
+    bid=None,
+    ask=None,
+    bid_size=None,
+    ask_size=None,
+    queue_volume=None,
+    queue_volume_share=0.1,
+    gross_metrics=false,
+    weighted_returns=false,
+    check_invariants=false,
+    undefined_ratios="legacy",
+    ratio_cap=None,
+    equity_basis="pnl",
+    return_type="simple",
+    return_equity="marked",
+    cost_accrual="exit",
+    net_floating=false,
+    sub_bars=None,
+    expiry_fill="close",
+    expiry_policy="close",
+    expiry_extension=None,
+    exit_priority="sl,tp,exp",
+    level_check="touch",
+    sl_order_type="stop",
+    sl_limit_offset=None,
+    overlap_threshold=1,
+    regimes=None,
+    benchmark=None,
+    cash_flows=None,
+    factors=None,
+    beta_window=60,
+    autocorr_lags=None,
+    duration_buckets=None,
+    warmup_bars=0,
+    start_time=None,
+    end_time=None,
+    orders=false,
+    timeline=None,
+    journal=None,
+    journal_label=None
+))]
+pub fn run_backtest(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    bootstrap_samples:      usize,
+    bootstrap_block_length: Option<usize>,
+    bootstrap_seed:         u64,
+    bootstrap_confidence:   f64,
+    tags:                   Option<&PyAny>,
+    contract_ids:           Option<&PyAny>,
+    roll_prices:            Option<&PyArray1<f64>>,
+    contract_type:          &str,
+    latency_bars:           usize,
+    latency_seconds:        Option<f64>,
+    fill_probability:       Option<f64>,
+    fill_full_depth:        f64,
+    fill_seed:              u64,
+    max_trailing_drawdown:  Option<f64>,
+    trailing_drawdown_pct:  bool,
+    throttle_window:        Option<usize>,
+    throttle_scale:         f64,
+    max_positions:          Option<usize>,
+    cash_constrained:       bool,
+    compounding:            bool,
+    max_loss:               Option<f64>,
+    max_loss_mode:          &str,
+    tick_size:              Option<f64>,
+    lot_size:               Option<f64>,
+    min_notional:           Option<f64>,
+    size_mode:              &str,
+    fill_rate:              Option<f64>,
+    volume:                 Option<&PyArray1<f64>>,
+    participation_rate:     f64,
+    slippage_sl:            Option<f64>,
+    slippage_tp:            Option<f64>,
+    slippage_exp:           Option<f64>,
+    maker_fee_rate:         Option<f64>,
+    taker_fee_rate:         Option<f64>,
+    leverage:               f64,
+    maintenance_margin:     Option<f64>,
+    liquidation_buffer:     f64,
+    signal_strength:        Option<&PyArray1<f64>>,
+    signal:                 Option<&PyArray1<i64>>,
+    signal_mode:            &str,
+    amendments:             Option<Vec<&PyDict>>,
+    sl_decay_bars:          Option<usize>,
+    sl_decay_steps:         Option<Vec<(usize, f64)>>,
+    trail_atr:              Option<&PyArray1<f64>>,
+    trail_mult:             f64,
+    funding_rates:          Option<&PyArray1<f64>>,
+    mark_price:             Option<&PyArray1<f64>>,
+    fx_rates:               Option<&PyArray1<f64>>,
+    dividends:              Option<&PyArray1<f64>>,
+    split_factors:          Option<&PyArray1<f64>>,
+    shortable:              Option<&PyArray1<bool>>,
+    borrow_available:       Option<&PyArray1<f64>>,
+    bid:                    Option<&PyArray1<f64>>,
+    ask:                    Option<&PyArray1<f64>>,
+    bid_size:               Option<&PyArray1<f64>>,
+    ask_size:               Option<&PyArray1<f64>>,
+    queue_volume:           Option<&PyArray1<f64>>,
+    queue_volume_share:     f64,
+    gross_metrics:          bool,
+    weighted_returns:       bool,
+    check_invariants:       bool,
+    undefined_ratios:       &str,
+    ratio_cap:              Option<f64>,
+    equity_basis:           &str,
+    return_type:            &str,
+    return_equity:          &str,
+    cost_accrual:           &str,
+    net_floating:           bool,
+    sub_bars:               Option<&PyDict>,
+    expiry_fill:            &str,
+    expiry_policy:          &str,
+    expiry_extension:       Option<f64>,
+    exit_priority:          &str,
+    level_check:            &str,
+    sl_order_type:          &str,
+    sl_limit_offset:        Option<f64>,
+    overlap_threshold:      usize,
+    regimes:                Option<&PyAny>,
+    benchmark:              Option<&PyArray1<f64>>,
+    cash_flows:             Option<&PyArray1<f64>>,
+    factors:                Option<&PyDict>,
+    beta_window:            usize,
+    autocorr_lags:          Option<Vec<usize>>,
+    duration_buckets:       Option<Vec<f64>>,
+    warmup_bars:            usize,
+    start_time:             Option<f64>,
+    end_time:               Option<f64>,
+    orders:                 bool,
+    timeline:               Option<&str>,
+    journal:                Option<&str>,
+    journal_label:          Option<&str>,
+) -> PyResult<PyObject> {
+    // 0) Reproducibility manifest of the call as given
+    let params = PyDict::new(py);
+    for (name, value) in [
+        ("entry_fee_rate",         entry_fee_rate.to_object(py)),
+        ("exit_fee_rate",          exit_fee_rate.to_object(py)),
+        ("slippage_rate",          slippage_rate.to_object(py)),
+        ("initial_equity",         initial_equity.to_object(py)),
+        ("bootstrap_samples",      bootstrap_samples.to_object(py)),
+        ("bootstrap_block_length", bootstrap_block_length.to_object(py)),
+        ("bootstrap_seed",         bootstrap_seed.to_object(py)),
+        ("bootstrap_confidence",   bootstrap_confidence.to_object(py)),
+        ("contract_type",          contract_type.to_object(py)),
+        ("latency_bars",           latency_bars.to_object(py)),
+        ("latency_seconds",        latency_seconds.to_object(py)),
+        ("fill_probability",       fill_probability.to_object(py)),
+        ("fill_full_depth",        fill_full_depth.to_object(py)),
+        ("queue_volume_share",     queue_volume_share.to_object(py)),
+        ("fill_seed",              fill_seed.to_object(py)),
+        ("max_trailing_drawdown",  max_trailing_drawdown.to_object(py)),
+        ("trailing_drawdown_pct",  trailing_drawdown_pct.to_object(py)),
+        ("throttle_window",        throttle_window.to_object(py)),
+        ("throttle_scale",         throttle_scale.to_object(py)),
+        ("max_positions",          max_positions.to_object(py)),
+        ("cash_constrained",       cash_constrained.to_object(py)),
+        ("compounding",            compounding.to_object(py)),
+        ("max_loss",               max_loss.to_object(py)),
+        ("max_loss_mode",          max_loss_mode.to_object(py)),
+        ("tick_size",              tick_size.to_object(py)),
+        ("lot_size",               lot_size.to_object(py)),
+        ("min_notional",           min_notional.to_object(py)),
+        ("size_mode",              size_mode.to_object(py)),
+        ("fill_rate",              fill_rate.to_object(py)),
+        ("participation_rate",     participation_rate.to_object(py)),
+        ("slippage_sl",            slippage_sl.to_object(py)),
+        ("slippage_tp",            slippage_tp.to_object(py)),
+        ("slippage_exp",           slippage_exp.to_object(py)),
+        ("maker_fee_rate",         maker_fee_rate.to_object(py)),
+        ("taker_fee_rate",         taker_fee_rate.to_object(py)),
+        ("leverage",               leverage.to_object(py)),
+        ("maintenance_margin",     maintenance_margin.to_object(py)),
+        ("liquidation_buffer",     liquidation_buffer.to_object(py)),
+        ("signal_mode",            signal_mode.to_object(py)),
+        ("amendments",             amendments.to_object(py)),
+        ("sl_decay_bars",          sl_decay_bars.to_object(py)),
+        ("sl_decay_steps",         sl_decay_steps.to_object(py)),
+        ("trail_mult",             trail_mult.to_object(py)),
+        ("gross_metrics",          gross_metrics.to_object(py)),
+        ("weighted_returns",       weighted_returns.to_object(py)),
+        ("check_invariants",       check_invariants.to_object(py)),
+        ("undefined_ratios",       undefined_ratios.to_object(py)),
+        ("ratio_cap",              ratio_cap.to_object(py)),
+        ("equity_basis",           equity_basis.to_object(py)),
+        ("return_type",            return_type.to_object(py)),
+        ("return_equity",          return_equity.to_object(py)),
+        ("cost_accrual",           cost_accrual.to_object(py)),
+        ("net_floating",           net_floating.to_object(py)),
+        ("expiry_fill",            expiry_fill.to_object(py)),
+        ("expiry_policy",          expiry_policy.to_object(py)),
+        ("expiry_extension",       expiry_extension.to_object(py)),
+        ("exit_priority",          exit_priority.to_object(py)),
+        ("level_check",            level_check.to_object(py)),
+        ("sl_order_type",          sl_order_type.to_object(py)),
+        ("sl_limit_offset",        sl_limit_offset.to_object(py)),
+        ("beta_window",            beta_window.to_object(py)),
+        ("autocorr_lags",          autocorr_lags.to_object(py)),
+        ("duration_buckets",       duration_buckets.to_object(py)),
+        ("warmup_bars",            warmup_bars.to_object(py)),
+        ("start_time",             start_time.to_object(py)),
+        ("end_time",               end_time.to_object(py)),
+        ("orders",                 orders.to_object(py)),
+        ("timeline",               timeline.to_object(py)),
+        ("overlap_threshold",      overlap_threshold.to_object(py)),
+    ] {
+        params.set_item(name, value)?;
+    }
+    let mut inputs = vec![
+        digest("timestamp", timestamp)?,
+        digest("open", open)?,
+        digest("high", high)?,
+        digest("low", low)?,
+        digest("close", close)?,
+        digest("long_signals", long_signals)?,
+        digest("short_signals", short_signals)?,
+        digest("long_tp", long_tp)?,
+        digest("long_sl", long_sl)?,
+        digest("short_tp", short_tp)?,
+        digest("short_sl", short_sl)?,
+        digest("long_size", long_size)?,
+        digest("short_size", short_size)?,
+        digest("expiration_times", expiration_times)?,
+    ];
+    for (name, arr) in [
+        ("roll_prices", roll_prices),
+        ("volume", volume),
+        ("signal_strength", signal_strength),
+        ("trail_atr", trail_atr),
+        ("funding_rates", funding_rates),
+        ("mark_price", mark_price),
+        ("fx_rates", fx_rates),
+        ("dividends", dividends),
+        ("split_factors", split_factors),
+        ("borrow_available", borrow_available),
+        ("bid", bid),
+        ("ask", ask),
+        ("bid_size", bid_size),
+        ("ask_size", ask_size),
+        ("queue_volume", queue_volume),
+        ("benchmark", benchmark),
+        ("cash_flows", cash_flows),
+    ] {
+        if let Some(arr) = arr {
+            inputs.push(digest(name, arr)?);
+        }
+    }
+    if let Some(s) = signal {
+        inputs.push(digest("signal", s)?);
+    }
+    if let Some(s) = shortable {
+        inputs.push(digest("shortable", s)?);
+    }
+    if let Some(d) = sub_bars {
+        for key in ["timestamp", "open", "high", "low", "close"] {
+            if let Some(arr) = d.get_item(key) {
+                inputs.push(digest(&format!("sub_bars.{}", key), arr.extract::<&PyArray1<f64>>()?)?);
+            }
+        }
+    }
+    let mut factor_series = Vec::new();
+    for (name, arr) in factors.iter().flat_map(|d| d.iter()) {
+        let name: String = name.extract()?;
+        let arr: &PyArray1<f64> = arr.extract()?;
+        inputs.push(digest(&format!("factors.{}", name), arr)?);
+        factor_series.push((name, array_to_vec(arr)?));
+    }
+    if let Some(t) = tags {
+        inputs.push(InputDigest::of_labels("tags", &tags_from_py(t)?));
+    }
+    if let Some(ids) = contract_ids {
+        inputs.push(InputDigest::of_labels("contract_ids", &tags_from_py(ids)?));
+    }
+    let regimes = regimes.map(tags_from_py).transpose()?;
+    if let Some(r) = &regimes {
+        inputs.push(InputDigest::of_labels("regimes", r));
+    }
+    let mut seeds = Vec::new();
+    if bootstrap_samples > 0 {
+        seeds.push(("bootstrap", bootstrap_seed));
+    }
+    if fill_probability.is_some() {
+        seeds.push(("fill", fill_seed));
+    }
+    let run_info = RunInfo::new(inputs, seeds);
+
+    // 1) Pull into Rust Vecs + validate
+    let mut market = market_from_py(timestamp, open, high, low, close)?;
+    let mut signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    if let Some(t) = tags {
+        signals.tags = Some(tags_from_py(t)?);
+    }
+    if let Some(s) = signal_strength {
+        apply_strength(&mut signals, &array_to_vec(s)?).map_err(PyValueError::new_err)?;
+    }
+    if let Some(d) = signal {
+        apply_direction(&mut signals, &array_to_vec(d)?).map_err(PyValueError::new_err)?;
+    }
+    let signal_mode = SignalMode::parse(signal_mode).map_err(PyValueError::new_err)?;
+    let expiry_fill = ExpiryFill::parse(expiry_fill).map_err(PyValueError::new_err)?;
+    let expiry_policy = ExpiryPolicy::parse(expiry_policy, expiry_extension).map_err(PyValueError::new_err)?;
+    let exit_priority = ExitPriority::parse(exit_priority).map_err(PyValueError::new_err)?;
+    let level_check = LevelCheck::parse(level_check).map_err(PyValueError::new_err)?;
+    let stop_type = StopType::parse(sl_order_type, sl_limit_offset).map_err(PyValueError::new_err)?;
+    let timeline = timeline.map(TimelineLayout::parse).transpose().map_err(PyValueError::new_err)?;
+    if signal_mode == SignalMode::Reverse && signals.direction.is_none() {
+        return Err(PyValueError::new_err("signal_mode=‘reverse’ needs a ‘signal’ array"));
+    }
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    skip_warmup(&mut signals, warmup_bars);
+    if let (Some(s), Some(e)) = (start_time, end_time) {
+        if s > e {
+            return Err(PyValueError::new_err("start_time must not be after end_time"));
+        }
+    }
+    restrict_time_range(&mut signals, &market.timestamps, start_time, end_time);
+    if !(0.0..1.0).contains(&bootstrap_confidence) {
+        return Err(PyValueError::new_err("bootstrap_confidence must be in [0, 1)"));
+    }
+
+    let rolls = match contract_ids {
+        Some(ids) => {
+            let ids = tags_from_py(ids)?;
+            let rp  = roll_prices.map(array_to_vec).transpose()?;
+            Some(build_schedule(&ids, &market.close, rp.as_deref()).map_err(PyValueError::new_err)?)
+        }
+        None => None,
+    };
+    let contract = ContractType::parse(contract_type).map_err(PyValueError::new_err)?;
+    if contract == ContractType::Inverse && rolls.is_some() {
+        return Err(PyValueError::new_err("contract_ids (rolls) are only supported for linear contracts"));
+    }
+    let latency = match (latency_bars, latency_seconds) {
+        (0, None)    => None,
+        (k, None)    => Some(Latency::Bars(k)),
+        (0, Some(s)) if s >= 0.0 => Some(Latency::Seconds(s)),
+        (0, Some(_)) => return Err(PyValueError::new_err("latency_seconds must be ≥ 0")),
+        _ => return Err(PyValueError::new_err("pass latency_bars or latency_seconds, not both")),
+    };
+    let fill_model = fill_probability.map(|p| FillModel {
+        touch_probability: p,
+        full_fill_depth:   fill_full_depth,
+        seed:              fill_seed,
+    });
+    if let Some(fm) = &fill_model {
+        fm.validate().map_err(PyValueError::new_err)?;
+    }
+    let queue_model = match (bid, ask, bid_size, ask_size) {
+        (None, None, None, None) => None,
+        (Some(bid), Some(ask), Some(bid_size), Some(ask_size)) => {
+            let volume = queue_volume.or(volume).ok_or_else(|| {
+                PyValueError::new_err("the queue model needs queue_volume (or volume)")
+            })?;
+            let q = QueueModel {
+                bid:         array_to_vec(bid)?,
+                ask:         array_to_vec(ask)?,
+                bid_size:    array_to_vec(bid_size)?,
+                ask_size:    array_to_vec(ask_size)?,
+                volume:      array_to_vec(volume)?,
+                level_share: queue_volume_share,
+            };
+            q.validate(market.close.len()).map_err(PyValueError::new_err)?;
+            if fill_model.is_some() {
+                return Err(PyValueError::new_err("pass fill_probability or L1 quotes (queue model), not both"));
+            }
+            if rolls.is_some() {
+                return Err(PyValueError::new_err("the queue model is not supported with contract_ids (rolls)"));
+            }
+            Some(q)
+        }
+        _ => return Err(PyValueError::new_err("bid, ask, bid_size and ask_size must be given together")),
+    };
+    let trailing_drawdown = match max_trailing_drawdown {
+        Some(limit) if limit.is_nan() || limit < 0.0 => {
+            return Err(PyValueError::new_err("max_trailing_drawdown must be ≥ 0"));
+        }
+        Some(_) if rolls.is_some() => {
+            return Err(PyValueError::new_err("max_trailing_drawdown is not supported with contract_ids (rolls)"));
+        }
+        Some(limit) => Some(TrailingDrawdown { limit, relative: trailing_drawdown_pct }),
+        None => None,
+    };
+    let throttle = match throttle_window {
+        Some(0) => return Err(PyValueError::new_err("throttle_window must be ≥ 1")),
+        Some(_) if !(0.0..=1.0).contains(&throttle_scale) => {
+            return Err(PyValueError::new_err("throttle_scale must be in [0, 1]"));
+        }
+        Some(window) => Some(EquityThrottle { window, scale: throttle_scale }),
+        None => None,
+    };
+    if max_positions == Some(0) {
+        return Err(PyValueError::new_err("max_positions must be ≥ 1"));
+    }
+    if compounding && initial_equity <= 0.0 {
+        return Err(PyValueError::new_err("compounding needs initial_equity > 0"));
+    }
+    let account = AccountRules { max_positions, cash_constrained, compounding };
+    let loss_cap = match max_loss {
+        Some(amount) if amount.is_nan() || amount <= 0.0 => {
+            return Err(PyValueError::new_err("max_loss must be > 0"));
+        }
+        Some(amount) => Some(LossCap {
+            amount,
+            mode: LossCapMode::parse(max_loss_mode).map_err(PyValueError::new_err)?,
+        }),
+        None => None,
+    };
+    let size_mode = SizeMode::parse(size_mode).map_err(PyValueError::new_err)?;
+    if size_mode == SizeMode::Notional && rolls.is_some() {
+        return Err(PyValueError::new_err("size_mode=\"notional\" is not supported with contract_ids (rolls)"));
+    }
+    let filters = InstrumentFilters { tick_size, lot_size, min_notional };
+    filters.validate().map_err(PyValueError::new_err)?;
+    if tick_size.is_some() && rolls.is_some() {
+        return Err(PyValueError::new_err("tick_size is not supported with contract_ids (rolls)"));
+    }
+    let filters = (filters != InstrumentFilters::default()).then_some(filters);
+    let partial_fills = if fill_rate.is_some() || volume.is_some() {
+        if fill_rate.is_some_and(|r| r.is_nan() || r <= 0.0) {
+            return Err(PyValueError::new_err("fill_rate must be > 0"));
+        }
+        if participation_rate.is_nan() || participation_rate <= 0.0 {
+            return Err(PyValueError::new_err("participation_rate must be > 0"));
+        }
+        if rolls.is_some() {
+            return Err(PyValueError::new_err("partial fills are not supported with contract_ids (rolls)"));
+        }
+        let volume = volume.map(array_to_vec).transpose()?;
+        if volume.as_ref().is_some_and(|v| v.len() != market.close.len()) {
+            return Err(PyValueError::new_err("‘volume’ length must match the bar series"));
+        }
+        Some(PartialFills { fill_rate, volume, participation_rate })
+    } else {
+        None
+    };
+    if leverage.is_nan() || leverage <= 0.0 {
+        return Err(PyValueError::new_err("leverage must be > 0"));
+    }
+    let liquidation = maintenance_margin.map(|maintenance_margin| LiquidationModel {
+        maintenance_margin,
+        buffer: liquidation_buffer,
+    });
+    if let Some(model) = &liquidation {
+        model.validate(leverage).map_err(PyValueError::new_err)?;
+    }
+    let time_stop = match (sl_decay_bars, sl_decay_steps) {
+        (None, None)        => None,
+        (Some(bars), None)  => Some(TimeStop::Linear { bars }),
+        (None, Some(steps)) => Some(TimeStop::Steps(steps)),
+        _ => return Err(PyValueError::new_err("pass sl_decay_bars or sl_decay_steps, not both")),
+    };
+    let ratio_policy = RatioPolicy::parse(undefined_ratios, ratio_cap).map_err(PyValueError::new_err)?;
+    let returns = ReturnSpec {
+        basis:  EquityBasis::parse(equity_basis).map_err(PyValueError::new_err)?,
+        kind:   ReturnKind::parse(return_type).map_err(PyValueError::new_err)?,
+        equity: ReturnEquity::parse(return_equity).map_err(PyValueError::new_err)?,
+    };
+    let cost_accrual = CostAccrual::parse(cost_accrual).map_err(PyValueError::new_err)?;
+    let sub_bars = match sub_bars {
+        Some(_) if rolls.is_some() => {
+            return Err(PyValueError::new_err("sub_bars are not supported with contract_ids (rolls)"));
+        }
+        Some(d) => {
+            let get = |key: &str| -> PyResult<&PyArray1<f64>> {
+                d.get_item(key)
+                    .ok_or_else(|| PyValueError::new_err(format!("sub_bars has no ‘{}’", key)))?
+                    .extract()
+            };
+            let bars = market_from_py(get("timestamp")?, get("open")?, get("high")?, get("low")?, get("close")?)?;
+            Some(SubBars::new(bars, &market.timestamps))
+        }
+        None => None,
+    };
+    if regimes.as_ref().is_some_and(|r| r.len() != market.close.len()) {
+        return Err(PyValueError::new_err("‘regimes’ length must match the bar series"));
+    }
+    let benchmark = benchmark.map(array_to_vec).transpose()?;
+    if let Some(b) = &benchmark {
+        if b.len() != market.close.len() {
+            return Err(PyValueError::new_err("‘benchmark’ length must match the bar series"));
+        }
+        if b.iter().any(|x| x.is_nan() || *x <= 0.0) {
+            return Err(PyValueError::new_err("‘benchmark’ prices must be positive (no NaN)"));
+        }
+    }
+    let cash_flows = cash_flows.map(array_to_vec).transpose()?;
+    if let Some(f) = &cash_flows {
+        if f.len() != market.close.len() {
+            return Err(PyValueError::new_err("‘cash_flows’ length must match the bar series"));
+        }
+        if f.iter().any(|x| !x.is_finite()) {
+            return Err(PyValueError::new_err("‘cash_flows’ must be finite (0 for no flow)"));
+        }
+    }
+    if let Some((name, _)) = factor_series.iter().find(|(_, f)| f.len() != market.close.len()) {
+        return Err(PyValueError::new_err(format!("factor ‘{}’ length must match the bar series", name)));
+    }
+    if let Some(edges) = &duration_buckets {
+        if edges.iter().any(|e| e.is_nan() || *e <= 0.0) || edges.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(PyValueError::new_err("duration_buckets must be increasing positive seconds"));
+        }
+    }
+    if beta_window == 1 {
+        return Err(PyValueError::new_err("beta_window must be 0 (off) or ≥ 2"));
+    }
+    let funding_rates = funding_rates.map(array_to_vec).transpose()?;
+    if let Some(rates) = &funding_rates {
+        if rates.len() != market.close.len() {
+            return Err(PyValueError::new_err("‘funding_rates’ length must match the bar series"));
+        }
+        if rolls.is_some() {
+            return Err(PyValueError::new_err("funding_rates are not supported with contract_ids (rolls)"));
+        }
+    }
+    let mark_price = mark_price.map(array_to_vec).transpose()?;
+    if let Some(mark) = &mark_price {
+        if mark.len() != market.close.len() {
+            return Err(PyValueError::new_err("‘mark_price’ length must match the bar series"));
+        }
+        if mark.iter().any(|x| x.is_nan() || *x <= 0.0) {
+            return Err(PyValueError::new_err("‘mark_price’ must be positive (no NaN)"));
+        }
+        if rolls.is_some() {
+            return Err(PyValueError::new_err("mark_price is not supported with contract_ids (rolls)"));
+        }
+    }
+    let fx_rates = fx_rates.map(array_to_vec).transpose()?;
+    if let Some(rates) = &fx_rates {
+        validate_rates(rates, market.close.len()).map_err(PyValueError::new_err)?;
+        if rolls.is_some() {
+            return Err(PyValueError::new_err("fx_rates are not supported with contract_ids (rolls)"));
+        }
+    }
+    let mut dividends = dividends.map(array_to_vec).transpose()?;
+    if dividends.as_ref().is_some_and(|d| d.len() != market.close.len()) {
+        return Err(PyValueError::new_err("‘dividends’ length must match the bar series"));
+    }
+    if let Some(factors) = split_factors {
+        let factors = array_to_vec(factors)?;
+        if factors.len() != market.close.len() {
+            return Err(PyValueError::new_err("‘split_factors’ length must match the bar series"));
+        }
+        if rolls.is_some() || contract == ContractType::Inverse {
+            return Err(PyValueError::new_err("split_factors need a linear contract without contract_ids (rolls)"));
+        }
+        adjust_for_splits(&mut market, &mut signals, dividends.as_mut(), &factors, size_mode);
+    }
+    if dividends.is_some() && (rolls.is_some() || contract == ContractType::Inverse) {
+        return Err(PyValueError::new_err("dividends need a linear contract without contract_ids (rolls)"));
+    }
+    let shortable = shortable.map(array_to_vec).transpose()?;
+    let borrow_units = borrow_available.map(array_to_vec).transpose()?;
+    let n = market.close.len();
+    if shortable.as_ref().is_some_and(|s| s.len() != n) || borrow_units.as_ref().is_some_and(|b| b.len() != n) {
+        return Err(PyValueError::new_err("‘shortable’ / ‘borrow_available’ length must match the bar series"));
+    }
+    if borrow_units.as_ref().is_some_and(|b| b.iter().any(|u| u.is_nan() || *u < 0.0)) {
+        return Err(PyValueError::new_err("borrow_available must be ≥ 0 (inf for unlimited)"));
+    }
+    let borrow_available = (shortable.is_some() || borrow_units.is_some())
+        .then(|| borrow_availability(shortable.as_deref(), borrow_units.as_deref(), n));
+    let chandelier = match trail_atr {
+        Some(atr) => Some(Chandelier { atr: array_to_vec(atr)?, mult: trail_mult }),
+        None      => None,
+    };
+    let exit_rules = if amendments.is_some() || time_stop.is_some() || chandelier.is_some() {
+        if rolls.is_some() {
+            return Err(PyValueError::new_err(
+                "amendments and stop schedules are not supported with contract_ids (rolls)"
+            ));
+        }
+        let amendments = amendments.map(amendments_from_py).transpose()?.unwrap_or_default();
+        let rules = ExitRules::new(amendments, time_stop, chandelier);
+        rules.validate(market.close.len()).map_err(PyValueError::new_err)?;
+        Some(rules)
+    } else {
+        None
+    };
+
+    // a taker rate replaces both market‐side rates
+    let config = BacktestConfig {
+        entry_fee_rate: taker_fee_rate.unwrap_or(entry_fee_rate),
+        exit_fee_rate:  taker_fee_rate.unwrap_or(exit_fee_rate),
+        maker_fee_rate,
+        slippage_rate,
+        slippage_sl,
+        slippage_tp,
+        slippage_exp,
+        initial_equity,
+        leverage: Some(leverage),
+        bootstrap: (bootstrap_samples > 0).then_some(BootstrapOptions {
+            n_samples:    bootstrap_samples,
+            block_length: bootstrap_block_length,
+            seed:         bootstrap_seed,
+            confidence:   bootstrap_confidence,
+        }),
+        rolls,
+        contract,
+        size_mode,
+        latency,
+        fill_model,
+        queue_model,
+        trailing_drawdown,
+        throttle,
+        account,
+        loss_cap,
+        filters,
+        partial_fills,
+        signal_mode,
+        exit_rules,
+        funding_rates,
+        mark_price,
+        fx_rates,
+        dividends,
+        borrow_available,
+        gross_metrics,
+        weighted_returns,
+        check_invariants,
+        ratio_policy,
+        cost_accrual,
+        net_floating,
+        returns,
+        sub_bars,
+        expiry_fill,
+        expiry_policy,
+        exit_priority,
+        level_check,
+        liquidation,
+        stop_type,
+        overlap_threshold: Some(overlap_threshold),
+        regimes,
+        benchmark,
+        cash_flows,
+        factors: factor_series,
+        beta_window,
+        autocorr_lags,
+        duration_edges: duration_buckets,
+    };
+    config.validate_fees().map_err(PyValueError::new_err)?;
+
+    // 2) Entries → exits → exposure → metrics
+    let result = py.allow_threads(|| run_engine(&market, &signals, &config));
+
+    // 3) Marshal Python output
+    let out = result_to_py(py, &result)?;
+    if config.trailing_drawdown.is_some() {
+        out.set_item("drawdown_breach", result.breach.as_ref().map(|b| breach_to_py(py, b)).transpose()?)?;
+    }
+    if orders {
+        let log = expand_orders(&result.closed, &result.open, &market.timestamps);
+        out.set_item("orders", orders_to_py(py, &log)?)?;
+    }
+    if config.liquidation.is_some() {
+        let positions: Vec<&Position> = result.closed.iter().chain(&result.open).collect();
+        let flags = near_liquidation_flags(&positions, market.close.len());
+        out.set_item("near_liquidation", PyArray1::from_vec(py, flags))?;
+    }
+    if let Some(layout) = timeline {
+        let positions: Vec<&Position> = result.closed.iter().chain(&result.open).collect();
+        out.set_item("timeline", timeline_to_py(py, &positions, &market.timestamps, layout)?)?;
+    }
+    let run_info = run_info_to_py(py, &run_info, params)?;
+    if let Some(path) = journal {
+        out.set_item("journal_run_id", append_run(py, path, journal_label, run_info, &result)?)?;
+    }
+    out.set_item("run_info", run_info)?;
+    Ok(out.into())
+}
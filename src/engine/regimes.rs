@@ -0,0 +1,73 @@
+// src/engine/regimes.rs
+
+use crate::engine::{
+    exposure::ExposureSnapshot,
+    metrics::{compute_trade_metrics, RatioPolicy, SideTradeMetrics},
+    position::Position,
+};
+
+/// Metrics of the bars and trades under one regime label
+#[derive(Debug)]
+pub struct RegimeMetrics {
+    pub label:        String,
+    pub bars:         usize,
+    pub bar_share:    f64,
+    /// Equity change over this regime's bars
+    pub pnl:          f64,
+    pub mean_return:  f64,
+    pub volatility:   f64,
+    /// Per‐bar Sharpe of this regime's bar returns
+    pub sharpe_ratio: f64,
+    /// Trades bucketed by the regime of their entry bar
+    pub trades:       SideTradeMetrics,
+}
+
+/// Bucket bars and trades by per‐bar regime label (first‐seen order;
+/// unlabelled bars are left out).  `returns[i-1]` is bar i's return.
+pub fn regime_breakdown(
+    regimes: &[Option<String>],
+    closed: &[Position],
+    exposure: &[ExposureSnapshot],
+    returns: &[f64],
+    policy: RatioPolicy,
+) -> Vec<RegimeMetrics> {
+    let mut labels: Vec<&str> = Vec::new();
+    for r in regimes.iter().flatten() {
+        if !labels.contains(&r.as_str()) {
+            labels.push(r);
+        }
+    }
+    let n = exposure.len();
+
+    labels
+        .into_iter()
+        .map(|label| {
+            let in_regime = |i: usize| regimes.get(i).and_then(|r| r.as_deref()) == Some(label);
+            let bars: Vec<usize> = (0..n).filter(|&i| in_regime(i)).collect();
+            let pnl = bars.iter().fold(0.0, |acc, &i| {
+                let prev = if i > 0 { exposure[i - 1].total_equity } else { 0.0 };
+                acc + exposure[i].total_equity - prev
+            });
+            let rets: Vec<f64> = bars.iter().filter(|&&i| i > 0).map(|&i| returns[i - 1]).collect();
+            let m = rets.len() as f64;
+            let mean_return = if m > 0.0 { rets.iter().sum::<f64>() / m } else { 0.0 };
+            let volatility = if m > 1.0 {
+                (rets.iter().map(|x| (x - mean_return).powi(2)).sum::<f64>() / (m - 1.0)).sqrt()
+            } else {
+                0.0
+            };
+            let trades: Vec<&Position> = closed.iter().filter(|p| in_regime(p.entry_index)).collect();
+
+            RegimeMetrics {
+                label:        label.to_string(),
+                bars:         bars.len(),
+                bar_share:    if n > 0 { bars.len() as f64 / n as f64 } else { 0.0 },
+                pnl,
+                mean_return,
+                volatility,
+                sharpe_ratio: policy.ratio(mean_return, volatility, 0.0),
+                trades:       compute_trade_metrics(trades, policy),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,164 @@
+// src/engine/rolls.rs
+
+use crate::engine::{
+    backtest::{BacktestConfig, MarketData, SignalSet},
+    position::Position,
+};
+
+/// Contract roll bars and the price the expiring contract is closed at
+#[derive(Clone, Debug, Default)]
+pub struct RollSchedule {
+    /// Bars whose OHLC belong to a new contract
+    pub roll_bars:  Vec<usize>,
+    /// Old‐contract exit price for each roll bar
+    pub old_prices: Vec<f64>,
+}
+
+/// Rolls wherever the contract label changes.  The expiring contract is
+/// closed at `roll_prices[j]` when given (and not NaN), else at `close[j-1]`.
+pub fn build_schedule(
+    contract_ids: &[Option<String>],
+    close: &[f64],
+    roll_prices: Option<&[f64]>,
+) -> Result<RollSchedule, String> {
+    if contract_ids.len() != close.len() {
+        return Err(format!(
+            "‘contract_ids’ length {} != expected {}", contract_ids.len(), close.len()
+        ));
+    }
+    if let Some(rp) = roll_prices {
+        if rp.len() != close.len() {
+            return Err(format!(
+                "‘roll_prices’ length {} != expected {}", rp.len(), close.len()
+            ));
+        }
+    }
+    let mut schedule = RollSchedule::default();
+    for j in 1..close.len() {
+        if contract_ids[j] != contract_ids[j - 1] {
+            let old = roll_prices
+                .map(|rp| rp[j])
+                .filter(|p| !p.is_nan())
+                .unwrap_or(close[j - 1]);
+            schedule.roll_bars.push(j);
+            schedule.old_prices.push(old);
+        }
+    }
+    Ok(schedule)
+}
+
+/// Panama back‐adjustment offsets: `adjusted[t] = raw[t] + offset[t]`, with the
+/// latest contract left unadjusted
+pub fn adjustment_offsets(schedule: &RollSchedule, open: &[f64]) -> Vec<f64> {
+    let n = open.len();
+    let mut offsets = vec![0.0; n];
+    let mut acc = 0.0;
+    let mut r = schedule.roll_bars.len();
+    for t in (0..n).rev() {
+        // rolls strictly after t shift bar t
+        while r > 0 && schedule.roll_bars[r - 1] > t {
+            r -= 1;
+            acc += open[schedule.roll_bars[r]] - schedule.old_prices[r];
+        }
+        offsets[t] = acc;
+    }
+    offsets
+}
+
+/// Continuous (back‐adjusted) market + signal levels
+pub fn back_adjust(market: &MarketData, signals: &SignalSet, offsets: &[f64]) -> (MarketData, SignalSet) {
+    let shift = |xs: &[f64]| -> Vec<f64> { xs.iter().zip(offsets).map(|(x, o)| x + o).collect() };
+    let adj_market = MarketData {
+        timestamps: market.timestamps.clone(),
+        open:       shift(&market.open),
+        high:       shift(&market.high),
+        low:        shift(&market.low),
+        close:      shift(&market.close),
+    };
+    let mut adj_signals = signals.clone();
+    adj_signals.long_tp  = shift(&signals.long_tp);
+    adj_signals.long_sl  = shift(&signals.long_sl);
+    adj_signals.short_tp = shift(&signals.short_tp);
+    adj_signals.short_sl = shift(&signals.short_sl);
+    (adj_market, adj_signals)
+}
+
+/// Re‐price positions simulated cost‐free on the continuous series.
+///
+/// Fills, fees and slippage are computed on raw contract prices; the gross
+/// PnL is the continuous price change, and every roll a position is carried
+/// through costs an exit on the old contract plus an entry on the new one.
+/// Entry/exit prices stay in adjusted terms (for marking against the
+/// continuous series) until `to_raw_prices`.
+pub fn apply_roll_accounting(
+    positions: &mut [Position],
+    schedule: &RollSchedule,
+    offsets: &[f64],
+    raw_open: &[f64],
+    config: &BacktestConfig,
+) {
+    let s = config.slippage_rate;
+    let fees = config.exit_fees();
+    for pos in positions.iter_mut() {
+        let long = pos.position_type == "long";
+        let dir  = if long { 1.0 } else { -1.0 };
+
+        // entry (raw price, adverse slippage)
+        let raw_open_px = pos.entry_price - offsets[pos.entry_index];
+        let raw_entry   = raw_open_px * (1.0 + dir * s);
+        pos.slippage_entry = (raw_entry - raw_open_px).abs();
+        pos.fee_entry      = pos.position_size * raw_entry * config.entry_fee_rate;
+        let adj_entry      = raw_entry + offsets[pos.entry_index];
+        pos.entry_price    = adj_entry;
+
+        // rolls carried through
+        let last = pos.exit_index.unwrap_or(usize::MAX);
+        pos.roll_count = 0;
+        pos.roll_cost  = 0.0;
+        for (r, &bar) in schedule.roll_bars.iter().enumerate() {
+            if bar > pos.entry_index && bar <= last {
+                let old = schedule.old_prices[r];
+                let new = raw_open[bar];
+                pos.roll_count += 1;
+                pos.roll_cost  += pos.position_size
+                    * (old * (fees.taker + s) + new * (config.entry_fee_rate + s));
+            }
+        }
+
+        let Some(exit_i) = pos.exit_index else { continue };
+        let raw_level = pos.exit_price.unwrap_or(0.0) - offsets[exit_i];
+        let s_exit    = config.exit_slippage().for_condition(pos.exit_condition.as_deref().unwrap_or("EXP"));
+        let raw_exit  = raw_level * (1.0 - dir * s_exit);
+        pos.slippage_exit = (raw_level - raw_exit).abs();
+        pos.fee_exit      = pos.position_size * raw_exit * fees.for_liquidity(pos.exit_liquidity.unwrap_or_default());
+        let adj_exit      = raw_exit + offsets[exit_i];
+
+        let gross = dir * (adj_exit - adj_entry) * pos.position_size;
+        let pnl   = gross - pos.fee_entry - pos.fee_exit - pos.roll_cost;
+        let notional = raw_entry * pos.position_size;
+
+        pos.exit_price      = Some(adj_exit);
+        pos.absolute_return = Some(if raw_entry != 0.0 { (adj_exit - adj_entry) / raw_entry } else { 0.0 });
+        pos.real_return     = Some(if notional != 0.0 { pnl / notional } else { 0.0 });
+        pos.pnl             = Some(pnl);
+    }
+}
+
+/// Report entry/exit prices and levels in raw contract terms
+pub fn to_raw_prices(positions: &mut [Position], offsets: &[f64]) {
+    for pos in positions.iter_mut() {
+        pos.entry_price -= offsets[pos.entry_index];
+        pos.tp          -= offsets[pos.signal_index];
+        pos.sl          -= offsets[pos.signal_index];
+        pos.entry_sl    -= offsets[pos.signal_index];
+        if let Some(liq) = pos.liquidation_price.as_mut() {
+            *liq -= offsets[pos.entry_index];
+        }
+        if let Some(b) = &mut pos.bracket {
+            b.shift(offsets[pos.signal_index]);
+        }
+        if let (Some(exit_i), Some(px)) = (pos.exit_index, pos.exit_price.as_mut()) {
+            *px -= offsets[exit_i];
+        }
+    }
+}
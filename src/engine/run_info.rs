@@ -0,0 +1,88 @@
+// src/engine/run_info.rs
+
+use crate::engine::par;
+
+/// Crate version stamped into every manifest
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Layout version of the result dict / JSON document; bump on any
+/// breaking change and teach `schema::upgrade` the step from the last one
+pub const SCHEMA_VERSION: u32 = 1;
+
+const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME:  u64 = 0x0000_0100_0000_01B3;
+
+/// Element hashed as one little‐endian 64‐bit word
+pub trait Word: Copy {
+    fn word(self) -> u64;
+}
+
+impl Word for f64 {
+    fn word(self) -> u64 { self.to_bits() }
+}
+
+impl Word for i64 {
+    fn word(self) -> u64 { self as u64 }
+}
+
+impl Word for bool {
+    fn word(self) -> u64 { self as u64 }
+}
+
+fn fnv1a_bytes(mut h: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        h ^= b as u64;
+        h = h.wrapping_mul(FNV_PRIME);
+    }
+    h
+}
+
+/// FNV‐1a over the raw bits, so equal hashes mean bit‐identical inputs
+pub fn fnv1a<T: Word>(xs: &[T]) -> u64 {
+    xs.iter().fold(FNV_OFFSET, |h, x| fnv1a_bytes(h, &x.word().to_le_bytes()))
+}
+
+/// FNV‐1a over optional labels (each length‐prefixed, None as a zero byte)
+pub fn fnv1a_labels(xs: &[Option<String>]) -> u64 {
+    xs.iter().fold(FNV_OFFSET, |h, x| match x {
+        Some(s) => {
+            let h = fnv1a_bytes(fnv1a_bytes(h, &[1]), &(s.len() as u64).to_le_bytes());
+            fnv1a_bytes(h, s.as_bytes())
+        }
+        None => fnv1a_bytes(h, &[0]),
+    })
+}
+
+/// Length and hash of one input series
+#[derive(Clone, Debug)]
+pub struct InputDigest {
+    pub name:   String,
+    pub length: usize,
+    pub hash:   u64,
+}
+
+impl InputDigest {
+    pub fn of<T: Word>(name: &str, xs: &[T]) -> Self {
+        InputDigest { name: name.to_string(), length: xs.len(), hash: fnv1a(xs) }
+    }
+
+    pub fn of_labels(name: &str, xs: &[Option<String>]) -> Self {
+        InputDigest { name: name.to_string(), length: xs.len(), hash: fnv1a_labels(xs) }
+    }
+}
+
+/// What a run needs to be reproduced: inputs, thread count and RNG seeds
+/// (parameter values are recorded by the Python entry point)
+#[derive(Clone, Debug)]
+pub struct RunInfo {
+    pub inputs:  Vec<InputDigest>,
+    pub threads: usize,
+    /// Seeds of the random components that were enabled
+    pub seeds:   Vec<(&'static str, u64)>,
+}
+
+impl RunInfo {
+    pub fn new(inputs: Vec<InputDigest>, seeds: Vec<(&'static str, u64)>) -> Self {
+        RunInfo { inputs, threads: par::current_num_threads(), seeds }
+    }
+}
@@ -1,12 +1,32 @@
 // src/engine/scan_entries.rs
 
-use crate::engine::position::Position;
+use std::ops::Range;
 
-/// For each signal on bar i:
+use crate::engine::{
+    backtest::SkippedEntry,
+    contract::{ContractType, SizeMode},
+    instrument::InstrumentFilters,
+    latency::Latency,
+    liquidity::Liquidity,
+    loss_cap::LossCap,
+    position::Position,
+};
+
+/// For each signal on a bar i in `signal_bars`:
 ///  - we fill at bar i+1 open (or i if it's the last bar)
 ///  - we panic if both long[i] and short[i] are true
 ///  - expiration_times is aligned to the *signal* bar (i)
+///  - with `latency`, the fill moves to the open of the delayed bar (the
+///    last bar if the delay runs past the data)
+///  - with `loss_cap`, stops risking more than the cap at the fill price are
+///    tightened or the size cut (originals kept on the position)
+///  - with `SizeMode::Notional`, sizes are notionals converted to units at
+///    the actual fill price
+///  - with `filters`, fills and levels are rounded to the tick, sizes floored
+///    to the lot step, and orders left empty or below the minimum notional
+///    are pushed to `skipped` instead
 pub fn scan_entries(
+    signal_bars: Range<usize>,
     timestamps: &[f64],
     open: &[f64],
     long: &[bool],
@@ -20,12 +40,18 @@ pub fn scan_entries(
     expiration_times: &[f64],
     entry_fee_rate: f64,
     slippage_rate: f64,
+    contract: ContractType,
+    size_mode: SizeMode,
+    latency: Option<Latency>,
+    loss_cap: Option<LossCap>,
+    filters: Option<InstrumentFilters>,
+    skipped: &mut Vec<SkippedEntry>,
 ) -> Vec<Position> {
     let n = open.len();
 
     // 1) Mutual-exclusion check + count total signals
     let mut total_signals = 0;
-    for i in 0..n {
+    for i in signal_bars.clone() {
         if long[i] && short[i] {
             panic!("Signal conflict at bar {}: both long and short are true", i);
         }
@@ -38,57 +64,126 @@ pub fn scan_entries(
     let mut positions = Vec::with_capacity(total_signals);
 
     // 3) Build Position structs
-    for i in 0..n {
+    for i in signal_bars {
         if !(long[i] || short[i]) {
             continue;
         }
 
         // fill bar
-        let entry_idx = if i + 1 < n { i + 1 } else { i };
-        let entry_ts  = timestamps[entry_idx];
-        let price     = open[entry_idx];
+        let intended = if i + 1 < n { i + 1 } else { i };
 
         // expiration is aligned to the *signal* bar
         let exp_time = expiration_times.get(i).copied();
         if let Some(et) = exp_time {
-            if et < entry_ts {
+            if et < timestamps[intended] {
                 panic!(
                     "Expiration time {} < entry time {} for signal bar {}",
-                    et, entry_ts, i
+                    et, timestamps[intended], i
                 );
             }
         }
 
+        let entry_idx = match latency {
+            Some(lat) => lat.fill_bar(timestamps, intended, intended).unwrap_or(n - 1),
+            None      => intended,
+        };
+        let entry_ts  = timestamps[entry_idx];
+        let price     = open[entry_idx];
+
         // helper closure to push a new position
-        let mut push_pos = |side: &str, tp: f64, sl: f64, size: f64| {
-            let entry_price    = if side=="long" {
-                price * (1.0 + slippage_rate)
+        let mut push_pos = |side: &str, tp: f64, raw_sl: f64, sized: f64| {
+            let f = filters.unwrap_or_default();
+            let entry_price    = f.round_fill(
+                if side=="long" {
+                    price * (1.0 + slippage_rate)
+                } else {
+                    price * (1.0 - slippage_rate)
+                },
+                side=="long",
+            );
+            let slippage_entry = (entry_price - price).abs();
+            let raw_size = match size_mode {
+                SizeMode::Units    => sized,
+                SizeMode::Notional => contract.size_for_notional(entry_price, sized),
+            };
+            let (sl, size) = match loss_cap {
+                Some(cap) => cap.apply(contract, side=="long", entry_price, raw_sl, raw_size),
+                None      => (raw_sl, raw_size),
+            };
+            let (tp, sl, size) = (f.round_level(tp), f.round_level(sl), f.round_size(size));
+            let reject = if size <= 0.0 && raw_size > 0.0 {
+                Some("lot_size")
+            } else if f.min_notional.is_some_and(|m| contract.notional(entry_price, size) < m) {
+                Some("min_notional")
             } else {
-                price * (1.0 - slippage_rate)
+                None
             };
-            let slippage_entry = (entry_price - price).abs();
-            let fee_entry      = size * entry_price * entry_fee_rate;
+            if let Some(reason) = reject {
+                skipped.push(SkippedEntry {
+                    signal_index:  i,
+                    entry_index:   entry_idx,
+                    position_type: side.into(),
+                    reason,
+                });
+                return;
+            }
+            let fee_entry      = contract.notional(entry_price, size) * entry_fee_rate;
+            // adverse drift between the intended and the delayed open
+            let latency_cost   = contract.pnl(side=="long", open[intended], price, size);
 
             positions.push(Position {
-                position_id:      entry_ts,
+                position_id:      positions.len() as u64,
+                entry_time:       entry_ts,
                 position_type:    side.into(),
+                signal_index:     i,
                 entry_index:      entry_idx,
                 entry_price,
                 tp,
                 sl,
                 expiration_time:  exp_time,
                 exit_index:       None,
+                exit_time:        None,
+                expiry_slippage:  None,
+                expiry_action:    None,
                 exit_price:       None,
                 exit_condition:   None,
                 position_size:    size,
+                contract,
                 fee_entry,
                 fee_exit:         0.0,
+                entry_liquidity:  Liquidity::Taker,
+                exit_liquidity:   None,
                 slippage_entry,
                 slippage_exit:    0.0,
                 absolute_return:  None,
                 real_return:      None,
                 pnl:              None,
                 is_closed:        false,
+                roll_count:       0,
+                roll_cost:        0.0,
+                funding_cost:     0.0,
+                dividend_income:  0.0,
+                strategy_id:      None,
+                symbol:           None,
+                tag:              None,
+                entry_delay:      latency.map(|_| entry_ts - timestamps[intended]),
+                exit_delay:       None,
+                latency_cost,
+                tp_attempts:      0,
+                tp_rejections:    0,
+                throttled:        false,
+                entry_sl:         sl,
+                original_sl:      (sl != raw_sl).then_some(raw_sl),
+                original_size:    (size != raw_size).then_some(raw_size),
+                fills:            Vec::new(),
+                unfilled_size:    0.0,
+                level_changes:    Vec::new(),
+                bracket:          None,
+                mfe:              0.0,
+                mae:              0.0,
+                liquidation_price: None,
+                near_liquidation: Vec::new(),
+                liquidation_bar:  None,
             });
         };
 
@@ -0,0 +1,332 @@
+// src/engine/schema.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    array_to_vec,
+    backtest::{finish, MarketData},
+    config_file::RUN_KWARGS,
+    exposure::{compute_exposure_series_with, exposure_from_equity, mark_open_positions},
+    external::config_from_py,
+    metrics::SCALAR_METRICS,
+    output::result_to_py,
+    position::Position,
+    run_info::SCHEMA_VERSION,
+    stages::position_from_py,
+    trade_filter::TradeFilter,
+};
+
+/// Top‐level lists every schema has, created empty when an old result
+/// lacks them
+const LIST_KEYS: &[&str] = &[
+    "closed_positions",
+    "open_positions",
+    "exposure_time_series",
+    "warnings",
+    "trade_equity_curve",
+];
+
+/// Bring a result dict of any earlier layout up to `SCHEMA_VERSION` in
+/// place (dicts without a version predate versioning: version 0)
+fn upgrade(py: Python<'_>, d: &PyDict) -> PyResult<u32> {
+    let from: u32 = match d.get_item("schema_version") {
+        Some(v) if !v.is_none() => v.extract()?,
+        _ => 0,
+    };
+    if from > SCHEMA_VERSION {
+        return Err(PyValueError::new_err(format!(
+            "result schema {} is newer than this version's ({}); upgrade the backtester",
+            from, SCHEMA_VERSION
+        )));
+    }
+    if from < 1 {
+        // 0 → 1: same layout, but older runs may miss the later lists
+        for key in LIST_KEYS {
+            if d.get_item(key).is_none() {
+                d.set_item(key, PyList::empty(py))?;
+            }
+        }
+    }
+    if d.get_item("metrics").is_none() {
+        return Err(PyValueError::new_err("result has no ‘metrics’"));
+    }
+    d.set_item("schema_version", SCHEMA_VERSION)?;
+    Ok(from)
+}
+
+/// Overall scalar metric from a result's `metrics` dict (headline, trade or
+/// time‐series level)
+fn scalar_metric(metrics: &PyDict, name: &str) -> PyResult<Option<f64>> {
+    let Some(overall) = metrics.get_item("overall") else {
+        return Ok(None);
+    };
+    let overall: &PyDict = overall.downcast()?;
+    let mut levels = vec![overall];
+    for sub in ["trade_metrics", "time_metrics"] {
+        if let Some(d) = overall.get_item(sub) {
+            levels.push(d.downcast()?);
+        }
+    }
+    for level in levels {
+        if let Some(v) = level.get_item(name) {
+            return Ok(v.extract::<f64>().ok());
+        }
+    }
+    Ok(None)
+}
+
+/// A stored `run_backtest` result loaded back for comparison.
+///
+/// `from_dict` / `from_json` accept results written by any earlier version
+/// and upgrade them to the current schema; `loaded_from_schema` keeps the
+/// version the data was written with.
+#[pyclass(name = "BacktestResult")]
+pub struct StoredResult {
+    data: Py<PyDict>,
+    loaded_from_schema: u32,
+}
+
+#[pymethods]
+impl StoredResult {
+    /// Load (a copy of) a result dict
+    #[staticmethod]
+    fn from_dict(py: Python<'_>, result: &PyDict) -> PyResult<Self> {
+        let d = result.copy()?;
+        let from = upgrade(py, d)?;
+        Ok(StoredResult { data: d.into(), loaded_from_schema: from })
+    }
+
+    /// Load a result saved with `json.dumps(out)` or `to_json()`
+    #[staticmethod]
+    fn from_json(py: Python<'_>, text: &str) -> PyResult<Self> {
+        let parsed = py.import("json")?.call_method1("loads", (text,))?;
+        let d: &PyDict = parsed
+            .downcast()
+            .map_err(|_| PyValueError::new_err("JSON result must be an object"))?;
+        Self::from_dict(py, d)
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(self.data.as_ref(py).copy()?.into())
+    }
+
+    /// JSON text of the result (numpy arrays written as lists)
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("default", py.eval("lambda o: o.tolist()", None, None)?)?;
+        py.import("json")?.call_method("dumps", (self.data.as_ref(py),), Some(kwargs))?.extract()
+    }
+
+    #[getter]
+    fn schema_version(&self) -> u32 {
+        SCHEMA_VERSION
+    }
+
+    #[getter]
+    fn loaded_from_schema(&self) -> u32 {
+        self.loaded_from_schema
+    }
+
+    /// Crate version that produced the result (None without a manifest)
+    #[getter]
+    fn version(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        match self.data.as_ref(py).get_item("run_info") {
+            Some(info) => {
+                let info: &PyDict = info.downcast()?;
+                info.get_item("version").map(|v| v.extract()).transpose()
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[getter]
+    fn closed_positions(&self, py: Python<'_>) -> PyObject {
+        self.data.as_ref(py).get_item("closed_positions").into_py(py)
+    }
+
+    #[getter]
+    fn open_positions(&self, py: Python<'_>) -> PyObject {
+        self.data.as_ref(py).get_item("open_positions").into_py(py)
+    }
+
+    #[getter]
+    fn exposure_time_series(&self, py: Python<'_>) -> PyObject {
+        self.data.as_ref(py).get_item("exposure_time_series").into_py(py)
+    }
+
+    #[getter]
+    fn metrics(&self, py: Python<'_>) -> PyObject {
+        self.data.as_ref(py).get_item("metrics").into_py(py)
+    }
+
+    /// Overall scalar metrics by name (the `grid_search` metric names)
+    fn scalar_metrics(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let metrics: &PyDict = self
+            .data
+            .as_ref(py)
+            .get_item("metrics")
+            .ok_or_else(|| PyValueError::new_err("result has no ‘metrics’"))?
+            .downcast()?;
+        let out = PyDict::new(py);
+        for name in SCALAR_METRICS {
+            if let Some(v) = scalar_metric(metrics, name)? {
+                out.set_item(name, v)?;
+            }
+        }
+        Ok(out.into())
+    }
+
+    /// Side‐by‐side scalar metrics: `{name: {"self", "other", "delta"}}`
+    /// for every metric both results report (or the `metrics` chosen)
+    #[pyo3(signature=(other, metrics=None))]
+    fn compare(&self, py: Python<'_>, other: &StoredResult, metrics: Option<Vec<String>>) -> PyResult<PyObject> {
+        let names = metrics.unwrap_or_else(|| SCALAR_METRICS.iter().map(|s| s.to_string()).collect());
+        let mine: &PyDict = self
+            .data
+            .as_ref(py)
+            .get_item("metrics")
+            .ok_or_else(|| PyValueError::new_err("result has no ‘metrics’"))?
+            .downcast()?;
+        let theirs: &PyDict = other
+            .data
+            .as_ref(py)
+            .get_item("metrics")
+            .ok_or_else(|| PyValueError::new_err("result has no ‘metrics’"))?
+            .downcast()?;
+        let out = PyDict::new(py);
+        for name in &names {
+            if let (Some(a), Some(b)) = (scalar_metric(mine, name)?, scalar_metric(theirs, name)?) {
+                let d = PyDict::new(py);
+                d.set_item("self",  a)?;
+                d.set_item("other", b)?;
+                d.set_item("delta", a - b)?;
+                out.set_item(name, d)?;
+            }
+        }
+        Ok(out.into())
+    }
+
+    /// The result restricted to the trades matching every given criterion,
+    /// with the exposure curve and metrics re‐derived from them.
+    ///
+    /// Trades are selected by entry time (`start_time` ≤ entry <
+    /// `end_time`), `side`, `tag` and `exit_condition`; the curve still
+    /// spans every bar.  Open trades are marked at `prices` (the close
+    /// series of the run) when given, otherwise the subset's equity only
+    /// moves when its trades exit.  Cost and metric options come from the
+    /// run's `run_info` parameters.
+    #[pyo3(signature=(start_time=None, end_time=None, side=None, tag=None, exit_condition=None, prices=None))]
+    fn filter_trades(
+        &self,
+        py:             Python<'_>,
+        start_time:     Option<f64>,
+        end_time:       Option<f64>,
+        side:           Option<String>,
+        tag:            Option<String>,
+        exit_condition: Option<String>,
+        prices:         Option<&PyArray1<f64>>,
+    ) -> PyResult<Self> {
+        let filter = TradeFilter { start_time, end_time, side, tag, exit_condition };
+        filter.validate().map_err(PyValueError::new_err)?;
+        let data = self.data.as_ref(py);
+
+        // the run's options, as far as they shape the metrics
+        let params = data
+            .get_item("run_info")
+            .map(|info| info.downcast::<PyDict>()?.get_item("params").map(|p| p.downcast::<PyDict>()).transpose())
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| PyValueError::new_err("result has no ‘run_info’ parameters to rebuild its metrics from"))?;
+        let known = PyDict::new(py);
+        for (k, v) in params {
+            if RUN_KWARGS.contains(&k.extract::<&str>()?) && !v.is_none() {
+                known.set_item(k, v)?;
+            }
+        }
+        let config = config_from_py(Some(known))?;
+
+        let timestamps = data
+            .get_item("exposure_time_series")
+            .map(|l| l.downcast::<PyList>()?.iter().map(|s| s.get_item("timestamp")?.extract()).collect())
+            .transpose()?
+            .unwrap_or_else(Vec::new);
+        let n = timestamps.len();
+        if n == 0 {
+            return Err(PyValueError::new_err("result has no exposure series"));
+        }
+        let mut positions: Vec<Position> = Vec::new();
+        for key in ["closed_positions", "open_positions"] {
+            let list: &PyList = data
+                .get_item(key)
+                .ok_or_else(|| PyValueError::new_err(format!("result has no ‘{}’", key)))?
+                .downcast()?;
+            for d in list {
+                let mut pos = position_from_py(d.downcast()?)?;
+                pos.contract = config.contract;
+                if pos.entry_index >= n || pos.exit_index.is_some_and(|x| x >= n) {
+                    return Err(PyValueError::new_err("position bar indices don't fit the exposure series"));
+                }
+                if filter.matches(&pos) {
+                    positions.push(pos);
+                }
+            }
+        }
+        positions.sort_by_key(|p| p.position_id);
+
+        let close = prices.map(array_to_vec).transpose()?;
+        let (exposure, marks) = match &close {
+            Some(close) if close.len() != n => {
+                return Err(PyValueError::new_err("‘prices’ length must match the result's bars"));
+            }
+            Some(close) => (
+                compute_exposure_series_with(
+                    &positions, close, &timestamps, config.initial_equity, config.leverage(), config.exposure_options(),
+                ),
+                mark_open_positions(&positions, close),
+            ),
+            None => {
+                let mut equity = vec![0.0; n];
+                for pos in &positions {
+                    if let (Some(x), Some(pnl)) = (pos.exit_index, pos.pnl) {
+                        equity[x] += pnl;
+                    }
+                }
+                let mut cum = config.initial_equity;
+                for e in &mut equity {
+                    cum += *e;
+                    *e = cum;
+                }
+                (exposure_from_equity(&positions, &timestamps, &equity, config.initial_equity), Vec::new())
+            }
+        };
+        let market = MarketData {
+            close: close.unwrap_or_default(),
+            timestamps,
+            open: Vec::new(), high: Vec::new(), low: Vec::new(),
+        };
+        let result = finish(&market, positions, marks, exposure, &config);
+        let out = result_to_py(py, &result)?;
+        if let Some(info) = data.get_item("run_info") {
+            out.set_item("run_info", info)?;
+        }
+        let fd = PyDict::new(py);
+        fd.set_item("start_time",     filter.start_time)?;
+        fd.set_item("end_time",       filter.end_time)?;
+        fd.set_item("side",           filter.side)?;
+        fd.set_item("tag",            filter.tag)?;
+        fd.set_item("exit_condition", filter.exit_condition)?;
+        out.set_item("trade_filter", fd)?;
+        Ok(StoredResult { data: out.into(), loaded_from_schema: SCHEMA_VERSION })
+    }
+
+    fn __getitem__(&self, py: Python<'_>, key: &str) -> PyResult<PyObject> {
+        self.data
+            .as_ref(py)
+            .get_item(key)
+            .map(|v| v.into_py(py))
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(key.to_string()))
+    }
+}
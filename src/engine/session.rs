@@ -0,0 +1,229 @@
+// src/engine/session.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    market_from_py, signals_from_py,
+    backtest::{run_engine, validate_signals, BacktestConfig, MarketData, SignalSet},
+    metrics::{lookup_metric, lower_is_better},
+    optimize::{
+        apply_builtin_params, check_metric_name, override_signals, params_to_py,
+        rank_indices, ParamSet, BUILTIN_PARAMS,
+    },
+};
+
+const DEFAULT_METRICS: &[&str] = &["total_return", "sharpe_ratio", "max_drawdown", "number_of_trades"];
+
+/// Preloaded backtest for external optimizers (Optuna, Bayesian opt, ...).
+///
+/// The market data and base signals are copied into Rust once; each trial
+/// only ships a small parameter dict in and scalar metrics out, and the
+/// engine runs with the GIL released.
+#[pyclass]
+pub struct BacktestSession {
+    market:       MarketData,
+    signals:      SignalSet,
+    config:       BacktestConfig,
+    objective:    String,
+    metric_names: Vec<String>,
+    trials:       Vec<(ParamSet, Vec<f64>)>,
+}
+
+impl BacktestSession {
+    /// Resolve one trial's (signals, config); Python‐side work only
+    fn prepare(&self, params: &PyDict, overrides: Option<&PyDict>) -> PyResult<(ParamSet, SignalSet, BacktestConfig)> {
+        let mut set: ParamSet = Vec::with_capacity(params.len());
+        for (k, v) in params.iter() {
+            let name: String = k.extract()?;
+            if overrides.is_none() && !BUILTIN_PARAMS.contains(&name.as_str()) {
+                return Err(PyValueError::new_err(format!(
+                    "param ‘{}’ is not builtin ({:?}); pass overrides to handle it",
+                    name, BUILTIN_PARAMS
+                )));
+            }
+            set.push((name, v.extract()?));
+        }
+        let mut signals = match overrides {
+            Some(d) => override_signals(&self.signals, d)?,
+            None    => self.signals.clone(),
+        };
+        let mut config = self.config.clone();
+        apply_builtin_params(&mut signals, &mut config, &self.market.close, &set);
+        validate_signals(&signals, &self.market.timestamps).map_err(PyValueError::new_err)?;
+        Ok((set, signals, config))
+    }
+
+    fn run(&self, signals: &SignalSet, config: &BacktestConfig) -> Vec<f64> {
+        let summary = run_engine(&self.market, signals, config).metrics;
+        self.metric_names
+            .iter()
+            .map(|m| lookup_metric(&summary, m).unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    fn metrics_to_py<'py>(&self, py: Python<'py>, values: &[f64]) -> PyResult<&'py PyDict> {
+        let d = PyDict::new(py);
+        for (name, v) in self.metric_names.iter().zip(values) {
+            d.set_item(name, v)?;
+        }
+        Ok(d)
+    }
+
+    fn objective_col(&self) -> usize {
+        self.metric_names.iter().position(|m| *m == self.objective).unwrap()
+    }
+}
+
+#[pymethods]
+impl BacktestSession {
+    #[new]
+    #[pyo3(signature=(
+        timestamp, open, high, low, close,
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+        entry_fee_rate, exit_fee_rate, slippage_rate,
+        initial_equity,
+        objective="sharpe_ratio",
+        metrics=None
+    ))]
+    fn new(
+        timestamp:        &PyArray1<f64>,
+        open:             &PyArray1<f64>,
+        high:             &PyArray1<f64>,
+        low:              &PyArray1<f64>,
+        close:            &PyArray1<f64>,
+        long_signals:     &PyArray1<bool>,
+        short_signals:    &PyArray1<bool>,
+        long_tp:          &PyArray1<f64>,
+        long_sl:          &PyArray1<f64>,
+        short_tp:         &PyArray1<f64>,
+        short_sl:         &PyArray1<f64>,
+        long_size:        &PyArray1<f64>,
+        short_size:       &PyArray1<f64>,
+        expiration_times: &PyArray1<f64>,
+        entry_fee_rate:   f64,
+        exit_fee_rate:    f64,
+        slippage_rate:    f64,
+        initial_equity:   f64,
+        objective:        &str,
+        metrics:          Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        let market = market_from_py(timestamp, open, high, low, close)?;
+        let signals = signals_from_py(
+            long_signals, short_signals,
+            long_tp, long_sl, short_tp, short_sl,
+            long_size, short_size,
+            expiration_times,
+        )?;
+        validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+
+        check_metric_name(objective)?;
+        let mut metric_names: Vec<String> = metrics
+            .unwrap_or_else(|| DEFAULT_METRICS.iter().map(|s| s.to_string()).collect());
+        for m in &metric_names {
+            check_metric_name(m)?;
+        }
+        if !metric_names.iter().any(|m| m == objective) {
+            metric_names.insert(0, objective.to_string());
+        }
+
+        Ok(BacktestSession {
+            market,
+            signals,
+            config: BacktestConfig {
+                entry_fee_rate,
+                exit_fee_rate,
+                slippage_rate,
+                initial_equity,
+                ..BacktestConfig::default()
+            },
+            objective: objective.to_string(),
+            metric_names,
+            trials: Vec::new(),
+        })
+    }
+
+    /// Run one trial and return its scalar metrics (recorded in `trials`).
+    ///
+    /// `params` may use the builtin grid names; `overrides` optionally
+    /// replaces signal arrays for this trial (any `run_backtest` array key).
+    #[pyo3(signature=(params, overrides=None))]
+    fn evaluate(&mut self, py: Python<'_>, params: &PyDict, overrides: Option<&PyDict>) -> PyResult<PyObject> {
+        let (set, signals, config) = self.prepare(params, overrides)?;
+        let values = py.allow_threads(|| self.run(&signals, &config));
+        let out = self.metrics_to_py(py, &values)?;
+        self.trials.push((set, values));
+        Ok(out.into())
+    }
+
+    /// Like `evaluate`, but returns only the objective value
+    #[pyo3(signature=(params, overrides=None))]
+    fn score(&mut self, py: Python<'_>, params: &PyDict, overrides: Option<&PyDict>) -> PyResult<f64> {
+        let (set, signals, config) = self.prepare(params, overrides)?;
+        let values = py.allow_threads(|| self.run(&signals, &config));
+        let s = values[self.objective_col()];
+        self.trials.push((set, values));
+        Ok(s)
+    }
+
+    /// Evaluate a batch of parameter dicts in parallel
+    fn evaluate_many(&mut self, py: Python<'_>, batch: Vec<&PyDict>) -> PyResult<PyObject> {
+        let mut jobs = Vec::with_capacity(batch.len());
+        for params in batch {
+            jobs.push(self.prepare(params, None)?);
+        }
+        let this = &*self;
+        let values: Vec<Vec<f64>> = py.allow_threads(|| {
+            jobs.par_iter().map(|(_, s, c)| this.run(s, c)).collect()
+        });
+
+        let out = PyList::empty(py);
+        for v in &values {
+            out.append(self.metrics_to_py(py, v)?)?;
+        }
+        for ((set, _, _), v) in jobs.into_iter().zip(values) {
+            self.trials.push((set, v));
+        }
+        Ok(out.into())
+    }
+
+    /// Every recorded trial as `{"params", "metrics"}` dicts
+    #[getter]
+    fn trials(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let out = PyList::empty(py);
+        for (set, values) in &self.trials {
+            let d = PyDict::new(py);
+            d.set_item("params",  params_to_py(py, set)?)?;
+            d.set_item("metrics", self.metrics_to_py(py, values)?)?;
+            out.append(d)?;
+        }
+        Ok(out.into())
+    }
+
+    /// Best recorded trial by the objective (None before any trial)
+    fn best_trial(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let col = self.objective_col();
+        let scores: Vec<f64> = self.trials.iter().map(|(_, v)| v[col]).collect();
+        match rank_indices(&scores, lower_is_better(&self.objective)).first() {
+            Some(&i) => {
+                let (set, values) = &self.trials[i];
+                let d = PyDict::new(py);
+                d.set_item("params",  params_to_py(py, set)?)?;
+                d.set_item("metrics", self.metrics_to_py(py, values)?)?;
+                Ok(d.into())
+            }
+            None => Ok(py.None()),
+        }
+    }
+
+    #[getter]
+    fn objective(&self) -> String {
+        self.objective.clone()
+    }
+}
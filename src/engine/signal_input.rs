@@ -0,0 +1,159 @@
+// src/engine/signal_input.rs
+
+use crate::engine::{
+    backtest::{validate_length, BacktestConfig, MarketData, SignalSet},
+    position::Position,
+    simulate_exits::close_position,
+};
+
+/// Replace the boolean entry arrays with a signed conviction per bar.
+///
+/// `strength[i] > 0` enters long and `< 0` short, with the bar's size scaled
+/// by `|strength[i]|`; 0 (or NaN) stays flat.  The boolean arrays must be
+/// all false so the two formats can't disagree.
+pub fn apply_strength(signals: &mut SignalSet, strength: &[f64]) -> Result<(), String> {
+    let n = strength.len();
+    validate_length(&signals.long_signals,  "long_signals",  n)?;
+    validate_length(&signals.short_signals, "short_signals", n)?;
+    validate_length(&signals.long_size,     "long_size",     n)?;
+    validate_length(&signals.short_size,    "short_size",    n)?;
+    if signals.long_signals.iter().chain(&signals.short_signals).any(|&b| b) {
+        return Err("pass either boolean long/short signals or ‘signal_strength’, not both".into());
+    }
+    for (i, &s) in strength.iter().enumerate() {
+        if s.is_nan() || s == 0.0 {
+            continue;
+        }
+        if !(-1.0..=1.0).contains(&s) {
+            return Err(format!("signal_strength {} outside [-1, 1] at index {}", s, i));
+        }
+        if s > 0.0 {
+            signals.long_signals[i] = true;
+            signals.long_size[i]   *= s;
+        } else {
+            signals.short_signals[i] = true;
+            signals.short_size[i]   *= -s;
+        }
+    }
+    Ok(())
+}
+
+/// What a `signal` (−1/0/+1) change does to positions already open
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignalMode {
+    /// Transitions only open positions; exits stay TP/SL/expiry
+    #[default]
+    Independent,
+    /// Positions also close once the signal leaves their side
+    Reverse,
+}
+
+impl SignalMode {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "independent" => Ok(SignalMode::Independent),
+            "reverse"     => Ok(SignalMode::Reverse),
+            other => Err(format!(
+                "unknown signal_mode ‘{}’ (expected ‘independent’ or ‘reverse’)", other
+            )),
+        }
+    }
+}
+
+/// Replace the boolean entry arrays with one −1/0/+1 direction per bar.
+///
+/// A position opens on every change to +1 (long) or −1 (short), so
+/// flat→long and short→long enter long and holding the signal doesn't
+/// re‐enter.  The series is kept on `signals.direction` for
+/// `SignalMode::Reverse`.
+pub fn apply_direction(signals: &mut SignalSet, direction: &[i64]) -> Result<(), String> {
+    let n = direction.len();
+    validate_length(&signals.long_signals,  "long_signals",  n)?;
+    validate_length(&signals.short_signals, "short_signals", n)?;
+    if signals.long_signals.iter().chain(&signals.short_signals).any(|&b| b) {
+        return Err("pass either boolean long/short signals or ‘signal’, not both".into());
+    }
+    let mut prev = 0;
+    for (i, &d) in direction.iter().enumerate() {
+        if !(-1..=1).contains(&d) {
+            return Err(format!("signal {} not in {{-1, 0, 1}} at index {}", d, i));
+        }
+        if d != prev {
+            signals.long_signals[i]  = d == 1;
+            signals.short_signals[i] = d == -1;
+        }
+        prev = d;
+    }
+    signals.direction = Some(direction.to_vec());
+    Ok(())
+}
+
+/// Ignore entries on the first `bars` bars (indicator warm‐up); the bars
+/// still count for exits and marking.  With a direction signal, a side
+/// already held when the warm‐up ends is entered on its first bar.
+pub fn skip_warmup(signals: &mut SignalSet, bars: usize) {
+    let n = signals.long_signals.len();
+    let bars = bars.min(n);
+    signals.long_signals[..bars].fill(false);
+    signals.short_signals[..bars].fill(false);
+    if let Some(d) = signals.direction.as_ref().and_then(|dir| dir.get(bars)) {
+        if bars > 0 && *d != 0 {
+            signals.long_signals[bars]  = *d == 1;
+            signals.short_signals[bars] = *d == -1;
+        }
+    }
+}
+
+/// Trade only within `[start, end]`: entries before `start` are dropped as
+/// in `skip_warmup` (earlier bars stay as context), entries after `end` are
+/// dropped, and positions still open expire at `end` at the latest.
+pub fn restrict_time_range(signals: &mut SignalSet, timestamps: &[f64], start: Option<f64>, end: Option<f64>) {
+    if let Some(start) = start {
+        skip_warmup(signals, timestamps.partition_point(|&ts| ts < start));
+    }
+    if let Some(end) = end {
+        let last = timestamps.partition_point(|&ts| ts <= end);
+        signals.long_signals[last..].fill(false);
+        signals.short_signals[last..].fill(false);
+        for exp in signals.expiration_times.iter_mut() {
+            *exp = exp.min(end);
+        }
+    }
+}
+
+/// `SignalMode::Reverse`: close each position at the fill of the first later
+/// signal bar that isn't its side (the bar the opposite entry fills on),
+/// unless TP/SL/expiry closed it earlier.  Exits fill at that bar's open; a
+/// flip with no later bar to fill on (the last bar) leaves the position open.
+pub fn close_on_signal(
+    positions: &mut [Position],
+    direction: &[i64],
+    market: &MarketData,
+    config: &BacktestConfig,
+) {
+    let n = direction.len();
+    let ts = &market.timestamps;
+    for pos in positions.iter_mut() {
+        let side = if pos.position_type == "long" { 1 } else { -1 };
+        let Some(j) = (pos.signal_index + 1..n).find(|&j| direction[j] != side) else { continue };
+        if j + 1 >= n {
+            continue;
+        }
+        let intended = j + 1;
+        let bar = match config.latency {
+            Some(lat) => match lat.fill_bar(ts, intended, intended) {
+                Some(bar) => bar,
+                None      => continue,
+            },
+            None => intended,
+        };
+        if bar <= pos.entry_index || pos.exit_index.is_some_and(|e| e < bar) {
+            continue;
+        }
+        pos.exit_delay = config.latency.map(|_| ts[bar] - ts[intended]);
+        close_position(
+            pos, bar, market.open[bar], "SIGNAL",
+            config.exit_fee_rate, config.exit_slippage().for_condition("SIGNAL"), config.filters,
+        );
+    }
+}
@@ -0,0 +1,319 @@
+// src/engine/significance.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    market_from_py, signals_from_py,
+    backtest::{run_engine, validate_signals, BacktestConfig, MarketData, SignalSet},
+    metrics::{lookup_metric, lower_is_better},
+    optimize::check_metric_name,
+    output::distribution_to_py,
+    rng::Rng,
+    stats::summarize,
+};
+
+const DEFAULT_METRICS: &[&str] = &["total_pnl", "sharpe_ratio", "max_drawdown", "win_rate"];
+
+/// Where an observed metric falls in a null distribution
+pub struct NullComparison {
+    pub observed:   f64,
+    /// One‐sided, (1 + #null at least as good) / (N + 1)
+    pub p_value:    f64,
+    /// Share of null samples strictly worse than observed
+    pub percentile: f64,
+    pub null:       Vec<f64>,
+}
+
+pub fn compare_to_null(name: &str, observed: f64, null: Vec<f64>) -> NullComparison {
+    let lower = lower_is_better(name);
+    let valid: Vec<f64> = null.iter().copied().filter(|x| !x.is_nan()).collect();
+    let as_good = valid
+        .iter()
+        .filter(|&&x| if lower { x <= observed } else { x >= observed })
+        .count();
+    let worse = valid
+        .iter()
+        .filter(|&&x| if lower { x > observed } else { x < observed })
+        .count();
+    NullComparison {
+        observed,
+        p_value:    (1 + as_good) as f64 / (valid.len() + 1) as f64,
+        percentile: if valid.is_empty() { f64::NAN } else { worse as f64 / valid.len() as f64 },
+        null,
+    }
+}
+
+/// Randomly re‐place the signal bars, keeping the long and short counts
+pub fn permute_signals(base: &SignalSet, rng: &mut Rng) -> SignalSet {
+    let mut dir: Vec<i8> = base
+        .long_signals
+        .iter()
+        .zip(&base.short_signals)
+        .map(|(&l, &s)| if l { 1 } else if s { -1 } else { 0 })
+        .collect();
+    rng.shuffle(&mut dir);
+
+    let mut s = base.clone();
+    s.long_signals  = dir.iter().map(|&d| d == 1).collect();
+    s.short_signals = dir.iter().map(|&d| d == -1).collect();
+    s
+}
+
+/// `n_entries` distinct random bars, each long with probability `long_fraction`
+pub fn random_entries(base: &SignalSet, n_entries: usize, long_fraction: f64, rng: &mut Rng) -> SignalSet {
+    let n = base.long_signals.len();
+    let mut bars: Vec<usize> = (0..n).collect();
+    let k = n_entries.min(n);
+    // partial Fisher–Yates: first k slots are a uniform sample
+    for i in 0..k {
+        let j = i + rng.below(n - i);
+        bars.swap(i, j);
+    }
+
+    let mut s = base.clone();
+    s.long_signals  = vec![false; n];
+    s.short_signals = vec![false; n];
+    for &b in &bars[..k] {
+        if rng.next_f64() < long_fraction {
+            s.long_signals[b] = true;
+        } else {
+            s.short_signals[b] = true;
+        }
+    }
+    s
+}
+
+/// Metrics of `n` randomised runs; `make` derives each run's signals
+pub fn null_runs<F>(
+    market: &MarketData,
+    config: &BacktestConfig,
+    metric_names: &[String],
+    n: usize,
+    seed: u64,
+    make: F,
+) -> Vec<Vec<f64>>
+where
+    F: Fn(&mut Rng) -> SignalSet + Sync,
+{
+    (0..n)
+        .into_par_iter()
+        .map(|k| {
+            let mut rng = Rng::fork(seed, k as u64);
+            let signals = make(&mut rng);
+            let summary = run_engine(market, &signals, config).metrics;
+            metric_names
+                .iter()
+                .map(|m| lookup_metric(&summary, m).unwrap_or(f64::NAN))
+                .collect()
+        })
+        .collect()
+}
+
+/// Observed metrics vs the null runs → `{name: {observed, p_value, percentile, null}}`
+pub(crate) fn comparisons_to_py<'py>(
+    py: Python<'py>,
+    metric_names: &[String],
+    observed: &[f64],
+    runs: &[Vec<f64>],
+) -> PyResult<&'py PyDict> {
+    let out = PyDict::new(py);
+    for (j, name) in metric_names.iter().enumerate() {
+        let null: Vec<f64> = runs.iter().map(|r| r[j]).collect();
+        let cmp = compare_to_null(name, observed[j], null);
+        let d = PyDict::new(py);
+        d.set_item("observed",   cmp.observed)?;
+        d.set_item("p_value",    cmp.p_value)?;
+        d.set_item("percentile", cmp.percentile)?;
+        d.set_item("null",       distribution_to_py(py, &summarize(cmp.null, 0.95))?)?;
+        out.set_item(name, d)?;
+    }
+    Ok(out)
+}
+
+pub(crate) fn metric_selection(metrics: Option<Vec<String>>) -> PyResult<Vec<String>> {
+    let names = metrics.unwrap_or_else(|| DEFAULT_METRICS.iter().map(|s| s.to_string()).collect());
+    for m in &names {
+        check_metric_name(m)?;
+    }
+    Ok(names)
+}
+
+/// Permutation test of the entry signals.
+///
+/// Each permutation scatters the same number of long and short signals over
+/// random bars (levels, sizes and expirations are read from the new bars) and
+/// re‐runs the backtest.  The p‐value is the share of permutations that did at
+/// least as well as the real signals.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    n_permutations=1000,
+    seed=0,
+    metrics=None
+))]
+pub fn permutation_test(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    n_permutations:   usize,
+    seed:             u64,
+    metrics:          Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let metric_names = metric_selection(metrics)?;
+
+    let (observed, runs) = py.allow_threads(|| {
+        let summary = run_engine(&market, &signals, &config).metrics;
+        let observed: Vec<f64> = metric_names
+            .iter()
+            .map(|m| lookup_metric(&summary, m).unwrap_or(f64::NAN))
+            .collect();
+        let runs = null_runs(&market, &config, &metric_names, n_permutations, seed, |rng| {
+            permute_signals(&signals, rng)
+        });
+        (observed, runs)
+    });
+
+    let out = PyDict::new(py);
+    out.set_item("n_permutations", n_permutations)?;
+    out.set_item("seed",           seed)?;
+    out.set_item("metrics",        comparisons_to_py(py, &metric_names, &observed, &runs)?)?;
+    Ok(out.into())
+}
+
+/// Random‐entry baseline.
+///
+/// Runs `n_runs` backtests whose entries are random bars (by default as many
+/// as the real strategy, with its long/short mix) but whose exits, levels and
+/// costs are identical.  Shows how much performance the entry signal adds on
+/// top of the exit logic alone.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    n_runs=1000,
+    seed=0,
+    n_entries=None,
+    long_fraction=None,
+    metrics=None
+))]
+pub fn random_entry_baseline(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    n_runs:           usize,
+    seed:             u64,
+    n_entries:        Option<usize>,
+    long_fraction:    Option<f64>,
+    metrics:          Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let metric_names = metric_selection(metrics)?;
+
+    let n_long  = signals.long_signals.iter().filter(|&&b| b).count();
+    let n_short = signals.short_signals.iter().filter(|&&b| b).count();
+    let n_entries = n_entries.unwrap_or(n_long + n_short);
+    let long_fraction = match long_fraction {
+        Some(f) if !(0.0..=1.0).contains(&f) => {
+            return Err(PyValueError::new_err("long_fraction must be in [0, 1]"))
+        }
+        Some(f) => f,
+        None if n_long + n_short > 0 => n_long as f64 / (n_long + n_short) as f64,
+        None => 0.5,
+    };
+
+    let (observed, runs) = py.allow_threads(|| {
+        let summary = run_engine(&market, &signals, &config).metrics;
+        let observed: Vec<f64> = metric_names
+            .iter()
+            .map(|m| lookup_metric(&summary, m).unwrap_or(f64::NAN))
+            .collect();
+        let runs = null_runs(&market, &config, &metric_names, n_runs, seed, |rng| {
+            random_entries(&signals, n_entries, long_fraction, rng)
+        });
+        (observed, runs)
+    });
+
+    let out = PyDict::new(py);
+    out.set_item("n_runs",        n_runs)?;
+    out.set_item("seed",          seed)?;
+    out.set_item("n_entries",     n_entries)?;
+    out.set_item("long_fraction", long_fraction)?;
+    out.set_item("metrics",       comparisons_to_py(py, &metric_names, &observed, &runs)?)?;
+    Ok(out.into())
+}
@@ -1,18 +1,240 @@
 // src/engine/simulate_exits.rs
 
-use rayon::prelude::*;
-use crate::engine::position::Position;
+use crate::engine::par::*;
+use crate::engine::{
+    bracket::{Bracket, StopType},
+    exit_rules::ExitRules,
+    fill_model::{FillModel, QueueModel},
+    instrument::InstrumentFilters,
+    latency::Latency,
+    liquidity::Liquidity,
+    position::{LevelChange, Position},
+    rng::Rng,
+    sub_bars::SubBars,
+};
 
-/// Parallel exit simulation: SL → TP → EXP.  
-/// Each position scans forward from its entry in parallel.
+/// Exit slippage rate per exit condition
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExitSlippage {
+    pub sl:  f64,
+    pub tp:  f64,
+    /// Expiry and other market closes at the bar close
+    pub exp: f64,
+}
+
+impl ExitSlippage {
+    pub fn for_condition(&self, condition: &str) -> f64 {
+        match condition {
+            "SL" => self.sl,
+            "TP" => self.tp,
+            _    => self.exp,
+        }
+    }
+}
+
+/// Exit fee rate per liquidity
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExitFees {
+    pub maker: f64,
+    pub taker: f64,
+}
+
+impl ExitFees {
+    pub fn for_liquidity(&self, liquidity: Liquidity) -> f64 {
+        match liquidity {
+            Liquidity::Maker => self.maker,
+            Liquidity::Taker => self.taker,
+        }
+    }
+}
+
+/// Where an expiring position is closed
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ExpiryFill {
+    /// Close of the first bar stamped at/after the expiry
+    #[default]
+    Close,
+    /// Open of the bar whose interval contains the expiry (before its SL/TP)
+    Open,
+    /// Open→close interpolated at the expiry's position within its bar
+    Interpolate,
+}
+
+impl ExpiryFill {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "close"       => Ok(ExpiryFill::Close),
+            "open"        => Ok(ExpiryFill::Open),
+            "interpolate" => Ok(ExpiryFill::Interpolate),
+            other => Err(format!(
+                "expiry_fill must be \"close\", \"open\" or \"interpolate\", got ‘{}’", other
+            )),
+        }
+    }
+}
+
+/// Which prices SL/TP are checked against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LevelCheck {
+    /// The bar's high/low touching the level
+    #[default]
+    Touch,
+    /// Only the bar's close at or past the level; the exit fills at that close
+    Close,
+}
+
+impl LevelCheck {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "touch" => Ok(LevelCheck::Touch),
+            "close" => Ok(LevelCheck::Close),
+            other => Err(format!("level_check must be \"touch\" or \"close\", got ‘{}’", other)),
+        }
+    }
+}
+
+/// Which exit wins when several trigger on one bar, first to last
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExitPriority(pub [&'static str; 3]);
+
+impl Default for ExitPriority {
+    fn default() -> Self {
+        ExitPriority(["SL", "TP", "EXP"])
+    }
+}
+
+impl ExitPriority {
+    /// A comma‐separated order of "sl", "tp" and "exp", e.g. "exp,sl,tp"
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let err = || format!("exit_priority must order \"sl\", \"tp\" and \"exp\" (e.g. \"sl,tp,exp\"), got ‘{}’", s);
+        let mut order = Vec::with_capacity(3);
+        for part in s.split(',') {
+            let condition = match part.trim().to_ascii_lowercase().as_str() {
+                "sl"  => "SL",
+                "tp"  => "TP",
+                "exp" => "EXP",
+                _     => return Err(err()),
+            };
+            if order.contains(&condition) {
+                return Err(err());
+            }
+            order.push(condition);
+        }
+        let order: [&'static str; 3] = order.try_into().map_err(|_| err())?;
+        Ok(ExitPriority(order))
+    }
+
+    /// The first of the triggered conditions
+    pub fn pick(&self, hit_sl: bool, hit_tp: bool, expired: bool) -> Option<&'static str> {
+        self.0.into_iter().find(|&c| match c {
+            "SL" => hit_sl,
+            "TP" => hit_tp,
+            _    => expired,
+        })
+    }
+
+    /// Whether `a` wins over `b`
+    pub fn before(&self, a: &str, b: &str) -> bool {
+        let rank = |c: &str| self.0.iter().position(|&x| x == c);
+        rank(a) < rank(b)
+    }
+}
+
+/// What an expiry does to a position whose take‐profit is still working
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ExpiryPolicy {
+    /// Close the whole position at market
+    #[default]
+    Close,
+    /// Close only the size the take‐profit doesn't cover; a position it
+    /// covers in full drops its expiry and runs on under its bracket
+    Remainder,
+    /// Push the expiry back by `seconds`, once; the position then closes at
+    /// market if neither leg filled by the new expiry
+    Extend { seconds: f64 },
+}
+
+impl ExpiryPolicy {
+    pub fn parse(s: &str, extension: Option<f64>) -> Result<Self, String> {
+        match (s, extension) {
+            ("close", _)     => Ok(ExpiryPolicy::Close),
+            ("remainder", _) => Ok(ExpiryPolicy::Remainder),
+            ("extend", Some(seconds)) if seconds.is_finite() && seconds > 0.0 => Ok(ExpiryPolicy::Extend { seconds }),
+            ("extend", _) => Err("expiry_policy=\"extend\" needs an expiry_extension > 0 (seconds)".into()),
+            (other, _) => Err(format!(
+                "expiry_policy must be \"close\", \"remainder\" or \"extend\", got ‘{}’", other
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExpiryPolicy::Close        => "close",
+            ExpiryPolicy::Remainder    => "remainder",
+            ExpiryPolicy::Extend { .. } => "extend",
+        }
+    }
+}
+
+/// End of bar j: the next timestamp, or the last bar's start plus the
+/// previous bar's duration
+fn bar_end(timestamps: &[f64], j: usize) -> f64 {
+    match timestamps.get(j + 1) {
+        Some(&t)       => t,
+        None if j > 0  => 2.0 * timestamps[j] - timestamps[j - 1],
+        None           => timestamps[j],
+    }
+}
+
+/// Parallel exit simulation.  
+/// Exits on the first bar any condition triggers; when several trigger on
+/// one bar, `priority` picks the exit (SL → TP → EXP by default).
+/// Each position scans forward from its entry in parallel, its TP and SL
+/// working as a one‐cancels‐the‐other `Bracket` (the stop leg of type
+/// `stop_type`).
+/// With `latency`, a trigger on bar j fills at the open of the delayed bar
+/// (or the final close if that lies past the data) instead of at the level.
+/// Take‐profits and stop‐limits filling at their limit pay the maker rate of
+/// `exit_fees`; stops, expiries and delayed (market) fills pay the taker rate.
+/// With `fill_model`, each TP touch fills only with a probability that grows
+/// with how far price traded through the level; a rejected touch leaves the
+/// position open (SL/EXP on that bar still apply).
+/// With `queue`, a TP touch fills only once the queue ahead of the resting
+/// order has traded (see `QueueModel`).
+/// With `rules`, TP/SL are updated at the start of each bar (amended levels
+/// are rounded to the tick like the originals).
+/// With `sub_bars`, a bar touching both levels exits on whichever its
+/// sub‐bars touched first (`priority` on a tie), and SL/TP exits record the
+/// touching sub‐bar's timestamp as `exit_time`.
+/// `expiry_fill` picks the expiry bar and price; every expiry records
+/// `expiry_slippage`, the seconds from the expiry to the moment it filled.
+/// With `level_check` = `Close`, SL/TP trigger only when a bar closes at or
+/// past them and exit at that close as market orders (so `fill_model`,
+/// `queue` and `sub_bars` don't apply); a stop‐limit whose limit the close
+/// is already past rests at the limit as usual.
+/// `expiry_policy` decides what an expiry does while the take‐profit still
+/// works; the position records the policy it was applied with as
+/// `expiry_action` (an expiry with no take‐profit working always closes).
 pub fn simulate_position_exits(
     positions: &mut [Position],
     timestamps: &[f64],
+    open: &[f64],
     high: &[f64],
     low: &[f64],
     close: &[f64],
-    exit_fee_rate: f64,
-    slippage_rate: f64,
+    exit_fees: ExitFees,
+    exit_slippage: ExitSlippage,
+    latency: Option<Latency>,
+    fill_model: Option<FillModel>,
+    queue: Option<&QueueModel>,
+    filters: Option<InstrumentFilters>,
+    rules: Option<&ExitRules>,
+    sub_bars: Option<&SubBars>,
+    expiry_fill: ExpiryFill,
+    expiry_policy: ExpiryPolicy,
+    priority: ExitPriority,
+    level_check: LevelCheck,
+    stop_type: StopType,
 ) {
     let n = high.len();
 
@@ -21,75 +243,219 @@ pub fn simulate_position_exits(
             return;
         }
 
+        // one stream per signal bar, so reruns share draws
+        let mut rng = fill_model.map(|fm| Rng::fork(fm.seed, pos.signal_index as u64));
+        let mut state = rules.map(|r| r.start(pos));
+        // (level, size still to trade before the TP fills)
+        let mut resting: Option<(f64, f64)> = None;
+        let mut bracket = Bracket::new(pos, stop_type);
+
         // walk bars from entry to end
         for j in pos.entry_index..n {
-            // 1) SL/TP checks
-            let hit_sl = if pos.position_type=="long" {
-                low[j] <= pos.sl
-            } else {
-                high[j] >= pos.sl
-            };
-            let hit_tp = if pos.position_type=="long" {
-                high[j] >= pos.tp
-            } else {
-                low[j] <= pos.tp
-            };
+            if let (Some(r), Some(state)) = (rules, state.as_mut()) {
+                let (tp, sl) = (pos.tp, pos.sl);
+                if r.apply(pos, state, j, high, low) {
+                    let f = filters.unwrap_or_default();
+                    pos.tp = f.round_level(pos.tp);
+                    pos.sl = f.round_level(pos.sl);
+                    if pos.tp.to_bits() != tp.to_bits() || pos.sl.to_bits() != sl.to_bits() {
+                        pos.level_changes.push(LevelChange { bar: j, tp: pos.tp, sl: pos.sl });
+                        bracket.amend(j, pos.tp, pos.sl);
+                    }
+                }
+            }
+
+            // 1) Expiration (inside this bar's interval unless `Close`)
+            let expires = |pos: &Position| pos.expiration_time.is_some_and(|et| {
+                timestamps[j] >= et || (expiry_fill != ExpiryFill::Close && et < bar_end(timestamps, j))
+            });
+            let mut expired = expires(pos);
+            if expired && pos.expiry_action.is_none() {
+                let protected = bracket.protected_size();
+                pos.expiry_action = Some(expiry_policy.name().to_string());
+                match expiry_policy {
+                    ExpiryPolicy::Remainder if protected > 0.0 && protected >= pos.position_size => {
+                        pos.expiration_time = None;
+                        expired = false;
+                    }
+                    ExpiryPolicy::Extend { seconds } if protected > 0.0 => {
+                        pos.expiration_time = pos.expiration_time.map(|et| et + seconds);
+                        expired = expires(pos);
+                    }
+                    _ => pos.expiry_action = Some(ExpiryPolicy::Close.name().to_string()),
+                }
+            }
+            let expired_at_open = expired && expiry_fill == ExpiryFill::Open;
+
+            // 2) SL/TP checks
+            let on_close = level_check == LevelCheck::Close;
+            let (o, h, l) = if on_close { (close[j], close[j], close[j]) } else { (open[j], high[j], low[j]) };
+            let stop_fill = if expired_at_open { None } else { bracket.stop_fill(o, h, l) };
+            let mut hit_sl = stop_fill.is_some();
+            let mut hit_tp = !expired_at_open && bracket.take_profit_touched(h, l);
+            // a stop that wins the bar leaves the take‐profit unworked
+            let stop_wins = hit_sl && priority.before("SL", "TP");
+            if let (Some(fm), Some(rng), false, false) = (fill_model, rng.as_mut(), stop_wins, on_close) {
+                if hit_tp {
+                    let through = if pos.position_type=="long" { high[j] - pos.tp } else { pos.tp - low[j] };
+                    pos.tp_attempts += 1;
+                    if rng.next_f64() >= fm.probability(pos.tp, through) {
+                        pos.tp_rejections += 1;
+                        hit_tp = false;
+                    }
+                }
+            }
 
-            // 2) Expiration
-            let expired = pos.expiration_time
-                .map_or(false, |et| timestamps[j] >= et);
-
-            if hit_sl || hit_tp || expired {
-                // Raw exit price
-                let raw_exit = if hit_sl {
-                    pos.sl
-                } else if hit_tp {
-                    pos.tp
-                } else {
-                    close[j]
+            if let (Some(q), false, false) = (queue, stop_wins, on_close) {
+                let sell = pos.position_type == "long";
+                if resting.is_none_or(|(level, _)| level != pos.tp) {
+                    resting = Some((pos.tp, q.queue_ahead(j, sell, pos.tp) + pos.position_size));
+                }
+                if hit_tp && !q.traded_through(j, sell, pos.tp) {
+                    let (level, left) = resting.unwrap();
+                    let left = left - q.level_volume(j);
+                    resting = Some((level, left));
+                    pos.tp_attempts += 1;
+                    if left > 0.0 {
+                        pos.tp_rejections += 1;
+                        hit_tp = false;
+                    }
+                }
+            }
+
+            let mut touched_time = None;
+            if let (Some(sb), true, false) = (sub_bars, hit_sl || hit_tp, on_close) {
+                let (sl_at, tp_at) = sb.first_touch(j, pos.position_type=="long", pos.sl, pos.tp);
+                if hit_sl && hit_tp {
+                    // sub‐bars settle the order; a tie falls back to `priority`
+                    if tp_at.is_some_and(|t| sl_at.is_none_or(|s| t < s)) {
+                        hit_sl = false;
+                    } else if sl_at.is_some_and(|s| tp_at.is_none_or(|t| s < t)) {
+                        hit_tp = false;
+                    }
+                }
+                if latency.is_none() {
+                    let touched = if hit_sl && (!hit_tp || priority.before("SL", "TP")) { sl_at } else { tp_at };
+                    touched_time = touched.map(|k| sb.bars.timestamps[k]);
+                }
+            }
+
+            if let Some(condition) = priority.pick(hit_sl, hit_tp, expired) {
+                // Raw exit price (and, for an expiry, when it fills)
+                let et = pos.expiration_time.unwrap_or(timestamps[j]);
+                // a stop‐limit filling at its resting limit, not on the trigger
+                let stop_rested = stop_fill.is_some_and(|f| f.to_bits() == bracket.stop_loss.limit_price.to_bits());
+                let (level, filled_at) = match condition {
+                    "SL" if on_close && !stop_rested => (close[j], bar_end(timestamps, j)),
+                    "TP" if on_close => (close[j], bar_end(timestamps, j)),
+                    "SL" => (stop_fill.unwrap(), timestamps[j]),
+                    "TP" => (pos.tp, timestamps[j]),
+                    _ => match expiry_fill {
+                        ExpiryFill::Close => (close[j], bar_end(timestamps, j)),
+                        ExpiryFill::Open  => (open[j], timestamps[j]),
+                        ExpiryFill::Interpolate => {
+                            let span = bar_end(timestamps, j) - timestamps[j];
+                            let frac = if span > 0.0 { ((et - timestamps[j]) / span).clamp(0.0, 1.0) } else { 0.0 };
+                            (open[j] + frac * (close[j] - open[j]), timestamps[j] + frac * span)
+                        }
+                    }
                 };
-                // Slippage on exit
-                let exit_price = if pos.position_type=="long" {
-                    raw_exit * (1.0 - slippage_rate)
-                } else {
-                    raw_exit * (1.0 + slippage_rate)
+                let (exit_i, raw_exit) = match latency {
+                    Some(lat) => match lat.fill_bar(timestamps, j, j + 1) {
+                        Some(k) => (k, open[k]),
+                        None    => (n - 1, close[n - 1]),
+                    },
+                    None => (j, level),
                 };
-                let slippage_exit = (raw_exit - exit_price).abs();
-                // Fees
-                let fee_exit = pos.position_size * exit_price * exit_fee_rate;
-
-                // Write back
-                pos.exit_index     = Some(j);
-                pos.exit_price     = Some(exit_price);
-                pos.exit_condition = Some(
-                    if hit_sl {"SL"} else if hit_tp {"TP"} else {"EXP"}
-                .to_string());
-                pos.slippage_exit  = slippage_exit;
-                pos.fee_exit       = fee_exit;
-                pos.is_closed      = true;
-
-                // PnL calculation
-                let gross_pnl = if pos.position_type=="long" {
-                    (exit_price - pos.entry_price) * pos.position_size
-                } else {
-                    (pos.entry_price - exit_price) * pos.position_size
+                if latency.is_some() {
+                    pos.exit_delay    = Some(timestamps[exit_i] - timestamps[j]);
+                    pos.latency_cost += pos.contract.pnl(
+                        pos.position_type=="long", raw_exit, level, pos.position_size,
+                    );
+                }
+                let liquidity = match (latency, condition) {
+                    (None, "TP") if !on_close => Liquidity::Maker,
+                    (None, "SL") => bracket.stop_liquidity(level),
+                    _            => Liquidity::Taker,
                 };
-                let pnl = gross_pnl - (pos.fee_entry + pos.fee_exit);
-
-                // Returns
-                let absolute_return = if pos.entry_price != 0.0 {
-                    (exit_price / pos.entry_price) - 1.0
-                } else { 0.0 };
-                let real_return = if pos.entry_price * pos.position_size != 0.0 {
-                    pnl / (pos.entry_price * pos.position_size)
-                } else { 0.0 };
+                pos.bracket = Some(bracket);
+                close_position(
+                    pos, exit_i, raw_exit, condition,
+                    exit_fees.for_liquidity(liquidity), exit_slippage.for_condition(condition), filters,
+                );
+                pos.exit_liquidity = Some(liquidity);
+                pos.exit_time = touched_time;
+                if condition == "EXP" {
+                    let filled_at = if latency.is_some() { timestamps[exit_i] } else { filled_at };
+                    pos.expiry_slippage = Some(filled_at - et);
+                }
 
-                pos.absolute_return = Some(absolute_return);
-                pos.real_return     = Some(real_return);
-                pos.pnl             = Some(pnl);
-
-                break;
+                return;
             }
         }
+        pos.bracket = Some(bracket);
     });
 }
+
+/// Close `pos` on bar `exit_i` at `raw_exit` (before slippage) as a taker
+/// and book its fees, PnL and returns (the fill is rounded to the tick
+/// against the trader).
+/// Clears the sub‐bar exit time and expiry slippage of any earlier exit and
+/// settles the bracket: the filled leg cancels its sibling.
+pub fn close_position(
+    pos: &mut Position,
+    exit_i: usize,
+    raw_exit: f64,
+    condition: &str,
+    exit_fee_rate: f64,
+    slippage_rate: f64,
+    filters: Option<InstrumentFilters>,
+) {
+    // Slippage on exit
+    let slipped = if pos.position_type=="long" {
+        raw_exit * (1.0 - slippage_rate)
+    } else {
+        raw_exit * (1.0 + slippage_rate)
+    };
+    let exit_price = filters.unwrap_or_default().round_fill(slipped, pos.position_type=="short");
+    let slippage_exit = (raw_exit - exit_price).abs();
+
+    // Write back
+    pos.exit_index     = Some(exit_i);
+    pos.exit_price     = Some(exit_price);
+    pos.exit_condition = Some(condition.to_string());
+    pos.slippage_exit  = slippage_exit;
+    pos.exit_time      = None;
+    pos.expiry_slippage = None;
+    pos.exit_liquidity = Some(Liquidity::Taker);
+    pos.is_closed      = true;
+    if let Some(b) = &mut pos.bracket {
+        b.settle(condition, exit_i);
+    }
+    book_exit(pos, exit_fee_rate);
+}
+
+/// Exit fee, PnL and returns of a closed position from its entry/exit prices
+pub fn book_exit(pos: &mut Position, exit_fee_rate: f64) {
+    let exit_price = pos.exit_price.unwrap_or(0.0);
+    pos.fee_exit = pos.contract.notional(exit_price, pos.position_size) * exit_fee_rate;
+
+    // PnL calculation
+    let gross_pnl = pos.contract.pnl(
+        pos.position_type=="long", pos.entry_price, exit_price, pos.position_size,
+    );
+    let pnl = gross_pnl - (pos.fee_entry + pos.fee_exit) - pos.funding_cost + pos.dividend_income;
+
+    // Returns
+    let absolute_return = if pos.entry_price != 0.0 {
+        (exit_price / pos.entry_price) - 1.0
+    } else { 0.0 };
+    let notional = pos.contract.notional(pos.entry_price, pos.position_size);
+    let real_return = if notional != 0.0 {
+        pnl / notional
+    } else { 0.0 };
+
+    pos.absolute_return = Some(absolute_return);
+    pos.real_return     = Some(real_return);
+    pos.pnl             = Some(pnl);
+}
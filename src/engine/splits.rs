@@ -0,0 +1,406 @@
+// src/engine/splits.rs
+
+use std::ops::Range;
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    market_from_py, signals_from_py,
+    backtest::{run_engine, validate_signals, BacktestConfig, BacktestResult, MarketData, SignalSet},
+    benchmark::{account_equity, bar_returns},
+    bootstrap::{compound_stats, sharpe},
+    metrics::lookup_metric,
+    output::distribution_to_py,
+    stats::summarize,
+    signal_input::restrict_time_range,
+    significance::metric_selection,
+};
+
+/// One contiguous block of bars of a train/test split
+#[derive(Clone, Debug)]
+pub struct Segment {
+    pub index: usize,
+    pub test:  bool,
+    pub bars:  Range<usize>,
+    /// Bars entries may fill on: `bars` minus the embargo at each edge that
+    /// borders a segment of the other role
+    pub trade: Range<usize>,
+}
+
+/// `n` bars cut into `n_segments` near‐equal blocks; `test` lists the test
+/// block indices, the rest are train
+pub fn embargoed_segments(n: usize, n_segments: usize, test: &[usize], embargo: usize) -> Vec<Segment> {
+    let bounds: Vec<usize> = (0..=n_segments).map(|k| k * n / n_segments).collect();
+    let is_test = |k: usize| test.contains(&k);
+    (0..n_segments)
+        .map(|k| {
+            let bars = bounds[k]..bounds[k + 1];
+            let mut trade = bars.clone();
+            if k > 0 && is_test(k - 1) != is_test(k) {
+                trade.start = (trade.start + embargo).min(trade.end);
+            }
+            if k + 1 < n_segments && is_test(k + 1) != is_test(k) {
+                trade.end = trade.end.saturating_sub(embargo).max(trade.start);
+            }
+            Segment { index: k, test: is_test(k), bars, trade }
+        })
+        .collect()
+}
+
+fn slice_market(m: &MarketData, r: &Range<usize>) -> MarketData {
+    MarketData {
+        timestamps: m.timestamps[r.clone()].to_vec(),
+        open:       m.open[r.clone()].to_vec(),
+        high:       m.high[r.clone()].to_vec(),
+        low:        m.low[r.clone()].to_vec(),
+        close:      m.close[r.clone()].to_vec(),
+    }
+}
+
+fn slice_signals(s: &SignalSet, r: &Range<usize>) -> SignalSet {
+    SignalSet {
+        long_signals:     s.long_signals[r.clone()].to_vec(),
+        short_signals:    s.short_signals[r.clone()].to_vec(),
+        long_tp:          s.long_tp[r.clone()].to_vec(),
+        long_sl:          s.long_sl[r.clone()].to_vec(),
+        short_tp:         s.short_tp[r.clone()].to_vec(),
+        short_sl:         s.short_sl[r.clone()].to_vec(),
+        long_size:        s.long_size[r.clone()].to_vec(),
+        short_size:       s.short_size[r.clone()].to_vec(),
+        expiration_times: s.expiration_times[r.clone()].to_vec(),
+        tags:             s.tags.as_ref().map(|t| t[r.clone()].to_vec()),
+        direction:        s.direction.as_ref().map(|d| d[r.clone()].to_vec()),
+    }
+}
+
+/// Backtest over one segment's bars only: entries restricted to its
+/// tradable bars, positions still open expire on its last tradable bar
+/// (`None` when the embargo leaves no tradable bar)
+pub fn segment_result(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    seg: &Segment,
+) -> Option<BacktestResult> {
+    if seg.trade.is_empty() {
+        return None;
+    }
+    let m = slice_market(market, &seg.bars);
+    let mut s = slice_signals(signals, &seg.bars);
+    let ts = &market.timestamps;
+    restrict_time_range(&mut s, &m.timestamps, Some(ts[seg.trade.start]), Some(ts[seg.trade.end - 1]));
+    Some(run_engine(&m, &s, config))
+}
+
+/// `segment_result`'s selected metrics
+pub fn run_segment(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    seg: &Segment,
+    metric_names: &[String],
+) -> Option<Vec<f64>> {
+    let summary = segment_result(market, signals, config, seg)?.metrics;
+    Some(
+        metric_names
+            .iter()
+            .map(|name| lookup_metric(&summary, name).unwrap_or(f64::NAN))
+            .collect(),
+    )
+}
+
+/// Embargoed train/test split.
+///
+/// Cuts the bars into `n_segments` contiguous blocks, marks `test_segments`
+/// (default: the last block) as test and the rest as train, and drops
+/// `embargo_bars` bars on each side of every train/test boundary so trades
+/// near one block can't leak into the other.  Each block of the `evaluate`
+/// role ("test", "train" or "all") is backtested on its own bars; positions
+/// still open expire at the end of the block's tradable bars.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    n_segments=5,
+    test_segments=None,
+    embargo_bars=0,
+    evaluate="test",
+    metrics=None
+))]
+pub fn embargoed_split(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    n_segments:       usize,
+    test_segments:    Option<Vec<usize>>,
+    embargo_bars:     usize,
+    evaluate:         &str,
+    metrics:          Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let metric_names = metric_selection(metrics)?;
+
+    let n = market.timestamps.len();
+    if n_segments < 2 || n_segments > n {
+        return Err(PyValueError::new_err("n_segments must be between 2 and the number of bars"));
+    }
+    let test = test_segments.unwrap_or_else(|| vec![n_segments - 1]);
+    if test.is_empty() || test.iter().any(|&k| k >= n_segments) {
+        return Err(PyValueError::new_err("test_segments must be non‐empty indices below n_segments"));
+    }
+    let (want_test, want_train) = match evaluate {
+        "test"  => (true, false),
+        "train" => (false, true),
+        "all"   => (true, true),
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unknown evaluate ‘{}’ (expected \"test\", \"train\" or \"all\")", other
+            )))
+        }
+    };
+
+    let segments: Vec<Segment> = embargoed_segments(n, n_segments, &test, embargo_bars)
+        .into_iter()
+        .filter(|s| if s.test { want_test } else { want_train })
+        .collect();
+
+    let runs: Vec<Option<Vec<f64>>> = py.allow_threads(|| {
+        segments
+            .par_iter()
+            .map(|seg| run_segment(&market, &signals, &config, seg, &metric_names))
+            .collect()
+    });
+
+    let ts = &market.timestamps;
+    let rows = PyList::empty(py);
+    for (seg, values) in segments.iter().zip(&runs) {
+        let row = PyDict::new(py);
+        row.set_item("index",      seg.index)?;
+        row.set_item("role",       if seg.test { "test" } else { "train" })?;
+        row.set_item("start_time", ts[seg.bars.start])?;
+        row.set_item("end_time",   ts[seg.bars.end - 1])?;
+        row.set_item("bars",       seg.bars.len())?;
+        row.set_item("trade_bars", seg.trade.len())?;
+        match values {
+            Some(values) => {
+                let d = PyDict::new(py);
+                for (name, v) in metric_names.iter().zip(values) {
+                    d.set_item(name, v)?;
+                }
+                row.set_item("metrics", d)?;
+            }
+            // embargo swallowed the whole block
+            None => row.set_item("metrics", py.None())?,
+        }
+        rows.append(row)?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("n_segments",    n_segments)?;
+    out.set_item("test_segments", test)?;
+    out.set_item("embargo_bars",  embargo_bars)?;
+    out.set_item("segments",      rows)?;
+    Ok(out.into())
+}
+
+/// All `k`‐subsets of `0..n` in lexicographic order
+pub fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut out = Vec::new();
+    if k == 0 || k > n {
+        return out;
+    }
+    let mut idx: Vec<usize> = (0..k).collect();
+    loop {
+        out.push(idx.clone());
+        // rightmost slot that can still move up
+        let Some(i) = (0..k).rev().find(|&i| idx[i] < n - k + i) else {
+            return out;
+        };
+        idx[i] += 1;
+        for j in i + 1..k {
+            idx[j] = idx[j - 1] + 1;
+        }
+    }
+}
+
+/// Backtest paths of a CPCV run: path `p` takes each group's test result
+/// from the `p`‐th split (in order) that tests that group, giving
+/// C(n−1, k−1) paths that each cover every group once
+pub fn cpcv_paths(splits: &[Vec<usize>], n_groups: usize) -> Vec<Vec<usize>> {
+    let per_group: Vec<Vec<usize>> = (0..n_groups)
+        .map(|g| (0..splits.len()).filter(|&s| splits[s].contains(&g)).collect())
+        .collect();
+    let n_paths = per_group.iter().map(|v| v.len()).min().unwrap_or(0);
+    (0..n_paths)
+        .map(|p| per_group.iter().map(|v| v[p]).collect())
+        .collect()
+}
+
+/// Combinatorial purged cross‐validation.
+///
+/// Cuts the bars into `n_groups` contiguous groups and forms every split
+/// with `n_test_groups` of them as test (the rest train), purging
+/// `embargo_bars` on each side of every train/test boundary.  Each split's
+/// test groups are backtested on their own bars, and the group results are
+/// stitched into C(n_groups−1, n_test_groups−1) full‐length out‐of‐sample
+/// paths; per‐path Sharpe (per bar, on account equity returns), max
+/// drawdown and total return are reported with their distributions.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    n_groups=6,
+    n_test_groups=2,
+    embargo_bars=0,
+    confidence=0.95
+))]
+pub fn cpcv(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    n_groups:         usize,
+    n_test_groups:    usize,
+    embargo_bars:     usize,
+    confidence:       f64,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let n = market.timestamps.len();
+    if n_groups < 2 || n_groups > n {
+        return Err(PyValueError::new_err("n_groups must be between 2 and the number of bars"));
+    }
+    if n_test_groups == 0 || n_test_groups >= n_groups {
+        return Err(PyValueError::new_err("n_test_groups must be between 1 and n_groups − 1"));
+    }
+    if !(0.0..1.0).contains(&confidence) {
+        return Err(PyValueError::new_err("confidence must be in [0, 1)"));
+    }
+
+    let splits = combinations(n_groups, n_test_groups);
+    let paths  = cpcv_paths(&splits, n_groups);
+
+    let stats: Vec<(f64, f64, f64)> = py.allow_threads(|| {
+        // account equity returns of each (split, test group) run
+        let group_returns: Vec<Vec<Vec<f64>>> = splits
+            .par_iter()
+            .map(|test| {
+                let segments = embargoed_segments(n, n_groups, test, embargo_bars);
+                (0..n_groups)
+                    .map(|g| {
+                        if !test.contains(&g) {
+                            return Vec::new();
+                        }
+                        segment_result(&market, &signals, &config, &segments[g])
+                            .map(|r| bar_returns(&account_equity(&r.exposure, initial_equity)))
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+        paths
+            .par_iter()
+            .map(|path| {
+                let returns: Vec<f64> = path
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(g, &s)| group_returns[s][g].iter().copied())
+                    .collect();
+                let (_, max_dd) = compound_stats(&returns, 0.0);
+                let total = returns.iter().fold(1.0, |eq, r| eq * (1.0 + r)) - 1.0;
+                (sharpe(&returns), max_dd, total)
+            })
+            .collect()
+    });
+
+    let rows = PyList::empty(py);
+    for (path, &(sharpe_ratio, max_drawdown, total_return)) in paths.iter().zip(&stats) {
+        let row = PyDict::new(py);
+        row.set_item("splits",       path.clone())?;
+        row.set_item("sharpe_ratio", sharpe_ratio)?;
+        row.set_item("max_drawdown", max_drawdown)?;
+        row.set_item("total_return", total_return)?;
+        rows.append(row)?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("n_groups",      n_groups)?;
+    out.set_item("n_test_groups", n_test_groups)?;
+    out.set_item("embargo_bars",  embargo_bars)?;
+    out.set_item("splits",        splits)?;
+    out.set_item("paths",         rows)?;
+    out.set_item("sharpe_ratio",  distribution_to_py(py, &summarize(stats.iter().map(|s| s.0).collect(), confidence))?)?;
+    out.set_item("max_drawdown",  distribution_to_py(py, &summarize(stats.iter().map(|s| s.1).collect(), confidence))?)?;
+    out.set_item("total_return",  distribution_to_py(py, &summarize(stats.iter().map(|s| s.2).collect(), confidence))?)?;
+    Ok(out.into())
+}
@@ -0,0 +1,404 @@
+// src/engine/stages.rs
+
+//! The pipeline stages of `run_backtest` as separate Python functions, so a
+//! run can be resumed from positions or snapshots produced elsewhere
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    array_to_vec, market_from_py, signals_from_py, tags_from_py,
+    backtest::{validate_signals, MarketData},
+    contract::{ContractType, SizeMode},
+    excursions::record_excursions,
+    exposure::{compute_exposure_series as exposure_series, ExposureSnapshot},
+    metrics::{compute_summary_metrics_with, EquityBasis, RatioPolicy, ReturnEquity, ReturnKind, ReturnSpec},
+    liquidity::Liquidity,
+    position::{Fill, Position},
+    scan_entries::scan_entries as scan,
+    simulate_exits::{simulate_position_exits, ExitFees, ExitSlippage, ExitPriority, ExpiryFill, ExpiryPolicy, LevelCheck},
+    bracket::StopType,
+    output::{closed_position_to_py, open_position_to_py, skipped_entry_to_py, snapshot_to_py, summary_metrics_to_py},
+};
+
+pub(crate) fn required<'a, T: FromPyObject<'a>>(d: &'a PyDict, what: &str, key: &str) -> PyResult<T> {
+    match d.get_item(key) {
+        Some(v) => v.extract(),
+        None => Err(PyValueError::new_err(format!("{} has no ‘{}’", what, key))),
+    }
+}
+
+pub(crate) fn optional<'a, T: FromPyObject<'a>>(d: &'a PyDict, key: &str) -> PyResult<Option<T>> {
+    match d.get_item(key) {
+        Some(v) if !v.is_none() => Ok(Some(v.extract()?)),
+        _ => Ok(None),
+    }
+}
+
+/// Optional "maker" / "taker" field
+pub(crate) fn liquidity_from_py(d: &PyDict, key: &str) -> PyResult<Option<Liquidity>> {
+    optional::<&str>(d, key)?
+        .map(Liquidity::parse)
+        .transpose()
+        .map_err(PyValueError::new_err)
+}
+
+/// Position → dict in the `run_backtest` layout, plus the fields needed to
+/// read it back (`signal_index`, `entry_sl`, `contract_type`)
+fn position_to_py<'py>(py: Python<'py>, pos: &Position) -> PyResult<&'py PyDict> {
+    let pd = if pos.is_closed {
+        closed_position_to_py(py, pos)?
+    } else {
+        open_position_to_py(py, pos, None)?
+    };
+    pd.set_item("signal_index",  pos.signal_index)?;
+    pd.set_item("entry_sl",      pos.entry_sl)?;
+    pd.set_item("contract_type", pos.contract.name())?;
+    Ok(pd)
+}
+
+/// Position from a dict as written by `position_to_py` (or by
+/// `run_backtest`); fields it lacks take their pre‐exit defaults
+pub(crate) fn position_from_py(d: &PyDict) -> PyResult<Position> {
+    const WHAT: &str = "position";
+    let position_type: String = required(d, WHAT, "position_type")?;
+    if position_type != "long" && position_type != "short" {
+        return Err(PyValueError::new_err(format!(
+            "position_type must be \"long\" or \"short\", got ‘{}’", position_type
+        )));
+    }
+    let contract = match optional::<&str>(d, "contract_type")? {
+        Some(s) => ContractType::parse(s).map_err(PyValueError::new_err)?,
+        None    => ContractType::Linear,
+    };
+    let entry_index: usize = required(d, WHAT, "entry_index")?;
+    let sl: f64 = required(d, WHAT, "sl")?;
+    let mut fills = Vec::new();
+    if let Some(list) = optional::<Vec<&PyDict>>(d, "fills")? {
+        for f in list {
+            fills.push(Fill {
+                bar:   required(f, "fill", "bar")?,
+                size:  required(f, "fill", "size")?,
+                price: required(f, "fill", "price")?,
+                fee:   required(f, "fill", "fee")?,
+            });
+        }
+    }
+    let exit_index: Option<usize> = optional(d, "exit_index")?;
+    Ok(Position {
+        position_id:      required(d, WHAT, "position_id")?,
+        entry_time:       required(d, WHAT, "entry_time")?,
+        position_type,
+        signal_index:     optional(d, "signal_index")?.unwrap_or(entry_index),
+        entry_index,
+        entry_price:      required(d, WHAT, "entry_price")?,
+        tp:               required(d, WHAT, "tp")?,
+        sl,
+        expiration_time:  optional(d, "expiration_time")?,
+        exit_index,
+        exit_time:        optional(d, "exit_time")?,
+        expiry_slippage:  optional(d, "expiry_slippage")?,
+        expiry_action:    optional(d, "expiry_action")?,
+        exit_price:       optional(d, "exit_price")?,
+        exit_condition:   optional(d, "exit_condition")?,
+        position_size:    required(d, WHAT, "position_size")?,
+        contract,
+        fee_entry:        optional(d, "fee_entry")?.unwrap_or(0.0),
+        fee_exit:         optional(d, "fee_exit")?.unwrap_or(0.0),
+        entry_liquidity:  liquidity_from_py(d, "entry_liquidity")?.unwrap_or_default(),
+        exit_liquidity:   liquidity_from_py(d, "exit_liquidity")?,
+        slippage_entry:   optional(d, "slippage_entry")?.unwrap_or(0.0),
+        slippage_exit:    optional(d, "slippage_exit")?.unwrap_or(0.0),
+        absolute_return:  optional(d, "absolute_return")?,
+        real_return:      optional(d, "real_return")?,
+        pnl:              optional(d, "pnl")?,
+        is_closed:        optional(d, "is_closed")?.unwrap_or(exit_index.is_some()),
+        roll_count:       optional(d, "roll_count")?.unwrap_or(0),
+        roll_cost:        optional(d, "roll_cost")?.unwrap_or(0.0),
+        funding_cost:     optional(d, "funding_cost")?.unwrap_or(0.0),
+        dividend_income:  optional(d, "dividend_income")?.unwrap_or(0.0),
+        strategy_id:      optional(d, "strategy_id")?,
+        symbol:           optional(d, "symbol")?,
+        tag:              optional(d, "tag")?,
+        entry_delay:      optional(d, "entry_delay")?,
+        exit_delay:       optional(d, "exit_delay")?,
+        latency_cost:     optional(d, "latency_cost")?.unwrap_or(0.0),
+        tp_attempts:      optional(d, "tp_attempts")?.unwrap_or(0),
+        tp_rejections:    optional(d, "tp_rejections")?.unwrap_or(0),
+        throttled:        optional(d, "throttled")?.unwrap_or(false),
+        entry_sl:         optional(d, "entry_sl")?.unwrap_or(sl),
+        original_sl:      optional(d, "original_sl")?,
+        original_size:    optional(d, "original_size")?,
+        fills,
+        unfilled_size:    optional(d, "unfilled_size")?.unwrap_or(0.0),
+        level_changes:    Vec::new(),
+        bracket:          None,
+        mfe:              optional(d, "mfe")?.unwrap_or(0.0),
+        mae:              optional(d, "mae")?.unwrap_or(0.0),
+        liquidation_price: optional(d, "liquidation_price")?,
+        near_liquidation: Vec::new(),
+        liquidation_bar:  optional(d, "liquidation_bar")?,
+    })
+}
+
+/// Positions from dicts, checked against a series of `n` bars
+fn positions_from_py(list: Vec<&PyDict>, n: usize) -> PyResult<Vec<Position>> {
+    let positions = list.into_iter().map(position_from_py).collect::<PyResult<Vec<_>>>()?;
+    for pos in &positions {
+        if pos.entry_index >= n || pos.exit_index.is_some_and(|x| x < pos.entry_index || x >= n) {
+            return Err(PyValueError::new_err(format!(
+                "position {} has bar indices outside the {} bars given", pos.position_id, n
+            )));
+        }
+    }
+    Ok(positions)
+}
+
+/// Exposure snapshot from a dict as written by `compute_exposure_series`
+fn snapshot_from_py(d: &PyDict) -> PyResult<ExposureSnapshot> {
+    const WHAT: &str = "exposure snapshot";
+    let or_zero = |key: &str| -> PyResult<f64> { Ok(optional(d, key)?.unwrap_or(0.0)) };
+    Ok(ExposureSnapshot {
+        timestamp:       required(d, WHAT, "timestamp")?,
+        long_exposure:   or_zero("long_exposure")?,
+        short_exposure:  or_zero("short_exposure")?,
+        total_exposure:  or_zero("total_exposure")?,
+        realized_equity: required(d, WHAT, "realized_equity")?,
+        floating_pnl:    required(d, WHAT, "floating_pnl")?,
+        total_equity:    required(d, WHAT, "total_equity")?,
+        open_longs:      optional(d, "open_longs")?.unwrap_or(0),
+        open_shorts:     optional(d, "open_shorts")?.unwrap_or(0),
+        avg_open_age:    or_zero("avg_open_age")?,
+        cash:            or_zero("cash")?,
+        margin_used:     or_zero("margin_used")?,
+        free_margin:     or_zero("free_margin")?,
+        long_notional:   or_zero("long_notional")?,
+        short_notional:  or_zero("short_notional")?,
+        total_notional:  or_zero("total_notional")?,
+        long_pct_of_equity:  or_zero("long_pct_of_equity")?,
+        short_pct_of_equity: or_zero("short_pct_of_equity")?,
+        total_pct_of_equity: or_zero("total_pct_of_equity")?,
+        long_avg_entry:  optional(d, "long_avg_entry")?.unwrap_or(f64::NAN),
+        short_avg_entry: optional(d, "short_avg_entry")?.unwrap_or(f64::NAN),
+    })
+}
+
+fn positions_to_py<'py>(py: Python<'py>, positions: &[Position]) -> PyResult<&'py PyList> {
+    let list = PyList::empty(py);
+    for pos in positions {
+        list.append(position_to_py(py, pos)?)?;
+    }
+    Ok(list)
+}
+
+/// Entry stage: the positions the signals open, with levels and entry costs
+/// but not yet exited.
+///
+/// Returns `{"positions": [...], "skipped_entries": [...]}`; the position
+/// dicts are accepted by `simulate_exits` as they are.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, slippage_rate,
+    contract_type="linear",
+    size_mode="units",
+    tags=None
+))]
+pub fn scan_entries<'py>(
+    py:               Python<'py>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    slippage_rate:    f64,
+    contract_type:    &str,
+    size_mode:        &str,
+    tags:             Option<&PyAny>,
+) -> PyResult<&'py PyDict> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals, long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size, expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let tags = tags.map(tags_from_py).transpose()?;
+    if tags.as_ref().is_some_and(|t| t.len() != market.timestamps.len()) {
+        return Err(PyValueError::new_err("tags must have one entry per bar"));
+    }
+    let contract = ContractType::parse(contract_type).map_err(PyValueError::new_err)?;
+    let size_mode = SizeMode::parse(size_mode).map_err(PyValueError::new_err)?;
+
+    let mut skipped = Vec::new();
+    let mut positions = scan(
+        0..market.open.len(),
+        &market.timestamps,
+        &market.open,
+        &signals.long_signals, &signals.short_signals,
+        &signals.long_tp, &signals.long_sl,
+        &signals.short_tp, &signals.short_sl,
+        &signals.long_size, &signals.short_size,
+        &signals.expiration_times,
+        entry_fee_rate,
+        slippage_rate,
+        contract,
+        size_mode,
+        None,
+        None,
+        None,
+        &mut skipped,
+    );
+    if let Some(tags) = &tags {
+        for pos in &mut positions {
+            pos.tag = tags[pos.signal_index].clone();
+        }
+    }
+
+    let out = PyDict::new(py);
+    out.set_item("positions", positions_to_py(py, &positions)?)?;
+    let sk = PyList::empty(py);
+    for s in &skipped {
+        sk.append(skipped_entry_to_py(py, s)?)?;
+    }
+    out.set_item("skipped_entries", sk)?;
+    Ok(out)
+}
+
+/// Exit stage: TP/SL/expiry exits of `positions` over the bars.
+///
+/// Positions already closed are passed through unchanged.  Returns every
+/// position in input order, closed ones with exit fields and excursions.
+/// With `maker_fee_rate`, take‐profit exits (resting limits) pay it instead
+/// of `exit_fee_rate`.
+#[pyfunction]
+#[pyo3(signature=(
+    positions,
+    timestamp, open, high, low, close,
+    exit_fee_rate, slippage_rate,
+    slippage_sl=None,
+    slippage_tp=None,
+    slippage_exp=None,
+    maker_fee_rate=None
+))]
+pub fn simulate_exits<'py>(
+    py:            Python<'py>,
+    positions:     Vec<&PyDict>,
+    timestamp:     &PyArray1<f64>,
+    open:          &PyArray1<f64>,
+    high:          &PyArray1<f64>,
+    low:           &PyArray1<f64>,
+    close:         &PyArray1<f64>,
+    exit_fee_rate: f64,
+    slippage_rate: f64,
+    slippage_sl:   Option<f64>,
+    slippage_tp:   Option<f64>,
+    slippage_exp:  Option<f64>,
+    maker_fee_rate: Option<f64>,
+) -> PyResult<&'py PyList> {
+    let market: MarketData = market_from_py(timestamp, open, high, low, close)?;
+    let mut positions = positions_from_py(positions, market.close.len())?;
+    let slippage = ExitSlippage {
+        sl:  slippage_sl.unwrap_or(slippage_rate),
+        tp:  slippage_tp.unwrap_or(slippage_rate),
+        exp: slippage_exp.unwrap_or(slippage_rate),
+    };
+    simulate_position_exits(
+        &mut positions,
+        &market.timestamps, &market.open, &market.high, &market.low, &market.close,
+        ExitFees { maker: maker_fee_rate.unwrap_or(exit_fee_rate), taker: exit_fee_rate }, slippage,
+        None, None, None, None, None, None,
+        ExpiryFill::default(), ExpiryPolicy::default(), ExitPriority::default(), LevelCheck::default(), StopType::default(),
+    );
+    record_excursions(&mut positions, &market.high, &market.low);
+    positions_to_py(py, &positions)
+}
+
+/// Exposure stage: per‐bar exposure, equity and margin of `positions`
+/// marked at `close`.
+#[pyfunction]
+#[pyo3(signature=(positions, timestamp, close, initial_equity, leverage=1.0))]
+pub fn compute_exposure_series<'py>(
+    py:             Python<'py>,
+    positions:      Vec<&PyDict>,
+    timestamp:      &PyArray1<f64>,
+    close:          &PyArray1<f64>,
+    initial_equity: f64,
+    leverage:       f64,
+) -> PyResult<&'py PyList> {
+    let timestamps = array_to_vec(timestamp)?;
+    let close = array_to_vec(close)?;
+    if timestamps.len() != close.len() {
+        return Err(PyValueError::new_err("timestamp and close must have the same length"));
+    }
+    if leverage.is_nan() || leverage <= 0.0 {
+        return Err(PyValueError::new_err("leverage must be > 0"));
+    }
+    let positions = positions_from_py(positions, close.len())?;
+    let snaps = exposure_series(&positions, &close, &timestamps, initial_equity, leverage);
+    let list = PyList::empty(py);
+    for s in &snaps {
+        list.append(snapshot_to_py(py, s)?)?;
+    }
+    Ok(list)
+}
+
+/// Metrics stage: the `metrics` dict of `run_backtest` from trades and an
+/// exposure series.
+///
+/// Only closed positions count as trades, so the output of
+/// `simulate_exits` can be passed whole; trades filtered or edited
+/// beforehand should come with the exposure recomputed from them.
+#[pyfunction]
+#[pyo3(signature=(
+    positions, exposure, initial_equity,
+    undefined_ratios="legacy",
+    ratio_cap=None,
+    equity_basis="pnl",
+    return_type="simple",
+    return_equity="marked"
+))]
+pub fn compute_summary_metrics<'py>(
+    py:               Python<'py>,
+    positions:        Vec<&PyDict>,
+    exposure:         Vec<&PyDict>,
+    initial_equity:   f64,
+    undefined_ratios: &str,
+    ratio_cap:        Option<f64>,
+    equity_basis:     &str,
+    return_type:      &str,
+    return_equity:    &str,
+) -> PyResult<&'py PyDict> {
+    let closed: Vec<Position> = positions
+        .into_iter()
+        .map(position_from_py)
+        .filter(|p| p.as_ref().map_or(true, |p| p.is_closed))
+        .collect::<PyResult<_>>()?;
+    let exposure = exposure.into_iter().map(snapshot_from_py).collect::<PyResult<Vec<_>>>()?;
+    if exposure.is_empty() {
+        return Err(PyValueError::new_err("exposure must have at least one snapshot"));
+    }
+    let policy = RatioPolicy::parse(undefined_ratios, ratio_cap).map_err(PyValueError::new_err)?;
+    let spec = ReturnSpec {
+        basis:  EquityBasis::parse(equity_basis).map_err(PyValueError::new_err)?,
+        kind:   ReturnKind::parse(return_type).map_err(PyValueError::new_err)?,
+        equity: ReturnEquity::parse(return_equity).map_err(PyValueError::new_err)?,
+    };
+    let metrics = compute_summary_metrics_with(initial_equity, &closed, &exposure, policy, spec);
+    summary_metrics_to_py(py, &metrics)
+}
@@ -0,0 +1,186 @@
+// src/engine/stats.rs
+
+/// Arithmetic mean (0 for empty input)
+pub fn mean(xs: &[f64]) -> f64 {
+    if xs.is_empty() { 0.0 } else { xs.iter().sum::<f64>() / xs.len() as f64 }
+}
+
+/// Pearson correlation of two equal‐length series (0 if either is flat)
+pub fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let (ma, mb) = (mean(a), mean(b));
+    let (mut cov, mut va, mut vb) = (0.0, 0.0, 0.0);
+    for (&x, &y) in a.iter().zip(b) {
+        cov += (x - ma) * (y - mb);
+        va  += (x - ma).powi(2);
+        vb  += (y - mb).powi(2);
+    }
+    if va > 0.0 && vb > 0.0 { cov / (va * vb).sqrt() } else { 0.0 }
+}
+
+/// Pairwise `correlation` of every series against every other
+pub fn correlation_matrix(series: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    series
+        .iter()
+        .map(|a| series.iter().map(|b| correlation(a, b)).collect())
+        .collect()
+}
+
+/// Linear‐interpolated percentile, `q` in [0, 1]; NaN for empty input
+pub fn percentile(xs: &[f64], q: f64) -> f64 {
+    let mut v: Vec<f64> = xs.iter().copied().filter(|x| !x.is_nan()).collect();
+    if v.is_empty() {
+        return f64::NAN;
+    }
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let pos  = q.clamp(0.0, 1.0) * (v.len() - 1) as f64;
+    let lo   = pos.floor() as usize;
+    let hi   = pos.ceil() as usize;
+    let frac = pos - lo as f64;
+    v[lo] + (v[hi] - v[lo]) * frac
+}
+
+/// Summary of a sampled distribution with a two‐sided confidence interval
+#[derive(Debug, Clone)]
+pub struct Distribution {
+    pub values:   Vec<f64>,
+    pub mean:     f64,
+    pub median:   f64,
+    pub ci_lower: f64,
+    pub ci_upper: f64,
+}
+
+pub fn summarize(values: Vec<f64>, confidence: f64) -> Distribution {
+    let alpha = (1.0 - confidence) / 2.0;
+    Distribution {
+        mean:     mean(&values),
+        median:   percentile(&values, 0.5),
+        ci_lower: percentile(&values, alpha),
+        ci_upper: percentile(&values, 1.0 - alpha),
+        values,
+    }
+}
+
+/// Standard normal CDF (Abramowitz–Stegun 7.1.26 erf, |error| < 1.5e‐7)
+pub fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t * (0.254_829_592
+        + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation, relative
+/// error < 1.2e‐9); ±∞ at 0 and 1
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-39.696_830_286_653_76, 220.946_098_424_520_5, -275.928_510_446_968_7,
+                         138.357_751_867_269, -30.664_798_066_147_16, 2.506_628_277_459_239];
+    const B: [f64; 5] = [-54.476_098_798_224_06, 161.585_836_858_040_9, -155.698_979_859_886_6,
+                         66.801_311_887_719_72, -13.280_681_552_885_72];
+    const C: [f64; 6] = [-0.007_784_894_002_430_293, -0.322_396_458_041_136_5, -2.400_758_277_161_838,
+                         -2.549_732_539_343_734, 4.374_664_141_464_968, 2.938_163_982_698_783];
+    const D: [f64; 4] = [0.007_784_695_709_041_462, 0.322_467_129_070_039_8, 2.445_134_137_142_996,
+                         3.754_408_661_907_416];
+    const P_LOW: f64 = 0.024_25;
+    if p.is_nan() || !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Sample skewness and (non‐excess) kurtosis; (0, 3) for flat or short input
+pub fn moments(xs: &[f64]) -> (f64, f64) {
+    let n = xs.len() as f64;
+    let m = mean(xs);
+    let m2 = xs.iter().map(|x| (x - m).powi(2)).sum::<f64>() / n;
+    if n < 3.0 || m2 <= 0.0 {
+        return (0.0, 3.0);
+    }
+    let m3 = xs.iter().map(|x| (x - m).powi(3)).sum::<f64>() / n;
+    let m4 = xs.iter().map(|x| (x - m).powi(4)).sum::<f64>() / n;
+    (m3 / m2.powf(1.5), m4 / (m2 * m2))
+}
+
+/// Probability that the true (per‐bar) Sharpe exceeds `benchmark`, given
+/// the observed `sharpe` over `n` returns with the given skewness and
+/// kurtosis (Bailey & López de Prado); NaN when undefined
+pub fn probabilistic_sharpe(sharpe: f64, benchmark: f64, n: usize, skewness: f64, kurtosis: f64) -> f64 {
+    let var = 1.0 - skewness * sharpe + (kurtosis - 1.0) / 4.0 * sharpe * sharpe;
+    if n < 2 || var.is_nan() || var <= 0.0 || sharpe.is_nan() {
+        return f64::NAN;
+    }
+    normal_cdf((sharpe - benchmark) * ((n - 1) as f64).sqrt() / var.sqrt())
+}
+
+/// Expected maximum Sharpe of `trials` independent zero‐skill trials whose
+/// Sharpes have variance `variance` (the deflated Sharpe's benchmark)
+pub fn expected_max_sharpe(variance: f64, trials: usize) -> f64 {
+    const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+    if trials < 2 || variance.is_nan() || variance <= 0.0 {
+        return 0.0;
+    }
+    let n = trials as f64;
+    variance.sqrt()
+        * ((1.0 - EULER_GAMMA) * normal_quantile(1.0 - 1.0 / n)
+            + EULER_GAMMA * normal_quantile(1.0 - 1.0 / (n * std::f64::consts::E)))
+}
+
+/// Sample autocorrelation at `lag` (0 if the series is flat or too short)
+pub fn autocorrelation(xs: &[f64], lag: usize) -> f64 {
+    if lag == 0 || lag >= xs.len() {
+        return 0.0;
+    }
+    let m = mean(xs);
+    let var: f64 = xs.iter().map(|x| (x - m).powi(2)).sum();
+    if var <= 0.0 {
+        return 0.0;
+    }
+    let cov: f64 = xs.windows(lag + 1).map(|w| (w[0] - m) * (w[lag] - m)).sum();
+    cov / var
+}
+
+/// Wald–Wolfowitz runs test on the signs of a series (zeros skipped)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunsTest {
+    /// Maximal same‐sign streaks
+    pub runs:          usize,
+    pub expected_runs: f64,
+    /// (runs − expected) / sd; negative means clustering, positive alternation
+    pub z_score:       f64,
+    /// Two‐sided normal p‐value (1 when undefined)
+    pub p_value:       f64,
+}
+
+pub fn runs_test(xs: &[f64]) -> RunsTest {
+    let signs: Vec<bool> = xs.iter().filter(|&&x| x != 0.0 && !x.is_nan()).map(|&x| x > 0.0).collect();
+    let runs = if signs.is_empty() { 0 } else { 1 + signs.windows(2).filter(|w| w[0] != w[1]).count() };
+    let n1 = signs.iter().filter(|&&s| s).count() as f64;
+    let n2 = signs.len() as f64 - n1;
+    let n  = n1 + n2;
+    if n1 == 0.0 || n2 == 0.0 || n < 2.0 {
+        return RunsTest { runs, expected_runs: runs as f64, z_score: 0.0, p_value: 1.0 };
+    }
+    let expected = 2.0 * n1 * n2 / n + 1.0;
+    let var = 2.0 * n1 * n2 * (2.0 * n1 * n2 - n) / (n * n * (n - 1.0));
+    let z = if var > 0.0 { (runs as f64 - expected) / var.sqrt() } else { 0.0 };
+    RunsTest { runs, expected_runs: expected, z_score: z, p_value: 2.0 * (1.0 - normal_cdf(z.abs())) }
+}
@@ -0,0 +1,194 @@
+// src/engine/stream_export.rs
+
+use std::path::Path;
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+
+use crate::engine::{
+    market_from_py, signals_from_py,
+    backtest::{validate_signals, BacktestConfig},
+    streaming::{stream_backtest as run_stream, Cell, CsvSink, StreamSink, StreamSummary},
+};
+
+/// Parquet files through pyarrow, one row group per chunk
+struct ParquetSink<'py> {
+    py:       Python<'py>,
+    dir:      &'py Path,
+    trades:   Option<&'py PyAny>,
+    exposure: Option<&'py PyAny>,
+}
+
+impl<'py> ParquetSink<'py> {
+    fn write(&self, writer: &mut Option<&'py PyAny>, name: &str, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> PyResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let data = PyDict::new(self.py);
+        for (k, col) in columns.iter().enumerate() {
+            let values = PyList::empty(self.py);
+            for row in rows {
+                match &row[k] {
+                    Cell::Num(v)  => values.append(v)?,
+                    Cell::Int(v)  => values.append(v)?,
+                    Cell::Text(s) => values.append(s)?,
+                }
+            }
+            data.set_item(col, values)?;
+        }
+        let pa = self.py.import("pyarrow")?;
+        let table = pa.getattr("Table")?.call_method1("from_pydict", (data,))?;
+        let w = match writer {
+            Some(w) => *w,
+            None => {
+                let path = self.dir.join(name);
+                let w = self.py
+                    .import("pyarrow.parquet")?
+                    .getattr("ParquetWriter")?
+                    .call1((path.to_string_lossy().to_string(), table.getattr("schema")?))?;
+                *writer = Some(w);
+                w
+            }
+        };
+        w.call_method1("write_table", (table,))?;
+        Ok(())
+    }
+
+    fn close(&self) -> PyResult<()> {
+        for w in [self.trades, self.exposure].into_iter().flatten() {
+            w.call_method0("close")?;
+        }
+        Ok(())
+    }
+}
+
+impl StreamSink for ParquetSink<'_> {
+    fn trades(&mut self, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String> {
+        let mut w = self.trades;
+        let r = self.write(&mut w, "trades.parquet", columns, rows).map_err(|e| e.to_string());
+        self.trades = w;
+        r
+    }
+
+    fn exposure(&mut self, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String> {
+        let mut w = self.exposure;
+        let r = self.write(&mut w, "exposure.parquet", columns, rows).map_err(|e| e.to_string());
+        self.exposure = w;
+        r
+    }
+}
+
+fn summary_to_py<'py>(py: Python<'py>, s: &StreamSummary, config: &BacktestConfig) -> PyResult<&'py PyDict> {
+    let policy = config.ratio_policy;
+    let d = PyDict::new(py);
+    d.set_item("bars",              s.bars)?;
+    d.set_item("number_of_trades",  s.number_of_trades)?;
+    d.set_item("open_positions",    s.open_positions)?;
+    d.set_item("skipped_entries",   s.skipped_entries)?;
+    d.set_item("win_rate",          s.win_rate())?;
+    d.set_item("profit_factor",     s.profit_factor(policy))?;
+    d.set_item("total_pnl",         s.total_pnl)?;
+    d.set_item("final_equity",      s.final_equity)?;
+    d.set_item("mean_return",       s.mean_return)?;
+    d.set_item("volatility",        s.volatility)?;
+    d.set_item("sharpe_ratio",      s.sharpe_ratio(policy))?;
+    d.set_item("cumulative_return", s.cumulative_return)?;
+    d.set_item("max_drawdown",      s.max_drawdown)?;
+    d.set_item("peak_positions_in_memory", s.peak_positions)?;
+    Ok(d)
+}
+
+/// Chunked backtest for very long bar series.
+///
+/// Processes `chunk_bars` bars at a time and writes closed trades and
+/// per‐bar exposure incrementally to `output_dir` (`trades.csv` /
+/// `exposure.csv`, or `.parquet` through pyarrow with `format="parquet"`),
+/// keeping only the positions still open in memory.  Trades still open after
+/// the last bar are written with `is_closed` 0.  Returns the headline
+/// metrics accumulated on the way and the written paths.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    output_dir,
+    chunk_bars=1_000_000,
+    format="csv"
+))]
+pub fn stream_backtest(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    output_dir:       &str,
+    chunk_bars:       usize,
+    format:           &str,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let dir = Path::new(output_dir);
+
+    let (summary, ext) = match format {
+        "csv" => {
+            let summary = py.allow_threads(|| {
+                let mut sink = CsvSink::create(dir)?;
+                let summary = run_stream(&market, &signals, &config, chunk_bars, &mut sink)?;
+                sink.finish()?;
+                Ok::<_, String>(summary)
+            });
+            (summary.map_err(PyValueError::new_err)?, "csv")
+        }
+        "parquet" => {
+            std::fs::create_dir_all(dir).map_err(|e| PyValueError::new_err(format!("{}: {}", output_dir, e)))?;
+            let mut sink = ParquetSink { py, dir, trades: None, exposure: None };
+            let summary = run_stream(&market, &signals, &config, chunk_bars, &mut sink).map_err(PyValueError::new_err)?;
+            sink.close()?;
+            (summary, "parquet")
+        }
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "format must be \"csv\" or \"parquet\", got ‘{}’", other
+            )))
+        }
+    };
+
+    let out = PyDict::new(py);
+    out.set_item("metrics", summary_to_py(py, &summary, &config)?)?;
+    out.set_item("trades_path",   dir.join(format!("trades.{}", ext)).to_string_lossy().to_string())?;
+    out.set_item("exposure_path", dir.join(format!("exposure.{}", ext)).to_string_lossy().to_string())?;
+    Ok(out.into())
+}
@@ -0,0 +1,394 @@
+// src/engine/streaming.rs
+
+//! Chunked runs for bar series too long to hold every trade and snapshot.
+//!
+//! Bars are processed `chunk_bars` at a time: the chunk's signals are
+//! scanned and their exits simulated (a position's exit only depends on its
+//! own path, so it is found as in a full run), the exposure of the positions
+//! open during the chunk is marked, and every snapshot and every trade that
+//! exited inside the chunk is handed to a `StreamSink` and dropped.  Memory
+//! holds the inputs, one chunk of snapshots and the positions still open;
+//! the headline metrics are accumulated on the way.
+//!
+//! Account‐level options (rules, throttle, drawdown stop, partial fills,
+//! funding, dividends, FX, rolls, reverse signals, cost accrual) need the
+//! whole run and are rejected.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::engine::{
+    backtest::{BacktestConfig, MarketData, SignalSet},
+    excursions::record_excursions,
+    exposure::{compute_exposure_series_with, CostAccrual, ExposureOptions, ExposureSnapshot},
+    metrics::{RatioPolicy, ReturnEquity, ReturnKind},
+    position::Position,
+    scan_entries::scan_entries,
+    signal_input::SignalMode,
+    simulate_exits::simulate_position_exits,
+};
+
+/// One output cell
+#[derive(Clone, Debug)]
+pub enum Cell<'a> {
+    Num(f64),
+    Int(u64),
+    Text(Option<&'a str>),
+}
+
+/// Destination of the streamed rows; `columns` are written once, before the
+/// first rows
+pub trait StreamSink {
+    fn trades(&mut self, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String>;
+    fn exposure(&mut self, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String>;
+}
+
+pub const TRADE_COLUMNS: &[&str] = &[
+    "position_id", "position_type", "signal_index", "entry_index", "entry_time", "entry_price",
+    "tp", "sl", "position_size", "exit_index", "exit_time", "exit_price", "exit_condition",
+    "fee_entry", "fee_exit", "pnl", "real_return", "mfe", "mae", "is_closed",
+];
+
+pub const EXPOSURE_COLUMNS: &[&str] = &[
+    "timestamp", "long_exposure", "short_exposure", "realized_equity", "floating_pnl",
+    "total_equity", "open_longs", "open_shorts", "margin_used", "total_notional",
+];
+
+fn trade_row<'a>(pos: &'a Position, timestamps: &[f64]) -> Vec<Cell<'a>> {
+    let opt = |v: Option<f64>| Cell::Num(v.unwrap_or(f64::NAN));
+    vec![
+        Cell::Int(pos.position_id),
+        Cell::Text(Some(&pos.position_type)),
+        Cell::Int(pos.signal_index as u64),
+        Cell::Int(pos.entry_index as u64),
+        Cell::Num(pos.entry_time),
+        Cell::Num(pos.entry_price),
+        Cell::Num(pos.tp),
+        Cell::Num(pos.sl),
+        Cell::Num(pos.position_size),
+        opt(pos.exit_index.map(|i| i as f64)),
+        opt(pos.exit_index.map(|i| pos.exit_time.unwrap_or(timestamps[i]))),
+        opt(pos.exit_price),
+        Cell::Text(pos.exit_condition.as_deref()),
+        Cell::Num(pos.fee_entry),
+        Cell::Num(pos.fee_exit),
+        opt(pos.pnl),
+        opt(pos.real_return),
+        Cell::Num(pos.mfe),
+        Cell::Num(pos.mae),
+        Cell::Int(pos.is_closed as u64),
+    ]
+}
+
+fn exposure_row(s: &ExposureSnapshot) -> Vec<Cell<'static>> {
+    vec![
+        Cell::Num(s.timestamp),
+        Cell::Num(s.long_exposure),
+        Cell::Num(s.short_exposure),
+        Cell::Num(s.realized_equity),
+        Cell::Num(s.floating_pnl),
+        Cell::Num(s.total_equity),
+        Cell::Int(s.open_longs as u64),
+        Cell::Int(s.open_shorts as u64),
+        Cell::Num(s.margin_used),
+        Cell::Num(s.total_notional),
+    ]
+}
+
+/// CSV files `trades.csv` and `exposure.csv` in a directory
+pub struct CsvSink {
+    trades:   BufWriter<File>,
+    exposure: BufWriter<File>,
+    headers:  (bool, bool),
+}
+
+impl CsvSink {
+    pub fn create(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let open = |name: &str| {
+            let path = dir.join(name);
+            File::create(&path)
+                .map(BufWriter::new)
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        };
+        Ok(CsvSink { trades: open("trades.csv")?, exposure: open("exposure.csv")?, headers: (false, false) })
+    }
+
+    pub fn finish(mut self) -> Result<(), String> {
+        self.trades.flush().map_err(|e| e.to_string())?;
+        self.exposure.flush().map_err(|e| e.to_string())
+    }
+}
+
+fn write_csv(out: &mut BufWriter<File>, header: &mut bool, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String> {
+    let mut text = String::new();
+    if !*header {
+        text.push_str(&columns.join(","));
+        text.push('\n');
+        *header = true;
+    }
+    for row in rows {
+        for (k, cell) in row.iter().enumerate() {
+            if k > 0 {
+                text.push(',');
+            }
+            match cell {
+                Cell::Num(v) if v.is_nan() => {}
+                Cell::Num(v) => text.push_str(&v.to_string()),
+                Cell::Int(v) => text.push_str(&v.to_string()),
+                Cell::Text(Some(s)) => text.push_str(s),
+                Cell::Text(None) => {}
+            }
+        }
+        text.push('\n');
+    }
+    out.write_all(text.as_bytes()).map_err(|e| e.to_string())
+}
+
+impl StreamSink for CsvSink {
+    fn trades(&mut self, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String> {
+        write_csv(&mut self.trades, &mut self.headers.0, columns, rows)
+    }
+
+    fn exposure(&mut self, columns: &[&str], rows: &[Vec<Cell<'_>>]) -> Result<(), String> {
+        write_csv(&mut self.exposure, &mut self.headers.1, columns, rows)
+    }
+}
+
+/// Headline metrics accumulated over the stream (same definitions as the
+/// full run's overall trade and time‐series metrics)
+#[derive(Clone, Debug, Default)]
+pub struct StreamSummary {
+    pub bars:              usize,
+    pub number_of_trades:  usize,
+    pub open_positions:    usize,
+    pub skipped_entries:   usize,
+    pub wins:              usize,
+    pub losses:            usize,
+    pub gross_wins:        f64,
+    pub gross_losses:      f64,
+    pub total_pnl:         f64,
+    pub final_equity:      f64,
+    pub mean_return:       f64,
+    pub volatility:        f64,
+    pub cumulative_return: f64,
+    pub max_drawdown:      f64,
+    /// Largest number of positions held in memory at once
+    pub peak_positions:    usize,
+}
+
+impl StreamSummary {
+    pub fn win_rate(&self) -> f64 {
+        if self.number_of_trades > 0 { self.wins as f64 / self.number_of_trades as f64 } else { 0.0 }
+    }
+
+    pub fn profit_factor(&self, policy: RatioPolicy) -> f64 {
+        policy.ratio(self.gross_wins, self.gross_losses, f64::INFINITY)
+    }
+
+    pub fn sharpe_ratio(&self, policy: RatioPolicy) -> f64 {
+        policy.ratio(self.mean_return, self.volatility, 0.0)
+    }
+}
+
+/// Running curve statistics (Welford mean / variance of the bar returns)
+struct CurveStats {
+    kind:   ReturnKind,
+    start:  Option<f64>,
+    prev:   Option<f64>,
+    last:   f64,
+    peak:   f64,
+    max_dd: f64,
+    count:  usize,
+    mean:   f64,
+    m2:     f64,
+}
+
+impl CurveStats {
+    fn push(&mut self, eq: f64) {
+        let start = *self.start.get_or_insert(eq);
+        if self.prev.is_none() {
+            self.peak = start;
+        }
+        if let Some(prev) = self.prev {
+            let r = self.kind.of(prev, eq);
+            self.count += 1;
+            let d = r - self.mean;
+            self.mean += d / self.count as f64;
+            self.m2 += d * (r - self.mean);
+        }
+        self.peak = self.peak.max(eq);
+        let dd = if self.peak != 0.0 { (self.peak - eq) / self.peak } else { 0.0 };
+        self.max_dd = self.max_dd.max(dd);
+        self.prev = Some(eq);
+        self.last = eq;
+    }
+}
+
+/// First config option a chunked run cannot honour
+fn unsupported(config: &BacktestConfig) -> Option<&'static str> {
+    let checks = [
+        (config.rolls.is_some(), "rolls"),
+        (config.trailing_drawdown.is_some(), "trailing_drawdown"),
+        (config.throttle.is_some(), "throttle"),
+        (config.account.is_active(), "account rules"),
+        (config.partial_fills.is_some(), "partial fills"),
+        (config.signal_mode == SignalMode::Reverse, "signal_mode=\"reverse\""),
+        (config.funding_rates.is_some(), "funding_rates"),
+        (config.mark_price.is_some(), "mark_price"),
+        (config.fx_rates.is_some(), "fx_rates"),
+        (config.dividends.is_some(), "dividends"),
+        (config.borrow_available.is_some(), "borrow_available"),
+        (config.cash_flows.is_some(), "cash_flows"),
+        (config.cost_accrual != CostAccrual::Exit, "cost_accrual"),
+        (config.net_floating, "net_floating"),
+    ];
+    checks.iter().find(|(on, _)| *on).map(|(_, name)| *name)
+}
+
+/// Copy of `pos` with bar indices relative to the chunk `[a, b)`: entered
+/// before it counts from its first bar, exiting after it stays open
+fn rebase(pos: &Position, a: usize, b: usize) -> Position {
+    let mut p = pos.clone();
+    p.entry_index = p.entry_index.saturating_sub(a);
+    p.exit_index = pos.exit_index.filter(|&x| x < b).map(|x| x - a);
+    p
+}
+
+/// Run the backtest `chunk_bars` bars at a time, streaming trades and
+/// exposure snapshots to `sink`; open positions at the end are written as
+/// trades with `is_closed` 0
+pub fn stream_backtest(
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+    chunk_bars: usize,
+    sink: &mut dyn StreamSink,
+) -> Result<StreamSummary, String> {
+    if let Some(option) = unsupported(config) {
+        return Err(format!("{} is not supported by chunked runs", option));
+    }
+    if chunk_bars == 0 {
+        return Err("chunk_bars must be > 0".into());
+    }
+    let n = market.close.len();
+    let spec = config.returns;
+    let base = spec.basis.base(config.initial_equity);
+    let mut curve = CurveStats {
+        kind: spec.kind, start: (base != 0.0).then_some(base), prev: None,
+        last: base, peak: base, max_dd: 0.0, count: 0, mean: 0.0, m2: 0.0,
+    };
+    let mut summary = StreamSummary { bars: n, ..StreamSummary::default() };
+    let mut carry: Vec<Position> = Vec::new();
+    let mut next_id = 0u64;
+    let mut realized = 0.0;
+
+    for a in (0..n).step_by(chunk_bars) {
+        let b = (a + chunk_bars).min(n);
+
+        // 1) this chunk's entries, exits found over the whole series
+        let mut skipped = Vec::new();
+        let mut fresh = scan_entries(
+            a..b,
+            &market.timestamps,
+            &market.open,
+            &signals.long_signals, &signals.short_signals,
+            &signals.long_tp, &signals.long_sl,
+            &signals.short_tp, &signals.short_sl,
+            &signals.long_size, &signals.short_size,
+            &signals.expiration_times,
+            config.entry_fee_rate,
+            config.slippage_rate,
+            config.contract,
+            config.size_mode,
+            config.latency,
+            config.loss_cap,
+            config.filters,
+            &mut skipped,
+        );
+        summary.skipped_entries += skipped.len();
+        for pos in &mut fresh {
+            pos.position_id += next_id;
+            if let Some(tags) = &signals.tags {
+                pos.tag = tags[pos.signal_index].clone();
+            }
+        }
+        next_id += fresh.len() as u64;
+        simulate_position_exits(
+            &mut fresh,
+            &market.timestamps, &market.open, &market.high, &market.low, &market.close,
+            config.exit_fees(), config.exit_slippage(),
+            config.latency, config.fill_model, config.queue_model.as_ref(), config.filters, config.exit_rules.as_ref(),
+            config.sub_bars.as_ref(), config.expiry_fill, config.expiry_policy, config.exit_priority, config.level_check, config.stop_type,
+        );
+        record_excursions(&mut fresh, &market.high, &market.low);
+        carry.extend(fresh);
+        summary.peak_positions = summary.peak_positions.max(carry.len());
+
+        // 2) mark the positions held during the chunk
+        let held: Vec<Position> = carry
+            .iter()
+            .filter(|p| p.entry_index < b)
+            .map(|p| rebase(p, a, b))
+            .collect();
+        let mut snaps = compute_exposure_series_with(
+            &held, &market.close[a..b], &market.timestamps[a..b],
+            config.initial_equity, config.leverage(), ExposureOptions::default(),
+        );
+        for s in &mut snaps {
+            s.realized_equity += realized;
+            s.total_equity    += realized;
+            s.cash            += realized;
+            s.free_margin     += realized;
+            s.set_equity_shares(config.initial_equity);
+            curve.push(base + match spec.equity {
+                ReturnEquity::Marked   => s.total_equity,
+                ReturnEquity::Realized => s.realized_equity,
+            });
+        }
+        if let Some(last) = snaps.last() {
+            summary.final_equity = config.initial_equity + last.total_equity;
+        }
+        let rows: Vec<_> = snaps.iter().map(exposure_row).collect();
+        sink.exposure(EXPOSURE_COLUMNS, &rows)?;
+
+        // 3) hand over the trades that exited inside the chunk
+        let (done, open): (Vec<Position>, Vec<Position>) =
+            carry.into_iter().partition(|p| p.exit_index.is_some_and(|x| x < b));
+        carry = open;
+        for pos in &done {
+            let pnl = pos.pnl.unwrap_or(0.0);
+            realized += pnl;
+            summary.total_pnl += pnl;
+            summary.number_of_trades += 1;
+            if pnl > 0.0 {
+                summary.wins += 1;
+                summary.gross_wins += pnl;
+            } else if pnl < 0.0 {
+                summary.losses += 1;
+                summary.gross_losses -= pnl;
+            }
+        }
+        let rows: Vec<_> = done.iter().map(|p| trade_row(p, &market.timestamps)).collect();
+        sink.trades(TRADE_COLUMNS, &rows)?;
+    }
+
+    summary.open_positions = carry.len();
+    let rows: Vec<_> = carry.iter().map(|p| trade_row(p, &market.timestamps)).collect();
+    sink.trades(TRADE_COLUMNS, &rows)?;
+
+    summary.mean_return = curve.mean;
+    summary.volatility = if curve.count > 1 { (curve.m2 / (curve.count - 1) as f64).sqrt() } else { 0.0 };
+    summary.max_drawdown = curve.max_dd;
+    let start = curve.start.unwrap_or(0.0);
+    summary.cumulative_return = if start != 0.0 {
+        match spec.kind {
+            ReturnKind::Simple => curve.last / start - 1.0,
+            ReturnKind::Log    => ReturnKind::Log.of(start, curve.last),
+        }
+    } else {
+        0.0
+    };
+    Ok(summary)
+}
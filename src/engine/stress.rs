@@ -0,0 +1,228 @@
+// src/engine/stress.rs
+
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::exceptions::PyValueError;
+use rayon::prelude::*;
+
+use crate::engine::{
+    market_from_py, signals_from_py,
+    backtest::{run_engine, validate_signals, BacktestConfig, MarketData},
+    metrics::lookup_metric,
+    significance::metric_selection,
+};
+
+/// Synthetic shock overlaid on the bar series
+#[derive(Clone, Debug)]
+pub enum Shock {
+    /// Every price from the first bar at/after `timestamp` scaled by (1 + magnitude)
+    Gap { timestamp: f64, magnitude: f64 },
+    /// Log‐moves of bars in [start, end] multiplied by `factor`; later bars
+    /// keep their shape from the new level
+    Volatility { start: f64, end: f64, factor: f64 },
+}
+
+impl Shock {
+    fn from_py(d: &PyDict) -> PyResult<Self> {
+        fn get(d: &PyDict, key: &str) -> PyResult<f64> {
+            d.get_item(key)
+                .ok_or_else(|| PyValueError::new_err(format!("shock is missing ‘{}’", key)))?
+                .extract()
+        }
+        let kind: String = d
+            .get_item("type")
+            .ok_or_else(|| PyValueError::new_err("shock is missing ‘type’"))?
+            .extract()?;
+        let shock = match kind.as_str() {
+            "gap" => Shock::Gap {
+                timestamp: get(d, "timestamp")?,
+                magnitude: get(d, "magnitude")?,
+            },
+            "volatility" => Shock::Volatility {
+                start:  get(d, "start")?,
+                end:    get(d, "end")?,
+                factor: get(d, "factor")?,
+            },
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown shock type ‘{}’ (expected \"gap\" or \"volatility\")", other
+                )))
+            }
+        };
+        match shock {
+            Shock::Gap { magnitude, .. } if magnitude <= -1.0 => {
+                Err(PyValueError::new_err("gap magnitude must be > -1"))
+            }
+            Shock::Volatility { start, end, factor } if factor.is_nan() || factor <= 0.0 || end < start => {
+                Err(PyValueError::new_err("volatility shock needs factor > 0 and start ≤ end"))
+            }
+            s => Ok(s),
+        }
+    }
+}
+
+/// Copy of `market` with `shock` applied (TP/SL levels are left untouched,
+/// so gaps can jump through them)
+pub fn apply_shock(market: &MarketData, shock: &Shock) -> MarketData {
+    let mut m = market.clone();
+    let n = m.close.len();
+    match *shock {
+        Shock::Gap { timestamp, magnitude } => {
+            let from = m.timestamps.partition_point(|&t| t < timestamp);
+            for j in from..n {
+                m.open[j]  *= 1.0 + magnitude;
+                m.high[j]  *= 1.0 + magnitude;
+                m.low[j]   *= 1.0 + magnitude;
+                m.close[j] *= 1.0 + magnitude;
+            }
+        }
+        Shock::Volatility { start, end, factor } => {
+            let from = m.timestamps.partition_point(|&t| t < start);
+            let to   = m.timestamps.partition_point(|&t| t <= end);
+            if from >= to {
+                return m;
+            }
+            // bars in the window: scale the log‐distance from the previous close
+            for j in from..to {
+                let (raw_ref, new_ref) = if j > 0 {
+                    (market.close[j - 1], m.close[j - 1])
+                } else {
+                    (market.open[0], market.open[0])
+                };
+                let scale = |x: f64| {
+                    if raw_ref > 0.0 && x > 0.0 { new_ref * (x / raw_ref).powf(factor) } else { x }
+                };
+                m.open[j]  = scale(market.open[j]);
+                m.high[j]  = scale(market.high[j]);
+                m.low[j]   = scale(market.low[j]);
+                m.close[j] = scale(market.close[j]);
+            }
+            // later bars: same returns from the shifted level
+            let level = if market.close[to - 1] != 0.0 { m.close[to - 1] / market.close[to - 1] } else { 1.0 };
+            for j in to..n {
+                m.open[j]  *= level;
+                m.high[j]  *= level;
+                m.low[j]   *= level;
+                m.close[j] *= level;
+            }
+        }
+    }
+    m
+}
+
+/// Scenario stress test.
+///
+/// Each scenario is a shock dict (`{"type": "gap", "timestamp", "magnitude"}`
+/// or `{"type": "volatility", "start", "end", "factor"}`, optional `"name"`)
+/// or `{"name", "shocks": [...]}` to combine several.  The same signals are
+/// re‐run on every shocked series and metric deltas vs the baseline reported.
+#[pyfunction]
+#[pyo3(signature=(
+    timestamp, open, high, low, close,
+    long_signals, short_signals,
+    long_tp, long_sl, short_tp, short_sl,
+    long_size, short_size,
+    expiration_times,
+    entry_fee_rate, exit_fee_rate, slippage_rate,
+    initial_equity,
+    scenarios,
+    metrics=None
+))]
+pub fn stress_test(
+    py: Python<'_>,
+    timestamp:        &PyArray1<f64>,
+    open:             &PyArray1<f64>,
+    high:             &PyArray1<f64>,
+    low:              &PyArray1<f64>,
+    close:            &PyArray1<f64>,
+    long_signals:     &PyArray1<bool>,
+    short_signals:    &PyArray1<bool>,
+    long_tp:          &PyArray1<f64>,
+    long_sl:          &PyArray1<f64>,
+    short_tp:         &PyArray1<f64>,
+    short_sl:         &PyArray1<f64>,
+    long_size:        &PyArray1<f64>,
+    short_size:       &PyArray1<f64>,
+    expiration_times: &PyArray1<f64>,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+    scenarios:        Vec<&PyDict>,
+    metrics:          Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let market = market_from_py(timestamp, open, high, low, close)?;
+    let signals = signals_from_py(
+        long_signals, short_signals,
+        long_tp, long_sl, short_tp, short_sl,
+        long_size, short_size,
+        expiration_times,
+    )?;
+    validate_signals(&signals, &market.timestamps).map_err(PyValueError::new_err)?;
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    let metric_names = metric_selection(metrics)?;
+
+    let mut parsed: Vec<(String, Vec<Shock>)> = Vec::with_capacity(scenarios.len());
+    for (k, d) in scenarios.iter().enumerate() {
+        let name: String = match d.get_item("name") {
+            Some(v) => v.extract()?,
+            None    => format!("scenario_{}", k),
+        };
+        let shocks = match d.get_item("shocks") {
+            Some(list) => list
+                .extract::<Vec<&PyDict>>()?
+                .into_iter()
+                .map(Shock::from_py)
+                .collect::<PyResult<Vec<_>>>()?,
+            None => vec![Shock::from_py(d)?],
+        };
+        parsed.push((name, shocks));
+    }
+
+    let score = |m: &MarketData| -> Vec<f64> {
+        let summary = run_engine(m, &signals, &config).metrics;
+        metric_names
+            .iter()
+            .map(|name| lookup_metric(&summary, name).unwrap_or(f64::NAN))
+            .collect()
+    };
+    let (baseline, runs) = py.allow_threads(|| {
+        let baseline = score(&market);
+        let runs: Vec<Vec<f64>> = parsed
+            .par_iter()
+            .map(|(_, shocks)| {
+                let shocked = shocks.iter().fold(market.clone(), |m, s| apply_shock(&m, s));
+                score(&shocked)
+            })
+            .collect();
+        (baseline, runs)
+    });
+
+    let values_to_py = |values: &[f64]| -> PyResult<&PyDict> {
+        let d = PyDict::new(py);
+        for (name, v) in metric_names.iter().zip(values) {
+            d.set_item(name, v)?;
+        }
+        Ok(d)
+    };
+    let rows = PyList::empty(py);
+    for ((name, _), values) in parsed.iter().zip(&runs) {
+        let deltas: Vec<f64> = values.iter().zip(&baseline).map(|(v, b)| v - b).collect();
+        let row = PyDict::new(py);
+        row.set_item("name",    name)?;
+        row.set_item("metrics", values_to_py(values)?)?;
+        row.set_item("deltas",  values_to_py(&deltas)?)?;
+        rows.append(row)?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("baseline",  values_to_py(&baseline)?)?;
+    out.set_item("scenarios", rows)?;
+    Ok(out.into())
+}
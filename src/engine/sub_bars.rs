@@ -0,0 +1,39 @@
+// src/engine/sub_bars.rs
+
+use std::ops::Range;
+
+use crate::engine::backtest::MarketData;
+
+/// Finer‐resolution bars used to order touches inside a coarse bar
+#[derive(Clone, Debug)]
+pub struct SubBars {
+    pub bars:   MarketData,
+    /// Sub‐bars whose timestamp lies in `[ts[j], ts[j+1])` for coarse bar j
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl SubBars {
+    pub fn new(bars: MarketData, coarse_ts: &[f64]) -> Self {
+        let ts = &bars.timestamps;
+        let ranges = (0..coarse_ts.len())
+            .map(|j| {
+                let start = ts.partition_point(|&t| t < coarse_ts[j]);
+                let end = match coarse_ts.get(j + 1) {
+                    Some(&next) => ts.partition_point(|&t| t < next),
+                    None        => ts.len(),
+                };
+                start..end.max(start)
+            })
+            .collect();
+        SubBars { bars, ranges }
+    }
+
+    /// First sub‐bar of coarse bar `j` touching the stop and the target
+    pub fn first_touch(&self, j: usize, long: bool, sl: f64, tp: f64) -> (Option<usize>, Option<usize>) {
+        let (high, low) = (&self.bars.high, &self.bars.low);
+        let range = self.ranges[j].clone();
+        let sl_at = range.clone().find(|&k| if long { low[k] <= sl } else { high[k] >= sl });
+        let tp_at = range.clone().find(|&k| if long { high[k] >= tp } else { low[k] <= tp });
+        (sl_at, tp_at)
+    }
+}
@@ -0,0 +1,18 @@
+// src/engine/throttle.rs
+
+/// Equity‐curve meta‐filter: while the strategy's own equity is below its
+/// `window`‐bar moving average, new entries are scaled by `scale` (0 pauses)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EquityThrottle {
+    pub window: usize,
+    pub scale:  f64,
+}
+
+/// Whether closing equity at bar e−1 is below its `window`‐bar moving
+/// average (`equity` holds the closes of bars 0..e)
+pub fn below_average(equity: &[f64], e: usize, window: usize) -> bool {
+    e >= window && {
+        let ma = equity[e - window..e].iter().sum::<f64>() / window as f64;
+        equity[e - 1] < ma
+    }
+}
@@ -0,0 +1,104 @@
+// src/engine/timeline.rs
+
+use crate::engine::position::Position;
+
+/// Shape of the `timeline` output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimelineLayout {
+    /// `{position_id: [event, ...]}`
+    Nested,
+    /// One list of events, each carrying its `position_id`
+    Flat,
+}
+
+impl TimelineLayout {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "nested" => Ok(TimelineLayout::Nested),
+            "flat"   => Ok(TimelineLayout::Flat),
+            other => Err(format!("timeline must be \"nested\" or \"flat\", got ‘{}’", other)),
+        }
+    }
+}
+
+/// One lifecycle step of a position
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub position_id: u64,
+    /// "signal", "order_placed", "filled", "levels_moved", "unfilled_cancelled"
+    /// or "exit"
+    pub kind:        &'static str,
+    pub bar:         usize,
+    pub timestamp:   f64,
+    /// Fill or exit price
+    pub price:       Option<f64>,
+    pub size:        Option<f64>,
+    /// Levels in force after `levels_moved`
+    pub tp:          Option<f64>,
+    pub sl:          Option<f64>,
+    /// Exit condition for `exit`
+    pub condition:   Option<String>,
+}
+
+/// Chronological lifecycle of one position: its signal bar, the order going
+/// out at the next bar's open, each entry fill (several under partial
+/// fills), every TP/SL move, the cancel of any unfilled size and the exit
+pub fn position_events(pos: &Position, timestamps: &[f64]) -> Vec<Event> {
+    let n = timestamps.len();
+    let event = |kind, bar: usize| Event {
+        position_id: pos.position_id,
+        kind,
+        bar,
+        timestamp: timestamps[bar],
+        price: None,
+        size: None,
+        tp: None,
+        sl: None,
+        condition: None,
+    };
+
+    let mut events = vec![event("signal", pos.signal_index)];
+    events.push(event("order_placed", (pos.signal_index + 1).min(n - 1)));
+    if pos.fills.is_empty() {
+        events.push(Event {
+            timestamp: pos.entry_time,
+            price: Some(pos.entry_price),
+            size: Some(pos.position_size),
+            ..event("filled", pos.entry_index)
+        });
+    } else {
+        for f in &pos.fills {
+            events.push(Event {
+                price: Some(f.price),
+                size: Some(f.size),
+                ..event("filled", f.bar)
+            });
+        }
+    }
+    for c in &pos.level_changes {
+        events.push(Event {
+            tp: Some(c.tp),
+            sl: Some(c.sl),
+            ..event("levels_moved", c.bar)
+        });
+    }
+    if pos.unfilled_size > 0.0 {
+        let last_fill = pos.fills.last().map_or(pos.entry_index, |f| f.bar);
+        events.push(Event {
+            size: Some(pos.unfilled_size),
+            ..event("unfilled_cancelled", pos.exit_index.unwrap_or(last_fill))
+        });
+    }
+    if let Some(exit_i) = pos.exit_index {
+        events.push(Event {
+            timestamp: pos.exit_time.unwrap_or(timestamps[exit_i]),
+            price: pos.exit_price,
+            size: Some(pos.position_size),
+            condition: pos.exit_condition.clone(),
+            ..event("exit", exit_i)
+        });
+    }
+    // stable: same‐bar events keep their lifecycle order
+    events.sort_by_key(|e| e.bar);
+    events
+}
@@ -0,0 +1,41 @@
+// src/engine/trade_filter.rs
+
+use crate::engine::position::Position;
+
+/// Which trades of a result to keep; unset criteria match every trade
+#[derive(Clone, Debug, Default)]
+pub struct TradeFilter {
+    /// Entered at or after this timestamp
+    pub start_time:     Option<f64>,
+    /// Entered before this timestamp
+    pub end_time:       Option<f64>,
+    /// "long" or "short"
+    pub side:           Option<String>,
+    pub tag:            Option<String>,
+    /// "TP", "SL", "EXP", … (still‐open trades never match)
+    pub exit_condition: Option<String>,
+}
+
+impl TradeFilter {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(side) = &self.side {
+            if side != "long" && side != "short" {
+                return Err(format!("side must be \"long\" or \"short\", got ‘{}’", side));
+            }
+        }
+        if let (Some(a), Some(b)) = (self.start_time, self.end_time) {
+            if b <= a {
+                return Err("end_time must be after start_time".into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, pos: &Position) -> bool {
+        self.start_time.is_none_or(|t| pos.entry_time >= t)
+            && self.end_time.is_none_or(|t| pos.entry_time < t)
+            && self.side.as_ref().is_none_or(|s| &pos.position_type == s)
+            && self.tag.as_ref().is_none_or(|t| pos.tag.as_ref() == Some(t))
+            && self.exit_condition.as_ref().is_none_or(|c| pos.exit_condition.as_ref() == Some(c))
+    }
+}
@@ -0,0 +1,117 @@
+// src/engine/warnings.rs
+
+use std::collections::BTreeMap;
+
+use crate::engine::{
+    backtest::{BacktestConfig, BacktestResult, MarketData, SignalSet},
+    position::Position,
+    simulate_exits::LevelCheck,
+};
+
+/// Individually listed occurrences per kind before summarising the rest
+const MAX_LISTED: usize = 5;
+
+/// Non‐fatal but suspicious conditions that shaped a run, as readable lines
+pub fn collect_warnings(
+    result: &BacktestResult,
+    market: &MarketData,
+    signals: &SignalSet,
+    config: &BacktestConfig,
+) -> Vec<String> {
+    let mut out = Vec::new();
+    let n = market.close.len();
+    let mut all: Vec<&Position> = result.closed.iter().chain(&result.open).collect();
+    all.sort_by_key(|p| p.position_id);
+
+    // signal levels on the wrong side of the fill exit on the entry bar
+    let mut wrong_side = Vec::new();
+    for pos in &all {
+        let (i, long) = (pos.signal_index, pos.position_type == "long");
+        let (tp_bad, sl_bad) = if long {
+            (signals.long_tp[i] <= pos.entry_price, signals.long_sl[i] >= pos.entry_price)
+        } else {
+            (signals.short_tp[i] >= pos.entry_price, signals.short_sl[i] <= pos.entry_price)
+        };
+        let outcome = if pos.exit_index == Some(pos.entry_index) { "trade exited same bar" } else { "level already crossed" };
+        if tp_bad {
+            let rel = if long { "at/below" } else { "at/above" };
+            wrong_side.push(format!("TP {} entry for {} at bar {} — {}", rel, pos.position_type, pos.entry_index, outcome));
+        }
+        if sl_bad {
+            let rel = if long { "at/above" } else { "at/below" };
+            wrong_side.push(format!("SL {} entry for {} at bar {} — {}", rel, pos.position_type, pos.entry_index, outcome));
+        }
+    }
+    push_listed(&mut out, wrong_side, "positions with TP/SL on the wrong side of entry");
+
+    // both levels inside one bar without sub‐bars to order them
+    if config.sub_bars.is_none() && config.latency.is_none() && config.level_check == LevelCheck::Touch {
+        let (first, other) = if config.exit_priority.before("SL", "TP") { ("SL", "TP") } else { ("TP", "SL") };
+        let ambiguous: Vec<String> = result.closed.iter()
+            .filter(|p| p.exit_condition.as_deref() == Some(first))
+            .filter_map(|p| {
+                let j = p.exit_index?;
+                let long = p.position_type == "long";
+                let other_touched = match (other, long) {
+                    ("TP", true)  => market.high[j] >= p.tp,
+                    ("TP", false) => market.low[j] <= p.tp,
+                    (_, true)     => market.low[j] <= p.sl,
+                    (_, false)    => market.high[j] >= p.sl,
+                };
+                other_touched.then(|| format!(
+                    "SL and TP both inside bar {} for {} from bar {} — resolved as {} (pass sub_bars to order them)",
+                    j, p.position_type, p.entry_index, first,
+                ))
+            })
+            .collect();
+        push_listed(&mut out, ambiguous, &format!("same‐bar SL/TP exits resolved as {}", first));
+    }
+
+    // signals on the final bar fill at that bar's own open
+    let last_bar = all.iter().filter(|p| p.signal_index + 1 >= n).count();
+    if last_bar > 0 {
+        out.push(format!("{} signal(s) on the last bar filled at that bar's open", last_bar));
+    }
+
+    let mut skipped: BTreeMap<&str, usize> = BTreeMap::new();
+    for s in &result.skipped {
+        *skipped.entry(s.reason).or_default() += 1;
+    }
+    for (reason, count) in skipped {
+        out.push(format!("{} signal(s) skipped due to {}", count, reason));
+    }
+
+    if !result.open.is_empty() {
+        out.push(format!(
+            "{} position(s) still open at the last bar — marked to {}, excluded from trade metrics",
+            result.open.len(),
+            if config.mark_price.is_some() { "the mark price" } else { "close" },
+        ));
+    }
+    // liquidation is reported, not simulated
+    let liquidated: Vec<String> = all
+        .iter()
+        .filter_map(|p| {
+            let bar = p.liquidation_bar?;
+            Some(format!(
+                "{} from bar {} crossed its liquidation price {} at bar {} — kept open (liquidation is not simulated)",
+                p.position_type, p.entry_index, p.liquidation_price?, bar,
+            ))
+        })
+        .collect();
+    push_listed(&mut out, liquidated, "positions past their liquidation price");
+
+    if all.is_empty() && result.skipped.is_empty() {
+        out.push("no signals produced a position".to_string());
+    }
+    out
+}
+
+/// Append the first `MAX_LISTED` lines and a count of the rest
+pub(crate) fn push_listed(out: &mut Vec<String>, lines: Vec<String>, what: &str) {
+    let extra = lines.len().saturating_sub(MAX_LISTED);
+    out.extend(lines.into_iter().take(MAX_LISTED));
+    if extra > 0 {
+        out.push(format!("… {} more {}", extra, what));
+    }
+}
@@ -0,0 +1,78 @@
+// src/engine/weighted_returns.rs
+
+use crate::engine::bootstrap::{span_years, SECONDS_PER_YEAR};
+
+/// Account performance with and without the timing of external flows
+#[derive(Clone, Debug)]
+pub struct WeightedReturns {
+    /// Chain‐linked bar returns with each bar's external flow taken out:
+    /// what the strategy earned per unit of capital
+    pub time_weighted:            f64,
+    pub time_weighted_annualized: f64,
+    /// Annual IRR of the investor's flows (initial equity and deposits in,
+    /// withdrawals and final equity out): what the capital actually earned
+    pub money_weighted:           f64,
+}
+
+/// `equity[t]` is account equity at bar t's close, including `flows[t]`
+/// (deposits positive) booked at that bar; without flows both measures
+/// describe the same growth
+pub fn weighted_returns(
+    timestamps: &[f64],
+    equity: &[f64],
+    initial_equity: f64,
+    flows: Option<&[f64]>,
+) -> WeightedReturns {
+    let flow = |t: usize| flows.map_or(0.0, |f| f[t]);
+
+    let mut growth = 1.0;
+    let mut prev = initial_equity;
+    for (t, &e) in equity.iter().enumerate() {
+        growth = if prev > 0.0 { growth * (e - flow(t)) / prev } else { f64::NAN };
+        prev = e;
+    }
+    let years = span_years(timestamps);
+    let time_weighted_annualized = if years > 0.0 && growth > 0.0 {
+        growth.powf(1.0 / years) - 1.0
+    } else {
+        f64::NAN
+    };
+
+    // investor's view: money in is negative
+    let t0 = timestamps.first().copied().unwrap_or(0.0);
+    let mut cash_flows = vec![(0.0, -initial_equity)];
+    for (t, &ts) in timestamps.iter().enumerate() {
+        if flow(t) != 0.0 {
+            cash_flows.push(((ts - t0) / SECONDS_PER_YEAR, -flow(t)));
+        }
+    }
+    if let Some(&last) = equity.last() {
+        cash_flows.push((years, last));
+    }
+
+    WeightedReturns {
+        time_weighted: growth - 1.0,
+        time_weighted_annualized,
+        money_weighted: if years > 0.0 { irr(&cash_flows) } else { f64::NAN },
+    }
+}
+
+/// Annual rate zeroing the NPV of `(years, amount)` flows, by bisection on
+/// the log growth rate (NaN when the NPV never changes sign)
+fn irr(cash_flows: &[(f64, f64)]) -> f64 {
+    let npv = |x: f64| cash_flows.iter().map(|&(y, a)| a * (-x * y).exp()).sum::<f64>();
+    let (mut lo, mut hi) = (-20.0_f64, 20.0_f64);
+    let (f_lo, f_hi) = (npv(lo), npv(hi));
+    if !(f_lo.is_finite() && f_hi.is_finite()) || f_lo.signum() == f_hi.signum() {
+        return f64::NAN;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if npv(mid).signum() == f_lo.signum() {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (0.5 * (lo + hi)).exp() - 1.0
+}
@@ -1,12 +1,55 @@
 // src/lib.rs
 
+// The Python entry points mirror numpy‐array keyword APIs, so long
+// argument lists are the norm rather than a smell here.
+#![allow(clippy::too_many_arguments)]
+// Without the Python layer much of the configurable engine is unreachable
+#![cfg_attr(not(feature = "python"), allow(dead_code))]
+
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::wrap_pyfunction;
 
 mod engine;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "bench")]
+pub mod bench;
 
+#[cfg(feature = "python")]
 #[pymodule]
 fn backtester(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(engine::run_backtest, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::optimize::grid_search, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::optimize::sensitivity_surface, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::optimize::compare_exit_policies, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::stream_export::stream_backtest, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::stages::scan_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::stages::simulate_exits, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::stages::compute_exposure_series, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::stages::compute_summary_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::external::compute_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::ensemble::merge_equity_curves, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::monte_carlo::monte_carlo_trades, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::significance::permutation_test, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::significance::random_entry_baseline, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::portfolio::run_portfolio_backtest, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::pairs::run_pair_backtest, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::stress::stress_test, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::jitter::execution_jitter, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::duckdb_export::to_duckdb, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::config_file::load_config, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::config_file::run_config, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::splits::embargoed_split, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::splits::cpcv, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::generators::generate_gbm, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::generators::generate_ou, m)?)?;
+    m.add_function(wrap_pyfunction!(engine::generators::generate_regime_switching, m)?)?;
+    m.add_class::<engine::session::BacktestSession>()?;
+    m.add_class::<engine::paper::PaperSession>()?;
+    m.add_class::<engine::schema::StoredResult>()?;
     Ok(())
 }
@@ -0,0 +1,182 @@
+// src/server.rs
+
+//! HTTP service mode (feature `server`, binary `backtester-server`).
+//!
+//! A small std‐only HTTP/1.1 server, one thread per connection:
+//!
+//! * `GET /health` → `{"status": "ok", "version": ...}`
+//! * `POST /backtest` with a JSON body of columns (`timestamp`, `open`,
+//!   `high`, `low`, `close`, optional signal/level/size/expiry columns) and
+//!   the scalar costs → the engine's JSON result, or `{"error": ...}` with
+//!   status 400.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::engine::{
+    backtest::{run_engine, validate_market, validate_signals, BacktestConfig, MarketData, SignalSet},
+    json::{error_json, parse, push_str, result_json, Json},
+    run_info::VERSION,
+};
+
+/// Largest request body accepted (bytes)
+const MAX_BODY: usize = 256 << 20;
+
+/// Longest a connection may stall between reads before it is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Accept connections on `addr` (e.g. "127.0.0.1:8080") until the process
+/// exits
+pub fn serve(addr: &str) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                std::thread::spawn(move || {
+                    // a client hanging up mid‐request only ends its thread
+                    let _ = handle(stream);
+                });
+            }
+            Err(e) => eprintln!("backtester-server: accept failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn handle(stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+
+    let mut content_length = 0usize;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let (status, body) = match (method.as_str(), path.as_str()) {
+        ("GET", "/health") => {
+            let mut out = String::from("{\"status\":\"ok\",\"version\":");
+            push_str(&mut out, VERSION);
+            out.push('}');
+            (200, out)
+        }
+        ("POST", "/backtest") if content_length > MAX_BODY => (413, error_json("request body too large")),
+        ("POST", "/backtest") => {
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body)?;
+            match String::from_utf8(body).map_err(|e| e.to_string()).and_then(|b| backtest(&b)) {
+                Ok(out) => (200, out),
+                Err(e)  => (400, error_json(&e)),
+            }
+        }
+        (_, "/backtest" | "/health") => (405, error_json("method not allowed")),
+        _ => (404, error_json("not found")),
+    };
+    respond(stream, status, &body)
+}
+
+fn respond(mut stream: TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _   => "Payload Too Large",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    )?;
+    stream.flush()
+}
+
+/// Column `name` as f64s: numbers, booleans as 1/0, null as `missing`
+fn column(req: &Json, name: &str, n: Option<usize>, missing: f64) -> Result<Option<Vec<f64>>, String> {
+    let Some(value) = req.get(name) else {
+        return Ok(None);
+    };
+    let Json::Array(items) = value else {
+        return Err(format!("‘{}’ must be an array", name));
+    };
+    if n.is_some_and(|n| items.len() != n) {
+        return Err(format!("‘{}’ length must match ‘timestamp’", name));
+    }
+    items
+        .iter()
+        .map(|v| match v {
+            Json::Num(x)  => Ok(*x),
+            Json::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+            Json::Null    => Ok(missing),
+            _ => Err(format!("‘{}’ must hold numbers", name)),
+        })
+        .collect::<Result<Vec<f64>, String>>()
+        .map(Some)
+}
+
+fn scalar(req: &Json, name: &str, default: Option<f64>) -> Result<f64, String> {
+    match req.get(name) {
+        Some(Json::Num(x)) => Ok(*x),
+        Some(_) => Err(format!("‘{}’ must be a number", name)),
+        None => default.ok_or_else(|| format!("missing ‘{}’", name)),
+    }
+}
+
+/// Run one `/backtest` request body
+fn backtest(body: &str) -> Result<String, String> {
+    let req = parse(body)?;
+    let timestamps = column(&req, "timestamp", None, f64::NAN)?.ok_or("missing ‘timestamp’")?;
+    let n = Some(timestamps.len());
+    let bar = |name: &str| column(&req, name, n, f64::NAN)?.ok_or_else(|| format!("missing ‘{}’", name));
+    let opt = |name: &str, default: f64| -> Result<Vec<f64>, String> {
+        Ok(column(&req, name, n, default)?.unwrap_or_else(|| vec![default; timestamps.len()]))
+    };
+    let flags = |name: &str| -> Result<Vec<bool>, String> {
+        Ok(opt(name, 0.0)?.into_iter().map(|v| v != 0.0 && !v.is_nan()).collect())
+    };
+
+    let mut market = MarketData {
+        open:  bar("open")?,
+        high:  bar("high")?,
+        low:   bar("low")?,
+        close: bar("close")?,
+        timestamps: timestamps.clone(),
+    };
+    let signals = SignalSet {
+        long_signals:     flags("long_signals")?,
+        short_signals:    flags("short_signals")?,
+        long_tp:          opt("long_tp", f64::NAN)?,
+        long_sl:          opt("long_sl", f64::NAN)?,
+        short_tp:         opt("short_tp", f64::NAN)?,
+        short_sl:         opt("short_sl", f64::NAN)?,
+        long_size:        opt("long_size", 0.0)?,
+        short_size:       opt("short_size", 0.0)?,
+        expiration_times: opt("expiration_times", f64::INFINITY)?,
+        tags:             None,
+        direction:        None,
+    };
+    let initial_equity = scalar(&req, "initial_equity", None)?;
+    let config = BacktestConfig {
+        entry_fee_rate: scalar(&req, "entry_fee_rate", Some(0.0))?,
+        exit_fee_rate:  scalar(&req, "exit_fee_rate", Some(0.0))?,
+        slippage_rate:  scalar(&req, "slippage_rate", Some(0.0))?,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+    validate_market(&mut market)?;
+    validate_signals(&signals, &market.timestamps)?;
+    Ok(result_json(&run_engine(&market, &signals, &config), initial_equity))
+}
@@ -0,0 +1,114 @@
+// src/wasm.rs
+
+//! WebAssembly entry points (feature `wasm`).
+//!
+//! A plain C ABI, so no binding generator is needed: the host allocates
+//! the input columns inside the module's memory with `bt_alloc`, fills
+//! them as `Float64Array` views, calls `bt_run` and decodes the UTF‐8 JSON
+//! at the returned pointer (`bt_result_len` bytes).  `js/backtester.js`
+//! wraps this into `runBacktest({...})`.
+
+use std::cell::RefCell;
+
+use crate::engine::{
+    backtest::{run_engine, validate_market, validate_signals, BacktestConfig, MarketData, SignalSet},
+    json::{error_json, result_json},
+};
+
+thread_local! {
+    /// JSON of the last `bt_run`, kept alive until the next call
+    static OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Allocate `len` zeroed f64 slots for one input column
+#[no_mangle]
+pub extern "C" fn bt_alloc(len: usize) -> *mut f64 {
+    let mut buf = vec![0.0f64; len].into_boxed_slice();
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+
+/// Release a column from `bt_alloc`.
+///
+/// # Safety
+/// `ptr` and `len` must come from one `bt_alloc` call, freed once.
+#[no_mangle]
+pub unsafe extern "C" fn bt_free(ptr: *mut f64, len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+/// Byte length of the JSON returned by the last `bt_run`
+#[no_mangle]
+pub extern "C" fn bt_result_len() -> usize {
+    OUTPUT.with(|o| o.borrow().len())
+}
+
+/// Run a backtest over `n` bars and return a pointer to its JSON result:
+/// `{"closed_positions", "open_positions", "equity", "metrics"}` or
+/// `{"error": ...}` when the inputs are invalid.  Signal columns are
+/// non‐zero for true; an expiration of +∞ never expires.
+///
+/// # Safety
+/// Every column pointer must reference `n` readable f64 values.
+#[no_mangle]
+pub unsafe extern "C" fn bt_run(
+    n:                usize,
+    timestamp:        *const f64,
+    open:             *const f64,
+    high:             *const f64,
+    low:              *const f64,
+    close:            *const f64,
+    long_signals:     *const f64,
+    short_signals:    *const f64,
+    long_tp:          *const f64,
+    long_sl:          *const f64,
+    short_tp:         *const f64,
+    short_sl:         *const f64,
+    long_size:        *const f64,
+    short_size:       *const f64,
+    expiration_times: *const f64,
+    entry_fee_rate:   f64,
+    exit_fee_rate:    f64,
+    slippage_rate:    f64,
+    initial_equity:   f64,
+) -> *const u8 {
+    let col   = |ptr: *const f64| std::slice::from_raw_parts(ptr, n).to_vec();
+    let flags = |ptr: *const f64| col(ptr).into_iter().map(|v| v != 0.0).collect();
+    let mut market = MarketData {
+        timestamps: col(timestamp),
+        open:       col(open),
+        high:       col(high),
+        low:        col(low),
+        close:      col(close),
+    };
+    let signals = SignalSet {
+        long_signals:     flags(long_signals),
+        short_signals:    flags(short_signals),
+        long_tp:          col(long_tp),
+        long_sl:          col(long_sl),
+        short_tp:         col(short_tp),
+        short_sl:         col(short_sl),
+        long_size:        col(long_size),
+        short_size:       col(short_size),
+        expiration_times: col(expiration_times),
+        tags:             None,
+        direction:        None,
+    };
+    let config = BacktestConfig {
+        entry_fee_rate,
+        exit_fee_rate,
+        slippage_rate,
+        initial_equity,
+        ..BacktestConfig::default()
+    };
+
+    let json = validate_market(&mut market)
+        .and_then(|_| validate_signals(&signals, &market.timestamps))
+        .map(|_| result_json(&run_engine(&market, &signals, &config), initial_equity))
+        .unwrap_or_else(|e| error_json(&e));
+    OUTPUT.with(|o| {
+        *o.borrow_mut() = json;
+        o.borrow().as_ptr()
+    })
+}